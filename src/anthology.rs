@@ -0,0 +1,324 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::errors::InkError;
+use crate::storage;
+use crate::wordcount::WordCounter;
+
+/// The collection manifest at the root of an anthology: its title, author,
+/// and the stories in the order `compile` assembles them.
+pub const ANTHOLOGY_FILE: &str = "Anthology.yml";
+
+/// Each story is a book of its own under `Stories/<slug>/`: Global Material,
+/// outline, `.ink-state.yml`, and manuscript, sharing the collection's git
+/// repository.
+pub const STORIES_DIR: &str = "Stories";
+
+/// Repo-relative output of the collection-level `compile`.
+pub const COLLECTION_FILE: &str = "Current version/Anthology.md";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Anthology {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub stories: Vec<StoryEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoryEntry {
+    /// Directory name under `Stories/`.
+    pub slug: String,
+    /// Heading of the story in the compiled collection (default: the slug).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Whether `repo` is the root of an anthology.
+pub fn is_anthology(repo: &Path) -> bool {
+    repo.join(ANTHOLOGY_FILE).is_file()
+}
+
+fn load(repo: &Path) -> Result<Anthology> {
+    let path = repo.join(ANTHOLOGY_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(InkError::ConfigInvalid(format!(
+                "{} not found in {} — start an anthology with init --story <slug>",
+                ANTHOLOGY_FILE,
+                repo.display()
+            ))
+            .into())
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", ANTHOLOGY_FILE)),
+    };
+    serde_yaml::from_str(&content).map_err(|e| {
+        InkError::ConfigInvalid(format!("{} is not valid: {}", ANTHOLOGY_FILE, e)).into()
+    })
+}
+
+/// A story slug names one directory: lowercase letters, digits, `-` and `_`.
+fn check_slug(slug: &str) -> Result<()> {
+    let valid = !slug.is_empty()
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(InkError::InvalidInput(format!(
+            "--story '{}': expected a slug of lowercase letters, digits, '-' and '_'",
+            slug
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Slugs of the initialized stories under `Stories/`, sorted.
+pub fn stories(repo: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(repo.join(STORIES_DIR)) else {
+        return Vec::new();
+    };
+    let mut slugs: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().join("Global Material/Config.yml").is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+    slugs.sort();
+    slugs
+}
+
+/// The book root of story `slug` of the anthology at `repo`, for commands
+/// run with `--story`.
+pub fn story_root(repo: &Path, slug: &str) -> Result<PathBuf> {
+    check_slug(slug)?;
+    let root = repo.join(STORIES_DIR).join(slug);
+    if !root.join("Global Material/Config.yml").is_file() {
+        let known = stories(repo);
+        return Err(InkError::InvalidInput(format!(
+            "no story '{}' in {} — {}",
+            slug,
+            repo.join(STORIES_DIR).display(),
+            match known.is_empty() {
+                true => "create it with init --story".to_string(),
+                false => format!("stories: {}", known.join(", ")),
+            }
+        ))
+        .into());
+    }
+    Ok(root)
+}
+
+/// Another story of the anthology holding a session lock. Stories share one
+/// working tree and its branches, so only one of them writes at a time.
+pub fn open_elsewhere(repo: &Path, slug: &str) -> Option<String> {
+    stories(repo)
+        .into_iter()
+        .find(|s| s != slug && repo.join(STORIES_DIR).join(s).join(".ink-running").exists())
+}
+
+/// Create `Stories/<slug>/` for `init --story` and list it last in
+/// Anthology.yml, which is created when missing. Returns the story's root.
+pub fn add_story(repo: &Path, slug: &str, title: &str) -> Result<PathBuf> {
+    check_slug(slug)?;
+    let root = repo.join(STORIES_DIR).join(slug);
+    if root.join("Global Material/Config.yml").exists() {
+        return Err(InkError::InvalidInput(format!(
+            "story '{}' already initialized — {}/Global Material/Config.yml exists",
+            slug,
+            root.display()
+        ))
+        .into());
+    }
+    let mut anthology = match is_anthology(repo) {
+        true => load(repo)?,
+        false => Anthology::default(),
+    };
+    if !anthology.stories.iter().any(|s| s.slug == slug) {
+        anthology.stories.push(StoryEntry {
+            slug: slug.to_string(),
+            title: (title != "Untitled").then(|| title.to_string()),
+        });
+        let yaml = serde_yaml::to_string(&anthology)
+            .with_context(|| format!("Failed to serialize {}", ANTHOLOGY_FILE))?;
+        std::fs::write(repo.join(ANTHOLOGY_FILE), yaml)
+            .with_context(|| format!("Failed to write {}", ANTHOLOGY_FILE))?;
+    }
+    std::fs::create_dir_all(&root)
+        .with_context(|| format!("Failed to create {}", root.display()))?;
+    Ok(root)
+}
+
+/// A compiled manuscript without its managed-file header.
+fn without_header(book: &str) -> &str {
+    let book = book.trim_start();
+    match book.strip_prefix("<!-- ⚠ INK-GATEWAY:MANAGED") {
+        Some(rest) => rest.split_once("-->").map_or("", |(_, after)| after),
+        None => book,
+    }
+    .trim()
+}
+
+/// Write the collection to `Current version/Anthology.md`: each story of
+/// Anthology.yml compiled and placed in order under its title, after the
+/// collection's title page and contents. Like `compile` of a book, the file
+/// is regenerated on every run and never committed.
+pub fn compile(repo: &Path) -> Result<Value> {
+    let anthology = load(repo)?;
+    if anthology.stories.is_empty() {
+        return Err(InkError::ConfigInvalid(format!("{} lists no stories", ANTHOLOGY_FILE)).into());
+    }
+    let mut parts = Vec::new();
+    let mut compiled = Vec::new();
+    let mut total = 0;
+    for entry in &anthology.stories {
+        let root = story_root(repo, &entry.slug)
+            .with_context(|| format!("{} lists {}", ANTHOLOGY_FILE, entry.slug))?;
+        storage::compile(&root)?;
+        let book = std::fs::read_to_string(root.join(storage::FULL_BOOK)).unwrap_or_default();
+        let body = without_header(&book);
+        let title = entry.title.clone().unwrap_or_else(|| entry.slug.clone());
+        let words = WordCounter::for_repo(&root).count(body);
+        total += words;
+        parts.push(match body.starts_with("# ") {
+            true => format!("{}\n", body),
+            false => format!("# {}\n\n{}\n", title, body),
+        });
+        compiled.push(json!({ "slug": entry.slug, "title": title, "word_count": words }));
+    }
+
+    let mut text = String::from(
+        "<!-- ⚠ INK-GATEWAY:MANAGED — compiled from Stories/ by `ink-cli compile`; \
+         regenerated on every run. -->\n\n",
+    );
+    if let Some(title) = &anthology.title {
+        text.push_str(&format!("# {}\n\n", title));
+    }
+    if let Some(author) = &anthology.author {
+        text.push_str(&format!("*{}*\n\n", author));
+    }
+    text.push_str("## Contents\n\n");
+    for (i, story) in compiled.iter().enumerate() {
+        text.push_str(&format!(
+            "{}. {}\n",
+            i + 1,
+            story["title"].as_str().unwrap_or("")
+        ));
+    }
+    for part in parts {
+        text.push('\n');
+        text.push_str(&part);
+    }
+    let path = repo.join(COLLECTION_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, &text).with_context(|| format!("Failed to write {}", COLLECTION_FILE))?;
+    info!(
+        "Compiled {} stories into {}",
+        compiled.len(),
+        COLLECTION_FILE
+    );
+
+    let listed: Vec<&str> = anthology.stories.iter().map(|s| s.slug.as_str()).collect();
+    let unlisted: Vec<String> = stories(repo)
+        .into_iter()
+        .filter(|s| !listed.contains(&s.as_str()))
+        .collect();
+    Ok(json!({
+        "status": "compiled",
+        "path": COLLECTION_FILE,
+        "stories": compiled,
+        "unlisted": unlisted,
+        "total_word_count": total,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An anthology with three stories; Anthology.yml lists two of them, in
+    /// reverse order.
+    fn anthology() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for (slug, title, prose) in [
+            ("salt", "Salt", "## Chapter 1\n\nThe tide came in.\n"),
+            (
+                "ferryman",
+                "Untitled",
+                "# The Ferryman\n\n## Chapter 1\n\nHe rowed.\n",
+            ),
+            ("drafts", "Drafts", ""),
+        ] {
+            let root = add_story(repo, slug, title).unwrap();
+            std::fs::create_dir_all(root.join("Global Material")).unwrap();
+            std::fs::create_dir_all(root.join("Current version")).unwrap();
+            std::fs::write(
+                root.join("Global Material/Config.yml"),
+                "target_length: 1\n",
+            )
+            .unwrap();
+            std::fs::write(
+                root.join(storage::FULL_BOOK),
+                format!("<!-- ⚠ INK-GATEWAY:MANAGED — Do not edit. -->\n\n{}", prose),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            repo.join(ANTHOLOGY_FILE),
+            "title: Tides\nstories:\n  - slug: ferryman\n  - slug: salt\n    title: Salt\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn compiles_stories_in_configured_order() {
+        let tmp = anthology();
+        let repo = tmp.path();
+
+        let result = compile(repo).unwrap();
+        assert_eq!(result["stories"][0]["title"], "ferryman");
+        assert_eq!(result["unlisted"], json!(["drafts"]));
+        let text = std::fs::read_to_string(repo.join(COLLECTION_FILE)).unwrap();
+        let ferryman = text.find("# The Ferryman").unwrap();
+        let salt = text.find("# Salt\n\n## Chapter 1\n\nThe tide").unwrap();
+        assert!(text.contains("# Tides\n\n## Contents\n\n1. ferryman\n2. Salt\n"));
+        assert!(ferryman < salt);
+        assert_eq!(text.matches("INK-GATEWAY:MANAGED").count(), 1);
+    }
+
+    #[test]
+    fn stories_resolve_by_slug() {
+        let tmp = anthology();
+        let repo = tmp.path();
+        assert!(story_root(repo, "salt").unwrap().ends_with("Stories/salt"));
+        let err = story_root(repo, "nope").unwrap_err();
+        assert!(format!("{:#}", err).contains("stories: drafts, ferryman, salt"));
+        assert!(story_root(repo, "../salt").is_err());
+    }
+
+    #[test]
+    fn an_existing_story_cannot_be_added_again() {
+        let tmp = anthology();
+        assert!(add_story(tmp.path(), "salt", "Salt").is_err());
+    }
+
+    #[test]
+    fn a_session_open_in_another_story_is_reported() {
+        let tmp = anthology();
+        let repo = tmp.path();
+        assert_eq!(open_elsewhere(repo, "salt"), None);
+        std::fs::write(repo.join("Stories/ferryman/.ink-running"), "").unwrap();
+        assert_eq!(open_elsewhere(repo, "salt").as_deref(), Some("ferryman"));
+        assert_eq!(open_elsewhere(repo, "ferryman"), None);
+    }
+}
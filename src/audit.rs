@@ -0,0 +1,319 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::warn;
+
+use crate::errors;
+use crate::git;
+use crate::journal;
+
+/// Append-only record of every command run on this working tree, one JSON
+/// object per line. Local like the rest of `.ink/`: never committed.
+const AUDIT_LOG: &str = ".ink/audit.log";
+
+/// MCP string arguments longer than this (prose, patches) are logged by size.
+const MAX_ARG_CHARS: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    /// `cli`, `mcp`, or `schedule` (a run of the `schedule` daemon).
+    pub source: String,
+    /// Subcommand (`session-close`, `report phrases`) or MCP tool name.
+    pub command: String,
+    /// Command-line arguments, or the MCP call's arguments.
+    pub args: Value,
+    /// Who asked: the MCP client (`clientInfo` of its `initialize`, `name/version`),
+    /// else `$INK_AGENT`, else the OS user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller: Option<String>,
+    /// The MCP request's JSON-RPC id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// `ok`, or the error code (`busy`, `git_failed`, …).
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// HEAD once the command finished.
+    pub commit: Option<String>,
+}
+
+/// A command being audited; `finish` writes its entry.
+pub struct Audit {
+    repo: PathBuf,
+    source: &'static str,
+    command: String,
+    args: Value,
+    caller: Option<String>,
+    request_id: Option<String>,
+    at: DateTime<Utc>,
+    started: Instant,
+}
+
+/// The process's caller: `$INK_AGENT` (set by the gateway), else the OS user.
+fn default_caller() -> Option<String> {
+    ["INK_AGENT", "USER", "USERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+}
+
+/// `args` with long strings replaced by their size.
+fn elide(args: &Value) -> Value {
+    match args {
+        Value::String(s) if s.chars().count() > MAX_ARG_CHARS => {
+            json!(format!("<{} chars>", s.chars().count()))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(elide).collect()),
+        Value::Object(map) => {
+            Value::Object(map.iter().map(|(k, v)| (k.clone(), elide(v))).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+impl Audit {
+    pub fn start(repo: &Path, source: &'static str, command: &str, args: &Value) -> Self {
+        Audit {
+            repo: repo.to_path_buf(),
+            source,
+            command: command.to_string(),
+            args: elide(args),
+            caller: default_caller(),
+            request_id: None,
+            at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Record who made the request, when known, instead of the process's caller.
+    #[allow(dead_code)] // used by ink-gateway-mcp only
+    pub fn caller(mut self, caller: Option<String>) -> Self {
+        if caller.is_some() {
+            self.caller = caller;
+        }
+        self
+    }
+
+    #[allow(dead_code)] // used by ink-gateway-mcp only
+    pub fn request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Append the entry for `result`. Best-effort: a log that cannot be
+    /// written is reported and never fails the command.
+    pub fn finish<T>(self, result: &Result<T>) {
+        // Never create a directory for a mistyped path
+        if !self.repo.is_dir() {
+            return;
+        }
+        let entry = AuditEntry {
+            at: self.at,
+            source: self.source.to_string(),
+            command: self.command,
+            args: self.args,
+            caller: self.caller,
+            request_id: self.request_id,
+            outcome: match result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => errors::code_of(e).to_string(),
+            },
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            commit: git::run_git(&self.repo, &["rev-parse", "HEAD"]).ok(),
+        };
+        if let Err(e) = append(&self.repo, &entry) {
+            warn!("Failed to write {}: {:#}", AUDIT_LOG, e);
+        }
+    }
+}
+
+fn append(repo: &Path, entry: &AuditEntry) -> Result<()> {
+    journal::ensure_ink_dir(repo)?;
+    let line = serde_json::to_string(entry).context("Failed to serialize audit entry")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(repo.join(AUDIT_LOG))
+        .with_context(|| format!("Failed to open {}", AUDIT_LOG))?;
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", AUDIT_LOG))
+}
+
+/// Start of an `audit --since` window: an RFC 3339 time, a `YYYY-MM-DD` date
+/// (midnight UTC), or an age such as `12h` or `3d`.
+pub fn parse_since(since: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(since) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    let age = since
+        .strip_suffix('h')
+        .and_then(|n| n.parse().ok())
+        .map(ChronoDuration::hours)
+        .or_else(|| {
+            since
+                .strip_suffix('d')
+                .and_then(|n| n.parse().ok())
+                .map(ChronoDuration::days)
+        });
+    match age {
+        Some(age) => Ok(now - age),
+        None => Err(errors::InkError::InvalidInput(format!(
+            "--since '{}': expected a date (2026-03-30), an RFC 3339 time, or an age (12h, 3d)",
+            since
+        ))
+        .into()),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AuditQuery {
+    pub since: Option<DateTime<Utc>>,
+    /// Entries whose command starts with this (`session` matches both ends).
+    pub command: Option<String>,
+    /// Entries of this caller only.
+    pub caller: Option<String>,
+    pub errors_only: bool,
+    /// The newest entries kept; 0 for all.
+    pub limit: usize,
+}
+
+/// Entries of `.ink/audit.log` matching `query`, oldest first. Lines that do
+/// not parse (a write cut off by a crash) are skipped.
+pub fn query(repo: &Path, query: &AuditQuery) -> Result<Value> {
+    let content = match std::fs::read_to_string(repo.join(AUDIT_LOG)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", AUDIT_LOG)),
+    };
+    let mut entries: Vec<AuditEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|e: &AuditEntry| query.since.is_none_or(|since| e.at >= since))
+        .filter(|e| {
+            query
+                .command
+                .as_deref()
+                .is_none_or(|c| e.command.starts_with(c))
+        })
+        .filter(|e| {
+            query
+                .caller
+                .as_deref()
+                .is_none_or(|c| e.caller.as_deref() == Some(c))
+        })
+        .filter(|e| !query.errors_only || e.outcome != "ok")
+        .collect();
+    let total = entries.len();
+    if query.limit > 0 && total > query.limit {
+        entries.drain(..total - query.limit);
+    }
+    Ok(json!({
+        "log": AUDIT_LOG,
+        "matched": total,
+        "entries": entries,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A successful MCP close with long prose, then a CLI status refused for
+    /// want of a session.
+    fn two_calls() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        let prose = "word ".repeat(100);
+        let closed: Result<()> = Ok(());
+        Audit::start(repo, "mcp", "session_close", &json!({ "prose": prose })).finish(&closed);
+        let refused: Result<()> = Err(errors::InkError::NoSession.into());
+        Audit::start(repo, "cli", "status", &json!(["status"])).finish(&refused);
+        tmp
+    }
+
+    #[test]
+    fn long_arguments_are_recorded_by_size() {
+        let tmp = two_calls();
+        let all = query(tmp.path(), &AuditQuery::default()).unwrap();
+        assert_eq!(all["matched"], 2);
+        assert_eq!(all["entries"][0]["args"]["prose"], "<500 chars>");
+    }
+
+    #[test]
+    fn failures_are_recorded_by_error_code() {
+        let tmp = two_calls();
+        let all = query(tmp.path(), &AuditQuery::default()).unwrap();
+        assert_eq!(all["entries"][1]["outcome"], "no_session");
+        let failed = AuditQuery {
+            errors_only: true,
+            ..AuditQuery::default()
+        };
+        let matched = query(tmp.path(), &failed).unwrap();
+        assert_eq!(matched["matched"], 1);
+        assert_eq!(matched["entries"][0]["command"], "status");
+    }
+
+    #[test]
+    fn a_limit_keeps_the_newest_entries() {
+        let tmp = two_calls();
+        let limited = AuditQuery {
+            limit: 1,
+            ..AuditQuery::default()
+        };
+        assert_eq!(
+            query(tmp.path(), &limited).unwrap()["entries"][0]["command"],
+            "status"
+        );
+    }
+
+    #[test]
+    fn since_takes_a_duration() {
+        let now = Utc::now();
+        assert_eq!(
+            parse_since("12h", now).unwrap(),
+            now - ChronoDuration::hours(12)
+        );
+        assert!(parse_since("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn records_and_queries_the_caller_of_each_request() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        let ok: Result<()> = Ok(());
+        for (client, id) in [("claude/1.2", "7"), ("cron-agent/0.3", "8")] {
+            Audit::start(repo, "mcp", "status", &json!({}))
+                .caller(Some(client.to_string()))
+                .request_id(id)
+                .finish(&ok);
+        }
+        Audit::start(repo, "mcp", "status", &json!({}))
+            .caller(None)
+            .finish(&ok);
+
+        let all = query(repo, &AuditQuery::default()).unwrap();
+        assert_eq!(all["entries"][0]["caller"], "claude/1.2");
+        assert_eq!(all["entries"][0]["request_id"], "7");
+        // Unknown client: the process's caller stands in, and no request id
+        assert_eq!(all["entries"][2]["caller"], json!(default_caller()));
+        assert!(all["entries"][2].get("request_id").is_none());
+
+        let by_caller = AuditQuery {
+            caller: Some("cron-agent/0.3".to_string()),
+            ..AuditQuery::default()
+        };
+        let matched = query(repo, &by_caller).unwrap();
+        assert_eq!(matched["matched"], 1);
+        assert_eq!(matched["entries"][0]["request_id"], "8");
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+use crate::git;
+
+/// What `backup` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackupFormat {
+    /// `git bundle` of every branch and tag — committed history only.
+    Bundle,
+    /// gzipped tarball of the whole working tree, `.git` and local state included.
+    Tar,
+}
+
+impl BackupFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            BackupFormat::Bundle => "bundle",
+            BackupFormat::Tar => "tar.gz",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            BackupFormat::Bundle => "bundle",
+            BackupFormat::Tar => "tar",
+        }
+    }
+
+    /// Guess the format of an existing backup from its name.
+    fn of_file(file: &Path) -> Self {
+        let name = file.to_string_lossy();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            BackupFormat::Tar
+        } else {
+            BackupFormat::Bundle
+        }
+    }
+}
+
+/// Write a timestamped backup of `repo` to `output` (default:
+/// `<repo>-YYYYMMDD-HHMMSS.<ext>` in the current directory). A bundle holds
+/// only commits, so uncommitted files are listed in the result.
+pub fn backup(repo: &Path, format: BackupFormat, output: Option<&Path>) -> Result<Value> {
+    anyhow::ensure!(
+        repo.join(".git").exists(),
+        "{} is not a git repository",
+        repo.display()
+    );
+    let file = match output {
+        Some(path) => path.to_path_buf(),
+        None => PathBuf::from(format!(
+            "{}-{}.{}",
+            crate::init::repo_name(repo),
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        )),
+    };
+    anyhow::ensure!(!file.exists(), "{} already exists", file.display());
+    // git and tar run inside the repo; the file name must not depend on it
+    let target = std::env::current_dir()?.join(&file);
+
+    let mut uncommitted = Vec::new();
+    match format {
+        BackupFormat::Bundle => {
+            info!("Bundling all branches and tags into {}", file.display());
+            git::run_git(
+                repo,
+                &["bundle", "create", &target.to_string_lossy(), "--all"],
+            )
+            .with_context(|| "Failed to create git bundle")?;
+            git::run_git(repo, &["bundle", "verify", &target.to_string_lossy()])
+                .with_context(|| "Bundle written but failed verification")?;
+            uncommitted = git::collect_modified_files(repo)?;
+            if !uncommitted.is_empty() {
+                warn!(
+                    "{} uncommitted file(s) are not in the bundle — use --format tar to include them",
+                    uncommitted.len()
+                );
+            }
+        }
+        BackupFormat::Tar => {
+            if target.starts_with(repo.canonicalize()?) {
+                bail!("write the tarball outside the repository (--output)");
+            }
+            info!("Archiving the repository into {}", file.display());
+            run_tar(
+                Command::new("tar")
+                    .args(["-czf", &target.to_string_lossy(), "-C"])
+                    .arg(repo)
+                    .arg("."),
+            )?;
+        }
+    }
+    let size_bytes = std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+    Ok(json!({
+        "status": "backed_up",
+        "format": format.label(),
+        "file": file.display().to_string(),
+        "size_bytes": size_bytes,
+        "head": git::run_git(repo, &["rev-parse", "HEAD"]).ok(),
+        "uncommitted": uncommitted,
+    }))
+}
+
+/// Verify `file` and unpack it into `dest`, which must not exist or be empty.
+/// A restored bundle's origin points at the bundle; `remote` replaces it.
+pub fn restore(file: &Path, dest: &Path, remote: Option<&str>) -> Result<Value> {
+    anyhow::ensure!(file.is_file(), "{} not found", file.display());
+    if dest.exists() && dest.read_dir()?.next().is_some() {
+        bail!("{} exists and is not empty", dest.display());
+    }
+    let format = BackupFormat::of_file(file);
+    let file = file
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", file.display()))?;
+    match format {
+        BackupFormat::Bundle => {
+            info!("Cloning from bundle {}", file.display());
+            let parent = dest
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            std::fs::create_dir_all(parent)?;
+            let target = std::env::current_dir()?.join(dest);
+            git::run_git(
+                parent,
+                &["clone", &file.to_string_lossy(), &target.to_string_lossy()],
+            )
+            .with_context(|| format!("{} is not a valid git bundle", file.display()))?;
+            git::run_git(dest, &["bundle", "verify", &file.to_string_lossy()])
+                .with_context(|| "Bundle failed verification")?;
+            // Local branches for every branch in the bundle, not just HEAD's
+            let heads = git::run_git(
+                dest,
+                &[
+                    "for-each-ref",
+                    "--format=%(refname:short)",
+                    "refs/remotes/origin",
+                ],
+            )?;
+            for remote_branch in heads
+                .lines()
+                .filter(|b| *b != "origin/HEAD" && *b != "origin")
+            {
+                let branch = remote_branch.trim_start_matches("origin/");
+                if git::run_git(
+                    dest,
+                    &[
+                        "show-ref",
+                        "--verify",
+                        "-q",
+                        &format!("refs/heads/{}", branch),
+                    ],
+                )
+                .is_err()
+                {
+                    git::run_git(dest, &["branch", "--track", branch, remote_branch])?;
+                }
+            }
+        }
+        BackupFormat::Tar => {
+            let listing = run_tar(Command::new("tar").arg("-tzf").arg(&file))
+                .with_context(|| format!("{} is not a readable tarball", file.display()))?;
+            let entries: Vec<&str> = listing
+                .lines()
+                .map(|l| l.trim_start_matches("./"))
+                .collect();
+            if let Some(bad) = entries
+                .iter()
+                .find(|e| e.starts_with('/') || e.split('/').any(|part| part == ".."))
+            {
+                bail!(
+                    "refusing to restore: archive entry '{}' escapes the target",
+                    bad
+                );
+            }
+            anyhow::ensure!(
+                entries.contains(&".git/HEAD"),
+                "{} does not contain a git repository",
+                file.display()
+            );
+            info!("Unpacking {} into {}", file.display(), dest.display());
+            std::fs::create_dir_all(dest)?;
+            run_tar(
+                Command::new("tar")
+                    .arg("-xzf")
+                    .arg(&file)
+                    .arg("-C")
+                    .arg(dest),
+            )?;
+            git::run_git(dest, &["fsck", "--no-dangling", "--no-progress"])
+                .with_context(|| "Restored repository failed git fsck")?;
+        }
+    }
+    if let Some(url) = remote {
+        git::run_git(dest, &["remote", "set-url", "origin", url])?;
+    }
+
+    let book = dest.join("Global Material/Config.yml").exists();
+    if !book {
+        warn!("Restored repository has no Global Material/Config.yml — not an ink book?");
+    }
+    Ok(json!({
+        "status": "restored",
+        "format": format.label(),
+        "path": dest.display().to_string(),
+        "head": git::run_git(dest, &["rev-parse", "HEAD"]).ok(),
+        "origin": git::run_git(dest, &["remote", "get-url", "origin"]).ok(),
+        "book": book,
+    }))
+}
+
+fn run_tar(cmd: &mut Command) -> Result<String> {
+    let out = cmd.output().with_context(|| "Failed to run tar")?;
+    if !out.status.success() {
+        bail!(
+            "tar failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A committed book at `<tmp>/book` with a `draft` branch and one
+    /// uncommitted file.
+    fn book(tmp: &Path) -> PathBuf {
+        let repo = tmp.join("book");
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        git::run_git(&repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(&repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(&repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 1\n",
+        )
+        .unwrap();
+        git::run_git(&repo, &["add", "-A"]).unwrap();
+        git::run_git(&repo, &["commit", "-q", "-m", "book"]).unwrap();
+        git::run_git(&repo, &["branch", "draft"]).unwrap();
+        std::fs::write(repo.join("notes.txt"), "not committed").unwrap();
+        repo
+    }
+
+    #[test]
+    fn bundle_round_trips_branches_but_not_uncommitted_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = book(tmp.path());
+
+        let bundle = tmp.path().join("book.bundle");
+        let made = backup(&repo, BackupFormat::Bundle, Some(&bundle)).unwrap();
+        assert_eq!(made["uncommitted"], json!(["notes.txt"]));
+        let restored = tmp.path().join("from-bundle");
+        let result = restore(&bundle, &restored, Some("git@example.com:a/book.git")).unwrap();
+        assert_eq!(result["book"], true);
+        assert_eq!(result["origin"], "git@example.com:a/book.git");
+        assert!(git::run_git(&restored, &["rev-parse", "--verify", "draft"]).is_ok());
+        assert!(!restored.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn tar_round_trips_the_working_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = book(tmp.path());
+
+        let tarball = tmp.path().join("book.tar.gz");
+        backup(&repo, BackupFormat::Tar, Some(&tarball)).unwrap();
+        let restored = tmp.path().join("from-tar");
+        restore(&tarball, &restored, None).unwrap();
+        assert!(restored.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn restore_refuses_a_non_empty_destination() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = book(tmp.path());
+
+        let tarball = tmp.path().join("book.tar.gz");
+        backup(&repo, BackupFormat::Tar, Some(&tarball)).unwrap();
+        assert!(restore(&tarball, &repo, None).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::errors::InkError;
+
+/// `## Beats` or `## Scene Beats` heading of an outline.
+fn beats_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^#{2,3}[ \t]+(?:scene[ \t]+)?beats[ \t]*$").unwrap())
+}
+
+/// A top-level list item: marker (group 1), optional `[x]` / `[ ]` box
+/// (group 2 is its mark), text (group 3).
+fn item_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^( ?(?:\d+[.)]|[-*+])[ \t]+)(?:\[([ xX])\][ \t]+)?(.*\S)").unwrap()
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Beat {
+    /// 1-based position in the beats list — what `--beats-done` refers to.
+    pub number: u32,
+    pub text: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Coverage {
+    pub done: u32,
+    pub total: u32,
+}
+
+/// Walk the beats section of `outline`, calling `f(beat, line_start)` for
+/// each beat. Template placeholders (`[Opening beat — …]`) are not beats.
+fn walk(outline: &str, mut f: impl FnMut(Beat, usize)) {
+    let mut in_beats = false;
+    let mut offset = 0;
+    let mut number = 0;
+    for line in outline.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_end();
+        if trimmed.starts_with('#') {
+            in_beats = beats_heading_re().is_match(trimmed);
+            continue;
+        }
+        if !in_beats {
+            continue;
+        }
+        let Some(c) = item_re().captures(trimmed) else {
+            continue;
+        };
+        let text = &c[3];
+        if c.get(2).is_none() && text.starts_with('[') && text.ends_with(']') {
+            continue;
+        }
+        number += 1;
+        let beat = Beat {
+            number,
+            text: text.to_string(),
+            done: c.get(2).is_some_and(|m| m.as_str() != " "),
+        };
+        f(beat, start);
+    }
+}
+
+/// The beats of `outline`, in order; empty without a beats section.
+pub fn parse(outline: &str) -> Vec<Beat> {
+    let mut beats = Vec::new();
+    walk(outline, |beat, _| beats.push(beat));
+    beats
+}
+
+/// `outline` with beats `numbers` checked (`1. [x] …`). Beats already
+/// checked stay as they are; a number with no beat is an error.
+pub fn mark_done(outline: &str, numbers: &[u32]) -> Result<String> {
+    let mut starts = Vec::new();
+    let mut total = 0;
+    walk(outline, |beat, start| {
+        total = beat.number;
+        if numbers.contains(&beat.number) && !beat.done {
+            starts.push(start);
+        }
+    });
+    if let Some(n) = numbers.iter().find(|&&n| n == 0 || n > total) {
+        return Err(InkError::InvalidInput(format!(
+            "beat {} does not exist — the outline has {} beat(s)",
+            n, total
+        ))
+        .into());
+    }
+    let mut marked = outline.to_string();
+    for start in starts.into_iter().rev() {
+        let line = &outline[start..];
+        let c = item_re().captures(line).expect("walked beat line");
+        let at = start + c[1].len();
+        // An unchecked `[ ] ` box is replaced, not doubled
+        let end = match c.get(2) {
+            Some(_) => start + c.get(3).unwrap().start(),
+            None => at,
+        };
+        marked.replace_range(at..end, "[x] ");
+    }
+    Ok(marked)
+}
+
+/// Checked and total beats of the outline at `rel`; None when the file is
+/// missing or lists no beats.
+pub fn coverage(repo: &Path, rel: &str) -> Result<Option<Coverage>> {
+    let path = repo.join(rel);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let outline =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", rel))?;
+    let beats = parse(&outline);
+    if beats.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Coverage {
+        done: beats.iter().filter(|b| b.done).count() as u32,
+        total: beats.len() as u32,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTLINE: &str = "# Chapter 3\n\n## Goal\n\n1. Not a beat\n\n## Scene Beats\n\n\
+                           1. Mara finds the letter\n   - nested detail\n2. [ ] The storm\n\
+                           3. [x] Harbour fire\n\n## Notes\n\n- not a beat\n";
+
+    #[test]
+    fn beats_are_the_numbered_items_under_scene_beats() {
+        let beats = parse(OUTLINE);
+        assert_eq!(
+            beats
+                .iter()
+                .map(|b| (b.text.as_str(), b.done))
+                .collect::<Vec<_>>(),
+            [
+                ("Mara finds the letter", false),
+                ("The storm", false),
+                ("Harbour fire", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn marking_beats_done_checks_them_off() {
+        let marked = mark_done(OUTLINE, &[1, 2, 3]).unwrap();
+        assert!(marked.contains(
+            "1. [x] Mara finds the letter\n   - nested detail\n2. [x] The storm\n3. [x] Harbour fire\n"
+        ));
+        assert!(parse(&marked).iter().all(|b| b.done));
+    }
+
+    #[test]
+    fn marking_a_missing_beat_fails() {
+        assert!(mark_done(OUTLINE, &[4]).is_err());
+    }
+
+    #[test]
+    fn template_placeholders_are_not_beats() {
+        assert!(parse(crate::init::CHAPTER_01_MD).is_empty());
+    }
+}
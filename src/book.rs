@@ -1,8 +1,10 @@
 use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
 use std::path::Path;
 
 use crate::config::Config;
 use crate::git;
+use crate::sequence;
 use crate::state::InkState;
 
 // ─── Constants ─────────────────────────────────────────────────────────────────
@@ -73,10 +75,28 @@ pub(crate) fn strip_author_ink_instructions(text: &str) -> String {
     out
 }
 
-/// Count prose words, ignoring HTML comment lines (e.g. `<!-- PAGE N -->`).
-/// Used by both session-close (maintenance) and session-open word_count (context)
-/// so both always report the same figure.
-pub fn count_prose_words(content: &str) -> u32 {
+/// Remove every HTML comment, including multi-line ones such as the managed
+/// Full_Book.md header. Used where only reader-facing prose should remain.
+pub(crate) fn strip_html_comments(text: &str) -> String {
+    static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| regex::Regex::new(r"(?s)<!--.*?-->").unwrap())
+        .replace_all(text, "")
+        .to_string()
+}
+
+/// Prose paragraphs of `text` with HTML comments (header, page markers) removed.
+pub(crate) fn prose_paragraphs(text: &str) -> Vec<String> {
+    strip_html_comments(text)
+        .split("\n\n")
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Words as pagination counts them: every whitespace token outside HTML
+/// comment lines. Page markers already in books were placed by this count,
+/// so it stays independent of the `word_count:` rules.
+fn page_words(content: &str) -> u32 {
     content
         .lines()
         .filter(|l| !l.trim_start().starts_with("<!--"))
@@ -84,6 +104,331 @@ pub fn count_prose_words(content: &str) -> u32 {
         .count() as u32
 }
 
+/// Replace previously validated passages in `book` with their reworked versions.
+///
+/// Each `<!-- INK:REWORKED:START/END -->` block in `validated` that carries an
+/// `<!-- INK:ORIGINAL:START/END -->` sub-block is matched against `book` by its
+/// original text, paragraph by paragraph: the original must cover whole
+/// paragraphs of the book (blank-line boundaries), and page markers between or
+/// inside them are skipped. When the original is found (last occurrence wins —
+/// the most recently validated copy), it is replaced in place with the reworked
+/// prose and the block is dropped from the returned remainder. Unmatched blocks
+/// stay in the remainder (markers intact) so they are appended as before.
+///
+/// Returns `(remainder, replacements_applied)`. Page markers are not recomputed:
+/// one inside a replaced passage is kept before the reworked paragraph at its
+/// position (after the passage when the rework has fewer paragraphs), so a
+/// replacement only shifts pagination by the word-count delta of the passage.
+pub(crate) fn apply_reworked_replacements(book: &mut String, validated: &str) -> (String, u32) {
+    apply_reworked_replacements_with(book, validated, |_, _| {})
+}
+
+/// `apply_reworked_replacements`, calling `on_replace(original, reworked)` for
+/// each passage replaced in place.
+pub(crate) fn apply_reworked_replacements_with(
+    book: &mut String,
+    validated: &str,
+    mut on_replace: impl FnMut(&str, &str),
+) -> (String, u32) {
+    let mut remainder: Vec<&str> = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let mut in_block = false;
+    let mut applied: u32 = 0;
+
+    for line in validated.lines() {
+        let t = line.trim();
+        if !in_block {
+            if t == "<!-- INK:REWORKED:START -->" {
+                in_block = true;
+                block.clear();
+                block.push(line);
+            } else {
+                remainder.push(line);
+            }
+            continue;
+        }
+        block.push(line);
+        if t != "<!-- INK:REWORKED:END -->" {
+            continue;
+        }
+        in_block = false;
+
+        let original = extract_original_passage(&block);
+        let reworked = strip_engine_markers(&block.join("\n"));
+        let reworked = reworked.trim();
+        let replaced = match original {
+            Some(orig) if !reworked.is_empty() && replace_passage(book, &orig, reworked) => {
+                on_replace(&orig, reworked);
+                true
+            }
+            _ => false,
+        };
+        if replaced {
+            applied += 1;
+        } else {
+            remainder.extend_from_slice(&block);
+        }
+    }
+    // Unterminated block: keep it verbatim rather than dropping prose
+    if in_block {
+        remainder.extend_from_slice(&block);
+    }
+
+    (remainder.join("\n"), applied)
+}
+
+/// A paragraph of Full_Book.md as reworked passages are matched against it.
+struct BookParagraph {
+    /// Byte span from its first to its last prose line (no trailing newline)
+    start: usize,
+    end: usize,
+    /// Its prose lines, trimmed, page markers left out
+    text: String,
+    /// Page markers since the previous paragraph, and between its own lines
+    markers_before: Vec<String>,
+    markers_inside: Vec<String>,
+}
+
+fn is_page_marker(line: &str) -> bool {
+    line.strip_prefix("<!-- PAGE ")
+        .and_then(|rest| rest.strip_suffix(" -->"))
+        .is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+/// Paragraphs of `text`: runs of lines up to a blank line. A page marker line
+/// never ends or starts one.
+fn book_paragraphs(text: &str) -> Vec<BookParagraph> {
+    let mut paragraphs: Vec<BookParagraph> = Vec::new();
+    let mut markers: Vec<String> = Vec::new();
+    let mut open = false;
+    let mut offset = 0;
+    for raw in text.split_inclusive('\n') {
+        let start = offset;
+        offset += raw.len();
+        let line = raw.trim_end_matches(['\n', '\r']);
+        let t = line.trim();
+        if t.is_empty() {
+            open = false;
+        } else if is_page_marker(t) {
+            markers.push(t.to_string());
+        } else if let Some(paragraph) = paragraphs.last_mut().filter(|_| open) {
+            paragraph.markers_inside.append(&mut markers);
+            paragraph.text.push('\n');
+            paragraph.text.push_str(t);
+            paragraph.end = start + line.len();
+        } else {
+            paragraphs.push(BookParagraph {
+                start,
+                end: start + line.len(),
+                text: t.to_string(),
+                markers_before: std::mem::take(&mut markers),
+                markers_inside: Vec::new(),
+            });
+            open = true;
+        }
+    }
+    paragraphs
+}
+
+/// Replace the last run of whole paragraphs of `book` that reads `original`
+/// with `reworked`, keeping the page markers met inside the run. False if no
+/// run matches.
+fn replace_passage(book: &mut String, original: &str, reworked: &str) -> bool {
+    let wanted: Vec<String> = book_paragraphs(original)
+        .into_iter()
+        .map(|p| p.text)
+        .collect();
+    let paragraphs = book_paragraphs(book);
+    if wanted.is_empty() || wanted.len() > paragraphs.len() {
+        return false;
+    }
+    let Some(run) = paragraphs
+        .windows(wanted.len())
+        .rev()
+        .find(|run| run.iter().map(|p| &p.text).eq(wanted.iter()))
+    else {
+        return false;
+    };
+
+    // Each marker goes before the reworked paragraph at its position
+    let mut markers: Vec<(usize, &str)> = Vec::new();
+    for (k, paragraph) in run.iter().enumerate() {
+        if k > 0 {
+            markers.extend(paragraph.markers_before.iter().map(|m| (k, m.as_str())));
+        }
+        markers.extend(paragraph.markers_inside.iter().map(|m| (k + 1, m.as_str())));
+    }
+    let replacement = if markers.is_empty() {
+        reworked.to_string()
+    } else {
+        let mut chunks: Vec<&str> = Vec::new();
+        let mut pending = markers.iter().peekable();
+        for (j, paragraph) in book_paragraphs(reworked).iter().enumerate() {
+            while let Some((_, marker)) = pending.next_if(|(k, _)| *k <= j) {
+                chunks.push(marker);
+            }
+            chunks.push(&reworked[paragraph.start..paragraph.end]);
+        }
+        chunks.extend(pending.map(|(_, marker)| *marker));
+        chunks.join("\n\n")
+    };
+    book.replace_range(run[0].start..run[run.len() - 1].end, &replacement);
+    true
+}
+
+/// Text between `<!-- INK:ORIGINAL:START -->` and `<!-- INK:ORIGINAL:END -->`,
+/// minus the `> **Original:**` label line. `None` if absent or empty.
+fn extract_original_passage(block: &[&str]) -> Option<String> {
+    let start = block
+        .iter()
+        .position(|l| l.trim() == "<!-- INK:ORIGINAL:START -->")?;
+    let end = block
+        .iter()
+        .position(|l| l.trim() == "<!-- INK:ORIGINAL:END -->")?;
+    if end <= start {
+        return None;
+    }
+    let original = block[start + 1..end]
+        .iter()
+        .filter(|l| !l.trim_start().starts_with("> **Original:**"))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    let original = original.trim();
+    if original.is_empty() {
+        None
+    } else {
+        Some(original.to_string())
+    }
+}
+
+// ─── Marker validation ─────────────────────────────────────────────────────────
+
+/// A structural problem in engine prose, reported by `validate_engine_markers`.
+#[derive(Debug, Serialize)]
+pub struct MarkerIssue {
+    /// 1-based line number in the submitted prose
+    pub line: usize,
+    pub problem: String,
+    /// The offending line (or the first lines of the offending block)
+    pub block: String,
+}
+
+/// Parse `INK:NEW` / `INK:REWORKED` / `INK:ORIGINAL` markers in engine prose and
+/// report every structural problem: unbalanced or mismatched START/END pairs,
+/// illegal nesting (only ORIGINAL may sit inside REWORKED), prose outside any
+/// block, and prose with no engine block at all. Empty result = well-formed.
+pub(crate) fn validate_engine_markers(prose: &str) -> Vec<MarkerIssue> {
+    fn marker(t: &str) -> Option<(&str, bool)> {
+        let inner = t.strip_prefix("<!-- INK:")?.strip_suffix(" -->")?;
+        let (kind, edge) = inner.split_once(':')?;
+        if !matches!(kind, "NEW" | "REWORKED" | "ORIGINAL") {
+            return None;
+        }
+        match edge {
+            "START" => Some((kind, true)),
+            "END" => Some((kind, false)),
+            _ => None,
+        }
+    }
+    fn excerpt(lines: &[&str]) -> String {
+        lines.iter().take(3).copied().collect::<Vec<_>>().join("\n")
+    }
+
+    let lines: Vec<&str> = prose.lines().collect();
+    let mut issues: Vec<MarkerIssue> = Vec::new();
+    // (kind, line index of START)
+    let mut stack: Vec<(&str, usize)> = Vec::new();
+    let mut outside_start: Option<usize> = None;
+    let mut saw_block = false;
+
+    let flush_outside = |from: Option<usize>, to: usize, issues: &mut Vec<MarkerIssue>| {
+        if let Some(start) = from {
+            issues.push(MarkerIssue {
+                line: start + 1,
+                problem: "prose outside any INK:NEW or INK:REWORKED block".to_string(),
+                block: excerpt(&lines[start..to]),
+            });
+        }
+    };
+
+    for (i, raw) in lines.iter().enumerate() {
+        let t = raw.trim();
+        match marker(t) {
+            Some((kind, true)) => {
+                flush_outside(outside_start.take(), i, &mut issues);
+                let allowed = match stack.last() {
+                    None => kind != "ORIGINAL",
+                    Some((parent, _)) => *parent == "REWORKED" && kind == "ORIGINAL",
+                };
+                if !allowed {
+                    issues.push(MarkerIssue {
+                        line: i + 1,
+                        problem: match stack.last() {
+                            None => format!("INK:{kind}:START outside an INK:REWORKED block"),
+                            Some((parent, _)) => {
+                                format!("INK:{kind}:START nested inside INK:{parent}")
+                            }
+                        },
+                        block: t.to_string(),
+                    });
+                }
+                stack.push((kind, i));
+                saw_block = true;
+            }
+            Some((kind, false)) => match stack.last() {
+                Some((open, _)) if *open == kind => {
+                    stack.pop();
+                }
+                Some((open, start)) => {
+                    issues.push(MarkerIssue {
+                        line: i + 1,
+                        problem: format!(
+                            "INK:{kind}:END closes INK:{open}:START opened at line {}",
+                            start + 1
+                        ),
+                        block: t.to_string(),
+                    });
+                    stack.pop();
+                }
+                None => issues.push(MarkerIssue {
+                    line: i + 1,
+                    problem: format!("INK:{kind}:END without a matching START"),
+                    block: t.to_string(),
+                }),
+            },
+            None => {
+                if stack.is_empty() && !t.is_empty() && outside_start.is_none() {
+                    outside_start = Some(i);
+                }
+            }
+        }
+    }
+    flush_outside(outside_start, lines.len(), &mut issues);
+
+    for (kind, start) in stack {
+        issues.push(MarkerIssue {
+            line: start + 1,
+            problem: format!("INK:{kind}:START is never closed"),
+            block: excerpt(&lines[start..]),
+        });
+    }
+
+    if !saw_block && !prose.trim().is_empty() {
+        issues.insert(
+            0,
+            MarkerIssue {
+                line: 1,
+                problem: "no INK:NEW or INK:REWORKED block in prose".to_string(),
+                block: excerpt(&lines),
+            },
+        );
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
 // ─── Pagination ────────────────────────────────────────────────────────────────
 
 /// Insert `<!-- PAGE N -->` markers into `new_content` at paragraph boundaries
@@ -106,7 +451,7 @@ fn insert_pagination(start_word_count: u32, new_content: &str, words_per_page: u
         if para.is_empty() {
             continue;
         }
-        // Count words the same way as count_prose_words — skip HTML comment lines so
+        // Count words the same way as page_words — skip HTML comment lines so
         // that `cumulative` never drifts from the book's true prose word count.
         let para_words = para
             .lines()
@@ -128,38 +473,108 @@ fn insert_pagination(start_word_count: u32, new_content: &str, words_per_page: u
     chunks.join("\n\n")
 }
 
-// ─── Full_Book.md I/O ──────────────────────────────────────────────────────────
+// ─── Scene breaks ──────────────────────────────────────────────────────────────
 
-/// Append `content` to `Full_Book.md` at `book_path` with pagination markers.
-/// Creates the file (with managed header) if it doesn't exist.
-/// Returns `(old_word_count, new_word_count)` — both computed in a single read,
-/// so callers don't need a separate pre-read to calculate words_added.
-pub(crate) fn append_to_full_book(
-    book_path: &Path,
-    content: &str,
-    words_per_page: u32,
-) -> Result<(u32, u32)> {
-    let mut book = if book_path.exists() {
-        std::fs::read_to_string(book_path).with_context(|| "Failed to read Full_Book.md")?
-    } else {
-        format!("{}\n", FULL_BOOK_HEADER)
+/// `***`, `---`, `* * *`, `___`, `~~~`, `⁂` alone on a line.
+pub(crate) fn is_scene_break(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    match marks.as_slice() {
+        ['⁂'] => true,
+        [first, ..] => {
+            marks.len() >= 3 && "*-_~".contains(*first) && marks.iter().all(|c| c == first)
+        }
+        [] => false,
+    }
+}
+
+/// Rewrite every scene break of `prose` — a separator line of any style, or
+/// two or more blank lines in a row — as `separator` with one blank line on
+/// each side. Adjacent breaks collapse into one, and breaks at the very start
+/// or end are dropped (the append spacing takes care of those). An empty
+/// `separator` returns `prose` unchanged.
+pub(crate) fn normalize_scene_breaks(prose: &str, separator: &str) -> String {
+    let separator = separator.trim();
+    if separator.is_empty() {
+        return prose.to_string();
+    }
+    let mut blocks: Vec<String> = Vec::new();
+    let mut para: Vec<&str> = Vec::new();
+    let mut blank_run = 0;
+    let mut pending_break = false;
+    let mut flush = |para: &mut Vec<&str>, pending_break: &mut bool| {
+        if para.is_empty() {
+            return;
+        }
+        if *pending_break && !blocks.is_empty() {
+            blocks.push(separator.to_string());
+        }
+        *pending_break = false;
+        blocks.push(para.join("\n"));
+        para.clear();
     };
+    for line in prose.lines() {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if is_scene_break(line) {
+            flush(&mut para, &mut pending_break);
+            pending_break = true;
+        } else {
+            if blank_run > 0 {
+                flush(&mut para, &mut pending_break);
+                pending_break |= blank_run >= 2;
+            }
+            para.push(line.trim_end());
+        }
+        blank_run = 0;
+    }
+    flush(&mut para, &mut pending_break);
+    blocks.join("\n\n")
+}
 
-    let old_words = count_prose_words(&book);
+// ─── Full_Book.md I/O ──────────────────────────────────────────────────────────
+
+/// Append `content` to the Full_Book.md text in `book`, with page markers
+/// continuing from the book's current word count. Returns the appended block.
+pub(crate) fn append_paginated(book: &mut String, content: &str, words_per_page: u32) -> String {
+    let old_words = page_words(book);
     let paginated = insert_pagination(old_words, content.trim(), words_per_page);
 
-    if !book.ends_with('\n') {
-        book.push('\n');
+    // Exactly one blank line between the book and the new prose
+    book.truncate(book.trim_end().len());
+    if !book.is_empty() {
+        book.push_str("\n\n");
     }
-    book.push('\n');
     book.push_str(&paginated);
     if !book.ends_with('\n') {
         book.push('\n');
     }
+    paginated
+}
 
-    let new_words = count_prose_words(&book);
-    std::fs::write(book_path, &book).with_context(|| "Failed to write Full_Book.md")?;
-    Ok((old_words, new_words))
+/// Renumber every `<!-- PAGE N -->` marker of a Full_Book.md text from
+/// scratch, after prose was cut out of the middle of it. Paragraph spacing is
+/// normalised to one blank line.
+pub(crate) fn repaginate(book: &str, words_per_page: u32) -> String {
+    let (header, body) = match book.trim_start().strip_prefix("<!--") {
+        Some(rest) => match rest.split_once("-->") {
+            Some((comment, body)) => (format!("<!--{}-->", comment), body),
+            None => (String::new(), book),
+        },
+        None => (String::new(), book),
+    };
+    let prose: Vec<&str> = body
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !is_page_marker(p))
+        .collect();
+    let paginated = insert_pagination(page_words(&header), &prose.join("\n\n"), words_per_page);
+    match (header.is_empty(), paginated.is_empty()) {
+        (true, _) => format!("{}\n", paginated),
+        (false, true) => format!("{}\n", header),
+        (false, false) => format!("{}\n\n{}\n", header, paginated),
+    }
 }
 
 // ─── Format check ──────────────────────────────────────────────────────────────
@@ -167,19 +582,16 @@ pub(crate) fn append_to_full_book(
 /// Scan `Full_Book.md` for structural issues without loading full prose into context.
 /// Returns `None` if the book passes all checks, or `Some(needs_formatting JSON)` if issues found.
 pub(crate) fn check_full_book_format(repo: &Path) -> Result<Option<serde_json::Value>> {
-    let book_path = repo.join("Current version").join("Full_Book.md");
-
     // Nothing to check if Full_Book.md doesn't exist yet
-    if !book_path.exists() {
+    let Some(content) = crate::storage::compiled_book(repo)
+        .with_context(|| "Failed to read Full_Book.md for format check")?
+    else {
         return Ok(None);
-    }
+    };
 
     let config = Config::load(repo)?;
     let state = InkState::load(repo).unwrap_or_default();
 
-    let content = std::fs::read_to_string(&book_path)
-        .with_context(|| "Failed to read Full_Book.md for format check")?;
-
     let mut format_issues: Vec<&'static str> = Vec::new();
 
     // ── Check 1: managed header ───────────────────────────────────────────────
@@ -255,7 +667,7 @@ pub(crate) fn check_full_book_format(repo: &Path) -> Result<Option<serde_json::V
         format_issues.push("page_markers_not_sequential");
     }
 
-    let total_word_count = count_prose_words(&content);
+    let total_word_count = crate::wordcount::WordCounter::new(&config).count(&content);
 
     let skeleton = serde_json::json!({
         "has_managed_header": has_managed_header,
@@ -263,6 +675,10 @@ pub(crate) fn check_full_book_format(repo: &Path) -> Result<Option<serde_json::V
         "heading_count": heading_count,
         "chapters_expected": config.chapter_count,
         "current_chapter": state.current_chapter,
+        "sequence": sequence::load_sequence(repo)?
+            .iter()
+            .map(|u| u.label.as_str())
+            .collect::<Vec<_>>(),
         "page_markers": {
             "count": page_count,
             "sequential": pages_sequential,
@@ -297,13 +713,9 @@ pub fn apply_format_patch(repo: &Path, patch: serde_json::Value) -> Result<serde
         ));
     }
 
-    let book_path = repo.join("Current version").join("Full_Book.md");
-    if !book_path.exists() {
-        return Err(anyhow!("Full_Book.md does not exist — nothing to patch"));
-    }
-
-    let mut content = std::fs::read_to_string(&book_path)
-        .with_context(|| "Failed to read Full_Book.md for format patch")?;
+    let mut content = crate::storage::read_book(repo)
+        .with_context(|| "Failed to read Full_Book.md for format patch")?
+        .ok_or_else(|| anyhow!("Full_Book.md does not exist — nothing to patch"))?;
 
     let mut patches_applied: u32 = 0;
     let mut warnings: Vec<String> = Vec::new();
@@ -364,16 +776,17 @@ pub fn apply_format_patch(repo: &Path, patch: serde_json::Value) -> Result<serde
         }
     }
 
-    // Write the modified file
-    std::fs::write(&book_path, &content).with_context(|| "Failed to write patched Full_Book.md")?;
+    // Write the modified file(s)
+    let written = crate::storage::write_book(repo, &content)
+        .with_context(|| "Failed to write patched Full_Book.md")?;
 
     // Commit and push
-    git::run_git(repo, &["add", "Current version/Full_Book.md"])
-        .with_context(|| "Failed to git add Full_Book.md")?;
-    git::run_git(repo, &["commit", "-m", "fmt: apply format corrections"])
+    let mut add = vec!["add", "--"];
+    add.extend(written.iter().map(String::as_str));
+    git::run_git(repo, &add).with_context(|| "Failed to git add Full_Book.md")?;
+    git::engine_commit(repo, &["-m", "fmt: apply format corrections"])
         .with_context(|| "Failed to commit format corrections")?;
-    git::run_git(repo, &["push", "origin", "main"])
-        .with_context(|| "Failed to push format corrections")?;
+    git::push_main(repo).with_context(|| "Failed to push format corrections")?;
 
     Ok(serde_json::json!({
         "status": "applied",
@@ -389,14 +802,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn count_words_ignores_html_comment_lines() {
+    fn page_words_ignores_html_comment_lines() {
         let content = "Hello world\n<!-- PAGE 1 -->\nFoo bar baz";
-        assert_eq!(count_prose_words(content), 5);
+        assert_eq!(page_words(content), 5);
     }
 
     #[test]
-    fn count_words_empty_input() {
-        assert_eq!(count_prose_words(""), 0);
+    fn page_words_empty_input() {
+        assert_eq!(page_words(""), 0);
     }
 
     #[test]
@@ -563,4 +976,172 @@ Old prose here.
         let result = check_full_book_format(tmp.path()).unwrap();
         assert!(result.is_none(), "clean book should return None");
     }
+
+    #[test]
+    fn reworked_block_replaces_original_in_book() {
+        let mut book = "Header\n\nThe rain fell.\n\nShe left.\n".to_string();
+        let validated = "\
+<!-- INK:REWORKED:START -->
+> **[Rework]** *More vivid*
+
+The storm hammered the roofs.
+
+<!-- INK:ORIGINAL:START -->
+> **Original:**
+
+The rain fell.
+
+<!-- INK:ORIGINAL:END -->
+<!-- INK:REWORKED:END -->
+<!-- INK:NEW:START -->
+New prose.
+<!-- INK:NEW:END -->";
+        let (remainder, applied) = apply_reworked_replacements(&mut book, validated);
+        assert_eq!(applied, 1);
+        assert!(book.contains("The storm hammered the roofs."));
+        assert!(!book.contains("The rain fell."));
+        assert!(!remainder.contains("INK:REWORKED"));
+        assert!(remainder.contains("New prose."));
+    }
+
+    #[test]
+    fn reworked_original_matches_whole_paragraphs_only() {
+        let mut book = "Header\n\nThe rain fell.\n\nThe rain fell on the roofs.\n".to_string();
+        let validated = "\
+<!-- INK:REWORKED:START -->
+The storm broke.
+<!-- INK:ORIGINAL:START -->
+The rain fell.
+<!-- INK:ORIGINAL:END -->
+<!-- INK:REWORKED:END -->";
+        let (_, applied) = apply_reworked_replacements(&mut book, validated);
+        assert_eq!(applied, 1);
+        assert_eq!(
+            book,
+            "Header\n\nThe storm broke.\n\nThe rain fell on the roofs.\n"
+        );
+
+        // A substring of a paragraph alone is not a match
+        let mut book = "Header\n\nThe rain fell on the roofs.\n".to_string();
+        let (remainder, applied) = apply_reworked_replacements(&mut book, validated);
+        assert_eq!(applied, 0);
+        assert_eq!(book, "Header\n\nThe rain fell on the roofs.\n");
+        assert!(remainder.contains("The storm broke."));
+    }
+
+    #[test]
+    fn reworked_original_matches_across_page_markers() {
+        let mut book = "\
+Header
+
+First line.
+
+<!-- PAGE 2 -->
+
+Second line,
+<!-- PAGE 3 -->
+still second.
+
+Untouched.
+"
+        .to_string();
+        let validated = "\
+<!-- INK:REWORKED:START -->
+First, reworked.
+
+Second, reworked.
+<!-- INK:ORIGINAL:START -->
+First line.
+
+Second line,
+still second.
+<!-- INK:ORIGINAL:END -->
+<!-- INK:REWORKED:END -->";
+        let (_, applied) = apply_reworked_replacements(&mut book, validated);
+        assert_eq!(applied, 1);
+        assert_eq!(
+            book,
+            "\
+Header
+
+First, reworked.
+
+<!-- PAGE 2 -->
+
+Second, reworked.
+
+<!-- PAGE 3 -->
+
+Untouched.
+"
+        );
+    }
+
+    #[test]
+    fn reworked_block_without_match_is_kept_for_append() {
+        let mut book = "Header\n\nSomething else.\n".to_string();
+        let validated = "\
+<!-- INK:REWORKED:START -->
+Rewritten.
+<!-- INK:ORIGINAL:START -->
+Not in the book.
+<!-- INK:ORIGINAL:END -->
+<!-- INK:REWORKED:END -->";
+        let before = book.clone();
+        let (remainder, applied) = apply_reworked_replacements(&mut book, validated);
+        assert_eq!(applied, 0);
+        assert_eq!(book, before);
+        assert!(remainder.contains("Rewritten."));
+    }
+
+    #[test]
+    fn marker_validation_accepts_well_formed_prose() {
+        let prose = "\
+<!-- INK:REWORKED:START -->
+> **[Rework]** *Fix*
+
+Rewritten.
+
+<!-- INK:ORIGINAL:START -->
+Old.
+<!-- INK:ORIGINAL:END -->
+<!-- INK:REWORKED:END -->
+
+<!-- INK:NEW:START -->
+New prose.
+<!-- INK:NEW:END -->
+";
+        assert!(validate_engine_markers(prose).is_empty());
+    }
+
+    #[test]
+    fn marker_validation_reports_unbalanced_and_stray_prose() {
+        let prose = "Stray line.\n<!-- INK:NEW:START -->\nNew prose.\n";
+        let issues = validate_engine_markers(prose);
+        assert_eq!(issues.len(), 2, "{issues:?}");
+        assert_eq!(issues[0].line, 1);
+        assert!(issues[0].problem.contains("outside"));
+        assert_eq!(issues[1].line, 2);
+        assert!(issues[1].problem.contains("never closed"));
+    }
+
+    #[test]
+    fn scene_breaks_normalised_and_appended_with_one_blank_line() {
+        let prose = "\n---\n\nMara ran.\nShe fell.\n\n* * *\n\n\n***\n\nDawn came.\n\n\n\nThe ship left.\n\n\
+                     Later, quiet.\n\n~~~\n";
+        assert_eq!(
+            normalize_scene_breaks(prose, "⁂"),
+            "Mara ran.\nShe fell.\n\n⁂\n\nDawn came.\n\n⁂\n\nThe ship left.\n\nLater, quiet."
+        );
+        assert_eq!(normalize_scene_breaks(prose, ""), prose);
+        // `-- he said` and a lone dash are prose, not breaks
+        assert_eq!(
+            normalize_scene_breaks("-- he said\n\n-", "***"),
+            "-- he said\n\n-"
+        );
+
+        let mut book = "Opening.\n\n\n".to_string();
+        append_paginated(&mut book, "Next.", 0);
+        assert_eq!(book, "Opening.\n\nNext.\n");
+    }
 }
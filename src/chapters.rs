@@ -0,0 +1,1294 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::info;
+
+use crate::book;
+use crate::config::{BookStorage, Config};
+use crate::git;
+use crate::journal;
+use crate::sequence::{self, Unit};
+use crate::state::InkState;
+use crate::storage;
+use crate::wordcount::WordCounter;
+
+const OUTLINE_DIR: &str = "Chapters material";
+
+/// `# Chapter 5`, `## CHAPTER 5: The Storm` — the number is group 2.
+pub(crate) fn chapter_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?im)^(#{1,3}[ \t]+chapter[ \t]+)(\d+)\b").unwrap())
+}
+
+/// `## Interlude after Chapter 5` — the number is group 2.
+fn interlude_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?im)^(#{1,3}[ \t]+interlude[ \t]+after[ \t]+(?:chapter[ \t]+)?)(\d+)\b")
+            .unwrap()
+    })
+}
+
+fn order_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(<!--\s*INK:ORDER\s+)(\d+(?:\.\d+)?)(\s*-->)").unwrap())
+}
+
+/// Chapters after `after` move by `delta`: +1 opens a gap at `after + 1`,
+/// -1 closes the one left by chapter `after`.
+#[derive(Debug, Clone, Copy)]
+struct Shift {
+    after: u32,
+    delta: i32,
+}
+
+impl Shift {
+    fn chapter(self, n: u32) -> u32 {
+        if n > self.after {
+            n.saturating_add_signed(self.delta)
+        } else {
+            n
+        }
+    }
+
+    fn order(self, order: f64) -> f64 {
+        if order > self.after as f64 {
+            order + self.delta as f64
+        } else {
+            order
+        }
+    }
+
+    /// Renamed unit file (`Chapter_NN.md`, `Interlude_after_NN.md`); other
+    /// units keep their name.
+    fn file(self, file: &str) -> String {
+        if let Some((n, name)) = Unit::interlude_after(file) {
+            return Unit::interlude_after_file(self.chapter(n), name.as_deref());
+        }
+        match Unit::from_file(file, "").and_then(|u| u.number) {
+            Some(n) => Unit::chapter(self.chapter(n)).file,
+            None => file.to_string(),
+        }
+    }
+
+    fn headings(self, text: &str) -> (String, u32) {
+        let mut changed = 0;
+        let mut shift = |caps: &regex::Captures| {
+            let n: u32 = caps[2].parse().unwrap_or(0);
+            let shifted = self.chapter(n);
+            if shifted != n {
+                changed += 1;
+            }
+            format!("{}{}", &caps[1], shifted)
+        };
+        let text = chapter_heading_re().replace_all(text, &mut shift);
+        let text = interlude_heading_re()
+            .replace_all(&text, &mut shift)
+            .into_owned();
+        (text, changed)
+    }
+
+    fn orders(self, text: &str) -> String {
+        order_re()
+            .replace_all(text, |caps: &regex::Captures| {
+                let order: f64 = caps[2].parse().unwrap_or(0.0);
+                format!("{}{}{}", &caps[1], self.order(order), &caps[3])
+            })
+            .into_owned()
+    }
+}
+
+/// What `renumber` changed.
+#[derive(Debug, Default)]
+struct Renumbered {
+    /// `(from, to)` repo-relative outline renames.
+    outlines: Vec<(String, String)>,
+    /// Chapter headings rewritten in the manuscript.
+    headings: u32,
+}
+
+/// Apply `shift` to the outline files (names, `# Chapter N` headings,
+/// `INK:ORDER` placements), the manuscript's chapter headings (and unit files
+/// with per-chapter storage), Titles.yml, `.ink-state.yml`, and `chapter_count`.
+fn renumber(repo: &Path, config: &Config, shift: Shift) -> Result<Renumbered> {
+    let mut done = Renumbered::default();
+
+    // Manuscript first: with per-chapter storage, read_book lists the files
+    // of the state's current chapter, which is about to move
+    if let Some(book) = storage::read_book(repo)? {
+        let (book, headings) = shift.headings(&book);
+        let book = storage::rename_units(&book, |file| shift.file(file));
+        storage::replace_book(repo, &book)?;
+        done.headings = headings;
+    }
+
+    let dir = repo.join(OUTLINE_DIR);
+    let mut outlines: Vec<(String, String, String)> = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| "Failed to read Chapters material/")? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let path = dir.join(&name);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        if Unit::from_file(&name, &content).is_none() {
+            continue;
+        }
+        let (renamed, _) = shift.headings(&shift.orders(&content));
+        let new_name = shift.file(&name);
+        if new_name != name || renamed != content {
+            outlines.push((name, new_name, renamed));
+        }
+    }
+    // Two passes so a rename never lands on a file that has yet to move
+    for (name, new_name, _) in &outlines {
+        if name != new_name {
+            std::fs::remove_file(dir.join(name))
+                .with_context(|| format!("Failed to move {}/{}", OUTLINE_DIR, name))?;
+        }
+    }
+    for (name, new_name, content) in outlines {
+        std::fs::write(dir.join(&new_name), content)
+            .with_context(|| format!("Failed to write {}/{}", OUTLINE_DIR, new_name))?;
+        if name != new_name {
+            done.outlines.push((
+                format!("{}/{}", OUTLINE_DIR, name),
+                format!("{}/{}", OUTLINE_DIR, new_name),
+            ));
+        }
+    }
+    done.outlines.sort();
+
+    crate::titles::renumber(repo, |n| Some(shift.chapter(n)))?;
+
+    let mut state = InkState::load(repo)?;
+    state.current_chapter = shift.chapter(state.current_chapter).max(1);
+    state.save(repo)?;
+
+    let config_rel = "Global Material/Config.yml";
+    let content = std::fs::read_to_string(repo.join(config_rel))
+        .with_context(|| format!("Failed to read {}", config_rel))?;
+    let count = config.chapter_count.saturating_add_signed(shift.delta);
+    std::fs::write(
+        repo.join(config_rel),
+        crate::maintenance::with_config_value(&content, "chapter_count", &count.to_string()),
+    )
+    .with_context(|| format!("Failed to write {}", config_rel))?;
+
+    // README lists chapters up to the current one; only a shift below it shows
+    if shift.after < state.current_chapter {
+        let sequence = sequence::load_sequence(repo)?;
+        let current = sequence::current_unit(&sequence, &state);
+        let listed = sequence::units_through(&sequence, &current, count);
+        crate::maintenance::update_readme_chapters(repo, &listed, true)?;
+    }
+    Ok(done)
+}
+
+/// Refuse to restructure chapters under a running session or a sealed book.
+fn ensure_idle(repo: &Path) -> Result<()> {
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        bail!("a session is in progress — restructure chapters after it closes");
+    }
+    if repo.join("COMPLETE").exists() {
+        bail!("book already complete — chapters cannot be restructured after sealing");
+    }
+    Ok(())
+}
+
+fn commit_restructure(repo: &Path, message: &str) -> Result<()> {
+    git::run_git(repo, &["add", "-A", "--", "."])?;
+    git::engine_commit(repo, &["-m", message])
+        .with_context(|| "Failed to commit the chapter change")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+    Ok(())
+}
+
+// ─── chapter insert ───────────────────────────────────────────────────────────
+
+/// Open a new chapter after chapter `after` (0 = before chapter 1): later
+/// chapters move up by one everywhere (outlines, manuscript headings, state,
+/// `chapter_count`), and an outline stub is written for the new chapter.
+pub fn insert_chapter(repo: &Path, after: u32, title: Option<&str>) -> Result<Value> {
+    ensure_idle(repo)?;
+    let config = Config::load(repo)?;
+    if after > config.chapter_count {
+        bail!(
+            "--after {} is past the last chapter ({})",
+            after,
+            config.chapter_count
+        );
+    }
+    let done = renumber(repo, &config, Shift { after, delta: 1 })?;
+
+    let chapter = after + 1;
+    let heading = match title {
+        Some(title) => format!("Chapter {}: {}", chapter, title.trim()),
+        None => format!("Chapter {}", chapter),
+    };
+    let outline = crate::init::CHAPTER_01_MD
+        .replacen("# Chapter 1", &format!("# {}", heading), 1)
+        .replace("Chapter 1 only", &format!("Chapter {} only", chapter));
+    let outline_rel = Unit::chapter(chapter).path();
+    std::fs::write(repo.join(&outline_rel), outline)
+        .with_context(|| format!("Failed to write {}", outline_rel))?;
+
+    commit_restructure(repo, &format!("chapter: insert chapter {}", chapter))?;
+    info!(
+        "Inserted chapter {} ({} renamed)",
+        chapter,
+        done.outlines.len()
+    );
+    let state = InkState::load(repo)?;
+    Ok(json!({
+        "status": "inserted",
+        "chapter": chapter,
+        "outline": outline_rel,
+        "renamed": done.outlines
+            .iter()
+            .map(|(from, to)| json!({ "from": from, "to": to }))
+            .collect::<Vec<_>>(),
+        "headings_renumbered": done.headings,
+        "current_chapter": state.current_chapter,
+        "chapter_count": config.chapter_count + 1,
+    }))
+}
+
+// ─── outline scaffold ─────────────────────────────────────────────────────────
+
+/// `**Chapters:** 4–9` (hyphen, en or em dash) in an act section.
+fn act_range_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?m)^\*\*Chapters:\*\*[ \t]*(\d+)(?:[ \t]*[-–—][ \t]*(\d+))?").unwrap()
+    })
+}
+
+/// The `## Act …` section of Outline.md that covers chapter `n`: the one
+/// whose `**Chapters:**` range holds it, or — ranges still placeholders — the
+/// act at the same share of the book. Returns `(heading, body)`, the range
+/// line left out.
+pub(crate) fn act_section(outline: &str, n: u32, chapter_count: u32) -> Option<(String, String)> {
+    let mut acts: Vec<(String, String)> = Vec::new();
+    let mut in_act = false;
+    for line in outline.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            in_act = heading.trim_start().to_lowercase().starts_with("act");
+            if in_act {
+                acts.push((heading.trim().to_string(), String::new()));
+            }
+            continue;
+        }
+        if line.trim() == "---" {
+            in_act = false;
+        }
+        if let (true, Some((_, body))) = (in_act, acts.last_mut()) {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    let covers = |body: &str| {
+        act_range_re().captures(body).is_some_and(|caps| {
+            let from: u32 = caps[1].parse().unwrap_or(0);
+            let to: u32 = caps
+                .get(2)
+                .map_or(from, |m| m.as_str().parse().unwrap_or(from));
+            (from..=to).contains(&n)
+        })
+    };
+    let index = match acts.iter().position(|(_, body)| covers(body)) {
+        Some(index) => index,
+        None if acts.is_empty() => return None,
+        None => {
+            let share = (n.max(1) - 1) as usize * acts.len() / chapter_count.max(n) as usize;
+            share.min(acts.len() - 1)
+        }
+    };
+    let (heading, body) = acts.swap_remove(index);
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines().filter(|l| !l.starts_with("**Chapters:**")) {
+        if !(line.trim().is_empty() && lines.last().is_some_and(|l| l.trim().is_empty())) {
+            lines.push(line);
+        }
+    }
+    Some((heading, lines.join("\n").trim().to_string()))
+}
+
+/// Outline for chapter `n` built from the chapter template, pre-filled with
+/// the Outline.md act it falls in, so an unattended run can keep writing and
+/// the author refines it later. Returns the repo-relative file written.
+pub(crate) fn scaffold_outline(repo: &Path, n: u32, chapter_count: u32) -> Result<String> {
+    let rel = Unit::chapter(n).path();
+    let mut outline = crate::init::CHAPTER_01_MD
+        .replacen("# Chapter 1", &format!("# Chapter {}", n), 1)
+        .replace("Chapter 1 only", &format!("Chapter {} only", n));
+    let story =
+        std::fs::read_to_string(repo.join("Global Material/Outline.md")).unwrap_or_default();
+    let note = match act_section(&story, n, chapter_count) {
+        Some((heading, body)) => format!(
+            "*Scaffolded by `advance-chapter` from the Outline.md act below — refine the goal \
+             and beats.*\n\n## From the Outline — {}\n\n{}\n\n",
+            heading, body
+        ),
+        None => "*Scaffolded by `advance-chapter` — Outline.md has no act section for this \
+                 chapter. Refine the goal and beats.*\n\n"
+            .to_string(),
+    };
+    if let Some(at) = outline.find("## Goal") {
+        outline.insert_str(at, &note);
+    }
+    std::fs::write(repo.join(&rel), outline).with_context(|| format!("Failed to write {}", rel))?;
+    info!("Scaffolded {}", rel);
+    Ok(rel)
+}
+
+// ─── chapter delete ───────────────────────────────────────────────────────────
+
+const ARCHIVE_DIR: &str = "Archive";
+
+/// Byte range of chapter `n` in a `read_book` text: its unit file with
+/// per-chapter storage, otherwise from its `# Chapter n` heading to the next
+/// chapter-level heading. The first line of the range is the marker or the
+/// heading. None if the chapter has no prose in the book.
+fn chapter_span(book: &str, n: u32, storage: BookStorage) -> Option<Range<usize>> {
+    if storage == BookStorage::Chapters {
+        return storage::unit_span(book, &Unit::chapter(n).file);
+    }
+    let mut span: Option<Range<usize>> = None;
+    let mut offset = 0;
+    for line in book.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if crate::import::unit_heading_re().is_match(trimmed) {
+            if span.is_some() {
+                break;
+            }
+            let this = chapter_heading_re()
+                .captures(trimmed)
+                .is_some_and(|caps| caps[2].parse() == Ok(n));
+            if this {
+                span = Some(offset..book.len());
+            }
+        }
+        offset += line.len();
+        if let Some(span) = &mut span {
+            span.end = offset;
+        }
+    }
+    span
+}
+
+/// The prose of a `chapter_span`, without its marker or heading line.
+/// Prose of chapter `n` in a `read_book` text, heading or marker line left out.
+pub(crate) fn chapter_prose(book: &str, n: u32, storage: BookStorage) -> Option<String> {
+    chapter_span(book, n, storage).map(|span| span_prose(book, &span))
+}
+
+fn span_prose(book: &str, span: &Range<usize>) -> String {
+    let text = &book[span.clone()];
+    text.split_once('\n')
+        .map_or("", |(_, prose)| prose)
+        .trim()
+        .to_string()
+}
+
+/// Cut chapter `n` out of a `read_book` text. Returns the remaining text and
+/// the removed section, heading included (None if the chapter has no prose).
+fn excise_chapter(book: &str, n: u32, storage: BookStorage) -> (String, Option<String>) {
+    match chapter_span(book, n, storage) {
+        Some(span) => (
+            format!("{}{}", &book[..span.start], &book[span.end..]),
+            Some(book[span].trim().to_string()),
+        ),
+        None => (book.to_string(), None),
+    }
+}
+
+/// Words written in chapter `n`, headings left out: the session counter's
+/// measure.
+fn chapter_words(book: &str, n: u32, config: &Config) -> u32 {
+    chapter_span(book, n, config.book_storage).map_or(0, |span| {
+        WordCounter::new(config).count(&span_prose(book, &span))
+    })
+}
+
+/// Remove chapter `n`: its outline goes, its prose is cut from the manuscript,
+/// later chapters move down by one, and pages and word counts are recomputed.
+/// Both the outline and the prose are kept in `Archive/` first.
+pub fn delete_chapter(repo: &Path, n: u32) -> Result<Value> {
+    ensure_idle(repo)?;
+    let config = Config::load(repo)?;
+    if n == 0 || n > config.chapter_count {
+        bail!(
+            "chapter {} does not exist (chapter_count is {})",
+            n,
+            config.chapter_count
+        );
+    }
+    if config.chapter_count < 2 {
+        bail!("the book has a single chapter — it cannot be deleted");
+    }
+    let mut state = InkState::load(repo)?;
+    let book_text = storage::read_book(repo)?.unwrap_or_default();
+    let (rest, removed) = excise_chapter(&book_text, n, config.book_storage);
+    if removed.is_none() && n < state.current_chapter && config.book_storage == BookStorage::Single
+    {
+        bail!(
+            "no '# Chapter {}' heading in Full_Book.md to bound its prose — \
+             run `ink-cli apply-format` or add the heading first",
+            n
+        );
+    }
+    let removed = removed.unwrap_or_default();
+    let words_removed = WordCounter::new(&config).count(&removed);
+
+    let outline_rel = Unit::chapter(n).path();
+    let outline = std::fs::read_to_string(repo.join(&outline_rel)).unwrap_or_default();
+    let archive_rel = format!(
+        "{}/deleted-chapter-{:02}-{}.md",
+        ARCHIVE_DIR,
+        n,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    std::fs::create_dir_all(repo.join(ARCHIVE_DIR))
+        .with_context(|| format!("Failed to create {}/", ARCHIVE_DIR))?;
+    std::fs::write(
+        repo.join(&archive_rel),
+        format!(
+            "# Deleted chapter {}\n\n## Outline\n\n{}\n\n## Prose ({} words)\n\n{}\n",
+            n,
+            outline.trim(),
+            words_removed,
+            book::strip_html_comments(&removed).trim()
+        ),
+    )
+    .with_context(|| format!("Failed to write {}", archive_rel))?;
+
+    // Writing resumes in the chapter that takes the deleted one's place (the
+    // previous one when the last is deleted); parked on its old number so the
+    // shift below lands it there
+    let was_current = state.current_chapter == n;
+    if was_current {
+        state.current_chapter = if n < config.chapter_count {
+            n + 1
+        } else {
+            n - 1
+        };
+        state.current_unit = None;
+        state.save(repo)?;
+    }
+    storage::replace_book(repo, &rest)?;
+    if repo.join(&outline_rel).exists() {
+        std::fs::remove_file(repo.join(&outline_rel))
+            .with_context(|| format!("Failed to remove {}", outline_rel))?;
+    }
+    crate::titles::renumber(repo, |k| (k != n).then_some(k))?;
+    let done = renumber(
+        repo,
+        &config,
+        Shift {
+            after: n,
+            delta: -1,
+        },
+    )?;
+
+    let book_text = storage::read_book(repo)?.unwrap_or_default();
+    let book_text = book::repaginate(&book_text, config.page_size());
+    storage::write_book(repo, &book_text)?;
+    let mut state = InkState::load(repo)?;
+    if was_current {
+        state.current_chapter_word_count =
+            chapter_words(&book_text, state.current_chapter, &config);
+        state.save(repo)?;
+    }
+
+    commit_restructure(repo, &format!("chapter: delete chapter {}", n))?;
+    info!("Deleted chapter {} ({} words archived)", n, words_removed);
+    let total =
+        storage::compiled_book(repo)?.map_or(0, |text| WordCounter::new(&config).count(&text));
+    Ok(json!({
+        "status": "deleted",
+        "chapter": n,
+        "words_removed": words_removed,
+        "archive": archive_rel,
+        "renamed": done.outlines
+            .iter()
+            .map(|(from, to)| json!({ "from": from, "to": to }))
+            .collect::<Vec<_>>(),
+        "headings_renumbered": done.headings,
+        "total_word_count": total,
+        "current_chapter": state.current_chapter,
+        "current_chapter_word_count": state.current_chapter_word_count,
+        "chapter_count": config.chapter_count - 1,
+    }))
+}
+
+// ─── chapter merge ────────────────────────────────────────────────────────────
+
+/// Fold chapter `b` into chapter `a = b - 1`: b's outline is appended to a's
+/// (its heading demoted), b's prose follows a's without its heading, later
+/// chapters move down by one, and `words_per_chapter` is re-spread over the
+/// new `chapter_count` so the book's length budget holds.
+pub fn merge_chapters(repo: &Path, a: u32, b: u32) -> Result<Value> {
+    ensure_idle(repo)?;
+    let config = Config::load(repo)?;
+    if a == 0 || b != a + 1 {
+        bail!(
+            "chapters {} and {} are not adjacent — merge N and N+1",
+            a,
+            b
+        );
+    }
+    if b > config.chapter_count {
+        bail!(
+            "chapter {} does not exist (chapter_count is {})",
+            b,
+            config.chapter_count
+        );
+    }
+    let sequence = sequence::load_sequence(repo)?;
+    if let Some(between) = sequence
+        .iter()
+        .find(|u| u.number.is_none() && u.order > a as f64 && u.order < b as f64)
+    {
+        bail!(
+            "{} sits between chapters {} and {} — move or delete it first",
+            between.label,
+            a,
+            b
+        );
+    }
+    let mut state = InkState::load(repo)?;
+    let storage = config.book_storage;
+    let book_text = storage::read_book(repo)?.unwrap_or_default();
+    let span_a = chapter_span(&book_text, a, storage);
+    let span_b = chapter_span(&book_text, b, storage);
+    let written = |n: u32| n < state.current_chapter || (n == a && span_b.is_some());
+    for (n, span) in [(a, &span_a), (b, &span_b)] {
+        if span.is_none() && written(n) && storage == BookStorage::Single {
+            bail!(
+                "no '# Chapter {}' heading in Full_Book.md to bound its prose — \
+                 run `ink-cli apply-format` or add the heading first",
+                n
+            );
+        }
+    }
+    let mut words_moved = 0;
+    if let Some(span_b) = span_b {
+        let prose = span_prose(&book_text, &span_b);
+        words_moved = WordCounter::new(&config).count(&prose);
+        let merged = match span_a {
+            Some(span_a) if span_a.end <= span_b.start => {
+                let mut merged = format!("{}\n\n", book_text[..span_a.end].trim_end());
+                if !prose.is_empty() {
+                    merged.push_str(&prose);
+                    merged.push_str("\n\n");
+                }
+                merged.push_str(&book_text[span_a.end..span_b.start]);
+                merged.push_str(&book_text[span_b.end..]);
+                merged
+            }
+            Some(_) => bail!("chapter {} comes after chapter {} in the manuscript", a, b),
+            // Per-chapter storage without a file for a yet: b's file becomes a's
+            None => {
+                let (file_a, file_b) = (Unit::chapter(a).file, Unit::chapter(b).file);
+                storage::rename_units(&book_text, |file| match file == file_b {
+                    true => file_a.clone(),
+                    false => file.to_string(),
+                })
+            }
+        };
+        if state.current_chapter == b {
+            // Parked on the chapter whose file stays, so read_book's current
+            // unit never revives b's file mid-way
+            state.current_chapter = a;
+            state.current_unit = None;
+            state.save(repo)?;
+        }
+        storage::replace_book(repo, &merged)?;
+    }
+
+    let outline_a_rel = Unit::chapter(a).path();
+    let outline_b_rel = Unit::chapter(b).path();
+    let outline_a = std::fs::read_to_string(repo.join(&outline_a_rel)).unwrap_or_default();
+    let outline_b = std::fs::read_to_string(repo.join(&outline_b_rel)).unwrap_or_default();
+    let outline = match outline_b.trim() {
+        "" => outline_a,
+        b_text => {
+            // b's headings move one level down under a's; its title line
+            // records where the section came from (and no longer reads as a
+            // chapter heading to renumber)
+            let demoted: Vec<String> = b_text
+                .lines()
+                .map(|line| match line.strip_prefix("# ") {
+                    Some(title) => format!("## Merged from {}", title.trim()),
+                    None if line.starts_with('#') => format!("#{}", line),
+                    None => line.to_string(),
+                })
+                .collect();
+            format!(
+                "{}\n\n---\n\n{}\n",
+                outline_a.trim_end(),
+                demoted.join("\n")
+            )
+        }
+    };
+    std::fs::write(repo.join(&outline_a_rel), outline)
+        .with_context(|| format!("Failed to write {}", outline_a_rel))?;
+    if repo.join(&outline_b_rel).exists() {
+        std::fs::remove_file(repo.join(&outline_b_rel))
+            .with_context(|| format!("Failed to remove {}", outline_b_rel))?;
+    }
+
+    let was_current = state.current_chapter == a || state.current_chapter == b;
+    // a keeps its own title
+    crate::titles::renumber(repo, |k| (k != b).then_some(k))?;
+    let done = renumber(
+        repo,
+        &config,
+        Shift {
+            after: a,
+            delta: -1,
+        },
+    )?;
+
+    let chapter_count = config.chapter_count - 1;
+    let words_per_chapter = config.target_length.div_ceil(chapter_count.max(1));
+    let config_rel = "Global Material/Config.yml";
+    let content = std::fs::read_to_string(repo.join(config_rel))
+        .with_context(|| format!("Failed to read {}", config_rel))?;
+    std::fs::write(
+        repo.join(config_rel),
+        crate::maintenance::with_config_value(
+            &content,
+            "words_per_chapter",
+            &words_per_chapter.to_string(),
+        ),
+    )
+    .with_context(|| format!("Failed to write {}", config_rel))?;
+
+    let book_text = storage::read_book(repo)?.unwrap_or_default();
+    let book_text = book::repaginate(&book_text, config.page_size());
+    storage::write_book(repo, &book_text)?;
+    let mut state = InkState::load(repo)?;
+    if was_current {
+        state.current_chapter_word_count = chapter_words(&book_text, a, &config);
+        state.save(repo)?;
+    }
+
+    commit_restructure(repo, &format!("chapter: merge chapter {} into {}", b, a))?;
+    info!(
+        "Merged chapter {} into {} ({} words moved)",
+        b, a, words_moved
+    );
+    Ok(json!({
+        "status": "merged",
+        "chapter": a,
+        "merged": b,
+        "words_moved": words_moved,
+        "chapter_words": chapter_words(&book_text, a, &config),
+        "renamed": done.outlines
+            .iter()
+            .map(|(from, to)| json!({ "from": from, "to": to }))
+            .collect::<Vec<_>>(),
+        "headings_renumbered": done.headings,
+        "current_chapter": state.current_chapter,
+        "current_chapter_word_count": state.current_chapter_word_count,
+        "chapter_count": chapter_count,
+        "words_per_chapter": words_per_chapter,
+    }))
+}
+
+// ─── outline-status ───────────────────────────────────────────────────────────
+
+/// Outlines the engine should have ahead of the current chapter before
+/// `outline-status` stops reporting `ok`.
+const OUTLINE_RUNWAY: u32 = 2;
+
+/// How far an outline file has been planned: `outlined` (author text beyond
+/// the template), `scaffolded` (an `advance-chapter` stub from Outline.md), or
+/// `stub` (template placeholders only).
+fn outline_kind(content: &str) -> &'static str {
+    let body = crate::front_matter::split(content).map_or(content, |(_, body)| body);
+    let body = book::strip_html_comments(body);
+    let mut planned = false;
+    let mut from_outline = false;
+    for line in body.lines().map(str::trim) {
+        if line.starts_with('#') {
+            // Copied from Outline.md by scaffold_outline, not planned here
+            from_outline = line.starts_with("## From the Outline");
+            continue;
+        }
+        let item = line
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches(['.', '-', '*', ' ']);
+        let note = line.starts_with('*') && line.ends_with('*');
+        let placeholder = item.starts_with('[') && item.ends_with(']');
+        if !from_outline && !note && !placeholder && item.chars().any(char::is_alphanumeric) {
+            planned = true;
+            break;
+        }
+    }
+    match planned {
+        true => "outlined",
+        // scaffold_outline's note
+        false if body.contains("*Scaffolded by `advance-chapter`") => "scaffolded",
+        false => "stub",
+    }
+}
+
+/// Outline coverage of chapters 1..=`chapter_count`: which have a planned
+/// outline, a stub, or nothing, and how many planned chapters lie ahead of
+/// the current one. `outrun` when the current chapter is ready to close and
+/// the next one has no planned outline; `low_runway` when fewer than
+/// `OUTLINE_RUNWAY` follow it.
+pub fn outline_status(repo: &Path) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let state = InkState::load(repo)?;
+    let book_sequence = sequence::load_sequence(repo)?;
+    let unit = sequence::current_unit(&book_sequence, &state);
+    let current = state.current_chapter;
+
+    let mut chapters = Vec::new();
+    let mut counts = std::collections::BTreeMap::<&str, u32>::new();
+    let mut kinds = std::collections::BTreeMap::new();
+    for n in 1..=config.chapter_count {
+        let rel = Unit::chapter(n).path();
+        let kind = match std::fs::read_to_string(repo.join(&rel)) {
+            Ok(content) => outline_kind(&content),
+            Err(_) => "missing",
+        };
+        *counts.entry(kind).or_default() += 1;
+        kinds.insert(n, kind);
+        chapters.push(json!({ "chapter": n, "file": rel, "outline": kind }));
+    }
+    let beyond: Vec<String> = book_sequence
+        .iter()
+        .filter(|u| u.number.is_some_and(|n| n > config.chapter_count))
+        .map(|u| u.path())
+        .collect();
+
+    let runway = (current + 1..=config.chapter_count)
+        .take_while(|n| kinds.get(n) == Some(&"outlined"))
+        .count() as u32;
+    let remaining = config.chapter_count.saturating_sub(current);
+    let close_suggested =
+        state.current_chapter_word_count >= (unit.target_words(&config) as f64 * 0.9) as u32;
+    let (status, message) = if remaining > 0 && runway == 0 && close_suggested {
+        (
+            "outrun",
+            format!(
+                "Chapter {} is ready to close but chapter {} has no planned outline",
+                current,
+                current + 1
+            ),
+        )
+    } else if runway < OUTLINE_RUNWAY.min(remaining) {
+        (
+            "low_runway",
+            format!(
+                "{} planned outline(s) ahead of chapter {} — plan chapter {} next",
+                runway,
+                current,
+                current + runway + 1
+            ),
+        )
+    } else {
+        (
+            "ok",
+            format!("{} planned outline(s) ahead of chapter {}", runway, current),
+        )
+    };
+
+    Ok(json!({
+        "status": status,
+        "message": message,
+        "chapter_count": config.chapter_count,
+        "current_chapter": current,
+        "runway": runway,
+        "outlined": counts.get("outlined").copied().unwrap_or(0),
+        "scaffolded": counts.get("scaffolded").copied().unwrap_or(0),
+        "stub": counts.get("stub").copied().unwrap_or(0),
+        "missing": counts.get("missing").copied().unwrap_or(0),
+        "chapters": chapters,
+        "beyond_chapter_count": beyond,
+    }))
+}
+
+// ─── reoutline ────────────────────────────────────────────────────────────────
+
+const STORY_OUTLINE: &str = "Global Material/Outline.md";
+
+/// Payload for an agent re-planning the rest of the book: Outline.md, the
+/// Summary.md story so far, the outlines of written chapters, and one slot per
+/// chapter after the current one with its outline as it stands. The agent
+/// answers with `reoutline --apply`.
+pub fn reoutline_payload(repo: &Path) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let state = InkState::load(repo)?;
+    let titles = crate::titles::load(repo)?;
+    let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).ok();
+    let summary = crate::context::truncate_summary(
+        &read("Global Material/Summary.md").unwrap_or_default(),
+        usize::MAX,
+    );
+    let summaries: Vec<&str> = summary.split("\n\n").filter(|p| !p.is_empty()).collect();
+
+    let slot = |n: u32| {
+        let rel = Unit::chapter(n).path();
+        let content = read(&rel);
+        json!({
+            "chapter": n,
+            "file": rel,
+            "title": titles.get(&n),
+            "part": config.part_of(n).map(|p| p.title),
+            "outline": content.as_deref().map_or("missing", outline_kind),
+            "content": content,
+        })
+    };
+    let current = state.current_chapter.min(config.chapter_count);
+    let written: Vec<Value> = (1..current).map(slot).collect();
+    let remaining: Vec<Value> = (current + 1..=config.chapter_count).map(slot).collect();
+
+    Ok(json!({
+        "status": "reoutline",
+        "chapter_count": config.chapter_count,
+        "current_chapter": current,
+        "story_outline": read(STORY_OUTLINE).unwrap_or_default(),
+        "summaries": summaries,
+        "written": written,
+        "current": slot(current),
+        "remaining": remaining,
+        "template": crate::init::CHAPTER_01_MD,
+        "instructions": "Write a detailed outline (Goal, Scene Beats, Notes, as in `template`) for \
+            each `remaining` chapter that continues the story the summaries and written chapters \
+            tell and lands the arc of `story_outline`. Keep what the author planned in outlines \
+            marked `outlined` unless the story has moved past it. Reply on the stdin of \
+            `ink-cli reoutline <repo> --apply` with {\"outlines\": [{\"chapter\": N, \
+            \"content\": \"# Chapter N ...\"}]}.",
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReoutlineReply {
+    outlines: Vec<ReoutlineEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReoutlineEntry {
+    chapter: u32,
+    content: String,
+}
+
+/// Write the outlines of a `reoutline` reply (`{"outlines": [{"chapter",
+/// "content"}]}`) into `Chapters material/`. Only chapters after the current
+/// one are accepted; the whole reply is checked before any file is written.
+/// Engine commit, best-effort push.
+pub fn apply_reoutline(repo: &Path, reply: &str) -> Result<Value> {
+    ensure_idle(repo)?;
+    let config = Config::load(repo)?;
+    let state = InkState::load(repo)?;
+    let reply: ReoutlineReply = serde_json::from_str(reply)
+        .with_context(|| "Failed to parse reoutline JSON — expected {\"outlines\": [...]}")?;
+    if reply.outlines.is_empty() {
+        bail!("reoutline reply has no outlines");
+    }
+    let mut seen = std::collections::BTreeSet::new();
+    for entry in &reply.outlines {
+        let n = entry.chapter;
+        if n <= state.current_chapter || n > config.chapter_count {
+            bail!(
+                "chapter {} is outside the remaining chapters ({}–{})",
+                n,
+                state.current_chapter + 1,
+                config.chapter_count
+            );
+        }
+        if !seen.insert(n) {
+            bail!("chapter {} appears twice in the reply", n);
+        }
+        if entry.content.trim().is_empty() {
+            bail!("outline for chapter {} is empty", n);
+        }
+    }
+
+    let mut written = Vec::new();
+    let mut replaced = Vec::new();
+    for entry in &reply.outlines {
+        let rel = Unit::chapter(entry.chapter).path();
+        let path = repo.join(&rel);
+        if std::fs::read_to_string(&path).is_ok_and(|c| outline_kind(&c) == "outlined") {
+            replaced.push(entry.chapter);
+        }
+        let mut content = entry.content.trim().to_string();
+        if !chapter_heading_re().is_match(&content) {
+            content = format!("# Chapter {}\n\n{}", entry.chapter, content);
+        }
+        content.push('\n');
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", rel))?;
+        written.push(rel);
+    }
+    commit_restructure(
+        repo,
+        &format!("chapter: reoutline {} chapter(s)", written.len()),
+    )?;
+    info!("Reoutlined {} chapter(s)", written.len());
+
+    Ok(json!({
+        "status": "reoutlined",
+        "written": written,
+        "replaced": replaced,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold_takes_the_act_covering_the_chapter() {
+        let outline = "# Outline\n\n---\n\n## Premise\n\nA heist.\n\n---\n\n\
+                       ## Act 1 — Setup\n\nThe crew forms.\n\n**Chapters:** 1–4\n\n\
+                       ## Act 2 — Confrontation\n\nThe vault fights back.\n\n**Chapters:** 5-9\n\n\
+                       ### Midpoint\n\nBetrayal.\n\n## Act 3 — Resolution\n\nGetaway.\n\n\
+                       **Chapters:** [n]–[n]\n\n---\n\n## Chapter Breakdown\n\nLater.\n";
+        let (heading, body) = act_section(outline, 6, 12).unwrap();
+        assert_eq!(heading, "Act 2 — Confrontation");
+        assert_eq!(body, "The vault fights back.\n\n### Midpoint\n\nBetrayal.");
+        // Past every filled range: the act at the same share of the book
+        assert_eq!(act_section(outline, 11, 12).unwrap().1, "Getaway.");
+        let template = crate::init::OUTLINE_MD;
+        assert_eq!(act_section(template, 1, 30).unwrap().0, "Act 1 — Setup");
+        assert!(act_section("# Outline\n", 3, 10).is_none());
+    }
+
+    #[test]
+    fn insert_renumbers_outlines_book_and_state() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in ["Global Material", "Chapters material", "Current version"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 3\nchapter_structure: linear\n\
+             words_per_session: 500\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join(".ink-state.yml"), "current_chapter: 3\n").unwrap();
+        for n in 1..=3 {
+            std::fs::write(
+                repo.join(format!("Chapters material/Chapter_0{}.md", n)),
+                format!("# Chapter {}: Part {}\n", n, n),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            repo.join("Chapters material/Interlude_Storm.md"),
+            "# Storm\n<!-- INK:ORDER 2.5 -->\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("Chapters material/Interlude_after_02.md"),
+            "# Interlude after Chapter 2\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join(storage::FULL_BOOK),
+            "## Chapter 1\n\none\n\n## Chapter 2\n\ntwo\n\n## CHAPTER 3: Part 3\n\nthree\n",
+        )
+        .unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "book"]).unwrap();
+
+        let result = insert_chapter(repo, 1, Some("The Gap")).unwrap();
+        assert_eq!(result["headings_renumbered"], 2);
+        assert_eq!(result["current_chapter"], 4);
+        assert_eq!(
+            result["renamed"][0],
+            json!({ "from": "Chapters material/Chapter_02.md", "to": "Chapters material/Chapter_03.md" })
+        );
+        let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).unwrap();
+        assert!(read("Chapters material/Chapter_02.md").starts_with("# Chapter 2: The Gap\n"));
+        assert_eq!(
+            read("Chapters material/Chapter_04.md"),
+            "# Chapter 4: Part 3\n"
+        );
+        assert!(read("Chapters material/Interlude_Storm.md").contains("INK:ORDER 3.5"));
+        assert_eq!(
+            read("Chapters material/Interlude_after_03.md"),
+            "# Interlude after Chapter 3\n"
+        );
+        assert_eq!(
+            read(storage::FULL_BOOK),
+            "## Chapter 1\n\none\n\n## Chapter 3\n\ntwo\n\n## CHAPTER 4: Part 3\n\nthree\n"
+        );
+        assert!(read("Global Material/Config.yml").contains("chapter_count: 4"));
+        assert_eq!(InkState::load(repo).unwrap().current_chapter, 4);
+        assert!(
+            insert_chapter(repo, 5, None).is_err(),
+            "past the last chapter"
+        );
+    }
+
+    #[test]
+    fn delete_archives_prose_and_closes_the_gap() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in ["Global Material", "Chapters material", "Current version"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 3\nchapter_structure: linear\n\
+             words_per_session: 500\nwords_per_page: 4\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join(".ink-state.yml"),
+            "current_chapter: 2\ncurrent_chapter_word_count: 3\n",
+        )
+        .unwrap();
+        for n in 1..=3 {
+            std::fs::write(
+                repo.join(format!("Chapters material/Chapter_0{}.md", n)),
+                format!("# Chapter {}: Part {}\n", n, n),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            repo.join(storage::FULL_BOOK),
+            "## Chapter 1\n\n<!-- PAGE 2 -->\n\none\n\n## Chapter 2\n\n<!-- PAGE 3 -->\n\n\
+             two too\n\n<!-- PAGE 4 -->\n\n## Chapter 3\n\n<!-- PAGE 5 -->\n\nthree\n",
+        )
+        .unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "book"]).unwrap();
+
+        let result = delete_chapter(repo, 2).unwrap();
+        assert_eq!(result["words_removed"], 5);
+        assert_eq!(result["total_word_count"], 8);
+        assert_eq!(result["current_chapter"], 2);
+        assert_eq!(result["current_chapter_word_count"], 1);
+        let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).unwrap();
+        assert_eq!(
+            read(storage::FULL_BOOK),
+            "## Chapter 1\n\n<!-- PAGE 2 -->\n\none\n\n## Chapter 2\n\n<!-- PAGE 3 -->\n\nthree\n"
+        );
+        assert_eq!(
+            read("Chapters material/Chapter_02.md"),
+            "# Chapter 2: Part 3\n"
+        );
+        assert!(!repo.join("Chapters material/Chapter_03.md").exists());
+        let archive = read(result["archive"].as_str().unwrap());
+        assert!(archive.contains("# Chapter 2: Part 2") && archive.contains("two too"));
+        assert!(read("Global Material/Config.yml").contains("chapter_count: 2"));
+        assert!(delete_chapter(repo, 3).is_err(), "past the last chapter");
+    }
+
+    #[test]
+    fn merge_folds_the_next_chapter_in() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in ["Global Material", "Chapters material", "Current version"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 3\nchapter_structure: linear\n\
+             words_per_session: 500\nwords_per_chapter: 20000\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join(".ink-state.yml"),
+            "current_chapter: 2\ncurrent_chapter_word_count: 2\n",
+        )
+        .unwrap();
+        for n in 1..=3 {
+            std::fs::write(
+                repo.join(format!("Chapters material/Chapter_0{}.md", n)),
+                format!("# Chapter {}: Part {}\n\n## Goal\n\nGoal {}\n", n, n, n),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            repo.join(storage::FULL_BOOK),
+            "## Chapter 1\n\none\n\n## Chapter 2\n\ntwo too\n",
+        )
+        .unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "book"]).unwrap();
+
+        assert!(merge_chapters(repo, 1, 3).is_err(), "not adjacent");
+        let result = merge_chapters(repo, 1, 2).unwrap();
+        assert_eq!(result["words_moved"], 2);
+        assert_eq!(result["current_chapter"], 1);
+        assert_eq!(result["current_chapter_word_count"], 3);
+        assert_eq!(result["words_per_chapter"], 30000);
+        let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).unwrap();
+        assert_eq!(read(storage::FULL_BOOK), "## Chapter 1\n\none\n\ntwo too\n");
+        let outline = read("Chapters material/Chapter_01.md");
+        assert!(outline.starts_with("# Chapter 1: Part 1\n"));
+        assert!(outline.contains("## Merged from Chapter 2: Part 2\n\n### Goal\n\nGoal 2"));
+        assert_eq!(
+            read("Chapters material/Chapter_02.md"),
+            "# Chapter 2: Part 3\n\n## Goal\n\nGoal 3\n"
+        );
+        assert!(!repo.join("Chapters material/Chapter_03.md").exists());
+        let config = read("Global Material/Config.yml");
+        assert!(config.contains("chapter_count: 2") && config.contains("words_per_chapter: 30000"));
+    }
+
+    #[test]
+    fn outline_status_classifies_outlines_and_flags_outrun() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in ["Global Material", "Chapters material"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 5\nchapter_structure: linear\n\
+             words_per_session: 500\nwords_per_chapter: 1000\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join(".ink-state.yml"),
+            "current_chapter: 1\ncurrent_chapter_word_count: 950\n",
+        )
+        .unwrap();
+        let planned = crate::init::CHAPTER_01_MD.replace(
+            "1. [Opening beat — where are we, who are we with, what is the immediate situation?]",
+            "1. Mara finds the letter.",
+        );
+        std::fs::write(repo.join("Chapters material/Chapter_01.md"), &planned).unwrap();
+        std::fs::write(
+            repo.join("Chapters material/Chapter_02.md"),
+            crate::init::CHAPTER_01_MD,
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("Global Material/Outline.md"),
+            "## Act 1 — Setup\n\nThe crew forms.\n\n**Chapters:** 1-5\n",
+        )
+        .unwrap();
+        scaffold_outline(repo, 3, 5).unwrap();
+        std::fs::write(repo.join("Chapters material/Chapter_04.md"), &planned).unwrap();
+        std::fs::write(repo.join("Chapters material/Chapter_07.md"), &planned).unwrap();
+
+        let report = outline_status(repo).unwrap();
+        let kinds: Vec<&str> = report["chapters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["outline"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            ["outlined", "stub", "scaffolded", "outlined", "missing"]
+        );
+        assert_eq!(report["status"], "outrun");
+        assert_eq!(report["runway"], 0);
+        assert_eq!(
+            report["beyond_chapter_count"][0],
+            "Chapters material/Chapter_07.md"
+        );
+
+        std::fs::write(repo.join("Chapters material/Chapter_02.md"), &planned).unwrap();
+        std::fs::write(repo.join("Chapters material/Chapter_03.md"), &planned).unwrap();
+        let report = outline_status(repo).unwrap();
+        assert_eq!(
+            (report["status"].as_str(), report["runway"].as_u64()),
+            (Some("ok"), Some(3))
+        );
+    }
+
+    #[test]
+    fn reoutline_lists_remaining_slots_and_applies_reply() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in ["Global Material", "Chapters material"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 4\nchapter_structure: linear\n\
+             words_per_session: 500\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join(".ink-state.yml"), "current_chapter: 2\n").unwrap();
+        std::fs::write(
+            repo.join("Global Material/Summary.md"),
+            "Mara leaves the harbour town with the stolen ledger hidden in her coat.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("Chapters material/Chapter_04.md"),
+            crate::init::CHAPTER_01_MD,
+        )
+        .unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-qm", "init"]).unwrap();
+
+        let payload = reoutline_payload(repo).unwrap();
+        assert_eq!(payload["written"].as_array().unwrap().len(), 1);
+        assert_eq!(payload["current"]["chapter"], 2);
+        let slots: Vec<(u64, &str)> = payload["remaining"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| {
+                (
+                    s["chapter"].as_u64().unwrap(),
+                    s["outline"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(slots, [(3, "missing"), (4, "stub")]);
+        assert_eq!(payload["summaries"].as_array().unwrap().len(), 1);
+
+        // Written and current chapters are refused, and nothing is written
+        let err = apply_reoutline(
+            repo,
+            r#"{"outlines": [{"chapter": 3, "content": "x"}, {"chapter": 2, "content": "x"}]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("outside the remaining chapters"));
+        assert!(!repo.join("Chapters material/Chapter_03.md").exists());
+
+        let result = apply_reoutline(
+            repo,
+            r###"{"outlines": [
+                {"chapter": 3, "content": "## Goal\n\nMara reaches the capital."},
+                {"chapter": 4, "content": "# Chapter 4: Ledger\n\n## Goal\n\nThe trial."}
+            ]}"###,
+        )
+        .unwrap();
+        assert_eq!(result["written"].as_array().unwrap().len(), 2);
+        let three = std::fs::read_to_string(repo.join("Chapters material/Chapter_03.md")).unwrap();
+        assert_eq!(
+            three,
+            "# Chapter 3\n\n## Goal\n\nMara reaches the capital.\n"
+        );
+        assert_eq!(outline_kind(&three), "outlined");
+        let log = git::run_git(repo, &["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(log.trim(), "chapter: reoutline 2 chapter(s)");
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use crate::errors::InkError;
+use crate::journal;
+
+/// Advisory lock file inside the local `.ink/` directory. Unlike `.ink-running`
+/// (a git-visible session lock), it only guards one machine's working tree.
+const LOCK_FILE: &str = ".ink/command.lock";
+
+/// Held for the duration of a mutating command; the OS releases the lock when
+/// the file is closed, so a crashed process never leaves it behind.
+#[derive(Debug)]
+pub struct CommandLock {
+    _file: File,
+}
+
+/// Take the repo's command lock for `command`, or fail immediately naming the
+/// command that holds it. Two commands interleaving git operations on the same
+/// working tree (a cron session-open and a manual approve, say) can leave
+/// branches and the index in states neither expects.
+pub fn acquire(repo: &Path, command: &str) -> Result<CommandLock> {
+    // Never create the repo directory as a side effect of a mistyped path
+    if !repo.is_dir() {
+        return Err(anyhow!("{} is not a directory", repo.display()));
+    }
+    journal::ensure_ink_dir(repo)?;
+    let path = repo.join(LOCK_FILE);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", LOCK_FILE))?;
+
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut holder = String::new();
+            let _ = file.read_to_string(&mut holder);
+            let holder = holder.trim();
+            return Err(InkError::Busy {
+                holder: if holder.is_empty() {
+                    "unknown command"
+                } else {
+                    holder
+                }
+                .to_string(),
+            }
+            .into());
+        }
+        Err(TryLockError::Error(e)) => {
+            return Err(e).with_context(|| format!("Failed to lock {}", LOCK_FILE));
+        }
+    }
+
+    // Record the holder for the error above; informational only
+    file.set_len(0)
+        .and_then(|_| file.rewind())
+        .and_then(|_| {
+            writeln!(
+                file,
+                "{} (pid {}, since {})",
+                command,
+                std::process::id(),
+                Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+            )
+        })
+        .with_context(|| format!("Failed to write {}", LOCK_FILE))?;
+    Ok(CommandLock { _file: file })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_command_is_refused_until_first_finishes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let held = acquire(tmp.path(), "session-open").unwrap();
+        let err = acquire(tmp.path(), "approve").unwrap_err().to_string();
+        assert!(err.contains("session-open (pid "), "{err}");
+        drop(held);
+        assert!(acquire(tmp.path(), "approve").is_ok());
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
+use crate::errors::InkError;
+
 fn default_language() -> String {
     "English".to_string()
 }
@@ -10,6 +13,14 @@ fn default_summary_context_entries() -> usize {
     5
 }
 
+fn default_timeline_context_entries() -> usize {
+    10
+}
+
+fn default_phrases_to_avoid() -> usize {
+    10
+}
+
 fn default_session_timeout_minutes() -> i64 {
     60
 }
@@ -18,19 +29,698 @@ fn default_words_per_page() -> u32 {
     250
 }
 
+fn default_reading_words_per_minute() -> u32 {
+    250
+}
+
 fn default_words_per_chapter() -> u32 {
     3000
 }
 
+fn default_scene_break() -> String {
+    "***".to_string()
+}
+
 fn default_context_window_tokens() -> u32 {
     200_000
 }
 
+fn default_audio_format() -> String {
+    "mp3".to_string()
+}
+
+fn default_tts_api_key_env() -> String {
+    "INK_TTS_API_KEY".to_string()
+}
+
+/// Text-to-speech backend used by `export audio`. Set exactly one of
+/// `command` (local program) or `api_url` (HTTP endpoint called via curl).
+#[derive(Debug, Deserialize)]
+pub struct TtsConfig {
+    /// Shell command that reads text on stdin and writes audio to `{output}`.
+    pub command: Option<String>,
+    /// HTTP endpoint receiving `{ "text", "voice", "format" }` as JSON and
+    /// returning the audio bytes.
+    pub api_url: Option<String>,
+    /// Environment variable holding the API bearer token (never stored in the repo).
+    #[serde(default = "default_tts_api_key_env")]
+    pub api_key_env: String,
+    pub voice: Option<String>,
+    /// Audio file extension, passed to the API as `format`.
+    #[serde(default = "default_audio_format")]
+    pub format: String,
+}
+
+/// Output format of an export profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManuscriptFormat {
+    Markdown,
+    Html,
+}
+
+impl ManuscriptFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ManuscriptFormat::Markdown => "md",
+            ManuscriptFormat::Html => "html",
+        }
+    }
+}
+
+/// A named manuscript export under `exports:`, run by `export --profile <name>`.
+#[derive(Debug, Deserialize)]
+pub struct ExportProfile {
+    pub format: ManuscriptFormat,
+    /// Repo-relative files placed before the book text, in order.
+    #[serde(default)]
+    pub front_matter: Vec<String>,
+    /// Repo-relative files placed after the book text, in order.
+    #[serde(default)]
+    pub back_matter: Vec<String>,
+    /// Chapter range such as `"1-12"` or `"5"`; the whole book when absent.
+    #[serde(default)]
+    pub chapters: Option<String>,
+    /// Repo-relative CSS file inlined into `html` exports.
+    #[serde(default)]
+    pub stylesheet: Option<String>,
+    /// Repo-relative output path (default: `Exports/<profile>.<md|html>`).
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+/// A chapter range such as `"1-12"` or `"5"` as an inclusive `(first, last)`.
+fn parse_chapter_range(raw: &str) -> Result<(u32, u32)> {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .with_context(|| format!("invalid chapter range '{}'", raw))
+    };
+    let (first, last) = match raw.split_once('-') {
+        Some((a, b)) => (parse(a)?, parse(b)?),
+        None => (parse(raw)?, parse(raw)?),
+    };
+    anyhow::ensure!(
+        first >= 1 && first <= last,
+        "invalid chapter range '{}' — expected N or N-M with 1 <= N <= M",
+        raw
+    );
+    Ok((first, last))
+}
+
+impl ExportProfile {
+    /// Parsed `chapters` as an inclusive `(first, last)` range.
+    pub fn chapter_range(&self) -> Result<Option<(u32, u32)>> {
+        self.chapters
+            .as_deref()
+            .map(parse_chapter_range)
+            .transpose()
+    }
+
+    /// Where the export is written, relative to the repo.
+    pub fn output_path(&self, name: &str) -> String {
+        self.output
+            .clone()
+            .unwrap_or_else(|| format!("Exports/{}.{}", name, self.format.extension()))
+    }
+}
+
+/// A part (or act) under `parts:`, grouping a range of chapters.
+#[derive(Debug, Deserialize)]
+pub struct PartConfig {
+    /// Divider heading, e.g. "Part One: The Fall".
+    pub title: String,
+    /// Chapter range such as `"1-8"`.
+    pub chapters: String,
+}
+
+impl PartConfig {
+    /// Parsed `chapters` as an inclusive `(first, last)` range.
+    pub fn chapter_range(&self) -> Result<(u32, u32)> {
+        parse_chapter_range(&self.chapters)
+    }
+}
+
+/// The part a chapter belongs to, as reported in the payload and status.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartInfo {
+    /// 1-based position in `parts:`.
+    pub number: usize,
+    pub title: String,
+    pub first_chapter: u32,
+    pub last_chapter: u32,
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+fn default_smtp_password_env() -> String {
+    "INK_SMTP_PASSWORD".to_string()
+}
+
+fn default_agent_timeout_minutes() -> u64 {
+    120
+}
+
+fn default_release_tag() -> String {
+    "v1.0".to_string()
+}
+
+fn default_github_token_env() -> String {
+    "GITHUB_TOKEN".to_string()
+}
+
+fn default_git_retries() -> u32 {
+    3
+}
+
+fn default_git_backoff_seconds() -> u64 {
+    2
+}
+
+fn default_git_max_backoff_seconds() -> u64 {
+    60
+}
+
+fn default_git_timeout_seconds() -> u64 {
+    300
+}
+
+fn default_session_commit_message() -> String {
+    "session: write prose".to_string()
+}
+
+fn default_chapter_commit_message() -> String {
+    "chapter: advance to {chapter}".to_string()
+}
+
+fn default_keep_last_tags() -> usize {
+    30
+}
+
+fn default_keep_monthly_tags() -> usize {
+    12
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Push notifications under `notifications:`. Both channels are sent with
+/// curl; secrets are read from environment variables, never from the repo.
+#[derive(Debug, Deserialize)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub ntfy: Option<NtfyConfig>,
+    #[serde(default)]
+    pub email: Option<EmailConfig>,
+    #[serde(default)]
+    pub on: NotificationEvents,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NtfyConfig {
+    pub topic: String,
+    #[serde(default = "default_ntfy_server")]
+    pub server: String,
+    /// Environment variable holding an access token for protected topics.
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmailConfig {
+    /// `smtps://host:465` or `smtp://host:587` (STARTTLS is required either way).
+    pub smtp_url: String,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Environment variable holding the SMTP username; no login when absent.
+    #[serde(default)]
+    pub username_env: Option<String>,
+    #[serde(default = "default_smtp_password_env")]
+    pub password_env: String,
+}
+
+/// Per-event toggles; every event is on unless switched off.
+#[derive(Debug, Deserialize)]
+pub struct NotificationEvents {
+    #[serde(default = "default_true")]
+    pub completion_ready: bool,
+    #[serde(default = "default_true")]
+    pub push_failed: bool,
+    #[serde(default = "default_true")]
+    pub stale_lock_recovered: bool,
+}
+
+impl Default for NotificationEvents {
+    fn default() -> Self {
+        NotificationEvents {
+            completion_ready: true,
+            push_failed: true,
+            stale_lock_recovered: true,
+        }
+    }
+}
+
+/// Unattended runs for `ink-cli schedule`, under `schedule:`.
+#[derive(Debug, Deserialize)]
+pub struct ScheduleConfig {
+    /// Five-field cron expression in local time, e.g. `"30 2 * * *"`.
+    pub cron: String,
+    /// Shell command receiving the session-open payload on stdin and printing
+    /// the prose (or `{ "prose", "summary" }` JSON) on stdout.
+    pub agent_command: String,
+    /// The agent is killed and the session released after this long.
+    #[serde(default = "default_agent_timeout_minutes")]
+    pub timeout_minutes: u64,
+}
+
+/// A book of a series under `series:`: shared canon files kept in another
+/// repository, which this book's own Global Material overrides section by
+/// section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesConfig {
+    /// Checkout of the canon repository, relative to this book or absolute.
+    pub canon: String,
+    /// Canon files merged in, from the canon's `Global Material/` when it has
+    /// one (a finished book can be the canon of its sequel), else its root.
+    #[serde(default = "default_canon_files")]
+    pub files: Vec<String>,
+}
+
+fn default_canon_files() -> Vec<String> {
+    ["Characters.md", "Lore.md", "Timeline.md"]
+        .map(String::from)
+        .to_vec()
+}
+
+/// Retries of git fetch/push under `git_retry:`. Only network failures
+/// (timeouts, unreachable host, dropped connection) are retried; auth and
+/// rejected pushes fail at once.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitRetryConfig {
+    /// Attempts after the first; 0 disables retrying.
+    #[serde(default = "default_git_retries")]
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each one after it.
+    #[serde(default = "default_git_backoff_seconds")]
+    pub backoff_seconds: u64,
+    #[serde(default = "default_git_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+}
+
+impl Default for GitRetryConfig {
+    fn default() -> Self {
+        GitRetryConfig {
+            retries: default_git_retries(),
+            backoff_seconds: default_git_backoff_seconds(),
+            max_backoff_seconds: default_git_max_backoff_seconds(),
+        }
+    }
+}
+
+/// Rules of the prose lint (`lint`, session-open `revision_hints`) under
+/// `prose_lint:`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProseLintConfig {
+    /// Rules to skip, from `PROSE_LINT_RULES`.
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    /// Replaces the built-in filter word list (felt, saw, heard, …).
+    #[serde(default)]
+    pub filter_words: Option<Vec<String>>,
+    #[serde(default = "default_max_adverbs_per_100")]
+    pub max_adverbs_per_100: f64,
+    /// Sentence-length standard deviation over mean below which the rhythm
+    /// is flagged as uniform.
+    #[serde(default = "default_min_sentence_variation")]
+    pub min_sentence_variation: f64,
+    /// Words within which a repeated three-word phrase is an echo.
+    #[serde(default = "default_echo_window")]
+    pub echo_window: usize,
+    /// Hints session-open sends for Review/current.md; 0 sends none.
+    #[serde(default = "default_revision_hints")]
+    pub revision_hints: usize,
+}
+
+pub const PROSE_LINT_RULES: &[&str] = &[
+    "repeated_words",
+    "filter_words",
+    "adverb_density",
+    "sentence_uniformity",
+    "echoes",
+];
+
+fn default_max_adverbs_per_100() -> f64 {
+    3.0
+}
+
+fn default_min_sentence_variation() -> f64 {
+    0.3
+}
+
+fn default_echo_window() -> usize {
+    150
+}
+
+fn default_revision_hints() -> usize {
+    10
+}
+
+impl Default for ProseLintConfig {
+    fn default() -> Self {
+        ProseLintConfig {
+            disabled: Vec::new(),
+            filter_words: None,
+            max_adverbs_per_100: default_max_adverbs_per_100(),
+            min_sentence_variation: default_min_sentence_variation(),
+            echo_window: default_echo_window(),
+            revision_hints: default_revision_hints(),
+        }
+    }
+}
+
+/// Which `ink-*` snapshot tags `prune-tags` keeps, under `tag_retention:`.
+/// A tag is kept when either rule keeps it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagRetention {
+    /// The newest N snapshots.
+    #[serde(default = "default_keep_last_tags")]
+    pub keep_last: usize,
+    /// The newest snapshot of each of the last N months that have one; 0 = none.
+    #[serde(default = "default_keep_monthly_tags")]
+    pub keep_monthly: usize,
+}
+
+impl Default for TagRetention {
+    fn default() -> Self {
+        TagRetention {
+            keep_last: default_keep_last_tags(),
+            keep_monthly: default_keep_monthly_tags(),
+        }
+    }
+}
+
+/// Messages of session and chapter-advance commits under `commit_messages:`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitMessages {
+    /// Placeholders: `{session_id}`, `{words}` (written this session),
+    /// `{chapter}` (unit being written), `{date}`.
+    #[serde(default = "default_session_commit_message")]
+    pub session: String,
+    /// Same placeholders; `{chapter}` is the unit advanced to and `{words}`
+    /// the word count of the one just finished.
+    #[serde(default = "default_chapter_commit_message")]
+    pub chapter_advance: String,
+    /// `Name <email>` entries added as `Co-authored-by:` trailers.
+    #[serde(default)]
+    pub co_authored_by: Vec<String>,
+}
+
+impl Default for CommitMessages {
+    fn default() -> Self {
+        CommitMessages {
+            session: default_session_commit_message(),
+            chapter_advance: default_chapter_commit_message(),
+            co_authored_by: Vec::new(),
+        }
+    }
+}
+
+const COMMIT_PLACEHOLDERS: &[&str] = &["session_id", "words", "chapter", "date"];
+
+fn placeholder_re() -> &'static regex::Regex {
+    static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| regex::Regex::new(r"\{(\w+)\}").unwrap())
+}
+
+impl CommitMessages {
+    /// Fill `template`'s placeholders from `vars` and append the co-author
+    /// trailers. Placeholders missing from `vars` become empty.
+    pub fn render(&self, template: &str, vars: &[(&str, &str)]) -> String {
+        let message = placeholder_re().replace_all(template, |caps: &regex::Captures| {
+            vars.iter()
+                .find(|(name, _)| *name == &caps[1])
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default()
+        });
+        let mut message = message.trim().to_string();
+        if !self.co_authored_by.is_empty() {
+            message.push('\n');
+            for co_author in &self.co_authored_by {
+                message.push_str(&format!("\nCo-authored-by: {}", co_author));
+            }
+        }
+        message
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (key, template) in [
+            ("session", &self.session),
+            ("chapter_advance", &self.chapter_advance),
+        ] {
+            anyhow::ensure!(
+                !template.trim().is_empty(),
+                "Config.yml: commit_messages.{} must not be empty",
+                key
+            );
+            for caps in placeholder_re().captures_iter(template) {
+                anyhow::ensure!(
+                    COMMIT_PLACEHOLDERS.contains(&&caps[1]),
+                    "Config.yml: commit_messages.{}: unknown placeholder {{{}}} (expected one of {})",
+                    key,
+                    &caps[1],
+                    COMMIT_PLACEHOLDERS
+                        .iter()
+                        .map(|p| format!("{{{}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+        for co_author in &self.co_authored_by {
+            anyhow::ensure!(
+                co_author.contains(" <") && co_author.ends_with('>'),
+                "Config.yml: commit_messages.co_authored_by '{}' must look like 'Name <email>'",
+                co_author
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Value of git's `gpg.format` for signed commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningFormat {
+    Openpgp,
+    Ssh,
+    X509,
+}
+
+impl SigningFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SigningFormat::Openpgp => "openpgp",
+            SigningFormat::Ssh => "ssh",
+            SigningFormat::X509 => "x509",
+        }
+    }
+}
+
+/// How `complete` publishes the release.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseVia {
+    /// The GitHub CLI, with its own login.
+    #[default]
+    Gh,
+    /// The REST API through curl, with the token in `token_env`.
+    Api,
+}
+
+/// GitHub release created by `complete` under `release:`.
+#[derive(Debug, Deserialize)]
+pub struct ReleaseConfig {
+    #[serde(default = "default_release_tag")]
+    pub tag: String,
+    /// Release title (default: the tag).
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub via: ReleaseVia,
+    /// Environment variable holding the token for `via: api`.
+    #[serde(default = "default_github_token_env")]
+    pub token_env: String,
+    /// Extra repo-relative files to attach, besides Full_Book.md and the
+    /// `export_on_complete` output.
+    #[serde(default)]
+    pub assets: Vec<String>,
+}
+
+/// Typography rules applied to validated prose before it reaches
+/// Full_Book.md, under `typography:` (`typography: {}` enables all of them).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypographyConfig {
+    /// Straight quotes to the book language's quotation marks, `'` to `’`.
+    #[serde(default = "default_true")]
+    pub quotes: bool,
+    /// `--` to an em dash.
+    #[serde(default = "default_true")]
+    pub dashes: bool,
+    /// `...` and `. . .` to `…`.
+    #[serde(default = "default_true")]
+    pub ellipses: bool,
+    /// French books only: non-breaking spaces before `; : ! ?` and inside `« »`.
+    #[serde(default = "default_true")]
+    pub french_spacing: bool,
+}
+
+/// What word counts leave out, under `word_count:` — one set of rules for
+/// every progress figure (session, book, chapter, status, export).
+#[derive(Debug, Clone, Deserialize)]
+pub struct WordCountConfig {
+    /// HTML comments: the managed header, page markers, notes.
+    #[serde(default = "default_true")]
+    pub exclude_comments: bool,
+    /// `<!-- INK… -->` engine markers and author instructions, even when
+    /// other comments count.
+    #[serde(default = "default_true")]
+    pub exclude_markers: bool,
+    /// Markdown `#` heading lines (chapter titles).
+    #[serde(default)]
+    pub exclude_headings: bool,
+    /// A leading `---` YAML front matter block.
+    #[serde(default = "default_true")]
+    pub exclude_front_matter: bool,
+}
+
+impl Default for WordCountConfig {
+    fn default() -> Self {
+        WordCountConfig {
+            exclude_comments: true,
+            exclude_markers: true,
+            exclude_headings: false,
+            exclude_front_matter: true,
+        }
+    }
+}
+
+/// Hunspell pass over new prose at session-close, under `spellcheck:`
+/// (`spellcheck: {}` enables it with the defaults).
+#[derive(Debug, Deserialize)]
+pub struct SpellcheckConfig {
+    /// Hunspell dictionary, e.g. `en_GB` (default: derived from `language`).
+    #[serde(default)]
+    pub dictionary: Option<String>,
+    /// Hunspell executable.
+    #[serde(default = "default_spellcheck_command")]
+    pub command: String,
+}
+
+impl SpellcheckConfig {
+    /// `dictionary`, or the usual hunspell dictionary of `language` — a name
+    /// (`French`) or a code (`fr`, `fr-CA`, `fr_CA`).
+    pub fn dictionary_for(&self, language: &str) -> Option<String> {
+        if let Some(d) = self.dictionary.as_ref().filter(|d| !d.trim().is_empty()) {
+            return Some(d.trim().to_string());
+        }
+        let language = language.trim().replace('-', "_");
+        if let Some((lang, region)) = language.split_once('_') {
+            return Some(format!("{}_{}", lang.to_lowercase(), region.to_uppercase()));
+        }
+        let dictionary = match language.to_lowercase().as_str() {
+            "english" | "en" => "en_US",
+            "french" | "français" | "francais" | "fr" => "fr_FR",
+            "german" | "deutsch" | "de" => "de_DE",
+            "spanish" | "español" | "espanol" | "es" => "es_ES",
+            "italian" | "italiano" | "it" => "it_IT",
+            "portuguese" | "português" | "portugues" | "pt" => "pt_PT",
+            "dutch" | "nederlands" | "nl" => "nl_NL",
+            "swedish" | "svenska" | "sv" => "sv_SE",
+            "danish" | "dansk" | "da" => "da_DK",
+            "norwegian" | "norsk" | "nb" => "nb_NO",
+            "polish" | "polski" | "pl" => "pl_PL",
+            _ => return None,
+        };
+        Some(dictionary.to_string())
+    }
+}
+
+fn default_spellcheck_command() -> String {
+    "hunspell".to_string()
+}
+
+/// How `session-close` reacts to malformed INK:NEW / INK:REWORKED markers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerValidation {
+    /// Reject the prose; nothing is written and the lock stays held.
+    #[default]
+    Strict,
+    /// Close normally and report the issues in the payload.
+    Warn,
+}
+
+fn default_quota_min_ratio() -> f64 {
+    0.5
+}
+
+fn default_quota_max_ratio() -> f64 {
+    2.0
+}
+
+/// How `session-close` reacts to prose far from `words_per_session`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaMode {
+    /// Close normally and report the gap in the payload and the Changelog.
+    #[default]
+    Warn,
+    /// Reject the prose; nothing is written and the lock stays held.
+    Reject,
+}
+
+/// Session length bounds under `session_quota:`, as shares of
+/// `words_per_session` (`session_quota: {}` warns outside half to double).
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionQuotaConfig {
+    #[serde(default)]
+    pub mode: QuotaMode,
+    /// Fewest words, as a share of `words_per_session`.
+    #[serde(default = "default_quota_min_ratio")]
+    pub min_ratio: f64,
+    /// Most words, as a share of `words_per_session`; 0 = no upper bound.
+    #[serde(default = "default_quota_max_ratio")]
+    pub max_ratio: f64,
+}
+
+/// Where the validated manuscript lives in `Current version/`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum BookStorage {
+    /// One `Full_Book.md`.
+    #[default]
+    Single,
+    /// One file per unit (`Chapter_NN.md`, `Prologue.md`, …); `compile`
+    /// generates Full_Book.md on demand.
+    Chapters,
+}
+
+/// Below this, `target_length` is treated as a probable page count by `lint`.
+const MIN_PLAUSIBLE_TARGET_LENGTH: u32 = 1000;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Config {
+    /// Title and author of the book, as init or the last `rename` set them.
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Language of the book; selects the readability formulas of session-close.
     #[serde(default = "default_language")]
-    #[allow(dead_code)] // read by the ink-engine agent via JSON, not by Rust code
     pub language: String,
     pub target_length: u32,
     pub chapter_count: u32,
@@ -38,27 +728,257 @@ pub struct Config {
     pub words_per_session: u32,
     #[serde(default = "default_summary_context_entries")]
     pub summary_context_entries: usize,
+    /// Most recent Timeline.md events session-open surfaces.
+    #[serde(default = "default_timeline_context_entries")]
+    pub timeline_context_entries: usize,
+    /// Crutch phrases and overused phrases session-open lists for the engine
+    /// to avoid; 0 disables.
+    #[serde(default = "default_phrases_to_avoid")]
+    pub phrases_to_avoid: usize,
     #[serde(default = "default_session_timeout_minutes")]
     pub session_timeout_minutes: i64,
     #[serde(default = "default_words_per_page")]
     pub words_per_page: u32,
+    /// Reading speed behind the `reading_minutes` estimates of status and
+    /// session-close.
+    #[serde(default = "default_reading_words_per_minute")]
+    pub reading_words_per_minute: u32,
+    /// Scene separator session-close writes in place of the engine's
+    /// (`***`, `---`, `* * *`, extra blank lines); empty leaves them as sent.
+    #[serde(default = "default_scene_break")]
+    pub scene_break: String,
     #[serde(default = "default_words_per_chapter")]
     pub words_per_chapter: u32,
     #[serde(default = "default_context_window_tokens")]
     pub context_window_tokens: u32,
+    #[serde(default)]
+    pub marker_validation: MarkerValidation,
+    /// Bounds on the words of one session, if enforced.
+    #[serde(default)]
+    pub session_quota: Option<SessionQuotaConfig>,
+    /// When true, session-close refuses prose that looks cut off
+    /// mid-generation unless forced; otherwise it only reports it.
+    #[serde(default)]
+    pub refuse_truncated: bool,
+    /// When true, session-close queues validated prose in Review/pending.md
+    /// instead of appending it to Full_Book.md; `approve` / `reject` settle it.
+    #[serde(default)]
+    pub require_approval: bool,
+    /// When true, session-close also runs the continuity checks that apply
+    /// to one session (unknown speakers, summary references to later chapters).
+    #[serde(default)]
+    pub continuity_on_close: bool,
+    #[serde(default)]
+    pub tts: Option<TtsConfig>,
+    /// Narrative rules sent verbatim in every session-open payload and checked
+    /// heuristically at session-close where their shape is recognised.
+    #[serde(default)]
+    pub constraints: Vec<String>,
+    /// Named manuscript export profiles, keyed by profile name.
+    #[serde(default)]
+    pub exports: BTreeMap<String, ExportProfile>,
+    /// Profile `complete` runs before the final seal, if any.
+    #[serde(default)]
+    pub export_on_complete: Option<String>,
+    #[serde(default)]
+    pub notifications: Option<NotificationsConfig>,
+    #[serde(default)]
+    pub schedule: Option<ScheduleConfig>,
+    #[serde(default)]
+    pub series: Option<SeriesConfig>,
+    /// Published branch; detected from origin when absent (see `git::branches`).
+    #[serde(default)]
+    pub main_branch: Option<String>,
+    /// Branch sessions write on before merging into the main branch (default `draft`).
+    #[serde(default)]
+    pub draft_branch: Option<String>,
+    /// Git remotes: the first is pushed to and fetched from (default `origin`),
+    /// the rest are best-effort mirrors updated after each publish.
+    #[serde(default)]
+    pub remotes: Vec<String>,
+    #[serde(default)]
+    pub git_retry: GitRetryConfig,
+    /// Any git command running longer than this is killed (default 300, 0 = never).
+    #[serde(default = "default_git_timeout_seconds")]
+    pub git_timeout_seconds: u64,
+    /// Identity of engine commits (session, lock, seal); the repo's own
+    /// user.name / user.email when absent. Human-edit commits keep the author's.
+    #[serde(default)]
+    pub git_author_name: Option<String>,
+    #[serde(default)]
+    pub git_author_email: Option<String>,
+    /// Sign every commit ink creates (`git commit -S`).
+    #[serde(default)]
+    pub sign_commits: bool,
+    /// Key to sign with; git's `user.signingkey` when absent.
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    /// Signature format; git's `gpg.format` when absent.
+    #[serde(default)]
+    pub signing_format: Option<SigningFormat>,
+    #[serde(default)]
+    pub commit_messages: CommitMessages,
+    #[serde(default)]
+    pub tag_retention: TagRetention,
+    #[serde(default)]
+    pub prose_lint: ProseLintConfig,
+    /// GitHub release published by `complete`, if any.
+    #[serde(default)]
+    pub release: Option<ReleaseConfig>,
+    /// Typography normalization of prose entering the manuscript, if any.
+    #[serde(default)]
+    pub typography: Option<TypographyConfig>,
+    /// Spellcheck of new prose at session-close, if any.
+    #[serde(default)]
+    pub spellcheck: Option<SpellcheckConfig>,
+    #[serde(default)]
+    pub word_count: WordCountConfig,
+    /// `chapters` keeps one prose file per unit instead of Full_Book.md;
+    /// switch with `migrate`, not by hand.
+    #[serde(default)]
+    pub book_storage: BookStorage,
+    /// Obsidian vault mode: session-open resolves `[[wiki-links]]` in the
+    /// Global Material, session-close regenerates `Index.md`.
+    #[serde(default)]
+    pub obsidian: bool,
+    /// `advance-chapter` writes a missing next outline from the template and
+    /// the matching Outline.md act instead of stopping at
+    /// `needs_chapter_outline`.
+    #[serde(default)]
+    pub scaffold_outlines: bool,
+    /// Parts or acts above the chapters, in order; compile and exports open
+    /// each with a divider heading.
+    #[serde(default)]
+    pub parts: Vec<PartConfig>,
+    /// Per-chapter `words_per_chapter` overrides, keyed by chapter number.
+    /// An outline's `target_words` front matter wins over both.
+    #[serde(default)]
+    pub chapter_targets: BTreeMap<u32, u32>,
 }
 
 impl Config {
+    /// Read and validate the book's Config.yml; any failure is
+    /// `config_invalid`.
     pub fn load(repo_path: &Path) -> Result<Self> {
         let config_path = repo_path.join("Global Material").join("Config.yml");
-        let content = std::fs::read_to_string(&config_path)
-            .with_context(|| format!("Failed to read Config.yml at {}", config_path.display()))?;
+        std::fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read Config.yml at {}", config_path.display()))
+            .and_then(|content| Self::parse(&content))
+            .map_err(|e| InkError::ConfigInvalid(format!("{:#}", e)).into())
+    }
+
+    /// Parse and validate Config.yml content without touching the filesystem.
+    pub fn parse(content: &str) -> Result<Self> {
         let config: Config =
-            serde_yaml::from_str(&content).with_context(|| "Failed to parse Config.yml")?;
+            serde_yaml::from_str(content).with_context(|| "Failed to parse Config.yml")?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Words per page for page↔word conversions. `words_per_page: 0` only
+    /// disables pagination markers, so conversions fall back to the default.
+    pub fn page_size(&self) -> u32 {
+        page_size_or_default(self.words_per_page)
+    }
+
+    pub fn words_to_pages(&self, words: u32) -> u32 {
+        words.div_ceil(self.page_size())
+    }
+
+    /// Minutes to read `words` at `reading_words_per_minute`, rounded up.
+    pub fn reading_minutes(&self, words: u32) -> u32 {
+        words.div_ceil(self.reading_words_per_minute.max(1))
+    }
+
+    /// Words that complete chapter `n`: its `chapter_targets` entry, else
+    /// `words_per_chapter`.
+    pub fn chapter_target(&self, n: u32) -> u32 {
+        self.chapter_targets
+            .get(&n)
+            .copied()
+            .unwrap_or(self.words_per_chapter)
+    }
+
+    /// The part holding `chapter`, if `parts:` covers it.
+    pub fn part_of(&self, chapter: u32) -> Option<PartInfo> {
+        self.parts.iter().enumerate().find_map(|(i, part)| {
+            let (first, last) = part.chapter_range().ok()?;
+            (first..=last).contains(&chapter).then(|| PartInfo {
+                number: i + 1,
+                title: part.title.clone(),
+                first_chapter: first,
+                last_chapter: last,
+            })
+        })
+    }
+
+    /// Flag values that parse and validate but are almost certainly wrong —
+    /// the usual mistakes an agent makes when filling Config.yml from init answers.
+    /// Returns one human-readable message per issue; empty when the config looks sane.
+    pub fn lint(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        // A page count pasted instead of words (pages × words_per_page) lands well below this.
+        if self.target_length < MIN_PLAUSIBLE_TARGET_LENGTH {
+            issues.push(format!(
+                "target_length={} looks like a page count, not words (expected pages × {})",
+                self.target_length,
+                self.page_size()
+            ));
+        }
+
+        let expected_chapters = self.target_length.div_ceil(self.words_per_chapter).max(1);
+        if self.chapter_count * 2 < expected_chapters || self.chapter_count > expected_chapters * 2
+        {
+            issues.push(format!(
+                "chapter_count={} is inconsistent with target_length/words_per_chapter ≈ {}",
+                self.chapter_count, expected_chapters
+            ));
+        }
+
+        if self.words_per_session > self.words_per_chapter {
+            issues.push(format!(
+                "words_per_session={} exceeds words_per_chapter={} — every session would overrun the chapter",
+                self.words_per_session, self.words_per_chapter
+            ));
+        }
+
+        if let Some(last) = self
+            .parts
+            .iter()
+            .filter_map(|p| p.chapter_range().ok())
+            .map(|(_, last)| last)
+            .max()
+        {
+            if last > self.chapter_count {
+                issues.push(format!(
+                    "parts reach chapter {} but chapter_count is {}",
+                    last, self.chapter_count
+                ));
+            }
+        }
+
+        if let Some(n) = self
+            .chapter_targets
+            .keys()
+            .find(|&&n| n > self.chapter_count)
+        {
+            issues.push(format!(
+                "chapter_targets sets chapter {} but chapter_count is {}",
+                n, self.chapter_count
+            ));
+        }
+
+        if self.git_timeout_seconds == 0 {
+            issues.push(
+                "git_timeout_seconds=0 disables the git timeout — a hung push blocks forever"
+                    .to_string(),
+            );
+        }
+
+        issues
+    }
+
     fn validate(&self) -> Result<()> {
         anyhow::ensure!(
             self.target_length > 0,
@@ -80,12 +1000,302 @@ impl Config {
             "Config.yml: words_per_chapter must be > 0, got {}",
             self.words_per_chapter
         );
+        for (n, words) in &self.chapter_targets {
+            anyhow::ensure!(
+                *n >= 1 && *words > 0,
+                "Config.yml: chapter_targets entry {}: {} needs a chapter number >= 1 and words > 0",
+                n,
+                words
+            );
+        }
         // words_per_page: 0 disables pagination (handled by insert_pagination).
         anyhow::ensure!(
             self.session_timeout_minutes > 0,
             "Config.yml: session_timeout_minutes must be > 0, got {}",
             self.session_timeout_minutes
         );
+        if let Some(tts) = &self.tts {
+            anyhow::ensure!(
+                tts.command.is_some() != tts.api_url.is_some(),
+                "Config.yml: tts needs exactly one of `command` or `api_url`"
+            );
+        }
+        for (name, profile) in &self.exports {
+            profile
+                .chapter_range()
+                .with_context(|| format!("Config.yml: exports.{}.chapters", name))?;
+            anyhow::ensure!(
+                profile.stylesheet.is_none() || profile.format == ManuscriptFormat::Html,
+                "Config.yml: exports.{}: stylesheet only applies to format: html",
+                name
+            );
+        }
+        if let Some(name) = &self.export_on_complete {
+            anyhow::ensure!(
+                self.exports.contains_key(name),
+                "Config.yml: export_on_complete names unknown profile '{}'",
+                name
+            );
+        }
+        if let Some(n) = &self.notifications {
+            anyhow::ensure!(
+                n.ntfy.is_some() || n.email.is_some(),
+                "Config.yml: notifications needs `ntfy` or `email`"
+            );
+            if let Some(email) = &n.email {
+                anyhow::ensure!(
+                    !email.to.is_empty(),
+                    "Config.yml: notifications.email.to needs at least one address"
+                );
+            }
+        }
+        if let Some(schedule) = &self.schedule {
+            crate::schedule::Cron::parse(&schedule.cron)
+                .with_context(|| "Config.yml: schedule.cron")?;
+            anyhow::ensure!(
+                !schedule.agent_command.trim().is_empty(),
+                "Config.yml: schedule.agent_command must not be empty"
+            );
+            anyhow::ensure!(
+                schedule.timeout_minutes > 0,
+                "Config.yml: schedule.timeout_minutes must be > 0"
+            );
+        }
+        if let Some(series) = &self.series {
+            anyhow::ensure!(
+                !series.canon.trim().is_empty(),
+                "Config.yml: series.canon must not be empty"
+            );
+            for file in &series.files {
+                anyhow::ensure!(
+                    file.ends_with(".md") && !file.contains(['/', '\\']),
+                    "Config.yml: series.files entry '{}' must be a Markdown file name",
+                    file
+                );
+            }
+        }
+        for (key, name) in [
+            ("main_branch", &self.main_branch),
+            ("draft_branch", &self.draft_branch),
+        ] {
+            if let Some(name) = name {
+                anyhow::ensure!(
+                    !name.is_empty()
+                        && !name.contains(|c: char| c.is_whitespace() || c == ':')
+                        && !name.starts_with('-'),
+                    "Config.yml: {} '{}' is not a valid branch name",
+                    key,
+                    name
+                );
+            }
+        }
+        for (i, remote) in self.remotes.iter().enumerate() {
+            anyhow::ensure!(
+                !remote.is_empty() && !remote.contains(|c: char| c.is_whitespace() || c == '/'),
+                "Config.yml: remotes: '{}' is not a valid remote name",
+                remote
+            );
+            anyhow::ensure!(
+                !self.remotes[..i].contains(remote),
+                "Config.yml: remotes lists '{}' twice",
+                remote
+            );
+        }
+        if let (Some(main), Some(draft)) = (&self.main_branch, &self.draft_branch) {
+            anyhow::ensure!(
+                main != draft,
+                "Config.yml: main_branch and draft_branch must differ"
+            );
+        }
+        self.commit_messages.validate()?;
+        anyhow::ensure!(
+            self.sign_commits || (self.signing_key.is_none() && self.signing_format.is_none()),
+            "Config.yml: signing_key / signing_format are set but sign_commits is not true"
+        );
+        if let Some(name) = &self.git_author_name {
+            anyhow::ensure!(
+                !name.trim().is_empty() && !name.contains(['<', '>', '\n']),
+                "Config.yml: git_author_name '{}' is not a valid git name",
+                name
+            );
+        }
+        if let Some(email) = &self.git_author_email {
+            anyhow::ensure!(
+                email.contains('@') && !email.contains(['<', '>', '\n', ' ']),
+                "Config.yml: git_author_email '{}' is not a valid email address",
+                email
+            );
+        }
+        anyhow::ensure!(
+            self.git_retry.backoff_seconds <= self.git_retry.max_backoff_seconds,
+            "Config.yml: git_retry.backoff_seconds ({}) exceeds max_backoff_seconds ({})",
+            self.git_retry.backoff_seconds,
+            self.git_retry.max_backoff_seconds
+        );
+        for rule in &self.prose_lint.disabled {
+            anyhow::ensure!(
+                PROSE_LINT_RULES.contains(&rule.as_str()),
+                "Config.yml: prose_lint.disabled has unknown rule '{}' (expected one of: {})",
+                rule,
+                PROSE_LINT_RULES.join(", ")
+            );
+        }
+        if let Some(spellcheck) = &self.spellcheck {
+            anyhow::ensure!(
+                spellcheck.dictionary_for(&self.language).is_some(),
+                "Config.yml: spellcheck has no dictionary for language '{}' — set spellcheck.dictionary (e.g. en_US)",
+                self.language
+            );
+        }
+        // rollback reverts to the newest snapshot; pruning must never take it
+        anyhow::ensure!(
+            self.tag_retention.keep_last >= 1,
+            "Config.yml: tag_retention.keep_last must be at least 1 (rollback needs the latest snapshot)"
+        );
+        let mut previous_last = 0;
+        for (i, part) in self.parts.iter().enumerate() {
+            anyhow::ensure!(
+                !part.title.trim().is_empty(),
+                "Config.yml: parts[{}]: title must not be empty",
+                i
+            );
+            let (first, last) = part
+                .chapter_range()
+                .with_context(|| format!("Config.yml: parts[{}].chapters", i))?;
+            anyhow::ensure!(
+                first > previous_last,
+                "Config.yml: parts[{}] ('{}') starts at chapter {}, inside or before the previous part",
+                i,
+                part.title,
+                first
+            );
+            previous_last = last;
+        }
+        if let Some(release) = &self.release {
+            anyhow::ensure!(
+                !release.tag.trim().is_empty() && !release.tag.contains(char::is_whitespace),
+                "Config.yml: release.tag must be a single word, got '{}'",
+                release.tag
+            );
+        }
         Ok(())
     }
 }
+
+fn page_size_or_default(words_per_page: u32) -> u32 {
+    if words_per_page == 0 {
+        default_words_per_page()
+    } else {
+        words_per_page
+    }
+}
+
+/// The git settings of Config.yml: branches, remotes, retries and timeout,
+/// engine identity, and commit signing.
+#[derive(Debug, Deserialize)]
+pub struct GitSettings {
+    #[serde(default)]
+    pub main_branch: Option<String>,
+    #[serde(default)]
+    pub draft_branch: Option<String>,
+    #[serde(default)]
+    pub remotes: Vec<String>,
+    #[serde(default)]
+    pub git_retry: GitRetryConfig,
+    #[serde(default = "default_git_timeout_seconds")]
+    pub git_timeout_seconds: u64,
+    #[serde(default)]
+    pub git_author_name: Option<String>,
+    #[serde(default)]
+    pub git_author_email: Option<String>,
+    #[serde(default)]
+    pub sign_commits: bool,
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    #[serde(default)]
+    pub signing_format: Option<SigningFormat>,
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        GitSettings {
+            main_branch: None,
+            draft_branch: None,
+            remotes: Vec::new(),
+            git_retry: GitRetryConfig::default(),
+            git_timeout_seconds: default_git_timeout_seconds(),
+            git_author_name: None,
+            git_author_email: None,
+            sign_commits: false,
+            signing_key: None,
+            signing_format: None,
+        }
+    }
+}
+
+/// Read only the git settings from Config.yml, tolerating a missing or
+/// incomplete file (git helpers run before init writes it).
+/// `value` as a double-quoted YAML scalar, safe for any title or name.
+pub fn yaml_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn read_git_settings(repo_path: &Path) -> GitSettings {
+    let path = repo_path.join("Global Material").join("Config.yml");
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<GitSettings>(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Read only `book_storage` from Config.yml, tolerating an incomplete file.
+pub fn read_book_storage(repo_path: &Path) -> BookStorage {
+    #[derive(Deserialize)]
+    struct StorageOnly {
+        #[serde(default)]
+        book_storage: BookStorage,
+    }
+    let path = repo_path.join("Global Material").join("Config.yml");
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<StorageOnly>(&c).ok())
+        .map(|s| s.book_storage)
+        .unwrap_or_default()
+}
+
+/// Read only `language` and `word_count` from Config.yml, tolerating an
+/// incomplete file — for word counts outside a loaded `Config`.
+pub fn read_word_count(repo_path: &Path) -> (String, WordCountConfig) {
+    #[derive(Deserialize)]
+    struct WordCountOnly {
+        #[serde(default = "default_language")]
+        language: String,
+        #[serde(default)]
+        word_count: WordCountConfig,
+    }
+    let path = repo_path.join("Global Material").join("Config.yml");
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<WordCountOnly>(&c).ok())
+        .map_or_else(
+            || (default_language(), WordCountConfig::default()),
+            |w| (w.language, w.word_count),
+        )
+}
+
+/// Read only `words_per_page` from Config.yml, tolerating an incomplete file.
+/// Used by init Q&A, which runs before the author's answers make the config valid.
+pub fn read_page_size(repo_path: &Path) -> u32 {
+    #[derive(Deserialize)]
+    struct PageOnly {
+        #[serde(default = "default_words_per_page")]
+        words_per_page: u32,
+    }
+    let path = repo_path.join("Global Material").join("Config.yml");
+    let words_per_page = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<PageOnly>(&c).ok())
+        .map(|p| p.words_per_page)
+        .unwrap_or_else(default_words_per_page);
+    page_size_or_default(words_per_page)
+}
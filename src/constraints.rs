@@ -0,0 +1,198 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+// ─── Output types ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct ConstraintViolation {
+    /// The Config.yml constraint, verbatim.
+    pub constraint: String,
+    /// What the heuristic matched, e.g. `forbidden term "dragon"`.
+    pub finding: String,
+    /// Up to ~120 characters of prose around the match.
+    pub excerpt: String,
+}
+
+// ─── Rule recognition ─────────────────────────────────────────────────────────
+
+/// A constraint the close-time checker knows how to test. Constraints that
+/// match none of these shapes are still sent to the engine, just not checked.
+enum Rule {
+    /// `No "dragons"`, `never mention "the Gate"` — quoted terms must not appear.
+    ForbiddenTerms(Vec<String>),
+    /// `No chapters from Mara's POV` — Mara must not be the perceiving character.
+    NoPov(String),
+    /// `No flashbacks after chapter 10`.
+    NoFlashbacksAfter(u32),
+}
+
+fn negated_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(no|never|avoid|don't|do not)\b").unwrap())
+}
+
+fn quoted_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#""([^"]+)"|“([^”]+)”"#).unwrap())
+}
+
+fn pov_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(\p{Lu}[\p{L}-]+)['’]s\s+(?i:pov|point of view|perspective)").unwrap()
+    })
+}
+
+fn flashback_rule_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)flashbacks?\b.*\b(?:after|beyond|past|from)\s+chapter\s+(\d+)").unwrap()
+    })
+}
+
+/// Phrases that usually open a flashback in past-tense narration.
+fn flashback_cue_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(years (?:earlier|ago|before)|long ago|back then|as a child|flashback|remembered the (?:day|night|summer|winter))\b",
+        )
+        .unwrap()
+    })
+}
+
+fn parse_rule(constraint: &str) -> Option<Rule> {
+    if !negated_re().is_match(constraint) {
+        return None;
+    }
+    if let Some(c) = flashback_rule_re().captures(constraint) {
+        return c[1].parse().ok().map(Rule::NoFlashbacksAfter);
+    }
+    if let Some(c) = pov_re().captures(constraint) {
+        return Some(Rule::NoPov(c[1].to_string()));
+    }
+    let terms: Vec<String> = quoted_re()
+        .captures_iter(constraint)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    (!terms.is_empty()).then_some(Rule::ForbiddenTerms(terms))
+}
+
+// ─── Checking ─────────────────────────────────────────────────────────────────
+
+pub(crate) fn excerpt(prose: &str, start: usize, end: usize) -> String {
+    let before: String = prose[..start]
+        .chars()
+        .rev()
+        .take(50)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = prose[end..].chars().take(50).collect();
+    format!("{}{}{}", before, &prose[start..end], after)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Heuristically test session prose against the Config.yml `constraints`.
+/// Only recognised shapes are checked (quoted forbidden terms, a named
+/// character's POV, flashbacks after a chapter); one violation is reported per
+/// constraint, at its first match.
+pub fn check(
+    constraints: &[String],
+    prose: &str,
+    current_chapter: u32,
+) -> Vec<ConstraintViolation> {
+    let mut violations = Vec::new();
+    for constraint in constraints {
+        let found = match parse_rule(constraint) {
+            None => None,
+            Some(Rule::ForbiddenTerms(terms)) => terms.iter().find_map(|term| {
+                let re = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term))).ok()?;
+                re.find(prose)
+                    .map(|m| (format!("forbidden term \"{}\"", term), m.start(), m.end()))
+            }),
+            Some(Rule::NoPov(name)) => {
+                // Third-person limited: the POV character is the one whose
+                // inner life the narration reports.
+                let re = Regex::new(&format!(
+                    r"\b{}\s+(?:thought|felt|wondered|realized|realised|knew|remembered|sensed|feared|hoped)\b",
+                    regex::escape(&name)
+                ))
+                .ok();
+                re.and_then(|re| re.find(prose)).map(|m| {
+                    (
+                        format!("narration follows {}'s thoughts", name),
+                        m.start(),
+                        m.end(),
+                    )
+                })
+            }
+            Some(Rule::NoFlashbacksAfter(chapter)) if current_chapter > chapter => {
+                flashback_cue_re().find(prose).map(|m| {
+                    (
+                        format!("flashback cue in chapter {}", current_chapter),
+                        m.start(),
+                        m.end(),
+                    )
+                })
+            }
+            Some(Rule::NoFlashbacksAfter(_)) => None,
+        };
+        if let Some((finding, start, end)) = found {
+            violations.push(ConstraintViolation {
+                constraint: constraint.clone(),
+                finding,
+                excerpt: excerpt(prose, start, end),
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn forbidden_term_matches_whole_word_case_insensitively() {
+        let c = rules(&["No \"dragon\" anywhere in the book"]);
+        assert_eq!(check(&c, "The dragonfly hovered.", 1).len(), 0);
+        let v = check(&c, "A Dragon circled the tower.", 1);
+        assert_eq!(v.len(), 1);
+        assert!(v[0].excerpt.contains("Dragon circled"));
+    }
+
+    #[test]
+    fn pov_rule_flags_named_characters_thoughts() {
+        let c = rules(&["No chapters from Mara's POV"]);
+        assert_eq!(check(&c, "Kael watched Mara leave.", 1).len(), 0);
+        assert_eq!(check(&c, "Mara wondered if he knew.", 1).len(), 1);
+    }
+
+    #[test]
+    fn flashback_rule_only_applies_after_chapter() {
+        let c = rules(&["No flashbacks after chapter 10"]);
+        let prose = "Years earlier, the city had burned.";
+        assert_eq!(check(&c, prose, 10).len(), 0);
+        assert_eq!(check(&c, prose, 11).len(), 1);
+    }
+
+    #[test]
+    fn unrecognised_constraints_are_not_checked() {
+        let c = rules(&[
+            "Keep the tone hopeful",
+            "No chapters from the antagonist's POV",
+        ]);
+        assert!(check(&c, "Mara wondered. Years earlier, dragons.", 20).is_empty());
+    }
+}
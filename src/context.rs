@@ -1,14 +1,31 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
 use std::path::Path;
 use std::sync::OnceLock;
+use std::time::Duration;
 use tracing::{info, warn};
 
-use crate::config::Config;
-use crate::git;
+use crate::beats::Beat;
+use crate::config::{Config, PartInfo};
+use crate::edits::{self, HumanEditGroups};
+use crate::front_matter::{self, FrontMatter};
+use crate::git::{self, Branches};
+use crate::metrics;
+use crate::notify::{self, Event};
+use crate::obsidian;
+use crate::prose_lint::ProseHint;
+use crate::sequence::{self, Next, Unit, UnitKind};
+use crate::signals;
 use crate::state::InkState;
+use crate::storage;
+use crate::threads::Setup;
+use crate::timeline::TimelineEntry;
+use crate::timings::StepTimer;
+use crate::wordcount::WordCounter;
 
 // ─── Shared regex (compiled once) ────────────────────────────────────────────
 
@@ -47,7 +64,17 @@ pub struct FileContent {
 #[derive(Debug, Serialize)]
 pub struct ChapterInfo {
     pub path: String,
+    /// "Chapter 3", "Prologue", "Interlude A", …
+    pub label: String,
+    pub kind: UnitKind,
+    /// Outline YAML front matter (status, POV, target words, …), when present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<FrontMatter>,
+    /// Outline text after the front matter.
     pub content: String,
+    /// Items of the outline's beats list, numbered for `--beats-done`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub beats: Vec<Beat>,
     pub modified_today: bool,
 }
 
@@ -69,6 +96,15 @@ pub struct CurrentReview {
     pub instructions: Vec<Instruction>,
 }
 
+/// Compact "previously on" block: the most recent narrative summaries plus the
+/// closing paragraphs of validated prose. Kept separate from `current_review`
+/// so continuity survives even when the rolling window is empty or truncated.
+#[derive(Debug, Default, Serialize)]
+pub struct Recap {
+    pub summaries: Vec<String>,
+    pub closing_prose: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct WordCount {
     pub total: u32,
@@ -76,22 +112,78 @@ pub struct WordCount {
     pub remaining: u32,
 }
 
+/// The session-open payload. Serialization is deterministic for a given repo
+/// state — fields in declaration order, `global_material` and `human_edits` by
+/// path, instructions in document order — so the payload hashes the same on
+/// every run (downstream prompt caches key on it). Covered by golden-file tests.
 #[derive(Debug, Serialize)]
 pub struct SessionPayload {
     pub session_already_run: bool,
     pub kill_requested: bool,
     pub stale_lock_recovered: bool,
+    /// Holder of the active lock when `session_already_run`; None otherwise.
+    pub lock_holder: Option<LockHolder>,
+    /// ID of the session this call opened; must be passed back to session-close.
+    /// None when no session was opened (already running or kill requested).
+    pub session_id: Option<String>,
     pub snapshot_tag: String,
     pub human_edits: Vec<String>,
+    /// `human_edits` classified by word-level diff: prose, material, instructions, other.
+    pub human_edit_groups: HumanEditGroups,
     pub config: ConfigSnapshot,
+    /// Config.yml `constraints`, verbatim.
+    pub constraints: Vec<String>,
+    /// Global Material, with a series' canon files merged in.
     pub global_material: Vec<FileContent>,
+    /// The series canon merged into `global_material` and `timeline`;
+    /// omitted outside a series.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<crate::series::SeriesCanon>,
+    /// Global Material/Metadata.yml, for reference only — the engine never
+    /// edits it; omitted when the book has none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<crate::metadata::Metadata>,
+    /// Wiki-links of the Global Material and the notes they reach; Obsidian mode only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wiki_links: Option<obsidian::WikiLinks>,
+    /// Chapters material/Titles.yml, by chapter number; omitted when empty.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub chapter_titles: BTreeMap<u32, String>,
     pub chapters: Chapters,
     pub current_review: CurrentReview,
+    pub recap: Recap,
+    /// Last `timeline_context_entries` rows of Global Material/Timeline.md;
+    /// omitted when it has none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineEntry>,
+    /// Threads.yml setups awaiting payoff that are due: planned for this
+    /// chapter or earlier, or any in the book's last chapters. Omitted when none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending_setups: Vec<Setup>,
+    /// Prose lint findings on `current_review.content`, at most
+    /// `prose_lint.revision_hints`; omitted when none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub revision_hints: Vec<ProseHint>,
+    /// Global Material/Crutches.yml, then the manuscript's most repeated
+    /// phrases, at most `phrases_to_avoid`; omitted when none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub phrases_to_avoid: Vec<String>,
     pub word_count: WordCount,
     pub chapter_close_suggested: bool,
     pub current_chapter_word_count: u32,
     pub chapter_progress_pct: u8,
     pub session_type: String,
+    /// Fetch/push attempts retried after network errors; omitted when none.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub network_retries: u32,
+    /// Milliseconds spent in each step (`fetch`, `human_edits`, `merge`,
+    /// `tag`, `push`, `lock`, `load_files`, …) and `total`. Last, so the
+    /// numbers that change every run stay out of the prefix prompt caches key on.
+    pub timings: BTreeMap<String, u64>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
 }
 
 #[derive(Debug, Serialize)]
@@ -102,11 +194,18 @@ pub struct ConfigSnapshot {
     pub words_per_session: u32,
     pub summary_context_entries: usize,
     pub words_per_chapter: u32,
+    /// Words that complete the current unit, per-chapter overrides applied.
+    pub unit_target_words: u32,
     pub current_chapter: u32,
+    /// Label of the unit being written — "Chapter 3", or "Prologue" etc.
+    pub current_unit: String,
+    /// Config.yml `parts:` entry holding the current chapter, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_part: Option<PartInfo>,
 }
 
 impl ConfigSnapshot {
-    fn new(config: &Config, current_chapter: u32) -> Self {
+    fn new(config: &Config, current_chapter: u32, unit: &Unit) -> Self {
         ConfigSnapshot {
             target_length: config.target_length,
             chapter_count: config.chapter_count,
@@ -114,7 +213,10 @@ impl ConfigSnapshot {
             words_per_session: config.words_per_session,
             summary_context_entries: config.summary_context_entries,
             words_per_chapter: config.words_per_chapter,
+            unit_target_words: unit.target_words(config),
             current_chapter,
+            current_unit: unit.label.clone(),
+            current_part: config.part_of(current_chapter),
         }
     }
 }
@@ -129,29 +231,257 @@ fn kill_path(repo: &Path) -> std::path::PathBuf {
     repo.join(".ink-kill")
 }
 
-/// Returns age of the lock file in minutes, or None if no lock exists.
+/// Contents of `.ink-running`. Locks written before session IDs existed hold
+/// only the RFC 3339 timestamp; they load with `session_id: None`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub started_at: DateTime<Utc>,
+    pub session_id: Option<String>,
+    /// Machine, process, and agent that took the lock; absent in older locks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Classified human edits from session-open, used for the Changelog at close.
+    #[serde(default)]
+    pub human_edits: HumanEditGroups,
+    /// Outline of the unit opened, whose beats `session-close --beats-done`
+    /// checks off even after an `advance-chapter`; absent in older locks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<String>,
+    /// Last `heartbeat` renewal; stale detection counts from here when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_at: Option<DateTime<Utc>>,
+}
+
+/// Who holds the session lock, as shown by `status` and a `session_already_run`
+/// payload. `.ink-running` minus the human edits it carries for the Changelog.
+#[derive(Debug, Serialize)]
+pub struct LockHolder {
+    pub session_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    pub host: Option<String>,
+    pub pid: Option<u32>,
+    pub agent: Option<String>,
+    /// Minutes since the last heartbeat (or the start).
+    pub idle_minutes: i64,
+}
+
+impl LockInfo {
+    pub fn holder(&self) -> LockHolder {
+        LockHolder {
+            session_id: self.session_id.clone(),
+            started_at: self.started_at,
+            heartbeat_at: self.heartbeat_at,
+            host: self.host.clone(),
+            pid: self.pid,
+            agent: self.agent.clone(),
+            idle_minutes: self.idle_minutes(),
+        }
+    }
+
+    /// Last sign of life: the newest heartbeat, or the start time.
+    pub fn last_seen(&self) -> DateTime<Utc> {
+        self.heartbeat_at
+            .map_or(self.started_at, |h| h.max(self.started_at))
+    }
+
+    /// Minutes since `last_seen`.
+    pub fn idle_minutes(&self) -> i64 {
+        Utc::now()
+            .signed_duration_since(self.last_seen())
+            .num_minutes()
+    }
+}
+
+/// Parse `.ink-running`, or None if no (readable) lock exists.
+pub fn read_lock(repo: &Path) -> Option<LockInfo> {
+    let content = std::fs::read_to_string(lock_path(repo)).ok()?;
+    if let Ok(info) = serde_yaml::from_str::<LockInfo>(&content) {
+        return Some(info);
+    }
+    let started_at = content.trim().parse().ok()?;
+    Some(LockInfo {
+        started_at,
+        session_id: None,
+        host: None,
+        pid: None,
+        agent: None,
+        human_edits: HumanEditGroups::default(),
+        outline: None,
+        heartbeat_at: None,
+    })
+}
+
+/// `.ink-running` as seen by the rest of the world: heartbeats are committed
+/// on `draft` (main only fast-forwards at close), so the working-tree lock is
+/// refreshed from the local and remote draft branches when they carry a newer
+/// heartbeat for the same session.
+pub fn read_live_lock(repo: &Path) -> Option<LockInfo> {
+    let mut lock = read_lock(repo)?;
+    let branches = git::branches(repo);
+    for rev in [branches.draft.clone(), branches.remote_draft()] {
+        let Ok(content) = git::run_git(repo, &["show", &format!("{}:.ink-running", rev)]) else {
+            continue;
+        };
+        let Ok(other) = serde_yaml::from_str::<LockInfo>(&content) else {
+            continue;
+        };
+        if other.session_id.is_some()
+            && other.session_id == lock.session_id
+            && other.last_seen() > lock.last_seen()
+        {
+            lock.heartbeat_at = other.heartbeat_at;
+        }
+    }
+    Some(lock)
+}
+
+/// Minutes since the working-tree lock's last sign of life (start or
+/// heartbeat), or None if no lock exists. File-only; session-open uses
+/// `read_live_lock` to see heartbeats pushed from another clone.
 pub fn read_lock_age(repo: &Path) -> Option<i64> {
-    let path = lock_path(repo);
-    let content = std::fs::read_to_string(&path).ok()?;
-    let timestamp: DateTime<Utc> = content.trim().parse().ok()?;
-    let age = Utc::now().signed_duration_since(timestamp).num_minutes();
-    Some(age)
+    read_lock(repo).map(|lock| lock.idle_minutes())
 }
 
-/// Writes .ink-running with current UTC timestamp, commits and pushes.
-pub fn create_lock(repo: &Path) -> Result<()> {
-    let now = Utc::now().to_rfc3339();
-    std::fs::write(lock_path(repo), &now).with_context(|| "Failed to write .ink-running")?;
+/// How often a running session renews its lock: a third of the timeout, so two
+/// beats can be missed before the lock looks stale.
+pub fn heartbeat_interval(repo: &Path) -> Duration {
+    let timeout = Config::load(repo)
+        .map(|c| c.session_timeout_minutes)
+        .unwrap_or(60);
+    Duration::from_secs((timeout.max(0) as u64 * 60 / 3).max(60))
+}
+
+/// Refresh `heartbeat_at` in `.ink-running` so a long session is not declared
+/// stale. Commits the lock alone on `draft` and pushes draft (best-effort).
+/// With `session_id`, only that session's lock is renewed.
+///
+/// Skipped while a session-close is journaled: its recovery keys off the
+/// draft head, which a heartbeat commit would move.
+pub fn heartbeat(repo: &Path, session_id: Option<&str>) -> Result<serde_json::Value> {
+    if crate::journal::is_pending(repo) {
+        return Ok(serde_json::json!({ "status": "skipped", "reason": "close_in_progress" }));
+    }
+    let Some(mut lock) = read_lock(repo) else {
+        return Ok(serde_json::json!({ "status": "no_lock" }));
+    };
+    if let Some(id) = session_id {
+        if lock.session_id.as_deref() != Some(id) {
+            return Ok(serde_json::json!({
+                "status": "not_owner",
+                "lock_session_id": lock.session_id,
+            }));
+        }
+    }
+    let branch = git::run_git(repo, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let Branches { draft, remote, .. } = git::branches(repo);
+    anyhow::ensure!(
+        branch == draft,
+        "heartbeat runs during a session, on the {} branch (currently on {})",
+        draft,
+        branch
+    );
+
+    let now = Utc::now();
+    lock.heartbeat_at = Some(now);
+    let content = serde_yaml::to_string(&lock).with_context(|| "Failed to serialize lock")?;
+    std::fs::write(lock_path(repo), content).with_context(|| "Failed to write .ink-running")?;
+    git::engine_commit(
+        repo,
+        &["-q", "-m", "chore: session heartbeat", "--", ".ink-running"],
+    )
+    .with_context(|| "Failed to commit heartbeat")?;
+
+    // draft is force-reset to main at every session-open, so after a stolen
+    // stale lock origin/draft may hold a dead session's heartbeats.
+    let pushed = match git::run_git(repo, &["push", "--force-with-lease", &remote, &draft]) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Could not push heartbeat (renewed locally only): {}", e);
+            false
+        }
+    };
+    info!("Session lock renewed at {}", now);
+    Ok(serde_json::json!({
+        "status": "renewed",
+        "session_id": lock.session_id,
+        "heartbeat_at": now,
+        "pushed": pushed,
+    }))
+}
+
+/// New session ID: UTC start time plus 32 random bits, e.g. `20261017T091500Z-3fa2c9d1`.
+/// Std's randomly keyed hasher is enough here — IDs only need to differ between
+/// sessions, not resist guessing.
+fn new_session_id(now: DateTime<Utc>) -> String {
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    format!("{}-{:08x}", now.format("%Y%m%dT%H%M%SZ"), random as u32)
+}
+
+/// This machine's name: `$HOSTNAME`, else the kernel's, else `hostname`.
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| {
+            std::process::Command::new("hostname")
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        })
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+}
+
+/// Who is running the session: `$INK_AGENT` (set by the gateway, e.g. a cron
+/// job name), else the binary name (`ink-cli` or `ink-gateway-mcp`).
+fn agent_name() -> String {
+    std::env::var("INK_AGENT")
+        .ok()
+        .filter(|a| !a.trim().is_empty())
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()?
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Writes .ink-running with the start time, a fresh session ID, the
+/// classified human edits, and the unit's outline, then commits and pushes.
+/// Returns the session ID.
+pub fn create_lock(repo: &Path, human_edits: &HumanEditGroups, outline: &str) -> Result<String> {
+    let started_at = Utc::now();
+    let lock = LockInfo {
+        started_at,
+        session_id: Some(new_session_id(started_at)),
+        host: hostname(),
+        pid: Some(std::process::id()),
+        agent: Some(agent_name()),
+        human_edits: human_edits.clone(),
+        outline: Some(outline.to_string()),
+        heartbeat_at: None,
+    };
+    let content = serde_yaml::to_string(&lock).with_context(|| "Failed to serialize lock")?;
+    std::fs::write(lock_path(repo), content).with_context(|| "Failed to write .ink-running")?;
 
     git::run_git(repo, &["add", ".ink-running"])
         .with_context(|| "Failed to git add .ink-running")?;
-    git::run_git(repo, &["commit", "-m", "chore: open session lock"])
+    git::engine_commit(repo, &["-m", "chore: open session lock"])
         .with_context(|| "Failed to commit .ink-running")?;
-    git::run_git(repo, &["push", "origin", "main"])
-        .with_context(|| "Failed to push .ink-running")?;
+    git::push_main(repo).with_context(|| "Failed to push .ink-running")?;
 
-    info!("Session lock created at {}", now);
-    Ok(())
+    let session_id = lock.session_id.unwrap_or_default();
+    info!("Session lock created at {} ({})", started_at, session_id);
+    Ok(session_id)
 }
 
 /// Removes the stale lock from the local filesystem only.
@@ -168,13 +498,36 @@ pub fn remove_stale_lock(repo: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Releases a lock this process just created, after an interrupt or a
+/// scheduled run whose agent failed.
+/// Returns to main (the draft branch may already be checked out), aborts any
+/// half-done merge, then commits and pushes the lock removal. Best-effort:
+/// every failure is logged, since the caller is already returning an error.
+pub(crate) fn release_interrupted_lock(repo: &Path) {
+    warn!("Interrupted after lock creation — releasing .ink-running");
+    git::abort_merge_if_in_progress(repo);
+    let Branches { main, remote, .. } = git::branches(repo);
+    let steps: [&dyn Fn() -> Result<String>; 4] = [
+        &|| git::run_git(repo, &["checkout", &main]),
+        &|| git::run_git(repo, &["rm", "-f", "--ignore-unmatch", ".ink-running"]),
+        &|| git::engine_commit(repo, &["-m", "chore: release session lock after interrupt"]),
+        &|| git::run_git(repo, &["push", &remote, &main]),
+    ];
+    for step in steps {
+        if let Err(e) = step() {
+            warn!("Lock release step failed: {}", e);
+            return;
+        }
+    }
+    info!("Session lock released");
+}
+
 /// Removes .ink-kill via git rm, commits, and pushes.
 pub fn delete_kill_file(repo: &Path) -> Result<()> {
     git::run_git(repo, &["rm", "-f", ".ink-kill"]).with_context(|| "Failed to git rm .ink-kill")?;
-    git::run_git(repo, &["commit", "-m", "chore: acknowledge kill request"])
+    git::engine_commit(repo, &["-m", "chore: acknowledge kill request"])
         .with_context(|| "Failed to commit kill acknowledgement")?;
-    git::run_git(repo, &["push", "origin", "main"])
-        .with_context(|| "Failed to push kill acknowledgement")?;
+    git::push_main(repo).with_context(|| "Failed to push kill acknowledgement")?;
     info!("Kill file removed");
     Ok(())
 }
@@ -198,12 +551,26 @@ pub fn load_global_material(repo: &Path, summary_entries: usize) -> Result<Vec<F
                 Some(n) => n.to_string_lossy().to_string(),
                 None => return Ok(None),
             };
-            // Skip Config.yml — it's surfaced separately
-            if filename == "Config.yml" {
+            // Skip Config.yml, Timeline.md, Crutches.yml and Metadata.yml — they're surfaced separately
+            if matches!(
+                filename.as_str(),
+                "Config.yml" | "Timeline.md" | "Crutches.yml" | "Metadata.yml"
+            ) {
                 return Ok(None);
             }
-            let mut content = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read Global Material/{}", filename))?;
+            // Images and other binaries belong in Assets/, which is never loaded
+            let mut content = match std::fs::read_to_string(&path) {
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                    warn!(
+                        "Skipping Global Material/{} — not UTF-8 text (move it to Assets/)",
+                        filename
+                    );
+                    return Ok(None);
+                }
+                read => {
+                    read.with_context(|| format!("Failed to read Global Material/{}", filename))?
+                }
+            };
             if filename == "Summary.md" {
                 content = truncate_summary(&content, summary_entries);
             }
@@ -275,23 +642,89 @@ fn truncate_to_last_words(text: &str, max_words: u32) -> String {
     paras[start_idx..].join("\n\n")
 }
 
-pub fn load_chapter(repo: &Path, num: u32, human_edits: &[String]) -> Result<Option<ChapterInfo>> {
-    let relative = format!("Chapters material/Chapter_{:02}.md", num);
+/// Number of trailing Full_Book.md paragraphs included in the session recap.
+const RECAP_CLOSING_PARAGRAPHS: usize = 3;
+
+/// Build the session recap from the last `summary_entries` Summary.md paragraphs
+/// and the final prose paragraphs of Full_Book.md (followed by Review/pending.md
+/// when prose awaits approval, its reworks applied in place). HTML comments (managed header, `<!-- PAGE N -->`
+/// markers) are skipped so only prose reaches the engine.
+pub fn load_recap(repo: &Path, summary_entries: usize) -> Result<Recap> {
+    let summary_path = repo.join("Global Material").join("Summary.md");
+    let summaries = if summary_path.exists() {
+        let content =
+            std::fs::read_to_string(&summary_path).with_context(|| "Failed to read Summary.md")?;
+        truncate_summary(&content, summary_entries)
+            .split("\n\n")
+            .map(|p| p.to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let mut book_text = storage::compiled_book(repo)?.unwrap_or_default();
+    // Prose awaiting approval comes after the book: the engine continues from
+    // it. Its reworks replace their originals in place, as approval will.
+    let pending_path = repo.join(crate::maintenance::PENDING_PROSE_FILE);
+    let pending = if pending_path.exists() {
+        let content = std::fs::read_to_string(&pending_path)
+            .with_context(|| "Failed to read Review/pending.md")?;
+        let (remainder, _) = crate::book::apply_reworked_replacements(&mut book_text, &content);
+        crate::book::strip_engine_markers(&remainder)
+    } else {
+        String::new()
+    };
+    let closing_prose = closing_paragraphs(
+        &format!("{}\n\n{}", book_text, pending),
+        RECAP_CLOSING_PARAGRAPHS,
+    );
+
+    Ok(Recap {
+        summaries,
+        closing_prose,
+    })
+}
+
+/// Return the last `n` prose paragraphs of `text`, ignoring HTML comments.
+fn closing_paragraphs(text: &str, n: usize) -> String {
+    let paras: Vec<String> = crate::book::strip_html_comments(text)
+        .split("\n\n")
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let start = paras.len().saturating_sub(n);
+    paras[start..].join("\n\n")
+}
+
+pub fn load_unit(repo: &Path, unit: &Unit, human_edits: &[String]) -> Result<Option<ChapterInfo>> {
+    let relative = unit.path();
     let path = repo.join(&relative);
 
     if !path.exists() {
         return Ok(None);
     }
 
-    let content = std::fs::read_to_string(&path)
-        .with_context(|| format!("Failed to read chapter {}", num))?;
+    let content =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", relative))?;
 
-    let modified_today = human_edits
-        .iter()
-        .any(|f| f.contains(&format!("Chapter_{:02}.md", num)));
+    let modified_today = human_edits.iter().any(|f| f == &relative);
+
+    // A malformed header should not block the session: warn and pass it through
+    let (front_matter, content) = match front_matter::split(&content) {
+        Ok((front, body)) => (front, body.to_string()),
+        Err(e) => {
+            warn!("{}: {:#}", relative, e);
+            (None, content)
+        }
+    };
 
     Ok(Some(ChapterInfo {
         path: relative,
+        label: unit.label.clone(),
+        kind: unit.kind,
+        front_matter,
+        beats: crate::beats::parse(&content),
         content,
         modified_today,
     }))
@@ -320,21 +753,17 @@ pub fn extract_ink_instructions(text: &str) -> (String, Vec<Instruction>) {
     (stripped, instructions)
 }
 
-pub fn load_word_count(repo: &Path, target: u32) -> Result<WordCount> {
-    let path = repo.join("Current version").join("Full_Book.md");
-
-    if !path.exists() {
+pub fn load_word_count(repo: &Path, target: u32, counter: &WordCounter) -> Result<WordCount> {
+    let Some(content) = storage::compiled_book(repo)? else {
         return Ok(WordCount {
             total: 0,
             target,
             remaining: target,
         });
-    }
-
-    let content = std::fs::read_to_string(&path).with_context(|| "Failed to read Full_Book.md")?;
+    };
 
     // Use the same counter as session-close so both modules always agree.
-    let total = crate::book::count_prose_words(&content);
+    let total = counter.count(&content);
     let remaining = target.saturating_sub(total);
 
     Ok(WordCount {
@@ -347,13 +776,31 @@ pub fn load_word_count(repo: &Path, target: u32) -> Result<WordCount> {
 // ─── Main orchestration ───────────────────────────────────────────────────────
 
 pub fn session_open(repo: &Path) -> Result<SessionPayload> {
+    git::take_network_retries();
+    let mut timer = StepTimer::start();
+    // 0. Finish or undo an interrupted session-close before touching branches
+    timer.step("recover");
+    crate::journal::recover(repo)?;
+
     // 1. Fetch remote state and switch to main — do NOT merge yet so that
     //    uncommitted local edits (e.g. INK instructions saved in an IDE) are
     //    detected and committed before origin/main can overwrite them.
+    signals::checkpoint("fetch")?;
     info!("Step 1: fetch and checkout main");
+    timer.step("fetch");
     git::preflight_fetch_and_checkout(repo)?;
 
-    // 2. Check for kill file — must happen before any git writes
+    // 2. Check for kill file — must happen before any git writes. `ink-cli kill`
+    //    pushes it from the author's clone, so origin/main counts too; it is
+    //    fast-forwarded in so the acknowledgement commit can remove it.
+    let origin_main = git::branches(repo).remote_main();
+    if !kill_path(repo).exists() && git::file_exists_at(repo, &origin_main, ".ink-kill") {
+        info!(
+            "Kill file found on {} — fast-forwarding to acknowledge it",
+            origin_main
+        );
+        git::merge_ff_origin_main(repo)?;
+    }
     let kill_requested = kill_path(repo).exists();
     if kill_requested {
         info!("Kill file detected — acknowledging and aborting");
@@ -367,8 +814,11 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
             session_already_run: false,
             kill_requested: true,
             stale_lock_recovered: false,
+            lock_holder: None,
+            session_id: None,
             snapshot_tag: String::new(),
             human_edits: vec![],
+            human_edit_groups: HumanEditGroups::default(),
             config: ConfigSnapshot {
                 target_length: 0,
                 chapter_count: 0,
@@ -376,9 +826,17 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
                 words_per_session: 0,
                 summary_context_entries: 5,
                 words_per_chapter: 3000,
+                unit_target_words: 3000,
                 current_chapter: 1,
+                current_unit: "Chapter 1".to_string(),
+                current_part: None,
             },
+            constraints: vec![],
             global_material: vec![],
+            series: None,
+            metadata: None,
+            wiki_links: None,
+            chapter_titles: BTreeMap::new(),
             chapters: Chapters {
                 current: None,
                 next: None,
@@ -387,6 +845,11 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
                 content: String::new(),
                 instructions: vec![],
             },
+            recap: Recap::default(),
+            timeline: vec![],
+            pending_setups: vec![],
+            revision_hints: vec![],
+            phrases_to_avoid: vec![],
             word_count: WordCount {
                 total: 0,
                 target: 0,
@@ -396,18 +859,28 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
             current_chapter_word_count: 0,
             chapter_progress_pct: 0,
             session_type: "writing".to_string(),
+            network_retries: git::take_network_retries(),
+            timings: timer.timings(),
         });
     }
 
     // 3. Load config and state
     info!("Step 3: loading config and state");
+    timer.step("load_state");
     let config = Config::load(repo)?;
-    let state = InkState::load(repo)?;
+    let mut state = InkState::load(repo)?;
+    let book_sequence = sequence::load_sequence(repo)?;
+    let book_words = storage::compiled_book(repo)?
+        .map(|content| WordCounter::new(&config).count(&content))
+        .unwrap_or(0);
+    let unit = sequence::opening_unit(&book_sequence, &state, book_words);
+    let unit_target = unit.target_words(&config);
 
     // 3b. Compute chapter close suggestion early — needed to decide whether to load
     //     the next chapter outline (skip it when not near a chapter boundary).
+    //     Prologues, interludes, and epilogues close after one session's words.
     let chapter_close_suggested =
-        state.current_chapter_word_count >= (config.words_per_chapter as f64 * 0.9) as u32;
+        state.current_chapter_word_count >= (unit_target as f64 * 0.9) as u32;
 
     // 4. Collect human edits BEFORE merging with origin so that local
     //    uncommitted changes (IDE saves, INK instructions, etc.) are captured
@@ -418,50 +891,80 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
     //    b) git diff origin/main → ALL diffs between local tree and remote,
     //       catching edits made when local HEAD was already behind origin
     info!("Step 4: collecting human edits (local working tree + diff vs origin)");
+    timer.step("human_edits");
     let mut human_edits = git::collect_modified_files(repo)?;
-    for f in git::collect_diffs_vs_remote(repo)? {
-        if !human_edits.contains(&f) {
-            human_edits.push(f);
-        }
-    }
+    human_edits.extend(git::collect_diffs_vs_remote(repo)?);
+    // Sorted so the payload does not depend on which method saw a file first
+    human_edits.sort();
+    human_edits.dedup();
+
+    // The commit the edits are measured against once they and origin/main are in
+    let edit_base = git::run_git(repo, &["rev-parse", "HEAD"]).ok();
 
     // 5. Commit human edits locally (no push — push_tags handles that below)
     if !human_edits.is_empty() {
+        signals::checkpoint("human edit commit")?;
         info!("Step 5: committing {} human edit(s)", human_edits.len());
         git::commit_human_edits(repo, &human_edits)?;
     }
 
     // 5b. Now safe to merge: local changes are committed, so the ff-merge
     //     cannot overwrite them.
-    info!("Step 5b: fast-forward merging origin/main");
-    git::merge_ff_origin_main(repo)?;
+    signals::checkpoint("merge")?;
+    info!("Step 5b: fast-forward merging {}", origin_main);
+    timer.step("merge");
+    if let Err(e) = git::merge_ff_origin_main(repo) {
+        git::abort_merge_if_in_progress(repo);
+        return Err(e);
+    }
+
+    // 5c. Classify the edits by word-level diff against the pre-edit commit
+    let human_edit_groups = edits::classify(repo, edit_base.as_deref(), &human_edits);
 
     // 6. Create snapshot tag
+    signals::checkpoint("snapshot tag")?;
     info!("Step 6: creating snapshot tag");
+    timer.step("tag");
     let snapshot_tag = git::create_snapshot_tag(repo)?;
 
     // 7. Push main + tags
+    signals::checkpoint("tag push")?;
     info!("Step 7: pushing main + tags");
-    git::push_tags(repo)?;
+    timer.step("push");
+    git::push_tags(repo).inspect_err(|e| crate::maintenance::notify_push_failed(repo, e))?;
 
     // 8. Check lock
     info!("Step 8: checking session lock");
+    timer.step("lock");
     let mut stale_lock_recovered = false;
 
-    match read_lock_age(repo) {
+    match read_live_lock(repo) {
         None => {
             // No lock — proceed normally
         }
-        Some(age) if age <= config.session_timeout_minutes => {
-            info!("Active lock found (age {}m) — session already running", age);
+        Some(lock) if lock.idle_minutes() <= config.session_timeout_minutes => {
+            info!(
+                "Active lock found (age {}m, held by {} on {}) — session already running",
+                lock.idle_minutes(),
+                lock.agent.as_deref().unwrap_or("unknown agent"),
+                lock.host.as_deref().unwrap_or("unknown host")
+            );
             return Ok(SessionPayload {
                 session_already_run: true,
                 kill_requested: false,
                 stale_lock_recovered: false,
+                lock_holder: Some(lock.holder()),
+                session_id: None,
                 snapshot_tag,
                 human_edits,
-                config: ConfigSnapshot::new(&config, state.current_chapter),
+                human_edit_groups,
+                config: ConfigSnapshot::new(&config, state.current_chapter, &unit),
+                constraints: config.constraints.clone(),
                 global_material: vec![],
+                series: None,
+                metadata: None,
+                wiki_links: None,
+                chapter_titles: BTreeMap::new(),
                 chapters: Chapters {
                     current: None,
                     next: None,
@@ -470,6 +973,11 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
                     content: String::new(),
                     instructions: vec![],
                 },
+                recap: Recap::default(),
+                timeline: vec![],
+                pending_setups: vec![],
+                revision_hints: vec![],
+                phrases_to_avoid: vec![],
                 word_count: WordCount {
                     total: 0,
                     target: config.target_length,
@@ -479,39 +987,91 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
                 current_chapter_word_count: state.current_chapter_word_count,
                 chapter_progress_pct: 0,
                 session_type: "writing".to_string(),
+                network_retries: git::take_network_retries(),
+                timings: timer.timings(),
             });
         }
-        Some(age) => {
-            warn!("Stale lock detected (age {}m) — recovering", age);
+        Some(lock) => {
+            warn!(
+                "Stale lock detected (age {}m) — recovering",
+                lock.idle_minutes()
+            );
             remove_stale_lock(repo)?;
             stale_lock_recovered = true;
+            notify::send(
+                repo,
+                Event::StaleLockRecovered,
+                &format!(
+                    "Session {} stopped renewing its lock {} minute(s) ago and was taken over by a new session.",
+                    lock.session_id.as_deref().unwrap_or("(unknown)"),
+                    lock.idle_minutes()
+                ),
+            );
         }
     }
 
     // 9. Create new session lock
+    signals::checkpoint("lock creation")?;
     info!("Step 9: creating session lock");
-    create_lock(repo)?;
+    let session_id = create_lock(repo, &human_edit_groups, &unit.path())?;
+
+    // From here on an interrupt must release the lock we just pushed, otherwise
+    // the next scheduled run would see an active session until the timeout.
+    let checkpoint = |step: &str| -> Result<()> {
+        signals::checkpoint(step).inspect_err(|_| release_interrupted_lock(repo))
+    };
 
     // 10. Setup draft branch
+    checkpoint("draft branch setup")?;
     info!("Step 10: setting up draft branch");
+    timer.step("draft");
     git::setup_draft_branch(repo)?;
 
     // 11. Load global material
     info!("Step 11: loading global material");
-    let global_material = load_global_material(repo, config.summary_context_entries)?;
+    timer.step("load_files");
+    let mut global_material = load_global_material(repo, config.summary_context_entries)?;
+    let series = match &config.series {
+        Some(series) => Some(crate::series::merge_into(
+            repo,
+            series,
+            &mut global_material,
+        )?),
+        None => None,
+    };
+    let wiki_links = if config.obsidian {
+        Some(obsidian::resolve_links(repo, &global_material)?)
+    } else {
+        None
+    };
 
-    // 12. Load current chapter
-    info!("Step 12: loading chapter {}", state.current_chapter);
-    let current_chapter = load_chapter(repo, state.current_chapter, &human_edits)?;
+    // 12. Load current unit (chapter, prologue, interlude, or epilogue). A fresh
+    //     book opening on a prologue records it, so the word count accrues to it.
+    info!("Step 12: loading {}", unit.label);
+    if state.current_unit.is_none() && unit.kind != UnitKind::Chapter {
+        sequence::set_current(&mut state, &unit, config.chapter_count);
+        state.save(repo)?;
+    }
+    let current_chapter = load_unit(repo, &unit, &human_edits)?;
 
-    // 13. Load next chapter only when chapter close is approaching — avoids sending
+    // 13. Load next unit only when chapter close is approaching — avoids sending
     //     the outline tokens every session when not near a chapter boundary.
     let next_chapter = if chapter_close_suggested {
-        info!(
-            "Step 13: chapter close suggested — loading next chapter {}",
-            state.current_chapter + 1
-        );
-        load_chapter(repo, state.current_chapter + 1, &human_edits)?
+        let next = match sequence::next_unit(&book_sequence, &unit, config.chapter_count) {
+            Next::Unit(u) => Some(u),
+            Next::MissingChapter(n) => Some(Unit::chapter(n)),
+            Next::End => None,
+        };
+        match next {
+            Some(next) => {
+                info!(
+                    "Step 13: chapter close suggested — loading next unit {}",
+                    next.label
+                );
+                load_unit(repo, &next, &human_edits)?
+            }
+            None => None,
+        }
     } else {
         info!("Step 13: chapter close not suggested — skipping next chapter load");
         None
@@ -550,15 +1110,37 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
         }
     }
 
+    // 14b'. Prose lint hints on the passage under review
+    let mut revision_hints = crate::prose_lint::check(&stripped_review, &config.prose_lint);
+    revision_hints.truncate(config.prose_lint.revision_hints);
+
+    // 14c. Build the recap from Summary.md + the tail of Full_Book.md
+    info!("Step 14c: building recap");
+    let recap = load_recap(repo, config.summary_context_entries)?;
+    // A series' canon chronology comes before this book's
+    let timeline = match &series {
+        Some(series) => crate::timeline::last(
+            [series.timeline.clone(), crate::timeline::load(repo)?].concat(),
+            config.timeline_context_entries,
+        ),
+        None => crate::timeline::recent(repo, config.timeline_context_entries)?,
+    };
+    let phrases_to_avoid = crate::phrases::to_avoid(
+        repo,
+        &crate::storage::read_book(repo)?.unwrap_or_default(),
+        config.phrases_to_avoid,
+    )?;
+
     // 15. Load word count
     info!("Step 15: loading word count");
-    let word_count = load_word_count(repo, config.target_length)?;
+    let word_count = load_word_count(repo, config.target_length, &WordCounter::new(&config))?;
 
     // 16. Build payload
+    checkpoint("payload output")?;
     let chapter_progress_pct = state
         .current_chapter_word_count
         .saturating_mul(100)
-        .checked_div(config.words_per_chapter)
+        .checked_div(unit_target)
         .unwrap_or(0)
         .min(100) as u8;
 
@@ -570,14 +1152,31 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
         "writing".to_string()
     };
 
+    metrics::session_opened();
     Ok(SessionPayload {
         session_already_run: false,
         kill_requested: false,
         stale_lock_recovered,
+        lock_holder: None,
+        session_id: Some(session_id),
         snapshot_tag,
         human_edits,
-        config: ConfigSnapshot::new(&config, state.current_chapter),
+        human_edit_groups,
+        config: ConfigSnapshot::new(&config, state.current_chapter, &unit),
+        constraints: config.constraints.clone(),
         global_material,
+        series,
+        // Like Titles.yml, a malformed file never blocks a session
+        metadata: crate::metadata::load(repo).unwrap_or_else(|e| {
+            tracing::warn!("{:#}", e);
+            None
+        }),
+        wiki_links,
+        // A malformed registry is the author's to fix; it never blocks a session
+        chapter_titles: crate::titles::load(repo).unwrap_or_else(|e| {
+            tracing::warn!("{:#}", e);
+            BTreeMap::new()
+        }),
         chapters: Chapters {
             current: current_chapter,
             next: next_chapter,
@@ -586,10 +1185,448 @@ pub fn session_open(repo: &Path) -> Result<SessionPayload> {
             content: stripped_review,
             instructions,
         },
+        recap,
+        timeline,
+        pending_setups: crate::threads::load(repo)
+            .map(|t| t.due(state.current_chapter, config.chapter_count))
+            .unwrap_or_else(|e| {
+                tracing::warn!("{:#}", e);
+                vec![]
+            }),
+        revision_hints,
+        phrases_to_avoid,
         word_count,
         chapter_close_suggested,
         current_chapter_word_count: state.current_chapter_word_count,
         chapter_progress_pct,
         session_type,
+        network_retries: git::take_network_retries(),
+        timings: timer.timings(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compare `json` (as printed by ink-cli) with `tests/golden/<name>`. Set
+    /// `INK_UPDATE_GOLDEN=1` to rewrite the file after an intentional payload change.
+    fn assert_golden(name: &str, json: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(name);
+        let actual = format!("{}\n", json);
+        if std::env::var_os("INK_UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing {} — run with INK_UPDATE_GOLDEN=1", name));
+        assert_eq!(
+            actual, expected,
+            "payload differs from tests/golden/{}",
+            name
+        );
+    }
+
+    /// A small initialized book at `<tmp>/book` with a bare origin, draft branch pushed.
+    fn book_fixture() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        git::run_git(
+            tmp.path(),
+            &["init", "-q", "--bare", "-b", "main", "origin.git"],
+        )
+        .unwrap();
+        let repo = tmp.path().join("book");
+        std::fs::create_dir_all(&repo).unwrap();
+        let files = [
+            (
+                "Global Material/Config.yml",
+                "target_length: 90000\nchapter_count: 3\nchapter_structure: linear\n\
+                 words_per_session: 1500\nwords_per_chapter: 3000\n",
+            ),
+            ("Global Material/Soul.md", "# Soul\n\nQuiet, exact prose.\n"),
+            (
+                "Global Material/Outline.md",
+                "# Outline\n\nA city remembers a fire.\n",
+            ),
+            (
+                "Global Material/Characters.md",
+                "# Characters\n\nMara, an archivist.\n",
+            ),
+            (
+                "Global Material/Lore.md",
+                "# Lore\n\nThe river has no name.\n",
+            ),
+            ("Global Material/Summary.md", "# Summary\n"),
+            (
+                "Chapters material/Chapter_01.md",
+                "# Chapter 1: Ash\n\nMara finds the ledger.\n\n\
+                 ## Beats\n\n1. Mara reads the ledger\n2. The ledger burns\n",
+            ),
+            (
+                "Chapters material/Chapter_02.md",
+                "# Chapter 2: Water\n\nThe river rises.\n",
+            ),
+            (
+                "Review/current.md",
+                "Mara opened the ledger at dawn.\n\n<!-- INK: make this colder -->\n",
+            ),
+            ("Current version/Full_Book.md", ""),
+        ];
+        for (rel, content) in files {
+            let path = repo.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "t@t"],
+            &["config", "user.name", "t"],
+            &["remote", "add", "origin", "../origin.git"],
+            &["add", "-A"],
+            &["commit", "-q", "-m", "init"],
+            &["push", "-q", "origin", "main", "main:draft"],
+        ] {
+            git::run_git(&repo, args).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn new_locks_record_host_pid_and_agent() {
+        let tmp = book_fixture();
+        let repo = tmp.path().join("book");
+        let session_id = create_lock(&repo, &HumanEditGroups::default(), "Chapter_01.md").unwrap();
+        let lock = read_lock(&repo).unwrap();
+        assert_eq!(lock.session_id, Some(session_id));
+        assert_eq!(lock.pid, Some(std::process::id()));
+        assert!(lock.host.is_some_and(|h| !h.is_empty()));
+        assert!(lock.agent.is_some_and(|a| !a.is_empty()));
+    }
+
+    #[test]
+    fn locks_from_older_versions_have_no_holder_details() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(".ink-running"),
+            "started_at: 2026-01-01T00:00:00Z\nsession_id: s1\n",
+        )
+        .unwrap();
+        let holder = read_lock(tmp.path()).unwrap().holder();
+        assert_eq!(holder.session_id.as_deref(), Some("s1"));
+        assert_eq!((holder.host, holder.pid, holder.agent), (None, None, None));
+
+        // The oldest locks are a bare timestamp
+        std::fs::write(tmp.path().join(".ink-running"), "2026-01-01T00:00:00Z\n").unwrap();
+        let lock = read_lock(tmp.path()).unwrap();
+        assert_eq!((lock.session_id, lock.pid), (None, None));
+    }
+
+    #[test]
+    fn holder_idle_time_counts_from_the_last_heartbeat() {
+        let now = Utc::now();
+        let lock = LockInfo {
+            started_at: now - chrono::Duration::hours(2),
+            session_id: Some("s1".to_string()),
+            host: Some("desk".to_string()),
+            pid: Some(42),
+            agent: Some("ink-cli".to_string()),
+            human_edits: HumanEditGroups::default(),
+            outline: None,
+            heartbeat_at: Some(now - chrono::Duration::minutes(10)),
+        };
+        let holder = serde_json::to_value(lock.holder()).unwrap();
+        assert_eq!(holder["idle_minutes"], 10);
+        assert_eq!(
+            (&holder["host"], &holder["pid"]),
+            (&serde_json::json!("desk"), &serde_json::json!(42))
+        );
+        assert!(holder.get("human_edits").is_none());
+    }
+
+    #[test]
+    fn session_payloads_match_golden_files() {
+        let tmp = book_fixture();
+        let repo = tmp.path().join("book");
+        // Author edits in several directories, including a new file
+        std::fs::write(
+            repo.join("Global Material/Lore.md"),
+            "# Lore\n\nThe river has a secret name.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("Global Material/Places.md"),
+            "# Places\n\nThe Archive.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("Chapters material/Chapter_01.md"),
+            "# Chapter 1: Ash\n\nMara finds the ledger. It is warm.\n\n\
+             ## Beats\n\n1. Mara reads the ledger\n2. The ledger burns\n",
+        )
+        .unwrap();
+
+        let mut open = session_open(&repo).unwrap();
+        let session_id = open.session_id.clone().unwrap();
+        // The only run-dependent values: a timestamped ID and tag, and timings
+        open.timings.values_mut().for_each(|ms| *ms = 0);
+        let json = serde_json::to_string_pretty(&open)
+            .unwrap()
+            .replace(&session_id, "<session-id>")
+            .replace(&open.snapshot_tag, "<snapshot-tag>");
+        assert_golden("session_open.json", &json);
+
+        let prose = "<!-- INK:REWORKED:START -->\nMara opened the ledger at a cold dawn.\n\
+                     <!-- INK:REWORKED:END -->\n<!-- INK:NEW:START -->\n\
+                     The ink had not yet dried.\n<!-- INK:NEW:END -->\n";
+        // A dry run predicts the close without writing anything
+        let preview = crate::maintenance::preview_close(
+            &repo,
+            &session_id,
+            prose,
+            Some("Mara reads the ledger."),
+            false,
+        )
+        .unwrap();
+        assert_eq!(git::run_git(&repo, &["status", "--porcelain"]).unwrap(), "");
+        assert!(preview
+            .full_book_append
+            .contains("Mara opened the ledger at dawn."));
+
+        let mut close = crate::maintenance::close_session(
+            &repo,
+            &session_id,
+            prose,
+            Some("Mara reads the ledger."),
+            &[],
+            &crate::maintenance::StoryUpdates {
+                beats_done: vec![1],
+                timeline_events: vec![crate::timeline::TimelineEvent::parse(
+                    "Day 1, dawn | Mara reads the ledger",
+                )
+                .unwrap()],
+                ..Default::default()
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(close["timeline_events"][0]["chapter"], "Chapter 1");
+        assert_eq!(
+            crate::timeline::recent(&repo, 5).unwrap()[0].date,
+            "Day 1, dawn"
+        );
+        assert!(
+            std::fs::read_to_string(repo.join("Chapters material/Chapter_01.md"))
+                .unwrap()
+                .ends_with("1. [x] Mara reads the ledger\n2. The ledger burns\n")
+        );
+        assert_eq!(close["total_word_count"], preview.total_word_count);
+        assert_eq!(
+            close["current_chapter_word_count"],
+            preview.current_chapter_word_count
+        );
+        for ms in close["timings"].as_object_mut().unwrap().values_mut() {
+            *ms = serde_json::json!(0);
+        }
+        assert_golden(
+            "session_close.json",
+            &serde_json::to_string_pretty(&close).unwrap(),
+        );
+    }
+
+    #[test]
+    fn heartbeat_and_holder_reach_other_clones_and_close_still_publishes() {
+        let tmp = book_fixture();
+        let repo = tmp.path().join("book");
+        let session_id = session_open(&repo).unwrap().session_id.unwrap();
+
+        let other_id = heartbeat(&repo, Some("someone-else")).unwrap();
+        assert_eq!(other_id["status"], "not_owner");
+        let renewed = heartbeat(&repo, Some(&session_id)).unwrap();
+        assert_eq!(renewed["status"], "renewed");
+        assert_eq!(renewed["pushed"], true);
+
+        // A second clone on main sees the original lock in its tree and the
+        // heartbeat only through origin/draft
+        git::run_git(tmp.path(), &["clone", "-q", "origin.git", "other"]).unwrap();
+        let other = tmp.path().join("other");
+        assert!(read_lock(&other).unwrap().heartbeat_at.is_none());
+        let live = read_live_lock(&other).unwrap();
+        assert_eq!(live.session_id.as_deref(), Some(session_id.as_str()));
+        assert!(live.heartbeat_at.is_some());
+
+        // ...and a session-open there reports who holds it
+        git::run_git(&other, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(&other, &["config", "user.name", "t"]).unwrap();
+        let second = session_open(&other).unwrap();
+        assert!(second.session_already_run);
+        let holder = second.lock_holder.expect("lock holder in payload");
+        assert_eq!(holder.session_id, Some(session_id.clone()));
+        assert_eq!(holder.pid, Some(std::process::id()));
+        assert!(holder.heartbeat_at.is_some() && holder.agent.is_some());
+
+        crate::maintenance::close_session(
+            &repo,
+            &session_id,
+            "<!-- INK:NEW:START -->\nThe ink had not yet dried.\n<!-- INK:NEW:END -->\n",
+            None,
+            &[],
+            &crate::maintenance::StoryUpdates::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(heartbeat(&repo, None).unwrap()["status"], "no_lock");
+        assert_eq!(
+            git::run_git(&repo, &["rev-parse", "main"]).unwrap(),
+            git::run_git(&repo, &["rev-parse", "origin/main"]).unwrap()
+        );
+    }
+
+    /// Every file of the working tree and `.ink/`, with its content; `.git` aside.
+    fn tree_snapshot(repo: &Path) -> BTreeMap<std::path::PathBuf, Vec<u8>> {
+        fn walk(dir: &Path, root: &Path, out: &mut BTreeMap<std::path::PathBuf, Vec<u8>>) {
+            for entry in std::fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.file_name().is_some_and(|n| n == ".git") {
+                    continue;
+                }
+                if path.is_dir() {
+                    walk(&path, root, out);
+                } else {
+                    let rel = path.strip_prefix(root).unwrap().to_path_buf();
+                    out.insert(rel, std::fs::read(&path).unwrap());
+                }
+            }
+        }
+        let mut out = BTreeMap::new();
+        walk(repo, repo, &mut out);
+        out
+    }
+
+    #[test]
+    fn session_close_dry_run_writes_nothing() {
+        let tmp = book_fixture();
+        let repo = tmp.path().join("book");
+        let session_id = session_open(&repo).unwrap().session_id.unwrap();
+        let prose = "<!-- INK:NEW:START -->\nThe ink had not yet dried.\n<!-- INK:NEW:END -->\n";
+
+        // Plain close, then one that require_approval would queue
+        for config_line in ["", "require_approval: true\n"] {
+            let config = repo.join("Global Material/Config.yml");
+            let mut content = std::fs::read_to_string(&config).unwrap();
+            content.push_str(config_line);
+            std::fs::write(&config, content).unwrap();
+            let head = git::run_git(&repo, &["rev-parse", "HEAD"]).unwrap();
+            let branches = git::run_git(&repo, &["branch", "-av"]).unwrap();
+            let before = tree_snapshot(&repo);
+
+            let preview = crate::maintenance::preview_close(
+                &repo,
+                &session_id,
+                prose,
+                Some("The ink dries."),
+                false,
+            )
+            .unwrap();
+            assert_eq!(preview.status, "ready");
+            assert_eq!(preview.current_md_word_count, 6);
+            assert_eq!(
+                preview.pending_file.is_some(),
+                !config_line.is_empty(),
+                "{config_line}"
+            );
+
+            assert_eq!(git::run_git(&repo, &["rev-parse", "HEAD"]).unwrap(), head);
+            assert_eq!(git::run_git(&repo, &["branch", "-av"]).unwrap(), branches);
+            assert_eq!(tree_snapshot(&repo), before, "dry run changed the tree");
+            assert!(!crate::journal::is_pending(&repo));
+            assert!(!repo.join(crate::maintenance::PENDING_PROSE_FILE).exists());
+        }
+    }
+
+    /// Summary.md entry `n`: long enough to count as a substantive paragraph.
+    fn summary_entry(n: u32) -> String {
+        format!(
+            "Session {n}: Mara follows the river north, reads the burned ledger \
+             by lamplight, and learns the archive kept a second copy."
+        )
+    }
+
+    #[test]
+    fn recap_keeps_the_last_summary_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let global = tmp.path().join("Global Material");
+        std::fs::create_dir_all(&global).unwrap();
+        let entries: Vec<String> = (1..=4).map(summary_entry).collect();
+        std::fs::write(
+            global.join("Summary.md"),
+            format!("# Summary\n\n{}\n\nShort note.\n", entries.join("\n\n")),
+        )
+        .unwrap();
+
+        let recap = load_recap(tmp.path(), 2).unwrap();
+        assert_eq!(recap.summaries, entries[2..].to_vec());
+        assert_eq!(load_recap(tmp.path(), 10).unwrap().summaries, entries);
+        assert!(load_recap(tmp.path(), 0).unwrap().summaries.is_empty());
+    }
+
+    #[test]
+    fn recap_closing_paragraphs_skip_ink_markers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::create_dir_all(repo.join("Current version")).unwrap();
+        std::fs::create_dir_all(repo.join("Review")).unwrap();
+        std::fs::write(
+            repo.join("Current version/Full_Book.md"),
+            format!(
+                "{}\n\nThe fire began at the docks.\n\n<!-- PAGE 2 -->\n\n\
+                 Mara ran.\n\nThe ledger survived.\n",
+                crate::book::FULL_BOOK_HEADER
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            load_recap(repo, 3).unwrap().closing_prose,
+            "The fire began at the docks.\n\nMara ran.\n\nThe ledger survived."
+        );
+
+        // Prose awaiting approval continues the book, engine markers left
+        // out; a rework stands in place of its original
+        std::fs::write(
+            repo.join(crate::maintenance::PENDING_PROSE_FILE),
+            "<!-- INK:REWORKED:START -->\n> **[Rework]** *Colder*\n\n\
+             The ledger, scorched, survived.\n\n<!-- INK:ORIGINAL:START -->\n\
+             The ledger survived.\n<!-- INK:ORIGINAL:END -->\n\
+             <!-- INK:REWORKED:END -->\n<!-- INK:NEW:START -->\n\
+             Dawn came grey.\n<!-- INK:NEW:END -->\n",
+        )
+        .unwrap();
+        let closing = load_recap(repo, 3).unwrap().closing_prose;
+        assert_eq!(
+            closing,
+            "Mara ran.\n\nThe ledger, scorched, survived.\n\nDawn came grey."
+        );
+        assert!(!closing.contains("The ledger survived."), "{closing}");
+    }
+
+    #[test]
+    fn recap_without_summary_or_book_is_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let recap = load_recap(tmp.path(), 5).unwrap();
+        assert!(recap.summaries.is_empty());
+        assert_eq!(recap.closing_prose, "");
+
+        // No Summary.md, but a book to continue from
+        std::fs::create_dir_all(tmp.path().join("Current version")).unwrap();
+        std::fs::write(
+            tmp.path().join("Current version/Full_Book.md"),
+            "Mara ran.\n",
+        )
+        .unwrap();
+        let recap = load_recap(tmp.path(), 5).unwrap();
+        assert!(recap.summaries.is_empty());
+        assert_eq!(recap.closing_prose, "Mara ran.");
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::strip_engine_markers;
+use crate::chapters::chapter_heading_re;
+use crate::state::InkState;
+
+const CHARACTERS_FILE: &str = "Global Material/Characters.md";
+const SUMMARY_FILE: &str = "Global Material/Summary.md";
+
+/// Capitalized words that tag speech without naming anyone.
+pub(crate) const NOT_NAMES: &[&str] = &[
+    "he", "she", "they", "it", "we", "you", "i", "someone", "somebody", "everyone", "nobody", "no",
+    "one", "another", "the", "a", "an", "his", "her", "their", "this", "that", "then", "who",
+];
+
+#[derive(Debug, Serialize)]
+pub struct ContinuityIssue {
+    /// `character_names`, `chapter_headings`, or `summary_chapters`.
+    pub check: &'static str,
+    pub detail: String,
+    /// Up to ~120 characters of text around the finding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+}
+
+/// `Mara said`, `asked Tomas` — the speaker is group 1 or 2.
+pub(crate) fn speaker_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let verbs =
+            "said|asked|replied|answered|whispered|murmured|muttered|shouted|cried|called|snapped";
+        Regex::new(&format!(
+            r"\b(\p{{Lu}}[\p{{L}}'’-]*)\s+(?:{v})\b|\b(?:{v})\s+(\p{{Lu}}[\p{{L}}'’-]*)",
+            v = verbs
+        ))
+        .unwrap()
+    })
+}
+
+fn summary_chapter_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\bchapters?\s+(\d+)\b").unwrap())
+}
+
+pub(crate) fn name_key(word: &str) -> String {
+    word.trim_end_matches("'s")
+        .trim_end_matches("’s")
+        .to_lowercase()
+}
+
+/// Speakers of `prose` whose name Characters.md (`characters`) never
+/// mentions. One issue per name, at its first speech tag.
+pub fn unknown_speakers(characters: &str, prose: &str) -> Vec<ContinuityIssue> {
+    let known: HashSet<String> = characters
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’' && c != '-')
+        .filter(|w| !w.is_empty())
+        .map(name_key)
+        .collect();
+    let prose = strip_engine_markers(prose);
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    for c in speaker_re().captures_iter(&prose) {
+        let m = c.get(1).or_else(|| c.get(2)).expect("one speaker group");
+        let key = name_key(m.as_str());
+        if NOT_NAMES.contains(&key.as_str()) || known.contains(&key) || !seen.insert(key) {
+            continue;
+        }
+        issues.push(ContinuityIssue {
+            check: "character_names",
+            detail: format!("{} speaks but is not in Characters.md", m.as_str()),
+            excerpt: Some(crate::constraints::excerpt(&prose, m.start(), m.end())),
+        });
+    }
+    issues
+}
+
+/// Chapter headings of the manuscript that do not count up one by one from
+/// Chapter 1: skipped, repeated, or backwards numbers.
+pub fn heading_sequence(book: &str) -> Vec<ContinuityIssue> {
+    let mut issues = Vec::new();
+    let mut previous = 0;
+    for c in chapter_heading_re().captures_iter(book) {
+        let Ok(n) = c[2].parse::<u32>() else {
+            continue;
+        };
+        if n != previous + 1 {
+            let detail = match previous {
+                0 => format!("the manuscript opens at Chapter {}", n),
+                p if n == p => format!("Chapter {} heading appears twice", n),
+                p => format!("Chapter {} follows Chapter {}", n, p),
+            };
+            issues.push(ContinuityIssue {
+                check: "chapter_headings",
+                detail,
+                excerpt: Some(c[0].trim().to_string()),
+            });
+        }
+        previous = n;
+    }
+    issues
+}
+
+/// Summary entries (paragraphs of `summary`) that mention a chapter after
+/// `current_chapter` — one the book has not reached.
+pub fn future_chapter_references(summary: &str, current_chapter: u32) -> Vec<ContinuityIssue> {
+    let mut issues = Vec::new();
+    for entry in summary
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+    {
+        let Some(c) = summary_chapter_re()
+            .captures_iter(entry)
+            .find(|c| c[1].parse::<u32>().is_ok_and(|n| n > current_chapter))
+        else {
+            continue;
+        };
+        let m = c.get(0).unwrap();
+        issues.push(ContinuityIssue {
+            check: "summary_chapters",
+            detail: format!(
+                "Summary.md entry mentions {} but the book is at Chapter {}",
+                m.as_str(),
+                current_chapter
+            ),
+            excerpt: Some(crate::constraints::excerpt(entry, m.start(), m.end())),
+        });
+    }
+    issues
+}
+
+/// Every continuity check over the repository, for `doctor`.
+pub fn check(repo: &Path) -> Result<Vec<ContinuityIssue>> {
+    let book = crate::storage::read_book(repo)?.unwrap_or_default();
+    let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).unwrap_or_default();
+    let mut issues = unknown_speakers(&read(CHARACTERS_FILE), &book);
+    issues.extend(heading_sequence(&book));
+    issues.extend(future_chapter_references(
+        &read(SUMMARY_FILE),
+        InkState::load(repo)?.current_chapter,
+    ));
+    Ok(issues)
+}
+
+/// The checks that apply to one session: its speakers and its summary.
+pub fn check_session(
+    repo: &Path,
+    prose: &str,
+    summary: Option<&str>,
+    current_chapter: u32,
+) -> Vec<ContinuityIssue> {
+    let characters = std::fs::read_to_string(repo.join(CHARACTERS_FILE)).unwrap_or_default();
+    let mut issues = unknown_speakers(&characters, prose);
+    if let Some(summary) = summary {
+        issues.extend(future_chapter_references(summary, current_chapter));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speakers_missing_from_characters_are_flagged() {
+        let characters = "# Characters\n\n## Mara Vell\n\nKeeper of the light.\n";
+        let prose = "\"Come,\" Mara said. \"Now,\" said Tomas. She said nothing. \
+                     \"Again,\" Tomas's brother asked.";
+        let speakers: Vec<String> = unknown_speakers(characters, prose)
+            .into_iter()
+            .map(|i| i.detail)
+            .collect();
+        assert_eq!(speakers, ["Tomas speaks but is not in Characters.md"]);
+    }
+
+    #[test]
+    fn repeated_and_out_of_order_headings_are_flagged() {
+        let book = "## Chapter 1\n\ntext\n\n## Chapter 2\n\ntext\n\n## Chapter 2\n\n\
+                    ## Chapter 4\n\n## Chapter 3\n";
+        let headings: Vec<String> = heading_sequence(book)
+            .into_iter()
+            .map(|i| i.detail)
+            .collect();
+        assert_eq!(
+            headings,
+            [
+                "Chapter 2 heading appears twice",
+                "Chapter 4 follows Chapter 2",
+                "Chapter 3 follows Chapter 4"
+            ]
+        );
+    }
+
+    #[test]
+    fn a_manuscript_not_opening_at_chapter_1_is_flagged() {
+        assert_eq!(
+            heading_sequence("# Chapter 2\n")[0].detail,
+            "the manuscript opens at Chapter 2"
+        );
+    }
+
+    #[test]
+    fn summaries_referencing_future_chapters_are_flagged() {
+        let summary = "Mara reaches the lighthouse (Chapter 3).\n\n\
+                       Foreshadows the fire of Chapter 9.";
+        let future = future_chapter_references(summary, 4);
+        assert_eq!(future.len(), 1);
+        assert!(future[0].detail.contains("Chapter 9"));
+    }
+}
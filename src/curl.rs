@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// curl options read from a config file (`-K <path>`) instead of the command
+/// line, so tokens and passwords never show in `ps` or `/proc/<pid>/cmdline`.
+/// The file is created owner-only (0600) in the temp directory and removed
+/// when dropped; keep it alive until curl has exited.
+pub struct SecretConfig {
+    path: PathBuf,
+}
+
+impl SecretConfig {
+    /// Write `options` — `("header", "Authorization: Bearer …")`,
+    /// `("user", "name:password")` — one per line, values quoted.
+    pub fn new(options: &[(&str, &str)]) -> Result<Self> {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "ink-curl-{}-{}-{}.cfg",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let mut open = std::fs::OpenOptions::new();
+        open.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut open, 0o600);
+        let mut file = open
+            .open(&path)
+            .with_context(|| format!("Failed to create curl config {}", path.display()))?;
+        // Owns the file from here on, so a failed write still removes it
+        let config = SecretConfig { path };
+        let content: String = options
+            .iter()
+            .map(|(option, value)| format!("{} = {}\n", option, quote(value)))
+            .collect();
+        file.write_all(content.as_bytes())
+            .with_context(|| format!("Failed to write curl config {}", config.path.display()))?;
+        Ok(config)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SecretConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// A double-quoted curl config value, with the escapes curl reads in one.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_go_to_an_owner_only_file_removed_on_drop() {
+        let config = SecretConfig::new(&[
+            ("header", "Authorization: Bearer s3cr\"et"),
+            ("user", "ada:pa\\ss"),
+        ])
+        .unwrap();
+        let path = config.path().to_path_buf();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "header = \"Authorization: Bearer s3cr\\\"et\"\nuser = \"ada:pa\\\\ss\"\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        drop(config);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::{prose_paragraphs, strip_engine_markers};
+use crate::config::Config;
+use crate::continuity::{name_key, speaker_re, NOT_NAMES};
+use crate::front_matter;
+use crate::sequence::Unit;
+
+/// Consecutive written chapters without a line that count as a silence.
+const SILENCE_CHAPTERS: usize = 3;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ChapterDialogue {
+    pub chapter: u32,
+    pub words: u32,
+    /// Words inside quotation marks.
+    pub dialogue_words: u32,
+    pub dialogue_pct: u8,
+    /// Paragraphs holding dialogue.
+    pub lines: u32,
+    /// Lines credited to each speaker by a dialogue tag (`Mara said`,
+    /// `asked Tomas`); untagged and pronoun-tagged lines are not credited.
+    pub speakers: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Silence {
+    pub speaker: String,
+    /// Whether an outline names the speaker as its `pov` character.
+    pub pov: bool,
+    pub from: u32,
+    pub to: u32,
+    pub chapters: usize,
+}
+
+/// Quoted speech: straight or curly double quotes, or guillemets.
+fn quote_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#""[^"]*"|“[^”]*”|«[^»]*»"#).unwrap())
+}
+
+fn pct(part: u32, whole: u32) -> u8 {
+    (part as u64 * 100)
+        .checked_div(whole as u64)
+        .unwrap_or(0)
+        .min(100) as u8
+}
+
+/// Dialogue and speakers of one chapter's prose. `names` maps each
+/// lowercased speaker to the spelling shown, shared across chapters.
+pub(crate) fn measure(
+    chapter: u32,
+    prose: &str,
+    names: &mut BTreeMap<String, String>,
+) -> ChapterDialogue {
+    let mut out = ChapterDialogue {
+        chapter,
+        ..ChapterDialogue::default()
+    };
+    for paragraph in prose_paragraphs(&strip_engine_markers(prose)) {
+        let words = paragraph.split_whitespace().count() as u32;
+        let spoken: u32 = quote_re()
+            .find_iter(&paragraph)
+            .map(|m| m.as_str().split_whitespace().count() as u32)
+            .sum();
+        out.words += words;
+        out.dialogue_words += spoken;
+        if spoken == 0 {
+            continue;
+        }
+        out.lines += 1;
+        let mut tagged = Vec::new();
+        for c in speaker_re().captures_iter(&paragraph) {
+            let m = c.get(1).or_else(|| c.get(2)).expect("one speaker group");
+            let key = name_key(m.as_str());
+            if NOT_NAMES.contains(&key.as_str()) || tagged.contains(&key) {
+                continue;
+            }
+            let name = names
+                .entry(key.clone())
+                .or_insert_with(|| m.as_str().trim_end_matches("'s").to_string())
+                .clone();
+            *out.speakers.entry(name).or_default() += 1;
+            tagged.push(key);
+        }
+    }
+    out.dialogue_pct = pct(out.dialogue_words, out.words);
+    out
+}
+
+/// Runs of `SILENCE_CHAPTERS` or more written chapters in which `speaker`
+/// has no line, counted from their first line — or from the start for a POV
+/// character.
+fn silences(chapters: &[ChapterDialogue], speaker: &str, pov: bool) -> Vec<Silence> {
+    let mut out = Vec::new();
+    let mut started = pov;
+    let mut run: Vec<u32> = Vec::new();
+    let mut close = |run: &mut Vec<u32>| {
+        if run.len() >= SILENCE_CHAPTERS {
+            out.push(Silence {
+                speaker: speaker.to_string(),
+                pov,
+                from: run[0],
+                to: run[run.len() - 1],
+                chapters: run.len(),
+            });
+        }
+        run.clear();
+    };
+    for c in chapters {
+        if c.speakers.contains_key(speaker) {
+            started = true;
+            close(&mut run);
+        } else if started {
+            run.push(c.chapter);
+        }
+    }
+    close(&mut run);
+    out
+}
+
+/// `report dialogue`: dialogue share and tagged lines per speaker for every
+/// written chapter, and the stretches in which a speaker goes silent.
+pub fn report(repo: &Path) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let book = crate::storage::read_book(repo)?.unwrap_or_default();
+    let mut names = BTreeMap::new();
+    let mut chapters = Vec::new();
+    let mut pov_characters: Vec<String> = Vec::new();
+    for n in 1..=config.chapter_count {
+        if let Ok(outline) = std::fs::read_to_string(repo.join(Unit::chapter(n).path())) {
+            if let Ok((Some(front), _)) = front_matter::split(&outline) {
+                if let Some(pov) = front.pov.filter(|p| !p.trim().is_empty()) {
+                    if !pov_characters.contains(&pov) {
+                        pov_characters.push(pov);
+                    }
+                }
+            }
+        }
+        if let Some(prose) = crate::chapters::chapter_prose(&book, n, config.book_storage) {
+            chapters.push(measure(n, &prose, &mut names));
+        }
+    }
+
+    // A multi-word POV name (`Mara Vell`) is credited under its first word,
+    // the form dialogue tags use.
+    let pov_names: Vec<String> = pov_characters
+        .iter()
+        .filter_map(|p| p.split_whitespace().next())
+        .map(|first| {
+            names
+                .get(&name_key(first))
+                .cloned()
+                .unwrap_or_else(|| first.to_string())
+        })
+        .collect();
+    let mut totals: BTreeMap<String, (u32, usize)> = BTreeMap::new();
+    for c in &chapters {
+        for (name, lines) in &c.speakers {
+            let entry = totals.entry(name.clone()).or_default();
+            entry.0 += lines;
+            entry.1 += 1;
+        }
+    }
+    let mut speakers: Vec<(&String, &(u32, usize))> = totals.iter().collect();
+    speakers.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.0.cmp(b.0)));
+
+    // Speakers with a single line are walk-ons; POV characters always count
+    let mut watched: Vec<&String> = totals
+        .iter()
+        .filter(|(_, (lines, _))| *lines >= 2)
+        .map(|(name, _)| name)
+        .chain(&pov_names)
+        .collect();
+    watched.sort();
+    watched.dedup();
+    let silent: Vec<Silence> = watched
+        .into_iter()
+        .flat_map(|name| silences(&chapters, name, pov_names.contains(name)))
+        .collect();
+
+    let words: u32 = chapters.iter().map(|c| c.words).sum();
+    let dialogue_words: u32 = chapters.iter().map(|c| c.dialogue_words).sum();
+    Ok(json!({
+        "words": words,
+        "dialogue_words": dialogue_words,
+        "dialogue_pct": pct(dialogue_words, words),
+        "chapters": chapters,
+        "speakers": speakers
+            .iter()
+            .map(|(name, (lines, in_chapters))| json!({
+                "name": name,
+                "lines": lines,
+                "chapters": in_chapters,
+                "pov": pov_names.contains(name),
+            }))
+            .collect::<Vec<_>>(),
+        "silences": silent,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dialogue_share_and_speakers_are_measured() {
+        let mut names = BTreeMap::new();
+        let one = measure(
+            1,
+            "The tide was out.\n\n\"Come,\" Mara said. \"Now.\"\n\n\
+             “Why?” asked Tomas. Mara's brother shrugged.\n\n“Because,” she said.",
+            &mut names,
+        );
+        assert_eq!((one.words, one.dialogue_words, one.lines), (17, 4, 3));
+        assert_eq!(one.dialogue_pct, 23);
+        assert_eq!(
+            one.speakers,
+            BTreeMap::from([("Mara".to_string(), 1), ("Tomas".to_string(), 1)])
+        );
+    }
+
+    /// Chapter 1 has Mara and Tomas speak; chapters 2–4 only Tomas.
+    fn chapters() -> Vec<ChapterDialogue> {
+        let mut names = BTreeMap::new();
+        let one = measure(1, "\"Come,\" Mara said. \"Why?\" asked Tomas.", &mut names);
+        let mut quiet = |n| measure(n, "\"Go,\" Tomas said.", &mut names);
+        vec![one, quiet(2), quiet(3), quiet(4)]
+    }
+
+    #[test]
+    fn a_speaker_gone_quiet_is_a_silence() {
+        let chapters = chapters();
+        let found = silences(&chapters, "Mara", false);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].from, found[0].to, found[0].chapters), (2, 4, 3));
+    }
+
+    #[test]
+    fn a_speaker_heard_throughout_has_no_silence() {
+        assert!(silences(&chapters(), "Tomas", false).is_empty());
+    }
+
+    #[test]
+    fn a_speaker_never_heard_is_silent_throughout() {
+        assert_eq!(silences(&chapters(), "Ilse", true)[0].chapters, 4);
+    }
+}
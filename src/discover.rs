@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::errors::InkError;
+
+/// Files that mark the root of a book repository, or of an anthology of them.
+const MARKERS: &[&str] = &[
+    "Global Material/Config.yml",
+    ".ink-state.yml",
+    crate::anthology::ANTHOLOGY_FILE,
+];
+
+/// The nearest of `start` and its ancestors holding `Global Material/Config.yml`,
+/// `.ink-state.yml`, or `Anthology.yml`, the way git finds `.git`.
+pub fn find_book(start: &Path) -> Option<&Path> {
+    start
+        .ancestors()
+        .find(|dir| MARKERS.iter().any(|m| dir.join(m).is_file()))
+}
+
+/// The book enclosing the current directory, for commands run without a
+/// repo path: `.` when it is the current directory itself.
+pub fn book_root() -> Result<PathBuf> {
+    let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+    match find_book(&cwd) {
+        Some(root) if root == cwd => Ok(PathBuf::from(".")),
+        Some(root) => Ok(root.to_path_buf()),
+        None => Err(InkError::NoBook {
+            dir: cwd.display().to_string(),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_enclosing_book() {
+        let tmp = tempfile::tempdir().unwrap();
+        let book = tmp.path().join("book");
+        let deep = book.join("Chapters material/drafts");
+        std::fs::create_dir_all(&deep).unwrap();
+        assert_eq!(find_book(&deep), None);
+
+        std::fs::write(book.join(".ink-state.yml"), "current_chapter: 1\n").unwrap();
+        assert_eq!(find_book(&deep), Some(book.as_path()));
+        assert_eq!(find_book(&book), Some(book.as_path()));
+        assert_eq!(find_book(tmp.path()), None);
+    }
+}
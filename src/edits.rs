@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::context::ink_re;
+use crate::git;
+use crate::wordcount::WordCounter;
+
+/// At or below this many changed words (added + removed), an edit is flagged
+/// `minor` — typo fixes and single-word swaps rather than rewrites.
+const MINOR_EDIT_WORDS: u32 = 3;
+
+// ─── Output types ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditKind {
+    /// Prose the author changed directly (Full_Book.md, current.md text).
+    Prose,
+    /// Story material: Global Material/ and chapter outlines.
+    Material,
+    /// New `<!-- INK: ... -->` instructions added to current.md.
+    Instruction,
+    /// Anything else (Config.yml, README, stray files).
+    Other,
+}
+
+impl EditKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EditKind::Prose => "Prose",
+            EditKind::Material => "Material",
+            EditKind::Instruction => "Instructions",
+            EditKind::Other => "Other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HumanEdit {
+    pub file: String,
+    pub kind: EditKind,
+    pub words_added: u32,
+    pub words_removed: u32,
+    /// Changed words ≤ MINOR_EDIT_WORDS — likely a typo fix.
+    pub minor: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub instructions_added: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl HumanEdit {
+    /// One Changelog bullet: "`file` — +12/−3 words (minor)".
+    pub fn describe(&self) -> String {
+        let mut line = format!(
+            "`{}` — +{}/−{} words",
+            self.file, self.words_added, self.words_removed
+        );
+        if self.instructions_added > 0 {
+            line.push_str(&format!(
+                ", {} instruction(s) added",
+                self.instructions_added
+            ));
+        }
+        if self.minor {
+            line.push_str(" (minor)");
+        }
+        line
+    }
+}
+
+/// Human edits grouped by kind, as reported in the session-open payload.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HumanEditGroups {
+    pub prose: Vec<HumanEdit>,
+    pub material: Vec<HumanEdit>,
+    pub instructions: Vec<HumanEdit>,
+    pub other: Vec<HumanEdit>,
+}
+
+impl HumanEditGroups {
+    fn push(&mut self, edit: HumanEdit) {
+        match edit.kind {
+            EditKind::Prose => self.prose.push(edit),
+            EditKind::Material => self.material.push(edit),
+            EditKind::Instruction => self.instructions.push(edit),
+            EditKind::Other => self.other.push(edit),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &HumanEdit> {
+        self.instructions
+            .iter()
+            .chain(&self.material)
+            .chain(&self.prose)
+            .chain(&self.other)
+    }
+
+    pub fn find(&self, file: &str) -> Option<&HumanEdit> {
+        self.iter().find(|e| e.file == file)
+    }
+}
+
+// ─── Classification ───────────────────────────────────────────────────────────
+
+/// Kind implied by the path alone; current.md is refined by `classify`.
+pub fn kind_for_path(file: &str) -> EditKind {
+    if file == "Global Material/Config.yml" {
+        EditKind::Other
+    } else if file.starts_with("Global Material/") || file.starts_with("Chapters material/") {
+        EditKind::Material
+    } else if file.starts_with("Current version/") || file == "Review/current.md" {
+        EditKind::Prose
+    } else {
+        EditKind::Other
+    }
+}
+
+/// `(words_added, words_removed)` between `base` and HEAD for one file, from
+/// `git diff --word-diff=porcelain`, counted by the book's `WordCounter`. A
+/// missing base counts the whole file as added. Assets (images, binaries) have
+/// no words.
+fn word_diff(repo: &Path, base: Option<&str>, file: &str, counter: &WordCounter) -> (u32, u32) {
+    if file.starts_with(&format!("{}/", crate::init::ASSETS_DIR)) {
+        return (0, 0);
+    }
+    let Some(base) = base else {
+        let text = std::fs::read_to_string(repo.join(file)).unwrap_or_default();
+        return (counter.count(&text), 0);
+    };
+    let raw = git::run_git(
+        repo,
+        &[
+            "diff",
+            "--word-diff=porcelain",
+            "--unified=0",
+            base,
+            "HEAD",
+            "--",
+            file,
+        ],
+    )
+    .unwrap_or_default();
+    count_word_diff(&raw, counter)
+}
+
+/// Words on the `+` and `-` lines of a porcelain word diff. `---` / `+++`
+/// are file headers only before the first `@@` hunk; after it they are
+/// changed prose starting with a dash or a plus.
+fn count_word_diff(raw: &str, counter: &WordCounter) -> (u32, u32) {
+    let (mut added, mut removed) = (0u32, 0u32);
+    let mut in_hunk = false;
+    for line in raw.lines() {
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk {
+            continue;
+        } else if let Some(words) = line.strip_prefix('+') {
+            added += counter.count(words);
+        } else if let Some(words) = line.strip_prefix('-') {
+            removed += counter.count(words);
+        }
+    }
+    (added, removed)
+}
+
+/// Number of author INK instructions in `file` at `rev` (0 if absent).
+fn instruction_count(repo: &Path, rev: &str, file: &str) -> u32 {
+    git::run_git(repo, &["show", &format!("{}:{}", rev, file)])
+        .map(|text| ink_re().find_iter(&text).count() as u32)
+        .unwrap_or(0)
+}
+
+/// Classify each human-edited file by comparing HEAD with `base`, the commit
+/// checked out before the edits were committed and origin/main merged in.
+/// current.md counts as an instruction edit when it gained INK instructions,
+/// otherwise as a prose edit.
+pub fn classify(repo: &Path, base: Option<&str>, files: &[String]) -> HumanEditGroups {
+    let counter = WordCounter::for_repo(repo);
+    let mut groups = HumanEditGroups::default();
+    for file in files {
+        let (words_added, words_removed) = word_diff(repo, base, file, &counter);
+        let mut kind = kind_for_path(file);
+        let mut instructions_added = 0;
+        if file == "Review/current.md" {
+            let before = base.map_or(0, |b| instruction_count(repo, b, file));
+            instructions_added = instruction_count(repo, "HEAD", file).saturating_sub(before);
+            if instructions_added > 0 {
+                kind = EditKind::Instruction;
+            }
+        }
+        groups.push(HumanEdit {
+            file: file.clone(),
+            kind,
+            words_added,
+            words_removed,
+            minor: words_added + words_removed <= MINOR_EDIT_WORDS,
+            instructions_added,
+        });
+    }
+    groups
+}
+
+/// Changelog "Human edits" section: the files the engine reported, grouped by
+/// kind. Details come from the classification recorded at session-open; files
+/// it does not know about are grouped by path only.
+pub fn changelog_section(reported: &[String], known: &HumanEditGroups) -> String {
+    let mut groups = HumanEditGroups::default();
+    for file in reported {
+        let edit = known.find(file).cloned().unwrap_or_else(|| HumanEdit {
+            file: file.clone(),
+            kind: kind_for_path(file),
+            words_added: 0,
+            words_removed: 0,
+            minor: false,
+            instructions_added: 0,
+        });
+        groups.push(edit);
+    }
+
+    let mut out = String::from("\n**Human edits:**\n");
+    for kind in [
+        EditKind::Instruction,
+        EditKind::Material,
+        EditKind::Prose,
+        EditKind::Other,
+    ] {
+        let edits: Vec<&HumanEdit> = groups.iter().filter(|e| e.kind == kind).collect();
+        if edits.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n*{}*\n", kind.label()));
+        for edit in edits {
+            if known.find(&edit.file).is_some() {
+                out.push_str(&format!("- {}\n", edit.describe()));
+            } else {
+                out.push_str(&format!("- `{}`\n", edit.file));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A repository with `files` committed, returning the commit.
+    fn commit(repo: &Path, files: &[(&str, &str)]) -> String {
+        for (rel, content) in files {
+            let path = repo.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "edit"]).unwrap();
+        git::run_git(repo, &["rev-parse", "HEAD"]).unwrap()
+    }
+
+    fn repo_with(files: &[(&str, &str)]) -> (tempfile::TempDir, String) {
+        let tmp = tempfile::tempdir().unwrap();
+        git::run_git(tmp.path(), &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(tmp.path(), &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(tmp.path(), &["config", "user.name", "t"]).unwrap();
+        let base = commit(tmp.path(), files);
+        (tmp, base)
+    }
+
+    #[test]
+    fn kind_follows_the_path() {
+        assert_eq!(kind_for_path("Global Material/Soul.md"), EditKind::Material);
+        assert_eq!(
+            kind_for_path("Chapters material/Chapter_02.md"),
+            EditKind::Material
+        );
+        assert_eq!(kind_for_path("Global Material/Config.yml"), EditKind::Other);
+        assert_eq!(
+            kind_for_path("Current version/Full_Book.md"),
+            EditKind::Prose
+        );
+        assert_eq!(kind_for_path("Review/current.md"), EditKind::Prose);
+        assert_eq!(kind_for_path("README.md"), EditKind::Other);
+    }
+
+    #[test]
+    fn current_md_with_new_instructions_is_an_instruction_edit() {
+        let (tmp, base) = repo_with(&[("Review/current.md", "Mara ran home.\n")]);
+        commit(
+            tmp.path(),
+            &[(
+                "Review/current.md",
+                "Mara ran home.\n\n<!-- INK: make it colder -->\n",
+            )],
+        );
+        let groups = classify(tmp.path(), Some(&base), &["Review/current.md".into()]);
+        assert_eq!(groups.instructions.len(), 1);
+        assert_eq!(groups.instructions[0].instructions_added, 1);
+        assert!(groups.prose.is_empty());
+    }
+
+    #[test]
+    fn small_prose_edits_are_minor_and_rewrites_are_not() {
+        let (tmp, base) = repo_with(&[
+            ("Review/current.md", "Mara ran home.\n"),
+            ("Global Material/Soul.md", "Quiet prose.\n"),
+        ]);
+        commit(
+            tmp.path(),
+            &[
+                ("Review/current.md", "Mara walked home.\n"),
+                (
+                    "Global Material/Soul.md",
+                    "Long sentences, cold light, and a narrator who never explains.\n",
+                ),
+            ],
+        );
+        let files = ["Review/current.md".into(), "Global Material/Soul.md".into()];
+        let groups = classify(tmp.path(), Some(&base), &files);
+        let prose = &groups.prose[0];
+        assert_eq!((prose.words_added, prose.words_removed), (1, 1));
+        assert!(prose.minor);
+        let material = &groups.material[0];
+        assert_eq!((material.words_added, material.words_removed), (10, 2));
+        assert!(!material.minor);
+    }
+
+    #[test]
+    fn missing_base_counts_the_whole_file_as_added() {
+        let (tmp, _) = repo_with(&[("Global Material/Lore.md", "The river has no name.\n")]);
+        let groups = classify(tmp.path(), None, &["Global Material/Lore.md".into()]);
+        assert_eq!(groups.material[0].words_added, 5);
+        assert_eq!(groups.material[0].words_removed, 0);
+    }
+
+    #[test]
+    fn word_diff_counts_prose_lines_that_start_with_a_dash() {
+        let (tmp, base) = repo_with(&[("Review/current.md", "Mara ran.\n\n-- she said softly\n")]);
+        commit(tmp.path(), &[("Review/current.md", "Mara ran.\n\n")]);
+        let counter = WordCounter::default();
+        let diff = word_diff(tmp.path(), Some(&base), "Review/current.md", &counter);
+        assert_eq!(diff, (0, 4));
+
+        let raw = "--- a/x.md\n+++ b/x.md\n@@ -1 +1 @@\n--- she said\n+++ plus\n~\n";
+        assert_eq!(count_word_diff(raw, &counter), (2, 3));
+    }
+
+    #[test]
+    fn word_diff_counts_cjk_by_character() {
+        let (tmp, base) = repo_with(&[
+            ("Global Material/Config.yml", "language: Chinese\n"),
+            ("Review/current.md", "林小雨走进了房间。\n"),
+        ]);
+        commit(tmp.path(), &[("Review/current.md", "林小雨走出了房间。\n")]);
+        let files = ["Review/current.md".into()];
+        // The line is one whitespace-separated token to git, eight characters
+        // to the book's counter
+        let edit = &classify(tmp.path(), Some(&base), &files).prose[0];
+        assert_eq!((edit.words_added, edit.words_removed), (8, 8));
+        assert_eq!(classify(tmp.path(), None, &files).prose[0].words_added, 8);
+    }
+}
@@ -0,0 +1,148 @@
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::Path;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Directories of book text `doctor` scans, recursively.
+const TEXT_DIRS: &[&str] = &[
+    "Global Material",
+    "Chapters material",
+    "Review",
+    "Current version",
+    "Changelog",
+];
+
+const TEXT_EXTENSIONS: &[&str] = &["md", "yml", "yaml", "txt"];
+
+/// `text` in Unicode NFC with LF line endings — the form every write path
+/// stores, so agents sending NFD or CRLF produce neither noisy diffs nor
+/// miscounts. Borrowed when already normalized.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    let lf: Cow<str> = if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    };
+    if matches!(is_nfc_quick(lf.chars()), IsNormalized::Yes) {
+        return lf;
+    }
+    let nfc: String = lf.nfc().collect();
+    if nfc == lf {
+        lf
+    } else {
+        Cow::Owned(nfc)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncodingIssue {
+    pub file: String,
+    /// `not UTF-8`, `CRLF line endings`, `mixed line endings`, `CR line
+    /// endings`, or `not NFC`.
+    pub problems: Vec<&'static str>,
+}
+
+fn problems(bytes: &[u8]) -> Vec<&'static str> {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return vec!["not UTF-8"];
+    };
+    let mut found = Vec::new();
+    let crlf = text.matches("\r\n").count();
+    let lone_cr = text.matches('\r').count() - crlf;
+    let lf = text.matches('\n').count() - crlf;
+    match (crlf > 0, lf > 0 || lone_cr > 0) {
+        (true, true) => found.push("mixed line endings"),
+        (true, false) => found.push("CRLF line endings"),
+        _ if lone_cr > 0 => found.push("CR line endings"),
+        _ => {}
+    }
+    if text.chars().nfc().ne(text.chars()) {
+        found.push("not NFC");
+    }
+    found
+}
+
+fn scan_dir(repo: &Path, dir: &Path, issues: &mut Vec<EncodingIssue>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            scan_dir(repo, &path, issues);
+            continue;
+        }
+        let text_file = path
+            .extension()
+            .is_some_and(|e| TEXT_EXTENSIONS.contains(&e.to_string_lossy().as_ref()));
+        let Some(bytes) = text_file.then(|| std::fs::read(&path).ok()).flatten() else {
+            continue;
+        };
+        let problems = problems(&bytes);
+        if !problems.is_empty() {
+            issues.push(EncodingIssue {
+                file: path
+                    .strip_prefix(repo)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned(),
+                problems,
+            });
+        }
+    }
+}
+
+/// Text files of the book (Markdown, YAML, plain text) that are not UTF-8,
+/// use CR or CRLF line endings, or are not in NFC — for `doctor`.
+pub fn scan(repo: &Path) -> Vec<EncodingIssue> {
+    let mut issues = Vec::new();
+    for dir in TEXT_DIRS {
+        scan_dir(repo, &repo.join(dir), &mut issues);
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_composes_and_uses_lf() {
+        let nfd = "Cafe\u{301} noir\r\nfin\r";
+        assert_eq!(normalize(nfd), "Café noir\nfin\n");
+    }
+
+    #[test]
+    fn normalized_text_is_borrowed_unchanged() {
+        assert!(matches!(normalize("Café\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn scan_flags_markdown_files_and_skips_binaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("Global Material");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Soul.md"), "Clean.\n").unwrap();
+        std::fs::write(dir.join("Lore.md"), "One\r\nTwo\nCafe\u{301}\n").unwrap();
+        std::fs::write(dir.join("Outline.md"), "One\r\nTwo\r\n").unwrap();
+        std::fs::write(dir.join("cover.png"), [0xff, 0xfe, b'\r']).unwrap();
+        let found: Vec<(String, Vec<&str>)> = scan(tmp.path())
+            .into_iter()
+            .map(|i| (i.file, i.problems))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "Global Material/Lore.md".to_string(),
+                    vec!["mixed line endings", "not NFC"]
+                ),
+                (
+                    "Global Material/Outline.md".to_string(),
+                    vec!["CRLF line endings"]
+                ),
+            ]
+        );
+    }
+}
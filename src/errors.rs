@@ -0,0 +1,402 @@
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::book::MarkerIssue;
+use crate::git::{self, GitTimeout};
+use crate::maintenance::QuotaIssue;
+use crate::truncation::TruncationSign;
+
+/// Failures a caller is expected to act on, each with a stable `code`. The
+/// CLI prints any error as one JSON object with a matching exit status; the
+/// MCP server returns the same object as an `isError` result. Errors of no
+/// kind below report as `error`.
+#[derive(Debug)]
+pub enum InkError {
+    /// session-close without a session lock.
+    NoSession,
+    /// The session lock belongs to another session.
+    SessionMismatch { session_id: String },
+    /// A repeat close of a closed session with different prose.
+    SessionClosed { session_id: String },
+    /// `action` is refused while a session holds the lock.
+    SessionActive { action: String },
+    /// Another ink command holds the working tree.
+    Busy { holder: String },
+    /// `complete` on a book that already is.
+    BookComplete,
+    /// Prose queued in Review/pending.md blocks the command.
+    PendingApproval,
+    /// Config.yml is missing, unparsable, or fails validation.
+    ConfigInvalid(String),
+    /// A git command exited non-zero (after any network retries).
+    GitFailed { command: String, message: String },
+    /// SIGINT / SIGTERM received; stopped before `step`.
+    Interrupted { step: String },
+    /// The MCP client cancelled the request; stopped before `step`.
+    Cancelled { step: String },
+    /// A missing or malformed argument.
+    InvalidInput(String),
+    /// No repo path given and no book encloses the current directory `dir`.
+    NoBook { dir: String },
+    /// Strict marker validation refused the session prose.
+    InvalidMarkers { issues: Vec<MarkerIssue> },
+    /// A rejecting `session_quota` refused the session prose.
+    QuotaViolation { quota: QuotaIssue },
+    /// `refuse_truncated` refused session prose that looks cut off.
+    Truncated { signs: Vec<TruncationSign> },
+    /// An MCP argument over the server's size limit.
+    #[allow(dead_code)] // raised by ink-gateway-mcp only
+    PayloadTooLarge {
+        param: String,
+        bytes: usize,
+        limit: usize,
+    },
+    /// The MCP server's per-repository budget of mutating calls is spent.
+    #[allow(dead_code)] // raised by ink-gateway-mcp only
+    RateLimited {
+        limit: u32,
+        retry_after_seconds: u64,
+    },
+}
+
+impl fmt::Display for InkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InkError::NoSession => write!(f, "no active session — run session-open first"),
+            InkError::SessionMismatch { session_id } => write!(
+                f,
+                "session ID mismatch — the active session is not {}; \
+                 only the session-open call that created the lock may close it",
+                session_id
+            ),
+            InkError::SessionClosed { session_id } => write!(
+                f,
+                "session {} is already closed with different prose — run session-open to start a new session",
+                session_id
+            ),
+            InkError::SessionActive { action } => {
+                write!(f, "session in progress — {} after session-close", action)
+            }
+            InkError::Busy { holder } => write!(
+                f,
+                "another ink command is running on this repository: {} — wait for it to finish and retry",
+                holder
+            ),
+            InkError::BookComplete => {
+                write!(f, "book already complete — COMPLETE marker already exists")
+            }
+            InkError::PendingApproval => write!(
+                f,
+                "Review/pending.md awaits approval — run approve or reject before complete"
+            ),
+            InkError::ConfigInvalid(message)
+            | InkError::GitFailed { message, .. }
+            | InkError::InvalidInput(message) => write!(f, "{}", message),
+            InkError::Interrupted { step } => write!(f, "interrupted by signal before {}", step),
+            InkError::Cancelled { step } => write!(f, "cancelled by client before {}", step),
+            InkError::NoBook { dir } => write!(
+                f,
+                "no book found in {} or any parent directory (looked for \
+                 Global Material/Config.yml, .ink-state.yml, or Anthology.yml) — pass the repository path",
+                dir
+            ),
+            InkError::InvalidMarkers { issues } => write!(
+                f,
+                "session prose has {} engine marker issue(s) — nothing was written and the \
+                 lock is still held; fix the markers and close again",
+                issues.len()
+            ),
+            InkError::QuotaViolation { quota } => write!(
+                f,
+                "session prose is {} words, {} the session quota — nothing was written and \
+                 the lock is still held; resubmit prose of {} words or more{}",
+                quota.words,
+                quota.problem,
+                quota.min_words,
+                quota
+                    .max_words
+                    .map(|max| format!(" and at most {}", max))
+                    .unwrap_or_default()
+            ),
+            InkError::Truncated { signs } => write!(
+                f,
+                "session prose looks cut off ({}) — nothing was written and the lock is still \
+                 held; resubmit the complete prose, or force the close if the ending is deliberate",
+                signs.iter().map(|s| s.check).collect::<Vec<_>>().join(", ")
+            ),
+            InkError::PayloadTooLarge {
+                param,
+                bytes,
+                limit,
+            } => write!(
+                f,
+                "{} is {} bytes, over the server's limit of {} — send less per call",
+                param, bytes, limit
+            ),
+            InkError::RateLimited {
+                limit,
+                retry_after_seconds,
+            } => write!(
+                f,
+                "rate limited: this repository takes {} mutating calls per minute — retry in {}s",
+                limit, retry_after_seconds
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InkError {}
+
+impl InkError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            InkError::NoSession => "no_session",
+            InkError::SessionMismatch { .. } => "session_mismatch",
+            InkError::SessionClosed { .. } => "session_closed",
+            InkError::SessionActive { .. } => "session_active",
+            InkError::Busy { .. } => "busy",
+            InkError::BookComplete => "book_complete",
+            InkError::PendingApproval => "pending_approval",
+            InkError::ConfigInvalid(_) => "config_invalid",
+            InkError::GitFailed { .. } => "git_failed",
+            InkError::Interrupted { .. } => "interrupted",
+            InkError::Cancelled { .. } => "cancelled",
+            InkError::InvalidInput(_) => "invalid_input",
+            InkError::NoBook { .. } => "no_book",
+            InkError::InvalidMarkers { .. } => "invalid_markers",
+            InkError::QuotaViolation { .. } => "quota_violation",
+            InkError::Truncated { .. } => "truncated",
+            InkError::PayloadTooLarge { .. } => "payload_too_large",
+            InkError::RateLimited { .. } => "rate_limited",
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        match self {
+            InkError::InvalidInput(_)
+            | InkError::NoBook { .. }
+            | InkError::InvalidMarkers { .. }
+            | InkError::QuotaViolation { .. }
+            | InkError::Truncated { .. }
+            | InkError::PayloadTooLarge { .. } => EXIT_INVALID_INPUT,
+            InkError::NoSession
+            | InkError::SessionMismatch { .. }
+            | InkError::SessionClosed { .. }
+            | InkError::SessionActive { .. }
+            | InkError::Busy { .. }
+            | InkError::RateLimited { .. } => EXIT_SESSION,
+            InkError::BookComplete | InkError::PendingApproval => EXIT_BOOK_STATE,
+            InkError::GitFailed { .. } => EXIT_GIT_FAILED,
+            InkError::ConfigInvalid(_) => EXIT_CONFIG_INVALID,
+            InkError::Interrupted { .. } | InkError::Cancelled { .. } => EXIT_INTERRUPTED,
+        }
+    }
+}
+
+/// Exit statuses of `ink-cli`, one per error class. 2 is also clap's status
+/// for a malformed command line.
+pub const EXIT_ERROR: i32 = 1;
+pub const EXIT_INVALID_INPUT: i32 = 2;
+pub const EXIT_SESSION: i32 = 3;
+pub const EXIT_BOOK_STATE: i32 = 4;
+pub const EXIT_GIT_TIMEOUT: i32 = 5;
+pub const EXIT_GIT_FAILED: i32 = 6;
+pub const EXIT_CONFIG_INVALID: i32 = 7;
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// The exit-status table shown by `ink-cli --help`.
+pub const EXIT_CODES_HELP: &str = "\
+Exit status (failures also print { \"error\": { \"code\", \"message\", \"exit_code\" } } on stdout):
+  0    success
+  1    error             anything not classified below
+  2    invalid_input     malformed command line or argument
+       no_book           no repo path given and none found from the current directory
+       payload_too_large an MCP argument over the server's size limit
+       invalid_markers, quota_violation, truncated
+                         session-close refused the prose; the lock is still held
+  3    no_session, session_mismatch, session_closed, session_active, busy
+                         session lock or command lock conflict
+       rate_limited      the MCP server's calls-per-minute budget is spent
+  4    book_complete, pending_approval
+                         the book's state does not allow the command
+  5    git_timeout       a git command ran past git_timeout_seconds
+  6    git_failed        a git command failed (push rejected, auth, network)
+  7    config_invalid    Config.yml missing, unparsable, or invalid
+  130  interrupted       SIGINT / SIGTERM (cancelled, over MCP)";
+
+/// The stable code of `err`, as `report` gives it, without consuming this
+/// thread's last git timeout.
+pub fn code_of(err: &anyhow::Error) -> &'static str {
+    if let Some(e) = err.chain().find_map(|e| e.downcast_ref::<InkError>()) {
+        e.code()
+    } else if err.chain().any(|e| e.is::<GitTimeout>()) {
+        "git_timeout"
+    } else {
+        "error"
+    }
+}
+
+/// The structured form of `err` and its CLI exit status:
+/// `{ "error": { "code", "message", "exit_code", … } }`, where `message` is
+/// the whole context chain. The code comes from the first `InkError` or
+/// `GitTimeout` in the chain, or from this thread's last git timeout
+/// (consumed), else `error`.
+pub fn report(err: &anyhow::Error) -> (Value, i32) {
+    let message = format!("{:#}", err);
+    let timeout = err
+        .chain()
+        .find_map(|e| e.downcast_ref::<GitTimeout>())
+        .cloned()
+        .or_else(git::take_timeout);
+    let (mut error, exit) = match err.chain().find_map(|e| e.downcast_ref::<InkError>()) {
+        Some(e) => {
+            let mut error = json!({ "code": e.code(), "message": message });
+            match e {
+                InkError::SessionMismatch { session_id }
+                | InkError::SessionClosed { session_id } => error["session_id"] = json!(session_id),
+                InkError::GitFailed { command, .. } => error["command"] = json!(command),
+                InkError::PayloadTooLarge { param, limit, .. } => {
+                    error["param"] = json!(param);
+                    error["limit_bytes"] = json!(limit);
+                }
+                InkError::RateLimited {
+                    retry_after_seconds,
+                    ..
+                } => error["retry_after_seconds"] = json!(retry_after_seconds),
+                InkError::InvalidMarkers { issues } => error["marker_issues"] = json!(issues),
+                InkError::QuotaViolation { quota } => error["quota"] = json!(quota),
+                InkError::Truncated { signs } => error["truncation_warnings"] = json!(signs),
+                _ => {}
+            }
+            (error, e.exit_code())
+        }
+        None => match timeout {
+            Some(t) => (
+                json!({
+                    "code": "git_timeout",
+                    "message": message,
+                    "command": t.command,
+                    "timeout_seconds": t.timeout_seconds,
+                }),
+                EXIT_GIT_TIMEOUT,
+            ),
+            None => (json!({ "code": "error", "message": message }), EXIT_ERROR),
+        },
+    };
+    error["exit_code"] = json!(exit);
+    (json!({ "error": error }), exit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn codes_survive_context() {
+        let err = Err::<(), _>(InkError::SessionMismatch {
+            session_id: "s1".to_string(),
+        })
+        .context("session-close")
+        .unwrap_err();
+        let (json, exit) = report(&err);
+        assert_eq!(json["error"]["code"], "session_mismatch");
+        assert_eq!(json["error"]["session_id"], "s1");
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("session-close: session ID mismatch"));
+        assert_eq!((exit, json["error"]["exit_code"].as_i64()), (3, Some(3)));
+    }
+
+    #[test]
+    fn unclassified_errors_exit_1() {
+        let (json, exit) = report(&anyhow::anyhow!("disk full"));
+        assert_eq!((json["error"]["code"].as_str(), exit), (Some("error"), 1));
+        assert_eq!(json["error"]["exit_code"], 1);
+    }
+
+    #[test]
+    fn each_error_class_has_its_own_exit_status() {
+        let cases = [
+            (InkError::InvalidInput("x".to_string()), EXIT_INVALID_INPUT),
+            (InkError::NoSession, EXIT_SESSION),
+            (
+                InkError::Busy {
+                    holder: "status".to_string(),
+                },
+                EXIT_SESSION,
+            ),
+            (InkError::BookComplete, EXIT_BOOK_STATE),
+            (InkError::PendingApproval, EXIT_BOOK_STATE),
+            (
+                InkError::GitFailed {
+                    command: "git push".to_string(),
+                    message: "rejected".to_string(),
+                },
+                EXIT_GIT_FAILED,
+            ),
+            (
+                InkError::ConfigInvalid("bad".to_string()),
+                EXIT_CONFIG_INVALID,
+            ),
+            (
+                InkError::Interrupted {
+                    step: "push".to_string(),
+                },
+                EXIT_INTERRUPTED,
+            ),
+        ];
+        for (err, exit) in cases {
+            let code = err.code();
+            assert_eq!(report(&anyhow::Error::from(err)).1, exit, "{code}");
+        }
+    }
+
+    #[test]
+    fn git_timeouts_exit_5_with_the_command() {
+        let err = anyhow::Error::from(GitTimeout {
+            command: "git fetch origin".to_string(),
+            timeout_seconds: 30,
+        })
+        .context("session-open");
+        let (json, exit) = report(&err);
+        assert_eq!(exit, EXIT_GIT_TIMEOUT);
+        assert_eq!(json["error"]["code"], "git_timeout");
+        assert_eq!(json["error"]["command"], "git fetch origin");
+        assert_eq!(json["error"]["timeout_seconds"], 30);
+    }
+
+    #[test]
+    fn help_lists_every_exit_status() {
+        for exit in [
+            EXIT_ERROR,
+            EXIT_INVALID_INPUT,
+            EXIT_SESSION,
+            EXIT_BOOK_STATE,
+            EXIT_GIT_TIMEOUT,
+            EXIT_GIT_FAILED,
+            EXIT_CONFIG_INVALID,
+            EXIT_INTERRUPTED,
+        ] {
+            assert!(
+                EXIT_CODES_HELP
+                    .lines()
+                    .any(|line| line.trim_start().starts_with(&format!("{exit} "))),
+                "exit status {exit} missing from --help"
+            );
+        }
+    }
+
+    #[test]
+    fn rate_limits_report_when_to_retry() {
+        let err = anyhow::Error::from(InkError::RateLimited {
+            limit: 30,
+            retry_after_seconds: 12,
+        });
+        let (json, exit) = report(&err);
+        assert_eq!(exit, EXIT_SESSION);
+        assert_eq!(json["error"]["code"], "rate_limited");
+        assert_eq!(json["error"]["retry_after_seconds"], 12);
+    }
+}
@@ -0,0 +1,833 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::info;
+
+use crate::book::{prose_paragraphs, strip_html_comments};
+use crate::config::{Config, ManuscriptFormat, TtsConfig};
+use crate::curl::SecretConfig;
+use crate::git;
+use crate::metadata;
+use crate::review::to_html;
+use crate::storage;
+use crate::wordcount::WordCounter;
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Most recent `ink-*` snapshot tag, or None if the book has never had a session.
+fn latest_snapshot_tag(repo: &Path) -> Option<String> {
+    let raw = git::run_git(repo, &["tag", "-l", "ink-*"]).ok()?;
+    raw.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .max()
+        .map(String::from)
+}
+
+/// Lowercase ASCII slug for file names: "Chapter 3: The Gate" → "chapter-3-the-gate".
+fn slugify(s: &str) -> String {
+    let mut slug = String::new();
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Group the paragraphs of `book` that are not in `old` by the chapter heading
+/// (`# ` / `## `) they fall under. Returns `(heading, paragraphs)` in book order;
+/// prose before the first heading is grouped under "Front matter".
+fn new_prose_by_chapter(old: &str, book: &str) -> Vec<(String, Vec<String>)> {
+    let seen: HashSet<String> = prose_paragraphs(old).into_iter().collect();
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    let mut heading = "Front matter".to_string();
+
+    for para in prose_paragraphs(book) {
+        let is_heading = para.starts_with("# ") || para.starts_with("## ");
+        if is_heading {
+            heading = para.trim_start_matches('#').trim().to_string();
+        }
+        if seen.contains(&para) {
+            continue;
+        }
+        match groups.last_mut() {
+            Some((h, paras)) if *h == heading => paras.push(para),
+            _ => groups.push((heading.clone(), vec![para])),
+        }
+    }
+    groups
+}
+
+/// Split `book` into sections at `# ` / `## ` headings, each tagged with the
+/// chapter it belongs to. `Chapter N` headings set the number; other headings
+/// (interludes, epilogue) travel with the chapter before them, and anything
+/// before the first chapter (title, prologue) is chapter 0.
+fn chapter_sections(book: &str) -> Vec<(u32, String)> {
+    static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let chapter_re =
+        RE.get_or_init(|| regex::Regex::new(r"(?i)^#{1,2}\s+chapter\s+(\d+)\b").unwrap());
+
+    let mut sections: Vec<(u32, String)> = Vec::new();
+    let mut chapter = 0;
+    let mut current = String::new();
+    for line in strip_html_comments(book).lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("# ") || trimmed.starts_with("## ") {
+            if !current.trim().is_empty() {
+                sections.push((chapter, current.trim().to_string()));
+            }
+            current.clear();
+            if let Some(n) = chapter_re
+                .captures(trimmed)
+                .and_then(|c| c[1].parse::<u32>().ok())
+            {
+                chapter = n;
+            }
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.trim().is_empty() {
+        sections.push((chapter, current.trim().to_string()));
+    }
+    sections
+}
+
+/// Collapse runs of blank lines left behind by stripped comments.
+fn tidy_blank_lines(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render `text` to `output` through the configured TTS backend.
+fn synthesize(tts: &TtsConfig, text: &str, output: &Path) -> Result<()> {
+    let output_str = output.to_string_lossy().to_string();
+    // Holds the API key for curl; removed once the backend has exited
+    let mut secrets: Option<SecretConfig> = None;
+
+    let mut child = if let Some(cmd) = &tts.command {
+        let quoted = format!("'{}'", output_str.replace('\'', "'\\''"));
+        Command::new("sh")
+            .arg("-c")
+            .arg(cmd.replace("{output}", &quoted))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to spawn TTS command: {}", cmd))?
+    } else if let Some(url) = &tts.api_url {
+        let key = std::env::var(&tts.api_key_env).map_err(|_| {
+            anyhow!(
+                "TTS API key not set — export it as ${} before running export audio",
+                tts.api_key_env
+            )
+        })?;
+        // Request body is streamed on stdin and the key read from a config file,
+        // so neither prose nor the key appears in the process list
+        let config = secrets.insert(SecretConfig::new(&[(
+            "header",
+            &format!("Authorization: Bearer {}", key),
+        )])?);
+        Command::new("curl")
+            .args(["-sS", "--fail", "-X", "POST", url])
+            .args(["-H", "Content-Type: application/json"])
+            .arg("-K")
+            .arg(config.path())
+            .args(["--data-binary", "@-", "-o", &output_str])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| "Failed to spawn curl for TTS API")?
+    } else {
+        bail!("Config.yml: tts needs exactly one of `command` or `api_url`");
+    };
+
+    let input = if tts.command.is_some() {
+        text.to_string()
+    } else {
+        serde_json::json!({
+            "text": text,
+            "voice": tts.voice,
+            "format": tts.format,
+        })
+        .to_string()
+    };
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .with_context(|| "Failed to send text to TTS backend")?;
+
+    let out = child
+        .wait_with_output()
+        .with_context(|| "Failed to wait for TTS backend")?;
+    drop(secrets);
+    if !out.status.success() {
+        bail!(
+            "TTS backend failed for {}: {}",
+            output.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+// ─── export audio ─────────────────────────────────────────────────────────────
+
+/// Render the prose added to Full_Book.md since `since` (a snapshot tag; default:
+/// the most recent `ink-*` tag) into one audio file per chapter under
+/// `Assets/audio/`, using the `tts` backend from Config.yml.
+///
+/// Audio files are proofing aids, not book content: the directory gets a
+/// `.gitignore` so they are never committed by the next session-open.
+pub fn export_audio(repo: &Path, since: Option<&str>) -> Result<serde_json::Value> {
+    let config = Config::load(repo)?;
+    let tts = config
+        .tts
+        .as_ref()
+        .ok_or_else(|| anyhow!("no TTS backend configured — add a `tts:` section to Config.yml"))?;
+
+    let since = match since {
+        Some(tag) => tag.to_string(),
+        None => latest_snapshot_tag(repo)
+            .ok_or_else(|| anyhow!("no ink-* snapshot tags found — pass --since <tag>"))?,
+    };
+
+    let book = storage::compiled_book(repo)?
+        .ok_or_else(|| anyhow!("Full_Book.md does not exist — nothing to render"))?;
+    // A tag from before Full_Book.md existed means everything is new
+    let old = storage::book_at(repo, &since).unwrap_or_default();
+
+    let groups = new_prose_by_chapter(&old, &book);
+    let audio_dir = repo.join("Assets").join("audio");
+    std::fs::create_dir_all(&audio_dir).with_context(|| "Failed to create Assets/audio/")?;
+    let ignore = audio_dir.join(".gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "*\n!.gitignore\n")
+            .with_context(|| "Failed to write Assets/audio/.gitignore")?;
+    }
+
+    let mut files: Vec<String> = Vec::new();
+    for (i, (heading, paras)) in groups.iter().enumerate() {
+        let text = paras
+            .iter()
+            .map(|p| p.trim_start_matches('#').trim())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let name = format!(
+            "{:02}-{}-{}.{}",
+            i + 1,
+            slugify(heading),
+            slugify(&since),
+            tts.format
+        );
+        let path: PathBuf = audio_dir.join(&name);
+        info!("Rendering {} ({} paragraphs)", name, paras.len());
+        synthesize(tts, &text, &path)?;
+        files.push(format!("Assets/audio/{}", name));
+    }
+
+    Ok(serde_json::json!({
+        "status": if files.is_empty() { "nothing_new" } else { "exported" },
+        "since": since,
+        "files": files,
+    }))
+}
+
+// ─── export --profile ─────────────────────────────────────────────────────────
+
+/// Run the export profile `name` from Config.yml: front matter, the chapters in
+/// range from Full_Book.md (comments and page markers stripped), back matter,
+/// written as Markdown or HTML (with the stylesheet inlined) to the profile's
+/// output path. Metadata.yml, when present, heads Markdown as a YAML metadata
+/// block and HTML as `<meta>` elements. The file is left in the working tree;
+/// nothing is committed.
+pub fn export_profile(repo: &Path, name: &str) -> Result<serde_json::Value> {
+    let config = Config::load(repo)?;
+    let profile = config.exports.get(name).ok_or_else(|| {
+        let known: Vec<&str> = config.exports.keys().map(String::as_str).collect();
+        anyhow!(
+            "no export profile '{}' in Config.yml (known: {})",
+            name,
+            if known.is_empty() {
+                "none".to_string()
+            } else {
+                known.join(", ")
+            }
+        )
+    })?;
+    let range = profile.chapter_range()?;
+
+    let read = |rel: &str| {
+        std::fs::read_to_string(repo.join(rel))
+            .map(|c| strip_html_comments(&c))
+            .with_context(|| format!("Failed to read {} for export profile '{}'", rel, name))
+    };
+
+    let book = strip_html_comments(
+        &storage::compiled_book(repo)?
+            .ok_or_else(|| anyhow!("Full_Book.md does not exist — nothing to export"))?,
+    );
+    let sections: Vec<(u32, String)> = chapter_sections(&book)
+        .into_iter()
+        .filter(|(n, _)| range.is_none_or(|(first, last)| (first..=last).contains(n)))
+        .collect();
+    let mut chapters: Vec<u32> = sections
+        .iter()
+        .map(|(n, _)| *n)
+        .filter(|n| *n > 0)
+        .collect();
+    chapters.dedup();
+    if sections.is_empty() {
+        bail!(
+            "export profile '{}' selects no text from Full_Book.md (chapters: {})",
+            name,
+            profile.chapters.as_deref().unwrap_or("all")
+        );
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    for rel in &profile.front_matter {
+        parts.push(read(rel)?);
+    }
+    let body: Vec<String> = sections.into_iter().map(|(_, text)| text).collect();
+    parts.push(storage::with_part_dividers(
+        &body.join("\n\n"),
+        &config.parts,
+    ));
+    for rel in &profile.back_matter {
+        parts.push(read(rel)?);
+    }
+    let markdown = tidy_blank_lines(&parts.join("\n\n")) + "\n";
+    let book_metadata = match repo.join(metadata::METADATA_FILE).exists() {
+        true => Some(metadata::effective(repo)?),
+        false => None,
+    };
+
+    let output = match profile.format {
+        ManuscriptFormat::Markdown => match book_metadata.as_ref().and_then(metadata::yaml_block) {
+            Some(block) => format!("{}\n{}", block, markdown),
+            None => markdown.clone(),
+        },
+        ManuscriptFormat::Html => {
+            let stylesheet = match &profile.stylesheet {
+                Some(rel) => Some(
+                    std::fs::read_to_string(repo.join(rel))
+                        .with_context(|| format!("Failed to read stylesheet {}", rel))?,
+                ),
+                None => None,
+            };
+            // The book's own title, even when the range leaves its heading out
+            let title = book_metadata
+                .as_ref()
+                .and_then(|m| m.title.as_deref())
+                .or_else(|| book.lines().find_map(|l| l.trim_start().strip_prefix("# ")))
+                .unwrap_or(name)
+                .trim()
+                .to_string();
+            let meta = book_metadata
+                .as_ref()
+                .map(metadata::html_meta)
+                .unwrap_or_default();
+            to_html(&title, &meta, &markdown, stylesheet.as_deref())
+        }
+    };
+
+    let output_rel = profile.output_path(name);
+    let output_path = repo.join(&output_rel);
+    if let Some(dir) = output_path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&output_path, &output)
+        .with_context(|| format!("Failed to write {}", output_rel))?;
+    info!("Exported profile '{}' to {}", name, output_rel);
+
+    Ok(serde_json::json!({
+        "status": "exported",
+        "profile": name,
+        "format": profile.format.extension(),
+        "output": output_rel,
+        "chapters": chapters,
+        "word_count": WordCounter::new(&config).count(&markdown),
+    }))
+}
+
+// ─── export scrivener ─────────────────────────────────────────────────────────
+
+const SUMMARY_REL: &str = "Global Material/Summary.md";
+
+/// Split `book` (comments stripped) at `# ` / `## ` headings into
+/// `(heading, body)` documents. A heading with no prose under it (the book
+/// title) is left out; prose before any heading becomes "Front matter".
+fn heading_documents(book: &str) -> Vec<(String, String)> {
+    let mut docs: Vec<(String, String)> = Vec::new();
+    let mut heading = "Front matter".to_string();
+    let mut body = String::new();
+    for line in book.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("# ") || trimmed.starts_with("## ") {
+            if !body.trim().is_empty() {
+                docs.push((heading, tidy_blank_lines(&body)));
+            }
+            heading = trimmed.trim_start_matches('#').trim().to_string();
+            body.clear();
+            continue;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    if !body.trim().is_empty() {
+        docs.push((heading, tidy_blank_lines(&body)));
+    }
+    docs
+}
+
+/// Summary.md entries, each under the heading Full_Book.md ended with when the
+/// entry was added — the unit that session wrote. Walks the commits that
+/// touched Summary.md, then the working tree for entries not yet committed.
+fn summaries_by_heading(repo: &Path) -> Vec<(String, String)> {
+    let last_heading = |book: &str| {
+        heading_documents(&strip_html_comments(book))
+            .pop()
+            .map(|(heading, _)| heading)
+            .unwrap_or_else(|| "Front matter".to_string())
+    };
+    let mut versions: Vec<(String, String)> = git::run_git(
+        repo,
+        &["log", "--reverse", "--format=%H", "--", SUMMARY_REL],
+    )
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|commit| {
+        let summary = git::run_git(repo, &["show", &format!("{}:{}", commit, SUMMARY_REL)]).ok()?;
+        let book = storage::book_at(repo, commit).unwrap_or_default();
+        Some((summary, book))
+    })
+    .collect();
+    if let Ok(summary) = std::fs::read_to_string(repo.join(SUMMARY_REL)) {
+        let book = storage::compiled_book(repo)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        versions.push((summary, book));
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut entries = Vec::new();
+    for (summary, book) in versions {
+        let heading = last_heading(&book);
+        for entry in summary.split("\n\n").map(str::trim) {
+            if !entry.is_empty() && !entry.starts_with('#') && seen.insert(entry.to_string()) {
+                entries.push((heading.clone(), entry.to_string()));
+            }
+        }
+    }
+    entries
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+/// Write a bundle Scrivener imports (File ▸ Import): one MultiMarkdown document
+/// per Full_Book.md section (`NN-<slug>.md`, `Title:` / `Synopsis:` metadata)
+/// and an OPML outline of the same sections whose notes are the synopsis
+/// cards, built from the Summary.md entries of the sessions that wrote each
+/// section. `output` is repo-relative (default `Exports/scrivener`); files of
+/// an earlier export there are replaced. Nothing is committed.
+pub fn export_scrivener(repo: &Path, output: Option<&Path>) -> Result<serde_json::Value> {
+    let book = storage::compiled_book(repo)?.unwrap_or_default();
+    let book = strip_html_comments(&book);
+    let docs = heading_documents(&book);
+    if docs.is_empty() {
+        bail!("Full_Book.md holds no prose to export");
+    }
+    let title = book
+        .lines()
+        .find_map(|l| l.trim_start().strip_prefix("# "))
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| crate::init::repo_name(repo));
+    let summaries = summaries_by_heading(repo);
+
+    let output_rel = output
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Exports/scrivener".to_string());
+    let dir = repo.join(&output_rel);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", output_rel))?;
+    static OWN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let own = OWN.get_or_init(|| regex::Regex::new(r"^(\d{2,}-[\w-]*\.md|.+\.opml)$").unwrap());
+    for entry in std::fs::read_dir(&dir)?.filter_map(|e| e.ok()) {
+        if own.is_match(&entry.file_name().to_string_lossy()) {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+
+    let mut outline = String::new();
+    let mut documents = Vec::new();
+    let mut cards = 0;
+    for (i, (heading, body)) in docs.iter().enumerate() {
+        let synopsis: Vec<&str> = summaries
+            .iter()
+            .filter(|(h, _)| h == heading)
+            .map(|(_, entry)| entry.as_str())
+            .collect();
+        cards += usize::from(!synopsis.is_empty());
+        let synopsis = synopsis.join("\n\n");
+
+        let name = format!("{:02}-{}.md", i + 1, slugify(heading));
+        let mut doc = format!("Title: {}\n", heading);
+        if !synopsis.is_empty() {
+            doc.push_str(&format!("Synopsis: {}\n", synopsis.replace("\n\n", " ")));
+        }
+        doc.push_str(&format!("\n# {}\n\n{}\n", heading, body));
+        std::fs::write(dir.join(&name), doc)
+            .with_context(|| format!("Failed to write {}", name))?;
+        documents.push(format!("{}/{}", output_rel, name));
+
+        outline.push_str(&format!("    <outline text=\"{}\"", xml_escape(heading)));
+        if !synopsis.is_empty() {
+            outline.push_str(&format!(" _note=\"{}\"", xml_escape(&synopsis)));
+        }
+        outline.push_str("/>\n");
+    }
+    let opml_name = format!("{}.opml", slugify(&title));
+    std::fs::write(
+        dir.join(&opml_name),
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head>\n    \
+             <title>{}</title>\n  </head>\n  <body>\n{}  </body>\n</opml>\n",
+            xml_escape(&title),
+            outline
+        ),
+    )
+    .with_context(|| format!("Failed to write {}", opml_name))?;
+    info!("Exported {} documents to {}", documents.len(), output_rel);
+
+    Ok(serde_json::json!({
+        "status": "exported",
+        "format": "scrivener",
+        "output": output_rel,
+        "opml": format!("{}/{}", output_rel, opml_name),
+        "documents": documents,
+        "synopsis_cards": cards,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+target_length: 90000
+chapter_count: 3
+chapter_structure: linear
+words_per_session: 1500
+exports:
+  print:
+    format: html
+    front_matter: [Global Material/Dedication.md]
+    chapters: 2-3
+    stylesheet: Assets/print.css
+    output: Out/print.html
+";
+
+    const BOOK: &str = "\
+<!-- managed by ink-cli -->
+
+# The Gate
+
+## Prologue
+
+Before.
+
+## Chapter 1
+
+One.
+
+<!-- PAGE 1 -->
+
+## Chapter 2
+
+Two.
+
+## Interlude A
+
+Between.
+
+## Chapter 3
+
+Three & more.
+";
+
+    #[test]
+    fn slugs_are_lowercase_ascii_words_joined_by_dashes() {
+        assert_eq!(slugify("Chapter 3: The Gate"), "chapter-3-the-gate");
+        assert_eq!(slugify("ink-2026-01-01-09-00"), "ink-2026-01-01-09-00");
+        assert_eq!(slugify("  Café — Night!  "), "caf-night");
+    }
+
+    #[test]
+    fn newest_snapshot_tag_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q"]).unwrap();
+        assert_eq!(latest_snapshot_tag(repo), None);
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "--allow-empty", "-m", "init"]).unwrap();
+        for tag in ["ink-2026-01-02-09-00", "ink-2026-01-10-09-00", "v1"] {
+            git::run_git(repo, &["tag", tag]).unwrap();
+        }
+        assert_eq!(
+            latest_snapshot_tag(repo).as_deref(),
+            Some("ink-2026-01-10-09-00")
+        );
+    }
+
+    #[test]
+    fn new_prose_is_grouped_under_its_heading() {
+        let old = BOOK.split("## Chapter 2").next().unwrap();
+        let groups = new_prose_by_chapter(old, BOOK);
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "Chapter 2".to_string(),
+                    vec!["## Chapter 2".into(), "Two.".into()]
+                ),
+                (
+                    "Interlude A".to_string(),
+                    vec!["## Interlude A".into(), "Between.".into()]
+                ),
+                (
+                    "Chapter 3".to_string(),
+                    vec!["## Chapter 3".into(), "Three & more.".into()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn new_prose_before_any_heading_is_front_matter() {
+        let groups = new_prose_by_chapter("# One\n\nOld.", "Intro.\n\n# One\n\nOld.\n\nNew.");
+        assert_eq!(
+            groups,
+            vec![
+                ("Front matter".to_string(), vec!["Intro.".into()]),
+                ("One".to_string(), vec!["New.".into()]),
+            ]
+        );
+    }
+
+    /// A book repo whose TTS backend copies its input to the output file, with
+    /// Chapter 1 snapshotted under an `ink-*` tag and Chapters 2–3 written since.
+    fn audio_repo(tts: &str) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::create_dir_all(repo.join("Current version")).unwrap();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            format!("target_length: 90000\nchapter_count: 3\nchapter_structure: linear\nwords_per_session: 1500\n{tts}"),
+        )
+        .unwrap();
+        let old = BOOK.split("## Chapter 2").next().unwrap();
+        std::fs::write(repo.join(storage::FULL_BOOK), old).unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "session"]).unwrap();
+        git::run_git(repo, &["tag", "ink-2026-01-01-09-00"]).unwrap();
+        std::fs::write(repo.join(storage::FULL_BOOK), BOOK).unwrap();
+        tmp
+    }
+
+    const COPY_TTS: &str = "tts:\n  command: \"cat > {output}\"\n  format: txt\n";
+
+    #[test]
+    fn audio_renders_one_file_per_chapter_since_the_last_snapshot() {
+        let tmp = audio_repo(COPY_TTS);
+        let repo = tmp.path();
+
+        let result = export_audio(repo, None).unwrap();
+        assert_eq!(result["status"], "exported");
+        assert_eq!(result["since"], "ink-2026-01-01-09-00");
+        assert_eq!(
+            result["files"],
+            serde_json::json!([
+                "Assets/audio/01-chapter-2-ink-2026-01-01-09-00.txt",
+                "Assets/audio/02-interlude-a-ink-2026-01-01-09-00.txt",
+                "Assets/audio/03-chapter-3-ink-2026-01-01-09-00.txt",
+            ])
+        );
+        let chapter_3 = std::fs::read_to_string(
+            repo.join("Assets/audio/03-chapter-3-ink-2026-01-01-09-00.txt"),
+        )
+        .unwrap();
+        assert_eq!(chapter_3, "Chapter 3\n\nThree & more.");
+        assert_eq!(
+            std::fs::read_to_string(repo.join("Assets/audio/.gitignore")).unwrap(),
+            "*\n!.gitignore\n"
+        );
+    }
+
+    #[test]
+    fn audio_since_the_current_book_has_nothing_new() {
+        let tmp = audio_repo(COPY_TTS);
+        let repo = tmp.path();
+        git::run_git(repo, &["commit", "-qam", "session"]).unwrap();
+        git::run_git(repo, &["tag", "ink-2026-01-02-09-00"]).unwrap();
+
+        let result = export_audio(repo, None).unwrap();
+        assert_eq!(result["status"], "nothing_new");
+        assert_eq!(result["files"], serde_json::json!([]));
+
+        let result = export_audio(repo, Some("ink-2026-01-01-09-00")).unwrap();
+        assert_eq!(result["files"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn audio_needs_a_tts_backend() {
+        let tmp = audio_repo("");
+        let err = export_audio(tmp.path(), None).unwrap_err();
+        assert!(err.to_string().contains("no TTS backend configured"));
+    }
+
+    #[test]
+    fn audio_needs_a_snapshot_tag() {
+        let tmp = audio_repo(COPY_TTS);
+        let repo = tmp.path();
+        git::run_git(repo, &["tag", "-d", "ink-2026-01-01-09-00"]).unwrap();
+        let err = export_audio(repo, None).unwrap_err();
+        assert!(err.to_string().contains("pass --since <tag>"));
+    }
+
+    #[test]
+    fn a_failing_tts_backend_reports_its_stderr() {
+        let tmp =
+            audio_repo("tts:\n  command: \"cat >/dev/null; echo voice unavailable >&2; exit 3\"\n");
+        let err = export_audio(tmp.path(), None).unwrap_err().to_string();
+        assert!(err.contains("TTS backend failed"), "{err}");
+        assert!(err.contains("voice unavailable"), "{err}");
+    }
+
+    #[test]
+    fn tts_api_needs_its_key_in_the_environment() {
+        let tts = TtsConfig {
+            command: None,
+            api_url: Some("http://127.0.0.1:9/tts".into()),
+            api_key_env: "INK_TEST_TTS_KEY_NEVER_SET".into(),
+            voice: None,
+            format: "mp3".into(),
+        };
+        let tmp = tempfile::tempdir().unwrap();
+        let err = synthesize(&tts, "Hello.", &tmp.path().join("out.mp3"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("$INK_TEST_TTS_KEY_NEVER_SET"), "{err}");
+    }
+
+    #[test]
+    fn profile_exports_chapter_range_with_matter() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for (rel, content) in [
+            ("Global Material/Config.yml", CONFIG),
+            ("Global Material/Dedication.md", "# For Ada\n"),
+            ("Current version/Full_Book.md", BOOK),
+            ("Assets/print.css", "body { font-family: serif; }\n"),
+        ] {
+            std::fs::create_dir_all(repo.join(rel).parent().unwrap()).unwrap();
+            std::fs::write(repo.join(rel), content).unwrap();
+        }
+
+        let result = export_profile(repo, "print").unwrap();
+        assert_eq!(result["output"], "Out/print.html");
+        assert_eq!(result["chapters"], serde_json::json!([2, 3]));
+
+        let html = std::fs::read_to_string(repo.join("Out/print.html")).unwrap();
+        assert!(html.contains("<title>The Gate</title>"));
+        assert!(html.contains("<style>\nbody { font-family: serif; }\n</style>"));
+        let order: Vec<usize> = ["For Ada", "Chapter 2", "Interlude A", "Three &amp; more."]
+            .iter()
+            .map(|s| html.find(s).unwrap_or_else(|| panic!("missing {s}")))
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+        for absent in ["Prologue", "Chapter 1", "PAGE", "managed"] {
+            assert!(!html.contains(absent), "{absent} should be excluded");
+        }
+
+        assert!(export_profile(repo, "ebook")
+            .unwrap_err()
+            .to_string()
+            .contains("known: print"));
+    }
+
+    #[test]
+    fn scrivener_bundle_has_a_document_and_card_per_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::create_dir_all(repo.join("Current version")).unwrap();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        let session = |book: &str, summary: &str| {
+            std::fs::write(repo.join(storage::FULL_BOOK), book).unwrap();
+            std::fs::write(repo.join(SUMMARY_REL), summary).unwrap();
+            git::run_git(repo, &["add", "-A"]).unwrap();
+            git::run_git(repo, &["commit", "-q", "-m", "session"]).unwrap();
+        };
+        let first = BOOK.split("## Chapter 2").next().unwrap();
+        session(first, "Mara waits.\n\nMara arrives.");
+        session(
+            BOOK,
+            "Mara waits.\n\nMara arrives.\n\nThe gate & the \"key\".",
+        );
+        export_scrivener(repo, None).unwrap();
+        std::fs::write(repo.join("Exports/scrivener/99-stale.md"), "").unwrap();
+
+        let result = export_scrivener(repo, None).unwrap();
+        assert!(!repo.join("Exports/scrivener/99-stale.md").exists());
+        assert_eq!(result["opml"], "Exports/scrivener/the-gate.opml");
+        assert_eq!(result["synopsis_cards"], 2);
+        assert_eq!(
+            result["documents"],
+            serde_json::json!([
+                "Exports/scrivener/01-prologue.md",
+                "Exports/scrivener/02-chapter-1.md",
+                "Exports/scrivener/03-chapter-2.md",
+                "Exports/scrivener/04-interlude-a.md",
+                "Exports/scrivener/05-chapter-3.md",
+            ])
+        );
+        let chapter_1 =
+            std::fs::read_to_string(repo.join("Exports/scrivener/02-chapter-1.md")).unwrap();
+        assert_eq!(
+            chapter_1,
+            "Title: Chapter 1\nSynopsis: Mara waits. Mara arrives.\n\n# Chapter 1\n\nOne.\n"
+        );
+        let opml = std::fs::read_to_string(repo.join(result["opml"].as_str().unwrap())).unwrap();
+        assert!(opml.contains("<title>The Gate</title>"));
+        assert!(opml.contains(
+            "<outline text=\"Chapter 3\" _note=\"The gate &amp; the &quot;key&quot;.\"/>"
+        ));
+        assert!(opml.contains("<outline text=\"Prologue\"/>"));
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// YAML front matter of an outline file (`Chapter_NN.md`, `Prologue.md`, …):
+///
+/// ```text
+/// ---
+/// status: drafting
+/// pov: Mara
+/// perspective: first
+/// target_words: 4000
+/// location: The lighthouse
+/// date: Day 3, dawn
+/// ---
+/// ```
+///
+/// Every key is optional; unknown keys are ignored.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FrontMatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Point-of-view character.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pov: Option<String>,
+    /// Narrative person: `first`, `second`, or `third` (`third person
+    /// limited` etc. also read); checked by session-close and `lint`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perspective: Option<String>,
+    /// Chapter goal in words.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_words: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// In-story timeline date, free text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+/// Split `content` into its front matter and the body after it. Content that
+/// does not open with a `---` line has none; an unclosed block is an error.
+pub fn split(content: &str) -> Result<(Option<FrontMatter>, &str)> {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return Ok((None, content));
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            let yaml = &rest[..offset];
+            let body = rest[offset + line.len()..].trim_start_matches(['\r', '\n']);
+            let front: Option<FrontMatter> =
+                serde_yaml::from_str(yaml).context("Invalid YAML front matter")?;
+            return Ok((Some(front.unwrap_or_default()), body));
+        }
+        offset += line.len();
+    }
+    anyhow::bail!("Front matter opened with `---` is never closed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_parses_known_keys_and_returns_body() {
+        let (front, body) = split(
+            "---\nstatus: drafting\npov: Mara\ntarget_words: 4000\n\
+             date: 2031-04-02\nmood: grim\n---\n\n# Chapter 3\n",
+        )
+        .unwrap();
+        let front = front.unwrap();
+        assert_eq!(front.pov.as_deref(), Some("Mara"));
+        assert_eq!(front.target_words, Some(4000));
+        assert_eq!(front.date.as_deref(), Some("2031-04-02"));
+        assert_eq!(front.location, None);
+        assert_eq!(body, "# Chapter 3\n");
+    }
+
+    #[test]
+    fn a_rule_after_the_first_line_is_not_front_matter() {
+        let (front, body) = split("# Chapter 3\n\n---\n").unwrap();
+        assert!(front.is_none());
+        assert_eq!(body, "# Chapter 3\n\n---\n");
+    }
+
+    #[test]
+    fn unterminated_front_matter_fails() {
+        assert!(split("---\npov: Mara\n# Chapter 3\n").is_err());
+    }
+
+    #[test]
+    fn a_mistyped_value_fails() {
+        assert!(split("---\ntarget_words: many\n---\n").is_err());
+    }
+}
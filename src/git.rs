@@ -1,42 +1,524 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use chrono::Local;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+use crate::config::{GitRetryConfig, GitSettings};
+use crate::errors::InkError;
+
+thread_local! {
+    /// Network retries made on this thread since the last `take_network_retries`.
+    static NETWORK_RETRIES: Cell<u32> = const { Cell::new(0) };
+    /// Last git command killed for running too long, until `take_timeout`.
+    static LAST_TIMEOUT: RefCell<Option<GitTimeout>> = const { RefCell::new(None) };
+}
+
+/// A git command killed after running longer than Config.yml
+/// `git_timeout_seconds` (a stuck credential prompt, a dead network).
+#[derive(Debug, Clone, Serialize)]
+pub struct GitTimeout {
+    pub command: String,
+    pub timeout_seconds: u64,
+}
+
+impl std::fmt::Display for GitTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` timed out after {}s and was killed",
+            self.command, self.timeout_seconds
+        )
+    }
+}
+
+impl std::error::Error for GitTimeout {}
+
+/// Run git in `repo`, killing it after `git_timeout_seconds`. `fetch` and
+/// `push` are retried with exponential backoff (Config.yml `git_retry`) when
+/// they fail for a network reason; a timeout is not retried. Commits are
+/// signed when `sign_commits` is on.
 pub fn run_git(repo: &Path, args: &[&str]) -> Result<String> {
-    let output = Command::new("git")
+    let settings = crate::config::read_git_settings(repo);
+    let timeout = (settings.git_timeout_seconds > 0)
+        .then(|| Duration::from_secs(settings.git_timeout_seconds));
+    let command = args.get(command_index(args)).copied();
+    let signed = signing_args(&settings, args);
+    let args: &[&str] = &signed.iter().map(String::as_str).collect::<Vec<_>>();
+    let retry = matches!(command, Some("fetch") | Some("push")).then_some(settings.git_retry);
+    let mut attempt = 0;
+    loop {
+        let output = output_within(repo, args, timeout)?;
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        match &retry {
+            Some(retry)
+                if attempt < retry.retries && classify_failure(&stderr) == Failure::Network =>
+            {
+                let delay = backoff(retry, attempt);
+                attempt += 1;
+                warn!(
+                    "git {} failed ({}), retry {}/{} in {}s",
+                    command.unwrap_or_default(),
+                    stderr.lines().last().unwrap_or_default(),
+                    attempt,
+                    retry.retries,
+                    delay.as_secs()
+                );
+                NETWORK_RETRIES.with(|n| n.set(n.get() + 1));
+                crate::signals::checkpoint(&format!("git {} retry", command.unwrap_or_default()))?;
+                std::thread::sleep(delay);
+            }
+            _ => {
+                let message = if attempt > 0 {
+                    format!(
+                        "git {:?} failed after {} retries: {}",
+                        args, attempt, stderr
+                    )
+                } else {
+                    format!("git {:?} failed: {}", args, stderr)
+                };
+                return Err(InkError::GitFailed {
+                    command: format!("git {}", args.join(" ")),
+                    message,
+                }
+                .into());
+            }
+        }
+    }
+}
+
+/// Position of the git subcommand in `args`, past leading `-c name=value` pairs.
+fn command_index(args: &[&str]) -> usize {
+    let mut i = 0;
+    while args.get(i) == Some(&"-c") {
+        i += 2;
+    }
+    i
+}
+
+/// `args` with signing options added when Config.yml `sign_commits` is on and
+/// the command creates commits — `commit`, `commit-tree`, or the start of a
+/// `rebase` (which keeps the option for `--continue`): `-c gpg.format=...` in
+/// front and `--gpg-sign[=key]` after the subcommand.
+fn signing_args(settings: &GitSettings, args: &[&str]) -> Vec<String> {
+    let at = command_index(args);
+    let mut out: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let creates_commits = match args.get(at) {
+        Some(&"commit") | Some(&"commit-tree") => true,
+        Some(&"rebase") => !matches!(
+            args.get(at + 1),
+            Some(&"--continue") | Some(&"--abort") | Some(&"--skip") | Some(&"--quit")
+        ),
+        _ => false,
+    };
+    if !settings.sign_commits || !creates_commits {
+        return out;
+    }
+    out.insert(
+        at + 1,
+        match &settings.signing_key {
+            Some(key) => format!("--gpg-sign={}", key),
+            None => "--gpg-sign".to_string(),
+        },
+    );
+    if let Some(format) = settings.signing_format {
+        out.splice(
+            0..0,
+            ["-c".to_string(), format!("gpg.format={}", format.as_str())],
+        );
+    }
+    out
+}
+
+/// Sign a throwaway commit of HEAD's tree (no ref is touched) to prove the
+/// configured key, format, and agent work. Used by `doctor`.
+pub fn check_signing(repo: &Path) -> Result<()> {
+    run_git(
+        repo,
+        &[
+            "commit-tree",
+            "HEAD^{tree}",
+            "-m",
+            "ink-gateway signing check",
+        ],
+    )
+    .map(|_| ())
+}
+
+/// Spawn git and collect its output, killing it once it has run for `timeout`.
+/// stdin is closed: git must never wait on input the caller cannot give.
+fn output_within(repo: &Path, args: &[&str], timeout: Option<Duration>) -> Result<Output> {
+    let mut child = Command::new("git")
         .args(args)
         .current_dir(repo)
-        .output()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .with_context(|| format!("Failed to spawn git with args: {:?}", args))?;
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    // Both pipes are drained on their own threads (a chatty command never
+    // blocks on a full pipe) and reach EOF when git exits, so waiting on them
+    // is waiting on git — without polling, and with the child still ours to kill.
+    let (tx, rx) = mpsc::channel();
+    drain(child.stdout.take(), 0, tx.clone());
+    drain(child.stderr.take(), 1, tx);
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let mut output = [Vec::new(), Vec::new()];
+    for _ in 0..output.len() {
+        let received = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok((i, buf)) => output[i] = buf,
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                let timeout = GitTimeout {
+                    command: format!("git {}", args.join(" ")),
+                    timeout_seconds: timeout.unwrap_or_default().as_secs(),
+                };
+                warn!("{}", timeout);
+                LAST_TIMEOUT.with(|t| *t.borrow_mut() = Some(timeout.clone()));
+                return Err(timeout.into());
+            }
+        }
+    }
+    let status = child.wait().context("Failed to wait for git")?;
+    let [stdout, stderr] = output;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Read `pipe` to the end on a new thread and send it tagged with `index`.
+fn drain(pipe: Option<impl Read + Send + 'static>, index: usize, tx: Sender<(usize, Vec<u8>)>) {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        let _ = tx.send((index, buf));
+    });
+}
+
+/// The git timeout of the last failed command on this thread, if it failed
+/// by timing out; cleared by the call. Lets the MCP server answer with a
+/// structured timeout result instead of an error string.
+#[allow(dead_code)] // used by ink-gateway-mcp only
+pub fn take_timeout() -> Option<GitTimeout> {
+    LAST_TIMEOUT.with(|t| t.borrow_mut().take())
+}
+
+/// Network retries made on this thread since the last call, for the
+/// `network_retries` field of session payloads. Resets the count.
+pub fn take_network_retries() -> u32 {
+    NETWORK_RETRIES.with(|n| n.replace(0))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Failure {
+    /// Bad or missing credentials: retrying cannot help, fail fast.
+    Auth,
+    /// Timeout, unreachable host, dropped connection: worth retrying.
+    Network,
+    /// Anything else (rejected push, unknown remote, ...).
+    Other,
+}
+
+/// Classify a failed fetch/push by git's stderr. Auth is checked first:
+/// HTTPS auth failures also say "unable to access".
+fn classify_failure(stderr: &str) -> Failure {
+    const AUTH: &[&str] = &[
+        "authentication failed",
+        "permission denied",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "host key verification failed",
+        "invalid username or password",
+        "the requested url returned error: 401",
+        "the requested url returned error: 403",
+    ];
+    const NETWORK: &[&str] = &[
+        "could not resolve host",
+        "connection timed out",
+        "operation timed out",
+        "timed out after",
+        "connection refused",
+        "connection reset",
+        "failed to connect",
+        "network is unreachable",
+        "the remote end hung up unexpectedly",
+        "early eof",
+        "rpc failed",
+        "unexpected disconnect",
+        "the requested url returned error: 429",
+        "the requested url returned error: 5",
+    ];
+    let stderr = stderr.to_lowercase();
+    if AUTH.iter().any(|p| stderr.contains(p)) {
+        Failure::Auth
+    } else if NETWORK.iter().any(|p| stderr.contains(p)) {
+        Failure::Network
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        bail!("git {:?} failed: {}", args, stderr)
+        Failure::Other
     }
 }
 
+/// Wait before retry number `attempt + 1`: `backoff_seconds` doubled per
+/// retry, capped at `max_backoff_seconds`.
+fn backoff(retry: &GitRetryConfig, attempt: u32) -> Duration {
+    let seconds = retry
+        .backoff_seconds
+        .saturating_mul(1u64 << attempt.min(32))
+        .min(retry.max_backoff_seconds);
+    Duration::from_secs(seconds)
+}
+
+/// The published branch (sessions merge into it, authors edit it), the
+/// branch sessions write on, and the remote both are pushed to. `main` /
+/// `draft` / `origin` unless Config.yml says otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branches {
+    pub main: String,
+    pub draft: String,
+    pub remote: String,
+}
+
+impl Branches {
+    /// Remote-tracking ref of main, e.g. `origin/main`.
+    pub fn remote_main(&self) -> String {
+        format!("{}/{}", self.remote, self.main)
+    }
+
+    pub fn remote_draft(&self) -> String {
+        format!("{}/{}", self.remote, self.draft)
+    }
+}
+
+/// Branch names for `repo`: `main_branch` / `draft_branch` and the first of
+/// `remotes` from Config.yml when set; otherwise the main branch is detected
+/// (the remote's HEAD, then a local `main` or `master`, then
+/// `init.defaultBranch`), draft is `draft`, and the remote `origin`.
+pub fn branches(repo: &Path) -> Branches {
+    let settings = crate::config::read_git_settings(repo);
+    let remote = settings
+        .remotes
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "origin".to_string());
+    Branches {
+        main: settings
+            .main_branch
+            .unwrap_or_else(|| detect_main_branch(repo, &remote)),
+        draft: settings.draft_branch.unwrap_or_else(|| "draft".to_string()),
+        remote,
+    }
+}
+
+/// Backup remotes from Config.yml `remotes` (all but the first).
+pub fn mirrors(repo: &Path) -> Vec<String> {
+    crate::config::read_git_settings(repo)
+        .remotes
+        .into_iter()
+        .skip(1)
+        .collect()
+}
+
+fn detect_main_branch(repo: &Path, remote: &str) -> String {
+    if let Ok(head) = run_git(
+        repo,
+        &[
+            "symbolic-ref",
+            "--short",
+            &format!("refs/remotes/{}/HEAD", remote),
+        ],
+    ) {
+        if let Some(name) = head.strip_prefix(&format!("{}/", remote)) {
+            return name.to_string();
+        }
+    }
+    for name in ["main", "master"] {
+        let local = format!("refs/heads/{}", name);
+        let remote = format!("refs/remotes/{}/{}", remote, name);
+        if run_git(repo, &["show-ref", "--verify", "-q", &local]).is_ok()
+            || run_git(repo, &["show-ref", "--verify", "-q", &remote]).is_ok()
+        {
+            return name.to_string();
+        }
+    }
+    run_git(repo, &["config", "init.defaultBranch"])
+        .ok()
+        .filter(|b| !b.is_empty())
+        // An empty clone has no branches yet; HEAD names the one git chose
+        .or_else(|| run_git(repo, &["symbolic-ref", "--short", "HEAD"]).ok())
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// `git commit <args>` as the engine: under Config.yml `git_author_name` /
+/// `git_author_email` when set (author and committer alike), so blame tells
+/// session commits from the author's. Human-edit commits use plain `run_git`.
+pub fn engine_commit(repo: &Path, args: &[&str]) -> Result<String> {
+    let settings = crate::config::read_git_settings(repo);
+    let identity: Vec<String> = [
+        settings.git_author_name.map(|n| format!("user.name={}", n)),
+        settings
+            .git_author_email
+            .map(|e| format!("user.email={}", e)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let mut full = Vec::new();
+    for setting in &identity {
+        full.extend(["-c", setting.as_str()]);
+    }
+    full.push("commit");
+    full.extend_from_slice(args);
+    run_git(repo, &full)
+}
+
+/// Push the main branch to the primary remote.
+pub fn push_main(repo: &Path) -> Result<String> {
+    let branches = branches(repo);
+    run_git(repo, &["push", &branches.remote, &branches.main])
+}
+
+/// Outcome of updating one backup remote.
+#[derive(Debug, Serialize)]
+pub struct MirrorResult {
+    pub remote: String,
+    pub pushed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Force-push main, draft, and tags to every backup remote. Best-effort: a
+/// mirror that is down or rejects the push is reported, never fatal, since
+/// the primary remote already has everything.
+pub fn push_mirrors(repo: &Path) -> Vec<MirrorResult> {
+    let branches = branches(repo);
+    let mut refspecs = vec![format!("+refs/heads/{0}:refs/heads/{0}", branches.main)];
+    if run_git(
+        repo,
+        &[
+            "show-ref",
+            "--verify",
+            "-q",
+            &format!("refs/heads/{}", branches.draft),
+        ],
+    )
+    .is_ok()
+    {
+        refspecs.push(format!("+refs/heads/{0}:refs/heads/{0}", branches.draft));
+    }
+    mirrors(repo)
+        .into_iter()
+        .map(|remote| {
+            let mut args = vec!["push", "--tags", remote.as_str()];
+            args.extend(refspecs.iter().map(String::as_str));
+            match run_git(repo, &args) {
+                Ok(_) => {
+                    info!("Mirrored to {}", remote);
+                    MirrorResult {
+                        remote,
+                        pushed: true,
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    warn!("Mirror push to {} failed: {}", remote, e);
+                    MirrorResult {
+                        remote,
+                        pushed: false,
+                        error: Some(e.to_string()),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
 /// Fetch remote state and switch to main. Does NOT merge — call
 /// `merge_ff_origin_main` separately after human edits are committed.
+/// A shallow clone (CI runners) is deepened to the full history and tags
+/// first: fast-forward merges and snapshot tags need the real ancestry.
 pub fn preflight_fetch_and_checkout(repo: &Path) -> Result<()> {
-    info!("Fetching origin...");
-    run_git(repo, &["fetch", "origin"]).with_context(|| "Failed to fetch from origin")?;
+    let branches = branches(repo);
+    let mut fetch = vec!["fetch"];
+    if is_shallow(repo) {
+        info!(
+            "Shallow clone — fetching full history and tags from {}...",
+            branches.remote
+        );
+        fetch.extend(["--unshallow", "--tags"]);
+    } else if tags_disabled(repo, &branches.remote) {
+        info!("Fetching {} (with tags)...", branches.remote);
+        fetch.push("--tags");
+    } else {
+        info!("Fetching {}...", branches.remote);
+    }
+    fetch.push(&branches.remote);
+    run_git(repo, &fetch).with_context(|| format!("Failed to fetch from {}", branches.remote))?;
 
-    info!("Checking out main...");
-    run_git(repo, &["checkout", "main"]).with_context(|| "Failed to checkout main")?;
+    info!("Checking out {}...", branches.main);
+    run_git(repo, &["checkout", &branches.main])
+        .with_context(|| format!("Failed to checkout {}", branches.main))?;
 
     Ok(())
 }
 
+/// Whether HEAD points at a commit (false in a freshly cloned empty repo).
+pub fn has_commits(repo: &Path) -> bool {
+    run_git(repo, &["rev-parse", "--verify", "-q", "HEAD"]).is_ok()
+}
+
+/// Whether the clone has truncated history (`git clone --depth`).
+pub fn is_shallow(repo: &Path) -> bool {
+    run_git(repo, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|out| out == "true")
+}
+
+/// Whether `remote` was cloned with `--no-tags`, so tags are not followed.
+fn tags_disabled(repo: &Path, remote: &str) -> bool {
+    run_git(repo, &["config", &format!("remote.{}.tagOpt", remote)])
+        .is_ok_and(|opt| opt == "--no-tags")
+}
+
+/// Object filter of a partial clone of `remote` (`git clone --filter`), e.g. `blob:none`.
+pub fn partial_clone_filter(repo: &Path, remote: &str) -> Option<String> {
+    run_git(
+        repo,
+        &["config", &format!("remote.{}.partialclonefilter", remote)],
+    )
+    .ok()
+    .filter(|f| !f.is_empty())
+}
+
+/// Whether `path` exists in the tree of `rev` (e.g. `origin/main`).
+pub fn file_exists_at(repo: &Path, rev: &str, path: &str) -> bool {
+    run_git(repo, &["cat-file", "-e", &format!("{}:{}", rev, path)]).is_ok()
+}
+
 /// Fast-forward local main onto origin/main. Call this AFTER human edits
 /// are committed so the merge cannot overwrite uncommitted local changes.
 pub fn merge_ff_origin_main(repo: &Path) -> Result<()> {
-    info!("Fast-forward merging origin/main...");
-    run_git(repo, &["merge", "--ff-only", "origin/main"])
-        .with_context(|| "Failed to merge origin/main (non-fast-forward?)")?;
+    let origin_main = branches(repo).remote_main();
+    info!("Fast-forward merging {}...", origin_main);
+    run_git(repo, &["merge", "--ff-only", &origin_main])
+        .with_context(|| format!("Failed to merge {} (non-fast-forward?)", origin_main))?;
     Ok(())
 }
 
@@ -44,7 +526,17 @@ pub fn merge_ff_origin_main(repo: &Path) -> Result<()> {
 /// This catches IDE saves that were never committed/pushed — the diff between
 /// what the user has locally and what the remote last committed.
 pub fn collect_diffs_vs_remote(repo: &Path) -> Result<Vec<String>> {
-    match run_git(repo, &["diff", "origin/main", "--name-only"]) {
+    let origin_main = branches(repo).remote_main();
+    match run_git(
+        repo,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            &origin_main,
+            "--name-only",
+        ],
+    ) {
         Ok(output) => Ok(output
             .lines()
             .map(|l| l.trim().to_string())
@@ -54,27 +546,33 @@ pub fn collect_diffs_vs_remote(repo: &Path) -> Result<Vec<String>> {
     }
 }
 
+/// Files changed in the working tree or index relative to HEAD, plus untracked
+/// files. Name-only listings keep paths with spaces unquoted (e.g.
+/// `Global Material/Lore.md`), which `git status --short` would wrap in quotes.
 pub fn collect_modified_files(repo: &Path) -> Result<Vec<String>> {
-    let output = run_git(repo, &["status", "--short"])?;
-    let files: Vec<String> = output
-        .lines()
-        .filter_map(|line| {
-            // git status --short format: "XY filename" (2-char status + space + path).
-            // Use .get() to avoid panic on multi-byte UTF-8 characters.
-            let raw = line.get(3..)?.trim().to_string();
-            if raw.is_empty() {
-                return None;
-            }
-            // For renames/copies ("R old -> new"), extract the destination path.
-            if let Some(arrow_pos) = raw.find(" -> ") {
-                let dest = raw[arrow_pos + 4..].trim().to_string();
-                if !dest.is_empty() {
-                    return Some(dest);
-                }
-            }
-            Some(raw)
-        })
-        .collect();
+    // A repo without commits has no HEAD to diff against — only untracked files count
+    let tracked = run_git(
+        repo,
+        &["-c", "core.quotePath=false", "diff", "HEAD", "--name-only"],
+    )
+    .unwrap_or_default();
+    let untracked = run_git(
+        repo,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+        ],
+    )?;
+    let mut files: Vec<String> = Vec::new();
+    for line in tracked.lines().chain(untracked.lines()) {
+        let path = line.trim();
+        if !path.is_empty() && !files.iter().any(|f| f == path) {
+            files.push(path.to_string());
+        }
+    }
     Ok(files)
 }
 
@@ -123,16 +621,375 @@ pub fn create_snapshot_tag(repo: &Path) -> Result<String> {
 }
 
 pub fn push_tags(repo: &Path) -> Result<()> {
-    run_git(repo, &["push", "origin", "main", "--tags"])
-        .with_context(|| "Failed to push main with tags")?;
+    let branches = branches(repo);
+    run_git(repo, &["push", &branches.remote, &branches.main, "--tags"])
+        .with_context(|| format!("Failed to push {} with tags", branches.main))?;
     Ok(())
 }
 
 pub fn setup_draft_branch(repo: &Path) -> Result<()> {
     // Create or force-reset draft to match main — atomic, never conflicts.
     // This matches the pattern used in complete_session (git branch -f draft main).
-    info!("Setting up draft branch (force-reset to main)...");
-    run_git(repo, &["checkout", "-B", "draft", "main"])
-        .with_context(|| "Failed to create/reset draft branch")?;
+    let branches = branches(repo);
+    info!(
+        "Setting up {} branch (force-reset to {})...",
+        branches.draft, branches.main
+    );
+    run_git(repo, &["checkout", "-B", &branches.draft, &branches.main])
+        .with_context(|| format!("Failed to create/reset {} branch", branches.draft))?;
     Ok(())
 }
+
+/// The multi-step git operation stopped midway in `repo`, if any: `rebase`,
+/// `merge`, `cherry-pick`, or `revert` — each undone with `git <op> --abort`.
+pub fn operation_in_progress(repo: &Path) -> Option<&'static str> {
+    [
+        ("rebase-merge", "rebase"),
+        ("rebase-apply", "rebase"),
+        ("MERGE_HEAD", "merge"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| {
+        run_git(repo, &["rev-parse", "--git-path", marker]).is_ok_and(|p| repo.join(p).exists())
+    })
+    .map(|(_, op)| op)
+}
+
+/// Abort an in-progress merge, if any. Best-effort: used on interrupt cleanup
+/// so a killed session never leaves MERGE_HEAD behind.
+pub fn abort_merge_if_in_progress(repo: &Path) {
+    if run_git(repo, &["rev-parse", "-q", "--verify", "MERGE_HEAD"]).is_ok() {
+        match run_git(repo, &["merge", "--abort"]) {
+            Ok(_) => info!("Aborted in-progress merge"),
+            Err(e) => warn!("Could not abort in-progress merge: {}", e),
+        }
+    }
+}
+
+/// Discard all uncommitted writes made by the current command: reset tracked
+/// files to HEAD and remove untracked files under `paths`. Best-effort —
+/// called on the interrupt path where there is nothing useful to return.
+pub fn discard_uncommitted_changes(repo: &Path, paths: &[&str]) {
+    abort_merge_if_in_progress(repo);
+    if let Err(e) = run_git(repo, &["reset", "--hard", "HEAD"]) {
+        warn!("Could not reset working tree after interrupt: {}", e);
+    }
+    if !paths.is_empty() {
+        let mut args = vec!["clean", "-fd", "--"];
+        args.extend_from_slice(paths);
+        if let Err(e) = run_git(repo, &args) {
+            warn!("Could not clean untracked files after interrupt: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branches_are_detected_without_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        run_git(repo, &["init", "-q", "-b", "master"]).unwrap();
+        run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        run_git(repo, &["config", "user.name", "t"]).unwrap();
+        run_git(repo, &["commit", "-q", "--allow-empty", "-m", "root"]).unwrap();
+        assert_eq!(
+            branches(repo),
+            Branches {
+                main: "master".into(),
+                draft: "draft".into(),
+                remote: "origin".into(),
+            }
+        );
+        assert!(mirrors(repo).is_empty());
+    }
+
+    #[test]
+    fn branches_and_remotes_come_from_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        run_git(repo, &["init", "-q", "-b", "master"]).unwrap();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "main_branch: trunk\ndraft_branch: ink/wip\nremotes: [github, gitea]\n",
+        )
+        .unwrap();
+        let configured = branches(repo);
+        assert_eq!(
+            (configured.main.as_str(), configured.draft.as_str()),
+            ("trunk", "ink/wip")
+        );
+        assert_eq!(configured.remote_main(), "github/trunk");
+        assert_eq!(mirrors(repo), ["gitea"]);
+    }
+
+    /// A committed repository at `<tmp>/book` whose Config.yml lists
+    /// `remotes`, each a bare repository beside it.
+    fn repo_with_remotes(tmp: &Path, remotes: &[&str]) -> std::path::PathBuf {
+        let repo = tmp.join("book");
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            format!("remotes: [{}]\n", remotes.join(", ")),
+        )
+        .unwrap();
+        run_git(&repo, &["init", "-q", "-b", "main"]).unwrap();
+        run_git(&repo, &["config", "user.email", "t@t"]).unwrap();
+        run_git(&repo, &["config", "user.name", "t"]).unwrap();
+        for remote in remotes {
+            let bare = format!("{remote}.git");
+            run_git(tmp, &["init", "-q", "--bare", "-b", "main", &bare]).unwrap();
+            run_git(&repo, &["remote", "add", remote, &format!("../{bare}")]).unwrap();
+        }
+        run_git(&repo, &["add", "-A"]).unwrap();
+        run_git(&repo, &["commit", "-q", "-m", "init"]).unwrap();
+        repo
+    }
+
+    #[test]
+    fn main_is_pushed_to_the_first_remote() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = repo_with_remotes(tmp.path(), &["github", "gitea"]);
+        push_main(&repo).unwrap();
+        let head = run_git(&repo, &["rev-parse", "main"]).unwrap();
+        assert_eq!(
+            run_git(&repo, &["ls-remote", "github", "main"])
+                .unwrap()
+                .split('\t')
+                .next(),
+            Some(head.as_str())
+        );
+        assert!(run_git(&repo, &["ls-remote", "gitea", "main"])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn mirrors_get_main_draft_and_tags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = repo_with_remotes(tmp.path(), &["origin", "backup"]);
+        run_git(&repo, &["branch", "draft"]).unwrap();
+        run_git(&repo, &["tag", "ink-2026-01-01-09-00"]).unwrap();
+
+        let results = push_mirrors(&repo);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            (results[0].remote.as_str(), results[0].pushed),
+            ("backup", true)
+        );
+        let refs = run_git(&repo, &["ls-remote", "backup"]).unwrap();
+        for name in [
+            "refs/heads/main",
+            "refs/heads/draft",
+            "refs/tags/ink-2026-01-01-09-00",
+        ] {
+            assert!(refs.contains(name), "{name} missing from {refs}");
+        }
+        // The primary remote is not a mirror
+        assert!(run_git(&repo, &["ls-remote", "origin"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_failing_mirror_is_reported_not_fatal() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = repo_with_remotes(tmp.path(), &["origin", "gone", "backup"]);
+        std::fs::remove_dir_all(tmp.path().join("gone.git")).unwrap();
+
+        let results = push_mirrors(&repo);
+        let gone = results.iter().find(|r| r.remote == "gone").unwrap();
+        assert!(!gone.pushed && gone.error.is_some());
+        assert!(
+            results
+                .iter()
+                .find(|r| r.remote == "backup")
+                .unwrap()
+                .pushed
+        );
+    }
+
+    #[test]
+    fn remote_names_are_validated() {
+        let config = |remotes: &str| {
+            crate::config::Config::parse(&format!(
+                "target_length: 90000\nchapter_count: 20\nchapter_structure: linear\n\
+                 words_per_session: 1500\nremotes: {remotes}\n"
+            ))
+        };
+        assert!(config("[github, gitea]").is_ok());
+        for bad in ["[origin, origin]", "['up stream']", "[github/main]", "['']"] {
+            assert!(config(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn engine_commits_use_the_configured_identity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        run_git(repo, &["init", "-q"]).unwrap();
+        run_git(repo, &["config", "user.email", "author@example.com"]).unwrap();
+        run_git(repo, &["config", "user.name", "Author"]).unwrap();
+        let last = || run_git(repo, &["log", "-1", "--format=%an <%ae> / %cn <%ce>"]).unwrap();
+
+        engine_commit(repo, &["-q", "--allow-empty", "-m", "unconfigured"]).unwrap();
+        assert_eq!(
+            last(),
+            "Author <author@example.com> / Author <author@example.com>"
+        );
+
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "git_author_name: Ink Engine\ngit_author_email: engine@ink.invalid\n",
+        )
+        .unwrap();
+        engine_commit(repo, &["-q", "--allow-empty", "-m", "session"]).unwrap();
+        assert_eq!(
+            last(),
+            "Ink Engine <engine@ink.invalid> / Ink Engine <engine@ink.invalid>"
+        );
+        run_git(repo, &["commit", "-q", "--allow-empty", "-m", "human"]).unwrap();
+        assert_eq!(
+            last(),
+            "Author <author@example.com> / Author <author@example.com>"
+        );
+    }
+
+    #[test]
+    fn commits_get_signing_options_when_enabled() {
+        let mut settings = GitSettings::default();
+        let commit = ["-c", "user.name=Ink", "commit", "-m", "session"];
+        assert_eq!(signing_args(&settings, &commit), commit);
+
+        settings.sign_commits = true;
+        assert_eq!(
+            signing_args(&settings, &commit),
+            [
+                "-c",
+                "user.name=Ink",
+                "commit",
+                "--gpg-sign",
+                "-m",
+                "session"
+            ]
+        );
+        settings.signing_key = Some("~/.ssh/id_ed25519.pub".into());
+        settings.signing_format = Some(crate::config::SigningFormat::Ssh);
+        assert_eq!(
+            signing_args(&settings, &["commit-tree", "HEAD^{tree}"]),
+            [
+                "-c",
+                "gpg.format=ssh",
+                "commit-tree",
+                "--gpg-sign=~/.ssh/id_ed25519.pub",
+                "HEAD^{tree}"
+            ]
+        );
+        assert_eq!(
+            signing_args(&settings, &["push", "origin"]),
+            ["push", "origin"]
+        );
+    }
+
+    #[test]
+    fn shallow_clone_is_deepened_with_tags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        run_git(&origin, &["init", "-q", "-b", "main"]).unwrap();
+        run_git(&origin, &["config", "user.email", "t@t"]).unwrap();
+        run_git(&origin, &["config", "user.name", "t"]).unwrap();
+        for n in 1..=3 {
+            run_git(
+                &origin,
+                &["commit", "-q", "--allow-empty", "-m", &n.to_string()],
+            )
+            .unwrap();
+            if n == 1 {
+                run_git(&origin, &["tag", "ink-snapshot-1"]).unwrap();
+            }
+        }
+        run_git(
+            tmp.path(),
+            &[
+                "clone",
+                "-q",
+                "--depth",
+                "1",
+                "--no-tags",
+                &format!("file://{}", origin.display()),
+                "clone",
+            ],
+        )
+        .unwrap();
+        let clone = tmp.path().join("clone");
+        assert!(is_shallow(&clone));
+
+        preflight_fetch_and_checkout(&clone).unwrap();
+        assert!(!is_shallow(&clone));
+        assert_eq!(
+            run_git(&clone, &["rev-list", "--count", "HEAD"]).unwrap(),
+            "3"
+        );
+        assert_eq!(run_git(&clone, &["tag"]).unwrap(), "ink-snapshot-1");
+    }
+
+    #[test]
+    fn network_failures_are_retried_with_capped_backoff() {
+        for stderr in [
+            "fatal: unable to access 'https://github.com/a/b.git/': Could not resolve host: github.com",
+            "ssh: connect to host github.com port 22: Connection timed out\nfatal: Could not read from remote repository.",
+            "error: RPC failed; HTTP 502 curl 22 The requested URL returned error: 502",
+        ] {
+            assert_eq!(classify_failure(stderr), Failure::Network, "{stderr}");
+        }
+        for stderr in [
+            "remote: Invalid username or password.\nfatal: Authentication failed for 'https://github.com/a/b.git/'",
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository.",
+            "fatal: unable to access 'https://github.com/a/b.git/': The requested URL returned error: 403",
+        ] {
+            assert_eq!(classify_failure(stderr), Failure::Auth, "{stderr}");
+        }
+        assert_eq!(
+            classify_failure(" ! [rejected]        main -> main (fetch first)"),
+            Failure::Other
+        );
+
+        let retry = GitRetryConfig {
+            retries: 5,
+            backoff_seconds: 2,
+            max_backoff_seconds: 10,
+        };
+        let waits: Vec<u64> = (0..5).map(|a| backoff(&retry, a).as_secs()).collect();
+        assert_eq!(waits, [2, 4, 8, 10, 10]);
+    }
+
+    #[test]
+    fn hung_git_is_killed_with_a_timeout_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        run_git(repo, &["init", "-q"]).unwrap();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "git_timeout_seconds: 1\n",
+        )
+        .unwrap();
+
+        let started = Instant::now();
+        let err = run_git(repo, &["-c", "alias.hang=!sleep 30", "hang"]).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let timeout = err.downcast_ref::<GitTimeout>().expect("a GitTimeout");
+        assert_eq!(timeout.timeout_seconds, 1);
+        assert_eq!(timeout.command, "git -c alias.hang=!sleep 30 hang");
+        assert_eq!(
+            take_timeout().map(|t| t.command),
+            Some(timeout.command.clone())
+        );
+        assert!(take_timeout().is_none());
+    }
+}
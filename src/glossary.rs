@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::strip_engine_markers;
+use crate::errors::InkError;
+use crate::timeline::{cell, split_row};
+
+/// Invented terms of the book: one table row per term.
+pub const GLOSSARY_FILE: &str = "Global Material/Glossary.md";
+
+pub const GLOSSARY_HEADER: &str = "# Glossary\n\n\
+    *Invented terms — one row per term. `Spelling` lists accepted variant forms \
+    (possessives are implicit). Extended by `session-close --new-terms`; \
+    checked by session-close and `lint`.*\n\n\
+    | Term | Definition | Spelling |\n\
+    |---|---|---|\n";
+
+/// Shortest word the misspelling check compares: shorter names sit too
+/// close to ordinary words.
+const MIN_FUZZY_LEN: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub definition: String,
+    /// Accepted variant forms: plurals, declensions, …
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spelling: Vec<String>,
+}
+
+impl GlossaryEntry {
+    /// `--new-terms "Aethryn | the drowned moon | Aethryns"`: term,
+    /// definition, then optional comma-separated variant spellings.
+    pub fn parse(arg: &str) -> Result<Self> {
+        let mut parts = arg.splitn(3, '|').map(str::trim);
+        let term = parts.next().unwrap_or_default();
+        let Some(definition) = parts.next().filter(|d| !d.is_empty()) else {
+            return Err(InkError::InvalidInput(format!(
+                "new term '{}' needs a definition: \"TERM | DEFINITION\"",
+                arg
+            ))
+            .into());
+        };
+        if term.is_empty() {
+            return Err(InkError::InvalidInput(format!("new term '{}' has no term", arg)).into());
+        }
+        Ok(GlossaryEntry {
+            term: term.to_string(),
+            definition: definition.to_string(),
+            spelling: parts.next().map(variants).unwrap_or_default(),
+        })
+    }
+
+    /// Lowercased words the entry accepts: the term's and its variants'.
+    pub(crate) fn words(&self) -> impl Iterator<Item = String> + '_ {
+        std::iter::once(&self.term)
+            .chain(&self.spelling)
+            .flat_map(|s| word_re().find_iter(s).map(|m| key(m.as_str())))
+    }
+}
+
+fn variants(cell: &str) -> Vec<String> {
+    cell.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// ─── File ─────────────────────────────────────────────────────────────────────
+
+/// Every entry of Glossary.md, in file order; empty without the file.
+pub fn load(repo: &Path) -> Result<Vec<GlossaryEntry>> {
+    let path = repo.join(GLOSSARY_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", GLOSSARY_FILE))?;
+    Ok(content
+        .lines()
+        .filter(|l| l.trim_start().starts_with('|'))
+        .map(split_row)
+        .filter(|cells| cells.len() >= 2 && !cells[0].is_empty())
+        .filter(|cells| cells[0] != "Term" && !cells[0].starts_with("---"))
+        .map(|cells| GlossaryEntry {
+            term: cells[0].clone(),
+            definition: cells[1].clone(),
+            spelling: cells.get(2).map(|c| variants(c)).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Append the entries whose term the glossary does not hold yet (case
+/// insensitive), creating the file with its header. Returns the added terms.
+pub fn append(repo: &Path, entries: &[GlossaryEntry]) -> Result<Vec<String>> {
+    let mut known: HashSet<String> = load(repo)?.iter().map(|e| e.term.to_lowercase()).collect();
+    let path = repo.join(GLOSSARY_FILE);
+    let mut content =
+        std::fs::read_to_string(&path).unwrap_or_else(|_| GLOSSARY_HEADER.to_string());
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    let mut added = Vec::new();
+    for e in entries {
+        if !known.insert(e.term.to_lowercase()) {
+            continue;
+        }
+        content.push_str(&format!(
+            "| {} | {} | {} |\n",
+            cell(&e.term),
+            cell(&e.definition),
+            cell(&e.spelling.join(", "))
+        ));
+        added.push(e.term.clone());
+    }
+    if !added.is_empty() {
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", GLOSSARY_FILE))?;
+    }
+    Ok(added)
+}
+
+// ─── Check ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct TermWarning {
+    /// The word as the prose spells it.
+    pub term: String,
+    /// `not in the glossary`, or `possible misspelling of "Aethryn"`.
+    pub finding: String,
+    /// Up to ~120 characters of prose around the first use.
+    pub excerpt: String,
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\p{L}(?:[\p{L}\p{M}'’-]*\p{L})?").unwrap())
+}
+
+/// Lowercased, without a possessive `'s`.
+fn key(word: &str) -> String {
+    let word = word
+        .strip_suffix("'s")
+        .or_else(|| word.strip_suffix("’s"))
+        .unwrap_or(word);
+    word.to_lowercase()
+}
+
+/// Lowercased words of the material that documents the book — every file of
+/// Global Material/ and Chapters material/. A name found there is not an
+/// undocumented invention.
+pub fn documented_words(repo: &Path) -> HashSet<String> {
+    let mut words = HashSet::new();
+    for dir in ["Global Material", "Chapters material"] {
+        let Ok(entries) = std::fs::read_dir(repo.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Ok(text) = std::fs::read_to_string(entry.path()) {
+                words.extend(word_re().find_iter(&text).map(|m| key(m.as_str())));
+            }
+        }
+    }
+    words
+}
+
+/// Whether the word at `start` opens a sentence, line, or quotation — where
+/// any word is capitalized.
+fn sentence_initial(prose: &str, start: usize) -> bool {
+    let before = &prose[..start];
+    let trimmed = before.trim_end_matches(|c: char| c.is_whitespace() || "\"“«'‘([*_".contains(c));
+    if before[trimmed.len()..].contains('\n') {
+        return true;
+    }
+    match trimmed.chars().last() {
+        None => true,
+        Some(c) => ".!?…:#>".contains(c),
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let next = (diag + usize::from(ca != *cb))
+                .min(row[j] + 1)
+                .min(row[j + 1] + 1);
+            diag = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+/// Heuristically check `prose` against the glossary: a capitalized word
+/// mid-sentence that neither the glossary nor `documented` knows is an
+/// invented term used before it was defined; a word one or two letters off a
+/// glossary term is a possible misspelling. One warning per word, at its
+/// first use.
+pub fn check(
+    glossary: &[GlossaryEntry],
+    documented: &HashSet<String>,
+    prose: &str,
+) -> Vec<TermWarning> {
+    let prose = strip_engine_markers(prose);
+    let accepted: HashSet<String> = glossary.iter().flat_map(GlossaryEntry::words).collect();
+    let fuzzy: BTreeSet<String> = accepted
+        .iter()
+        .filter(|w| w.chars().count() >= MIN_FUZZY_LEN)
+        .cloned()
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut warnings = Vec::new();
+    for m in word_re().find_iter(&prose) {
+        let word = m.as_str();
+        let k = key(word);
+        if accepted.contains(&k) || documented.contains(&k) || !seen.insert(k.clone()) {
+            continue;
+        }
+        let len = k.chars().count();
+        let near = (len >= MIN_FUZZY_LEN)
+            .then(|| {
+                fuzzy
+                    .iter()
+                    .find(|t| edit_distance(&k, t) <= if t.chars().count() >= 8 { 2 } else { 1 })
+            })
+            .flatten();
+        let finding = if let Some(t) = near {
+            let term = glossary
+                .iter()
+                .find(|e| e.words().any(|w| &w == t))
+                .map_or(t.as_str(), |e| e.term.as_str());
+            format!("possible misspelling of \"{}\"", term)
+        } else if len >= 3
+            && word.chars().next().is_some_and(char::is_uppercase)
+            && !word.chars().all(|c| !c.is_lowercase())
+            && !sentence_initial(&prose, m.start())
+        {
+            "not in the glossary".to_string()
+        } else {
+            continue;
+        };
+        warnings.push(TermWarning {
+            term: word.to_string(),
+            finding,
+            excerpt: crate::constraints::excerpt(&prose, m.start(), m.end()),
+        });
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> GlossaryEntry {
+        GlossaryEntry::parse("Aethryn | the drowned moon | Aethryns").unwrap()
+    }
+
+    #[test]
+    fn entries_parse_term_meaning_and_spellings() {
+        assert_eq!(entry().spelling, ["Aethryns"]);
+    }
+
+    #[test]
+    fn an_entry_needs_a_meaning() {
+        assert!(GlossaryEntry::parse("Aethryn").is_err());
+    }
+
+    #[test]
+    fn appending_skips_known_terms_and_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        let entry = entry();
+        assert_eq!(
+            append(repo, std::slice::from_ref(&entry)).unwrap(),
+            ["Aethryn"]
+        );
+        assert!(append(repo, std::slice::from_ref(&entry))
+            .unwrap()
+            .is_empty());
+        assert_eq!(load(repo).unwrap(), [entry]);
+    }
+
+    #[test]
+    fn check_flags_unknown_terms_and_near_misspellings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Characters.md"),
+            "# Characters\n\nMara Vell, keeper of the light.\n",
+        )
+        .unwrap();
+        let documented = documented_words(repo);
+        let prose = "Mara watched the Aethryn rise. The Aethryns' light fell on \
+                     Vell's boat, and on Kestrin Hold. Below, the Aethrin glowed.\n\
+                     \"Quiet,\" she said.";
+        let found: Vec<(String, String)> = check(&[entry()], &documented, prose)
+            .into_iter()
+            .map(|w| (w.term, w.finding))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Kestrin".to_string(), "not in the glossary".to_string()),
+                ("Hold".to_string(), "not in the glossary".to_string()),
+                (
+                    "Aethrin".to_string(),
+                    "possible misspelling of \"Aethryn\"".to_string()
+                ),
+            ]
+        );
+    }
+}
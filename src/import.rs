@@ -0,0 +1,314 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::info;
+
+use crate::book::{self, FULL_BOOK_HEADER};
+use crate::config::Config;
+use crate::git;
+use crate::sequence::Unit;
+use crate::state::InkState;
+use crate::storage;
+use crate::wordcount::WordCounter;
+
+/// One unit of an imported manuscript, split at its chapter headings.
+#[derive(Debug, PartialEq)]
+struct Section {
+    /// Heading text ("Chapter 3", "Prologue"); None for prose before any heading.
+    heading: Option<String>,
+    /// Chapter number for numbered chapters ("Chapter 3", or the Nth "Chapter" heading).
+    number: Option<u32>,
+    body: String,
+}
+
+pub(crate) fn unit_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^#{1,3}\s+((chapter)\b\s*(\d+)?.*|(?:prologue|interlude|epilogue)\b.*)$")
+            .unwrap()
+    })
+}
+
+/// Manuscript text as Markdown: `.md` / `.markdown` / `.txt` as-is, `.docx`
+/// through pandoc (which must be on PATH).
+fn read_manuscript(file: &Path) -> Result<String> {
+    let ext = file
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "md" | "markdown" | "txt" => std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display())),
+        "docx" => {
+            info!("Converting {} with pandoc", file.display());
+            let out = Command::new("pandoc")
+                .args(["--from", "docx", "--to", "gfm", "--wrap=none"])
+                .arg(file)
+                .output()
+                .with_context(|| "Failed to run pandoc — install it to import .docx manuscripts")?;
+            if !out.status.success() {
+                bail!(
+                    "pandoc could not convert {}: {}",
+                    file.display(),
+                    String::from_utf8_lossy(&out.stderr).trim()
+                );
+            }
+            Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+        }
+        _ => bail!(
+            "unsupported manuscript format '{}' — use .md, .txt, or .docx",
+            file.display()
+        ),
+    }
+}
+
+/// Split a manuscript at its chapter-level headings (Chapter, Prologue,
+/// Interlude, Epilogue at `#`–`###`), normalised to `## `. Other headings
+/// (the title, scene titles) stay in the body.
+fn split_sections(text: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        heading: None,
+        number: None,
+        body: String::new(),
+    }];
+    let mut chapters = 0;
+    for line in text.replace("\r\n", "\n").lines() {
+        let Some(caps) = unit_heading_re().captures(line.trim()) else {
+            let body = &mut sections.last_mut().unwrap().body;
+            body.push_str(line);
+            body.push('\n');
+            continue;
+        };
+        let number = caps.get(2).map(|_| {
+            chapters = caps
+                .get(3)
+                .and_then(|n| n.as_str().parse().ok())
+                .unwrap_or(chapters + 1);
+            chapters
+        });
+        sections.push(Section {
+            heading: Some(caps[1].trim().to_string()),
+            number,
+            body: String::new(),
+        });
+    }
+    sections.retain(|s| s.heading.is_some() || !s.body.trim().is_empty());
+    sections
+}
+
+/// Where writing resumes: the last imported chapter, or the one after it once
+/// it has reached `words_per_chapter`. Without chapter headings the word count
+/// alone decides. Returns `(current_chapter, current_chapter_word_count)`.
+fn estimate_position(sections: &[Section], config: &Config) -> (u32, u32) {
+    let per_chapter = config.words_per_chapter.max(1);
+    let counter = WordCounter::new(config);
+    let (chapter, words) = match sections.iter().rev().find(|s| s.number.is_some()) {
+        Some(last) => (last.number.unwrap(), counter.count(&last.body)),
+        None => {
+            let total: u32 = sections.iter().map(|s| counter.count(&s.body)).sum();
+            (total / per_chapter + 1, total % per_chapter)
+        }
+    };
+    if words >= per_chapter && chapter < config.chapter_count {
+        (chapter + 1, 0)
+    } else {
+        (chapter.clamp(1, config.chapter_count.max(1)), words)
+    }
+}
+
+fn outline_stub(number: u32, source: &str, words: Option<u32>) -> String {
+    let origin = match words {
+        Some(words) => format!(
+            "*Imported from {} — {} words were already written. Record what this chapter \
+             establishes so later sessions stay consistent.*",
+            source, words
+        ),
+        None => format!(
+            "*Scaffolded by the import of {} — outline this chapter before its session.*",
+            source
+        ),
+    };
+    format!(
+        "# Chapter {number}\n\n{origin}\n\n---\n\n## Goal\n\n[What happens by the end of this chapter?]\n\n\
+         ## Scene Beats\n\n1. [Opening beat]\n\n## Notes\n\n[Details that matter to you as the author.]\n"
+    )
+}
+
+/// Import an existing manuscript into a freshly initialized book: its prose
+/// becomes Full_Book.md (paginated, chapter headings normalised to `## `),
+/// `synopsis` seeds Summary.md, `.ink-state.yml` points at the chapter to
+/// continue, and outline stubs are written for imported chapters and the
+/// current one. Commits and pushes (best-effort), like init.
+pub fn import_manuscript(repo: &Path, file: &Path, synopsis: Option<&Path>) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let counter = WordCounter::new(&config);
+    let existing = storage::compiled_book(repo)?.unwrap_or_default();
+    if counter.count(&book::strip_html_comments(&existing)) > 0 {
+        bail!("Full_Book.md already holds prose — import only into a freshly initialized book");
+    }
+    anyhow::ensure!(
+        !repo.join(".ink-running").exists(),
+        "a session is in progress — import after it closes"
+    );
+
+    let sections = split_sections(&crate::encoding::normalize(&read_manuscript(file)?));
+    let mut prose = String::new();
+    for section in &sections {
+        if let Some(heading) = &section.heading {
+            prose.push_str(&format!("## {}\n\n", heading));
+        }
+        prose.push_str(section.body.trim());
+        prose.push_str("\n\n");
+    }
+    anyhow::ensure!(
+        !prose.trim().is_empty(),
+        "{} holds no prose",
+        file.display()
+    );
+    let mut full_book = format!("{}\n", FULL_BOOK_HEADER);
+    book::append_paginated(&mut full_book, prose.trim(), config.page_size());
+    let words = counter.count(&full_book);
+
+    let (current_chapter, chapter_words) = estimate_position(&sections, &config);
+    let mut state = InkState::load(repo)?;
+    state.current_chapter = current_chapter;
+    state.current_chapter_word_count = chapter_words;
+    state.current_unit = None;
+    state.save(repo)?;
+    // After the state: per-chapter storage files prose without headings under
+    // the chapter writing resumes in
+    storage::write_manuscript(repo, &full_book)?;
+
+    let mut summary_seeded = false;
+    if let Some(synopsis) = synopsis {
+        let text = std::fs::read_to_string(synopsis)
+            .with_context(|| format!("Failed to read synopsis {}", synopsis.display()))?;
+        let summary_path = repo.join("Global Material/Summary.md");
+        let mut summary = std::fs::read_to_string(&summary_path).unwrap_or_default();
+        if !summary.trim().is_empty() {
+            summary.push_str("\n\n");
+        }
+        summary.push_str(text.trim());
+        std::fs::write(&summary_path, summary).with_context(|| "Failed to write Summary.md")?;
+        summary_seeded = true;
+    }
+
+    // Stubs for every imported chapter and the one writing resumes in; an
+    // outline the author already filled in is never replaced.
+    let source = file
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut outlines_created = Vec::new();
+    let imported = sections.iter().filter_map(|s| s.number).max().unwrap_or(0);
+    for number in 1..=imported.max(current_chapter) {
+        let rel = Unit::chapter(number).path();
+        let path = repo.join(&rel);
+        let untouched = std::fs::read_to_string(&path)
+            .is_ok_and(|c| c == crate::init::CHAPTER_01_MD && number == 1);
+        if path.exists() && !untouched {
+            continue;
+        }
+        let chapter_words = sections
+            .iter()
+            .find(|s| s.number == Some(number))
+            .map(|s| counter.count(&s.body));
+        std::fs::write(&path, outline_stub(number, &source, chapter_words))
+            .with_context(|| format!("Failed to write {}", rel))?;
+        outlines_created.push(rel);
+    }
+
+    git::run_git(repo, &["add", "-A"])?;
+    git::engine_commit(
+        repo,
+        &["-m", &format!("import: {} ({} words)", source, words)],
+    )
+    .with_context(|| "Failed to commit the import")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+
+    info!(
+        "Imported {} words; writing resumes in chapter {}",
+        words, current_chapter
+    );
+    Ok(json!({
+        "status": "imported",
+        "words": words,
+        "pages": config.words_to_pages(words),
+        "sections": sections
+            .iter()
+            .map(|s| json!({
+                "heading": s.heading,
+                "words": counter.count(&s.body),
+            }))
+            .collect::<Vec<_>>(),
+        "chapters_detected": imported > 0,
+        "current_chapter": current_chapter,
+        "current_chapter_word_count": chapter_words,
+        "summary_seeded": summary_seeded,
+        "outlines_created": outlines_created,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANUSCRIPT: &str = "# The Gate\n\nBy A. Writer\n\n## Prologue\n\nBefore it all.\n\n\
+                              # CHAPTER 1: Arrival\n\none two three four\n\n### Scene\n\n\
+                              Chapter two\n\n## Chapter\n\nfive six\n";
+
+    fn config(words_per_chapter: u32) -> Config {
+        Config::parse(&format!(
+            "target_length: 60000\nchapter_count: 20\nchapter_structure: linear\n\
+             words_per_session: 500\nwords_per_chapter: {}\n",
+            words_per_chapter
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn manuscript_splits_at_chapter_level_headings() {
+        let sections = split_sections(MANUSCRIPT);
+        let headings: Vec<_> = sections.iter().map(|s| s.heading.as_deref()).collect();
+        assert_eq!(
+            headings,
+            [
+                None,
+                Some("Prologue"),
+                Some("CHAPTER 1: Arrival"),
+                Some("Chapter")
+            ]
+        );
+        assert!(sections[2].body.contains("### Scene\n\nChapter two"));
+    }
+
+    #[test]
+    fn unnumbered_chapters_follow_the_previous_number() {
+        assert_eq!(split_sections(MANUSCRIPT)[3].number, Some(2));
+    }
+
+    #[test]
+    fn a_short_last_chapter_resumes_mid_chapter() {
+        let sections = split_sections(MANUSCRIPT);
+        assert_eq!(estimate_position(&sections, &config(3)), (2, 2));
+    }
+
+    #[test]
+    fn a_full_last_chapter_resumes_at_the_next() {
+        let sections = split_sections(MANUSCRIPT);
+        assert_eq!(estimate_position(&sections, &config(2)), (3, 0));
+    }
+
+    #[test]
+    fn prose_without_chapter_headings_is_measured_in_chapter_lengths() {
+        assert_eq!(
+            estimate_position(&split_sections("one two three four five"), &config(2)),
+            (3, 1)
+        );
+    }
+}
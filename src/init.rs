@@ -6,7 +6,10 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::config;
+use crate::errors::InkError;
 use crate::git;
+use crate::maintenance::with_config_value;
 
 // ─── Seed content ─────────────────────────────────────────────────────────────
 
@@ -63,8 +66,10 @@ The command outputs JSON with a `questions` array. Each entry has `question`, `h
 Questions 1–4 populate `Config.yml`:
 - Q1: language → `language:` field
 - Q2: book type (Flash fiction / Short story / Novel) — use to infer defaults for Q3 and Q4
-- Q3: target pages → `target_length: <pages × 250>`; also compute `chapter_count: <ceil(target_words / 3000)>`
-- Q4: pages per session → `words_per_session: <pages × 250>`
+- Q3: target pages → `target_length: <pages × words_per_page>`; also compute `chapter_count: <ceil(target_words / 3000)>`
+- Q4: pages per session → `words_per_session: <pages × words_per_page>`
+
+`words_per_page` is read from `Config.yml` (default 250) — use the value already in the file.
 
 | File | What content to place inside the `[...]` placeholders |
 |---|---|
@@ -89,14 +94,26 @@ Stop. Notify the author the book is ready — they can review `Global Material/`
 
 const CONFIG_YML: &str = include_str!("../templates/Config.yml");
 const SOUL_MD: &str = include_str!("../templates/Soul.md");
-const OUTLINE_MD: &str = include_str!("../templates/Outline.md");
+pub(crate) const OUTLINE_MD: &str = include_str!("../templates/Outline.md");
 const CHARACTERS_MD: &str = include_str!("../templates/Characters.md");
 const LORE_MD: &str = include_str!("../templates/Lore.md");
-const CHAPTER_01_MD: &str = include_str!("../templates/Chapter_01.md");
+pub(crate) const CHAPTER_01_MD: &str = include_str!("../templates/Chapter_01.md");
 const CURRENT_MD: &str = include_str!("../templates/current.md");
 const AGENTS_MD: &str = include_str!("../templates/AGENTS.md");
 const README_MD: &str = include_str!("../templates/README.md");
 
+/// Directory for cover art, reference images, and other binary assets. Stored
+/// with Git LFS and never loaded into the session context.
+pub const ASSETS_DIR: &str = "Assets";
+
+/// `.gitattributes` rules routing `Assets/` through Git LFS. Without git-lfs
+/// installed the filter is undefined and git stores the files as-is.
+const LFS_ATTRIBUTES: &str = "\
+# Cover art and reference assets (ink-gateway): stored with Git LFS
+Assets/** filter=lfs diff=lfs merge=lfs -text
+Assets/.gitkeep !filter !diff !merge text
+";
+
 #[derive(Serialize)]
 pub struct Question {
     pub question: &'static str,
@@ -148,20 +165,25 @@ pub fn run_init(repo_path: &Path, title: &str, author: &str) -> Result<InitPaylo
         "Review",
         "Changelog",
         "Current version",
+        ASSETS_DIR,
     ] {
         fs::create_dir_all(repo_path.join(dir))?;
     }
 
     let write_file = |rel: &str, contents: &str, files: &mut Vec<String>| -> Result<()> {
         let full = repo_path.join(rel);
-        fs::write(&full, contents)?;
+        fs::write(&full, crate::encoding::normalize(contents).as_bytes())?;
         files.push(rel.to_string());
         Ok(())
     };
 
     write_file(
         "Global Material/Config.yml",
-        &fill(CONFIG_YML, title, author),
+        &fill(
+            CONFIG_YML,
+            &config::yaml_quote(title),
+            &config::yaml_quote(author),
+        ),
         &mut files_created,
     )?;
     write_file(
@@ -185,6 +207,26 @@ pub fn run_init(repo_path: &Path, title: &str, author: &str) -> Result<InitPaylo
         &mut files_created,
     )?;
     write_file("Global Material/Summary.md", "", &mut files_created)?;
+    write_file(
+        crate::phrases::CRUTCHES_FILE,
+        crate::phrases::CRUTCHES_TEMPLATE,
+        &mut files_created,
+    )?;
+    write_file(
+        crate::glossary::GLOSSARY_FILE,
+        crate::glossary::GLOSSARY_HEADER,
+        &mut files_created,
+    )?;
+    write_file(
+        crate::threads::THREADS_FILE,
+        crate::threads::THREADS_TEMPLATE,
+        &mut files_created,
+    )?;
+    write_file(
+        crate::timeline::TIMELINE_FILE,
+        crate::timeline::TIMELINE_HEADER,
+        &mut files_created,
+    )?;
     write_file(
         "Chapters material/Chapter_01.md",
         &fill(CHAPTER_01_MD, title, author),
@@ -202,6 +244,12 @@ pub fn run_init(repo_path: &Path, title: &str, author: &str) -> Result<InitPaylo
         &mut files_created,
     )?;
     write_file("Changelog/.gitkeep", "", &mut files_created)?;
+    if !repo_path.join(ASSETS_DIR).join(".gitkeep").exists() {
+        write_file("Assets/.gitkeep", "", &mut files_created)?;
+    }
+    if add_lfs_attributes(repo_path)? {
+        files_created.push(".gitattributes".to_string());
+    }
     write_file(
         "Current version/Full_Book.md",
         "<!-- ⚠ INK-GATEWAY:MANAGED — Do not edit this file directly.\n\
@@ -216,7 +264,7 @@ pub fn run_init(repo_path: &Path, title: &str, author: &str) -> Result<InitPaylo
         &mut files_created,
     )?;
 
-    git_commit_and_push(repo_path)?;
+    git_commit_and_push(repo_path, "init: scaffold book repository")?;
 
     let questions = vec![
         // ── Language ──────────────────────────────────────────────────────────
@@ -235,7 +283,7 @@ pub fn run_init(repo_path: &Path, title: &str, author: &str) -> Result<InitPaylo
         },
         Question {
             question: "How many pages should the finished book be?",
-            hint: "Flash fiction: 5 · Short story: 20 · Novel: 250 — each page ≈ 250 words",
+            hint: "Flash fiction: 5 · Short story: 20 · Novel: 250 — each page ≈ words_per_page words (250 by default)",
             target_file: "Global Material/Config.yml",
             options: None,
         },
@@ -355,7 +403,7 @@ pub fn run_seed(repo_path: &Path) -> Result<SeedPayload> {
         ],
     )?;
 
-    if let Err(e) = git::run_git(repo_path, &["push", "origin", "main"]) {
+    if let Err(e) = git::push_main(repo_path) {
         tracing::warn!("git push skipped: {}", e);
     }
 
@@ -365,19 +413,208 @@ pub fn run_seed(repo_path: &Path) -> Result<SeedPayload> {
     })
 }
 
+// ─── spinoff ──────────────────────────────────────────────────────────────────
+
+/// Global Material a spinoff takes from its source by default: the world and
+/// the voice.
+pub const SPINOFF_FILES: [&str; 3] = ["Soul.md", "Lore.md", "Characters.md"];
+
+/// The new book's own plot and progress: always started fresh.
+const SPINOFF_FRESH: [&str; 2] = ["Outline.md", "Summary.md"];
+
+/// `ink-cli spinoff`: initialize `repo_path` like `init`, then replace the
+/// scaffolded Global Material named in `copy` (default `SPINOFF_FILES`) with
+/// the source book's, as a second commit. Outline, Summary, chapter outlines,
+/// the manuscript, and `.ink-state.yml` stay fresh. The init questions whose
+/// file was copied are dropped.
+pub fn run_spinoff(
+    repo_path: &Path,
+    source: &Path,
+    copy: &[String],
+    title: &str,
+    author: &str,
+) -> Result<serde_json::Value> {
+    let source_material = source.join("Global Material");
+    if !source_material.join("Config.yml").exists() {
+        return Err(InkError::InvalidInput(format!(
+            "{} is not a book — Global Material/Config.yml is missing",
+            source.display()
+        ))
+        .into());
+    }
+    let copy: Vec<String> = if copy.is_empty() {
+        SPINOFF_FILES.map(String::from).to_vec()
+    } else {
+        copy.to_vec()
+    };
+    for file in &copy {
+        if SPINOFF_FRESH.contains(&file.as_str()) || file.contains(['/', '\\']) {
+            return Err(InkError::InvalidInput(format!(
+                "--copy {}: a spinoff copies Global Material files other than {}",
+                file,
+                SPINOFF_FRESH.join(" and ")
+            ))
+            .into());
+        }
+        if !source_material.join(file).is_file() {
+            return Err(InkError::InvalidInput(format!(
+                "--copy {}: no Global Material/{} in {}",
+                file,
+                file,
+                source.display()
+            ))
+            .into());
+        }
+    }
+
+    let mut payload = run_init(repo_path, title, author)?;
+    let mut files_copied = Vec::new();
+    for file in &copy {
+        let content = fs::read_to_string(source_material.join(file)).with_context(|| {
+            format!(
+                "Failed to read {}/Global Material/{}",
+                source.display(),
+                file
+            )
+        })?;
+        // The sequel's own title, not the source's
+        let content = match file.as_str() {
+            "Config.yml" => {
+                let content = with_config_value(&content, "title", &config::yaml_quote(title));
+                with_config_value(&content, "author", &config::yaml_quote(author))
+            }
+            _ => crate::rename::retitled(&content, title),
+        };
+        let rel = format!("Global Material/{}", file);
+        fs::write(
+            repo_path.join(&rel),
+            crate::encoding::normalize(&content).as_bytes(),
+        )
+        .with_context(|| format!("Failed to write {}", rel))?;
+        files_copied.push(rel);
+    }
+    let source_name = repo_name(source);
+    git_commit_and_push(
+        repo_path,
+        &format!("init: spinoff of {} ({})", source_name, copy.join(", ")),
+    )?;
+
+    payload
+        .questions
+        .retain(|q| !files_copied.iter().any(|f| f == q.target_file));
+    Ok(serde_json::json!({
+        "status": "spun_off",
+        "source": source_name,
+        "source_complete": source.join("COMPLETE").exists(),
+        "title": payload.title,
+        "author": payload.author,
+        "files_copied": files_copied,
+        "files_created": payload.files_created,
+        "questions": payload.questions,
+    }))
+}
+
 // ─── reset ────────────────────────────────────────────────────────────────────
 
-/// Wipe all book content so the repository can be re-initialized with `init`.
-/// The user must type the repository directory name to confirm — this is a
-/// destructive, irreversible operation.
-pub fn run_reset(repo_path: &Path) -> Result<()> {
-    let repo_name = repo_path
+/// Directory name of the repository — what the author types to confirm a reset.
+pub(crate) fn repo_name(repo_path: &Path) -> String {
+    repo_path
         .canonicalize()
         .unwrap_or_else(|_| repo_path.to_path_buf())
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("this-repository")
-        .to_string();
+        .to_string()
+}
+
+// ─── clone ────────────────────────────────────────────────────────────────────
+
+/// Directory name `git clone` would pick for `url`: its last path segment
+/// without `.git`.
+fn clone_dir_name(url: &str) -> Option<&str> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    (!name.is_empty()).then_some(name)
+}
+
+/// Clone `url` into `dest` (default: the repository name in the current
+/// directory) and return the clone's path. An existing non-empty `dest` is
+/// refused by git itself.
+pub fn clone_repo(url: &str, dest: Option<&Path>) -> Result<std::path::PathBuf> {
+    let dest = match dest {
+        Some(dest) => dest.to_path_buf(),
+        None => clone_dir_name(url)
+            .map(std::path::PathBuf::from)
+            .ok_or_else(|| anyhow!("cannot derive a directory name from {} — pass one", url))?,
+    };
+    let parent = match dest.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
+    fs::create_dir_all(&parent)?;
+    // git runs in `parent`; an absolute target keeps `dest` relative to here
+    let target = std::env::current_dir()?.join(&dest);
+    tracing::info!("Cloning {} into {}", url, dest.display());
+    git::run_git(&parent, &["clone", url, &target.to_string_lossy()])
+        .with_context(|| format!("Failed to clone {}", url))?;
+    Ok(dest)
+}
+
+/// What a fresh clone of a non-empty repository needs next: the doctor report
+/// when it is a book, or how to turn it into one.
+pub fn onboarding_report(repo_path: &Path) -> Result<serde_json::Value> {
+    let path = repo_path.display().to_string();
+    if !repo_path.join("Global Material/Config.yml").exists() {
+        return Ok(serde_json::json!({
+            "status": "not_initialized",
+            "path": path,
+            "next_steps": [
+                format!("ink-cli init \"{}\" --title \"<title>\" --author \"<author>\" — scaffold the book", path),
+                format!("or ink-cli seed \"{}\" — add CLAUDE.md / GEMINI.md so an agent initializes it", path),
+            ],
+        }));
+    }
+    let doctor = crate::maintenance::doctor(repo_path, false)?;
+    // A fresh clone has no local draft branch; session-open creates it
+    let failing: Vec<&str> = doctor["checks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|c| c["ok"] == false && c["name"] != "draft_branch")
+        .filter_map(|c| c["name"].as_str())
+        .collect();
+    let next_steps = if !failing.is_empty() {
+        vec![format!(
+            "Fix the failing doctor checks ({}), then re-run ink-cli doctor",
+            failing.join(", ")
+        )]
+    } else if repo_path.join("COMPLETE").exists() {
+        vec!["The book is complete — no sessions left to run".to_string()]
+    } else {
+        vec![
+            "Read AGENTS.md for the session workflow".to_string(),
+            format!(
+                "ink-cli session-open \"{}\" — start a writing session",
+                path
+            ),
+        ]
+    };
+    Ok(serde_json::json!({
+        "status": "ready",
+        "path": path,
+        "doctor": doctor,
+        "next_steps": next_steps,
+    }))
+}
+
+/// Wipe all book content so the repository can be re-initialized with `init`.
+/// The user must type the repository directory name to confirm — this is a
+/// destructive, irreversible operation.
+pub fn run_reset(repo_path: &Path) -> Result<()> {
+    let repo_name = repo_name(repo_path);
 
     println!();
     println!(
@@ -398,6 +635,24 @@ pub fn run_reset(repo_path: &Path) -> Result<()> {
     }
 
     println!("\n  Removing book content…");
+    reset_book(repo_path, input.trim())?;
+
+    println!("\n  Reset complete.");
+    println!("  Run `ink-cli init <repo-path> --title \"...\" --author \"...\"` to start fresh.\n");
+
+    Ok(())
+}
+
+/// Non-interactive reset (MCP `reset`): `confirmation` must be the repository
+/// directory name, exactly as the CLI prompt asks for.
+pub fn reset_book(repo_path: &Path, confirmation: &str) -> Result<serde_json::Value> {
+    let repo_name = repo_name(repo_path);
+    if confirmation != repo_name {
+        anyhow::bail!(
+            "confirmation does not match the repository name «{}» — reset refused",
+            repo_name
+        );
+    }
 
     // Remove all tracked content directories and files in one git rm call.
     // --ignore-unmatch silences errors for files that don't exist.
@@ -443,25 +698,43 @@ pub fn run_reset(repo_path: &Path) -> Result<()> {
         ],
     )?;
 
-    if let Err(e) = git::run_git(repo_path, &["push", "origin", "main"]) {
-        tracing::warn!("git push skipped: {}", e);
-    }
+    let pushed = match git::push_main(repo_path) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("git push skipped: {}", e);
+            false
+        }
+    };
 
-    println!("\n  Reset complete.");
-    println!("  Run `ink-cli init <repo-path> --title \"...\" --author \"...\"` to start fresh.\n");
+    Ok(serde_json::json!({
+        "status": "reset",
+        "repo": repo_name,
+        "pushed": pushed,
+    }))
+}
 
-    Ok(())
+/// Append the Assets/ LFS rules to `.gitattributes` unless already there (a
+/// re-init after `reset` keeps the file). Returns whether it was written.
+pub(crate) fn add_lfs_attributes(repo_path: &Path) -> Result<bool> {
+    let path = repo_path.join(".gitattributes");
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    if content.contains("Assets/** filter=lfs") {
+        return Ok(false);
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(LFS_ATTRIBUTES);
+    fs::write(&path, content).with_context(|| "Failed to write .gitattributes")?;
+    Ok(true)
 }
 
-fn git_commit_and_push(repo_path: &Path) -> Result<()> {
+fn git_commit_and_push(repo_path: &Path, message: &str) -> Result<()> {
     git::run_git(repo_path, &["add", "-A"])?;
-    git::run_git(
-        repo_path,
-        &["commit", "-m", "init: scaffold book repository"],
-    )?;
+    git::run_git(repo_path, &["commit", "-m", message])?;
 
     // Push is best-effort: skip if no remote is configured (common in local smoke tests)
-    if let Err(e) = git::run_git(repo_path, &["push", "origin", "main"]) {
+    if let Err(e) = git::push_main(repo_path) {
         tracing::warn!("git push skipped: {}", e);
     }
 
@@ -484,6 +757,8 @@ pub fn run_interactive_qa(repo_path: &Path, payload: &InitPayload) -> Result<()>
         (12, "Chapter 1"),
     ];
 
+    let page_size = config::read_page_size(repo_path);
+
     println!();
     println!("  Ink Gateway — Book Setup");
     println!("  «{}» by {}", payload.title, payload.author);
@@ -530,7 +805,7 @@ pub fn run_interactive_qa(repo_path: &Path, payload: &InitPayload) -> Result<()>
                 default_session
             };
             let default_str = default_val.to_string();
-            let words = default_val * 250;
+            let words = default_val * page_size;
             let dynamic_hint = if i == 2 {
                 format!("Suggested for {}: {} pages (~{} words) — press Enter to accept or type another number.", book_type, default_val, words)
             } else {
@@ -587,8 +862,8 @@ pub fn run_interactive_qa(repo_path: &Path, payload: &InitPayload) -> Result<()>
         .find(|(i, _)| *i == 3)
         .and_then(|(_, a)| a.trim().parse::<u32>().ok());
     if let (Some(tp), Some(sp)) = (target_pages, session_pages) {
-        let target_words = tp * 250;
-        let session_words = sp * 250;
+        let target_words = tp * page_size;
+        let session_words = sp * page_size;
         let chapters = target_words.div_ceil(3000).max(1);
         println!();
         println!(
@@ -632,6 +907,7 @@ pub fn run_interactive_qa(repo_path: &Path, payload: &InitPayload) -> Result<()>
 /// are combined under section headings.
 fn write_answers_to_files(repo_path: &Path, answers: &[(usize, String)]) -> Result<()> {
     let map: HashMap<usize, &str> = answers.iter().map(|(i, a)| (*i, a.as_str())).collect();
+    let page_size = config::read_page_size(repo_path);
 
     // Config.yml — language (q0), target pages (q2), session pages (q3); chapter_count derived
     {
@@ -646,8 +922,8 @@ fn write_answers_to_files(repo_path: &Path, answers: &[(usize, String)]) -> Resu
             .get(&3)
             .and_then(|s| s.trim().parse::<u32>().ok())
             .unwrap_or(0);
-        let target_words = target_pages * 250;
-        let session_words = session_pages * 250;
+        let target_words = target_pages * page_size;
+        let session_words = session_pages * page_size;
         let chapter_count = target_words.div_ceil(3000).max(1);
         let updated = content
             .lines()
@@ -759,7 +1035,7 @@ fn write_answers_to_files(repo_path: &Path, answers: &[(usize, String)]) -> Resu
                 .and_then(|s| s.trim().parse::<u32>().ok())
                 .unwrap_or(0);
             if !lang.is_empty() || target_pages > 0 {
-                let target_words = target_pages * 250;
+                let target_words = target_pages * page_size;
                 let content =
                     fs::read_to_string(&readme_path).with_context(|| "Failed to read README.md")?;
                 let mut updated = content
@@ -808,7 +1084,7 @@ fn commit_qa_answers(repo_path: &Path) -> Result<()> {
         ],
     )?;
 
-    if let Err(e) = git::run_git(repo_path, &["push", "origin", "main"]) {
+    if let Err(e) = git::push_main(repo_path) {
         tracing::warn!("git push skipped: {}", e);
     }
 
@@ -861,20 +1137,19 @@ pub fn update_agents(repo_path: &Path) -> Result<serde_json::Value> {
         }));
     }
 
-    let commit = Command::new("git")
-        .args([
+    git::run_git(
+        repo_path,
+        &[
             "commit",
             "-m",
             "chore: update agent files to latest ink-gateway version",
-        ])
-        .current_dir(repo_path)
-        .status()?;
-    if !commit.success() {
-        anyhow::bail!("git commit failed");
-    }
+        ],
+    )
+    .with_context(|| "git commit failed")?;
 
+    let branches = git::branches(repo_path);
     let push = Command::new("git")
-        .args(["push", "origin", "main"])
+        .args(["push", &branches.remote, &branches.main])
         .current_dir(repo_path)
         .status()?;
     if !push.success() {
@@ -886,3 +1161,121 @@ pub fn update_agents(repo_path: &Path) -> Result<serde_json::Value> {
         "files_updated": files_updated,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_dir_name_matches_git() {
+        assert_eq!(
+            clone_dir_name("https://github.com/ada/my-book.git"),
+            Some("my-book")
+        );
+        assert_eq!(
+            clone_dir_name("git@github.com:ada/my-book.git"),
+            Some("my-book")
+        );
+        assert_eq!(clone_dir_name("/srv/git/my-book/"), Some("my-book"));
+        assert_eq!(clone_dir_name("host:book"), Some("book"));
+        assert_eq!(clone_dir_name(".git"), None);
+    }
+
+    #[test]
+    fn spinoff_copies_the_world_and_starts_the_plot_fresh() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("book-1");
+        std::fs::create_dir_all(source.join("Global Material")).unwrap();
+        for (file, content) in [
+            ("Config.yml", "target_length: 90000\n"),
+            ("Soul.md", "# Soul\n\nSpare, wry.\n"),
+            ("Lore.md", "# Lore — Book 1\n\nThe salt towns.\n"),
+            ("Characters.md", "# Characters\n\n## Mara\n"),
+            ("Outline.md", "# Outline\n\nBook 1 plot.\n"),
+        ] {
+            std::fs::write(source.join("Global Material").join(file), content).unwrap();
+        }
+        let repo = tmp.path().join("book-2");
+        std::fs::create_dir_all(&repo).unwrap();
+        git::run_git(&repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(&repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(&repo, &["config", "user.name", "t"]).unwrap();
+
+        let outline = ["Outline.md".to_string()];
+        let err = run_spinoff(&repo, &source, &outline, "Book 2", "Ada").unwrap_err();
+        assert_eq!(crate::errors::code_of(&err), "invalid_input");
+        assert!(!repo.join("Global Material").exists());
+
+        let payload = run_spinoff(&repo, &source, &[], "Book 2", "Ada").unwrap();
+        assert_eq!(payload["status"], "spun_off");
+        assert_eq!(payload["source"], "book-1");
+        let read = |file: &str| std::fs::read_to_string(repo.join("Global Material").join(file));
+        assert_eq!(
+            read("Lore.md").unwrap(),
+            "# Lore — Book 2\n\nThe salt towns.\n"
+        );
+        assert!(read("Outline.md")
+            .unwrap()
+            .starts_with("# Outline — Book 2"));
+        let questions = payload["questions"].as_array().unwrap();
+        assert!(questions
+            .iter()
+            .all(|q| q["target_file"] != "Global Material/Characters.md"));
+        assert!(git::run_git(&repo, &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+    }
+
+    /// A committed book at `<tmp>/<name>`, main pushed to a bare origin.
+    fn committed_book(tmp: &Path, name: &str) -> std::path::PathBuf {
+        git::run_git(tmp, &["init", "-q", "--bare", "-b", "main", "origin.git"]).unwrap();
+        let repo = tmp.join(name);
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 90000\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join(".ink-state.yml"), "current_chapter: 1\n").unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "t@t"],
+            &["config", "user.name", "t"],
+            &["remote", "add", "origin", "../origin.git"],
+            &["add", "-A"],
+            &["commit", "-q", "-m", "init"],
+            &["push", "-q", "origin", "main"],
+        ] {
+            git::run_git(&repo, args).unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn reset_book_refuses_a_wrong_confirmation() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = committed_book(tmp.path(), "my-book");
+        let err = reset_book(&repo, "other-book").unwrap_err();
+        assert!(err.to_string().contains("«my-book»"), "{err}");
+        assert!(repo.join("Global Material/Config.yml").exists());
+    }
+
+    #[test]
+    fn reset_book_with_the_repository_name_wipes_the_book() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = committed_book(tmp.path(), "my-book");
+        let result = reset_book(&repo, "my-book").unwrap();
+        assert_eq!(result["status"], "reset");
+        assert_eq!(result["pushed"], true);
+        assert!(!repo.join("Global Material").exists());
+        assert!(!repo.join(".ink-state.yml").exists());
+        assert!(repo.join("Chapters material/.gitkeep").exists());
+        assert!(git::run_git(&repo, &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            git::run_git(&repo, &["rev-parse", "main"]).unwrap(),
+            git::run_git(&repo, &["rev-parse", "origin/main"]).unwrap()
+        );
+    }
+}
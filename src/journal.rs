@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::git;
+
+/// Local, never-committed bookkeeping directory. Its own `.gitignore` keeps
+/// `git add -A` in session-close from picking it up.
+const INK_DIR: &str = ".ink";
+const JOURNAL_FILE: &str = ".ink/journal/close.yml";
+const LAST_RECOVERY_FILE: &str = ".ink/journal/last_recovery.yml";
+
+// ─── Journal entry ────────────────────────────────────────────────────────────
+
+/// Write-ahead record of a session-close in progress. Written before the first
+/// file is touched and removed once the session is published, so its presence
+/// on the next command means the close was interrupted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseJournal {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    /// `draft` before the close. If the branch has moved, the session commit
+    /// exists and only publishing is left; otherwise the writes are undone.
+    pub draft_head: String,
+    /// Repo-relative files the close may write, delete, or stage.
+    pub files: Vec<String>,
+}
+
+/// What `recover` did with an interrupted close.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// The session commit existed: draft and main were pushed.
+    Resumed,
+    /// The commit never happened: journaled files were restored from `draft_head`.
+    RolledBack,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Recovery {
+    pub session_id: String,
+    pub action: RecoveryAction,
+    pub recovered_at: DateTime<Utc>,
+}
+
+pub(crate) fn ensure_ink_dir(repo: &Path) -> Result<PathBuf> {
+    let dir = repo.join(INK_DIR);
+    std::fs::create_dir_all(dir.join("journal"))
+        .with_context(|| "Failed to create .ink/journal")?;
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "*\n").with_context(|| "Failed to write .ink/.gitignore")?;
+    }
+    Ok(dir)
+}
+
+/// Write `content` to `rel` via a temporary file and rename, so a crash never
+/// leaves a half-written journal behind.
+fn write_atomic(repo: &Path, rel: &str, content: &str) -> Result<()> {
+    ensure_ink_dir(repo)?;
+    let path = repo.join(rel);
+    let tmp = path.with_extension("yml.tmp");
+    std::fs::write(&tmp, content).with_context(|| format!("Failed to write {}", rel))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", rel))
+}
+
+/// Record the start of a session-close. Must be called before any file in
+/// `files` is modified.
+pub fn begin(repo: &Path, session_id: &str, files: Vec<String>) -> Result<()> {
+    let entry = CloseJournal {
+        session_id: session_id.to_string(),
+        started_at: Utc::now(),
+        draft_head: git::run_git(repo, &["rev-parse", &git::branches(repo).draft])
+            .with_context(|| "Failed to resolve draft before session-close")?,
+        files,
+    };
+    let content =
+        serde_yaml::to_string(&entry).with_context(|| "Failed to serialize close journal")?;
+    write_atomic(repo, JOURNAL_FILE, &content)
+}
+
+/// Drop the journal: the close finished, or its writes were already undone.
+pub fn finish(repo: &Path) {
+    let path = repo.join(JOURNAL_FILE);
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Could not remove {}: {}", JOURNAL_FILE, e);
+        }
+    }
+}
+
+/// Whether an interrupted close is waiting for recovery (a single stat).
+pub fn is_pending(repo: &Path) -> bool {
+    repo.join(JOURNAL_FILE).exists()
+}
+
+/// The journal of an interrupted close, if any.
+pub fn pending(repo: &Path) -> Result<Option<CloseJournal>> {
+    let path = repo.join(JOURNAL_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", JOURNAL_FILE))?;
+    serde_yaml::from_str(&content)
+        .map(Some)
+        .with_context(|| format!("Failed to parse {}", JOURNAL_FILE))
+}
+
+/// The most recent recovery, kept for `doctor`.
+pub fn last_recovery(repo: &Path) -> Option<Recovery> {
+    let content = std::fs::read_to_string(repo.join(LAST_RECOVERY_FILE)).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+/// Whether `draft` has moved past the journaled head, i.e. the session commit exists.
+pub fn is_committed(repo: &Path, journal: &CloseJournal) -> bool {
+    git::run_git(repo, &["rev-parse", &git::branches(repo).draft])
+        .map(|head| head != journal.draft_head)
+        .unwrap_or(false)
+}
+
+// ─── Recovery ─────────────────────────────────────────────────────────────────
+
+/// Put every journaled file back to its state at `draft_head`: tracked files are
+/// checked out (worktree and index), files the commit did not have are removed.
+fn roll_back(repo: &Path, journal: &CloseJournal) -> Result<()> {
+    git::abort_merge_if_in_progress(repo);
+    git::run_git(repo, &["checkout", &git::branches(repo).draft])
+        .with_context(|| "Failed to checkout draft for rollback")?;
+    for file in &journal.files {
+        let tracked = git::run_git(
+            repo,
+            &[
+                "cat-file",
+                "-e",
+                &format!("{}:{}", journal.draft_head, file),
+            ],
+        )
+        .is_ok();
+        if tracked {
+            git::run_git(repo, &["checkout", &journal.draft_head, "--", file])
+                .with_context(|| format!("Failed to restore {}", file))?;
+        } else {
+            git::run_git(
+                repo,
+                &["rm", "-q", "--cached", "--ignore-unmatch", "--", file],
+            )
+            .with_context(|| format!("Failed to unstage {}", file))?;
+            let path = repo.join(file);
+            if path.exists() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", file))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finish or undo a session-close that was interrupted (crash, kill, failed
+/// push). Called at the start of every command that writes to the book.
+///
+/// - Session commit on draft: publishing is re-run, which is idempotent; the
+///   journal stays if it fails so the next command retries.
+/// - No commit: the journaled files are restored, which leaves the session
+///   open with its lock so the engine can re-run session-close.
+pub fn recover(repo: &Path) -> Result<Option<Recovery>> {
+    let Some(journal) = pending(repo)? else {
+        return Ok(None);
+    };
+    let action = if is_committed(repo, &journal) {
+        warn!(
+            "Session-close of {} was interrupted after its commit — publishing it now",
+            journal.session_id
+        );
+        crate::maintenance::publish_session(repo).with_context(|| {
+            format!(
+                "Failed to publish interrupted session-close of {}",
+                journal.session_id
+            )
+        })?;
+        RecoveryAction::Resumed
+    } else {
+        warn!(
+            "Session-close of {} was interrupted before its commit — rolling back its writes",
+            journal.session_id
+        );
+        roll_back(repo, &journal)?;
+        RecoveryAction::RolledBack
+    };
+
+    let recovery = Recovery {
+        session_id: journal.session_id,
+        action,
+        recovered_at: Utc::now(),
+    };
+    let content =
+        serde_yaml::to_string(&recovery).with_context(|| "Failed to serialize recovery record")?;
+    write_atomic(repo, LAST_RECOVERY_FILE, &content)?;
+    finish(repo);
+    info!(
+        "Recovered interrupted session-close ({:?})",
+        recovery.action
+    );
+    Ok(Some(recovery))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_repo() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q", "-b", "draft"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(repo.join("book.md"), "validated\n").unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "base"]).unwrap();
+        tmp
+    }
+
+    #[test]
+    fn uncommitted_close_is_rolled_back() {
+        let tmp = git_repo();
+        let repo = tmp.path();
+        begin(
+            repo,
+            "s1",
+            vec!["book.md".into(), "Changelog/new.md".into()],
+        )
+        .unwrap();
+
+        // Crash mid-close: one file rewritten and staged, one created
+        std::fs::write(repo.join("book.md"), "validated\nhalf-written\n").unwrap();
+        std::fs::create_dir_all(repo.join("Changelog")).unwrap();
+        std::fs::write(repo.join("Changelog/new.md"), "# Session\n").unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        assert!(git::run_git(repo, &["status", "--porcelain"])
+            .unwrap()
+            .lines()
+            .all(|l| !l.contains(".ink")));
+
+        let recovery = recover(repo).unwrap().expect("journal should be recovered");
+        assert!(matches!(recovery.action, RecoveryAction::RolledBack));
+        assert_eq!(
+            std::fs::read_to_string(repo.join("book.md")).unwrap(),
+            "validated\n"
+        );
+        assert!(!repo.join("Changelog/new.md").exists());
+        assert_eq!(git::run_git(repo, &["status", "--porcelain"]).unwrap(), "");
+        assert!(pending(repo).unwrap().is_none());
+        assert!(last_recovery(repo).is_some());
+        assert!(recover(repo).unwrap().is_none());
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::strip_engine_markers;
+use crate::config::Config;
+use crate::front_matter::{self, FrontMatter};
+use crate::sequence::Unit;
+
+/// Narration words below which a missing first- or second-person pronoun is
+/// not worth flagging: a short passage can be all description.
+const MIN_NARRATION_WORDS: usize = 100;
+
+// ─── Output types ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct PovWarning {
+    /// The declared point of view, e.g. `Mara, first person`.
+    pub expected: String,
+    /// What the heuristic matched, e.g. `first-person narration ("I")`.
+    pub finding: String,
+    /// Up to ~120 characters of prose around the match.
+    pub excerpt: String,
+}
+
+// ─── Perspective ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Perspective {
+    First,
+    Second,
+    Third,
+}
+
+impl Perspective {
+    /// `first`, `first person`, `1st`, `third-person limited`, …
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if value.starts_with("first") || value.starts_with("1st") {
+            Some(Perspective::First)
+        } else if value.starts_with("second") || value.starts_with("2nd") {
+            Some(Perspective::Second)
+        } else if value.starts_with("third") || value.starts_with("3rd") {
+            Some(Perspective::Third)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Perspective::First => "first person",
+            Perspective::Second => "second person",
+            Perspective::Third => "third person",
+        }
+    }
+}
+
+/// Dialogue in straight, curly, or guillemet quotes.
+fn dialogue_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#""[^"\n]*"|“[^”]*”|«[^»]*»"#).unwrap())
+}
+
+fn first_person_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b(?:I|I'm|I’m|I'd|I’d|(?i:me|my|mine|myself))\b").unwrap())
+}
+
+fn second_person_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\b(?:you|your|yours|yourself)\b").unwrap())
+}
+
+/// `prose` with dialogue blanked out (same byte offsets), so pronouns
+/// characters speak are not read as narration.
+fn narration(prose: &str) -> String {
+    dialogue_re()
+        .replace_all(prose, |c: &regex::Captures| " ".repeat(c[0].len()))
+        .into_owned()
+}
+
+/// Heuristically test `prose` against the point of view an outline declares
+/// in its front matter (`perspective`, and `pov` for the character). One
+/// warning per finding, at its first match; nothing without a `perspective`.
+pub fn check_pov(front: &FrontMatter, prose: &str) -> Vec<PovWarning> {
+    let Some(perspective) = front.perspective.as_deref().and_then(Perspective::parse) else {
+        return vec![];
+    };
+    let expected = match &front.pov {
+        Some(pov) => format!("{}, {}", pov, perspective.label()),
+        None => perspective.label().to_string(),
+    };
+    let prose = strip_engine_markers(prose);
+    let text = narration(&prose);
+    let words = text.split_whitespace().count();
+    let excerpt = |start: usize, end: usize| crate::constraints::excerpt(&prose, start, end);
+    let opening = || {
+        let start = prose.len() - prose.trim_start().len();
+        excerpt(start, start)
+    };
+
+    let mut warnings = Vec::new();
+    let mut warn = |finding: String, excerpt: String| {
+        warnings.push(PovWarning {
+            expected: expected.clone(),
+            finding,
+            excerpt,
+        })
+    };
+    match perspective {
+        Perspective::Third | Perspective::Second => {
+            if let Some(m) = first_person_re().find(&text) {
+                warn(
+                    format!("first-person narration (\"{}\")", m.as_str()),
+                    excerpt(m.start(), m.end()),
+                );
+            }
+        }
+        Perspective::First => {
+            if words >= MIN_NARRATION_WORDS && !first_person_re().is_match(&text) {
+                warn("no first-person narration".to_string(), opening());
+            }
+            // The narrator named from outside: "Mara thought"
+            if let Some(pov) = &front.pov {
+                let re = Regex::new(&format!(
+                    r"\b{}\s+(?:thought|felt|wondered|realized|realised|knew|remembered)\b",
+                    regex::escape(pov.trim())
+                ))
+                .ok();
+                if let Some(m) = re.and_then(|re| re.find(&text)) {
+                    warn(
+                        format!("narrator {} described in the third person", pov.trim()),
+                        excerpt(m.start(), m.end()),
+                    );
+                }
+            }
+        }
+    }
+    if perspective == Perspective::Second
+        && words >= MIN_NARRATION_WORDS
+        && !second_person_re().is_match(&text)
+    {
+        warn("no second-person narration".to_string(), opening());
+    }
+    warnings
+}
+
+/// POV warnings for session prose written against the outline at `rel`;
+/// empty when it is missing or declares no perspective.
+pub fn check_outline_pov(repo: &Path, rel: &str, prose: &str) -> Vec<PovWarning> {
+    let Ok(outline) = std::fs::read_to_string(repo.join(rel)) else {
+        return vec![];
+    };
+    match front_matter::split(&outline) {
+        Ok((Some(front), _)) => check_pov(&front, prose),
+        _ => vec![],
+    }
+}
+
+// ─── lint ─────────────────────────────────────────────────────────────────────
+
+/// `lint`: run the POV check over the written prose of every chapter whose
+/// outline declares a `perspective`, the prose rules over every written
+/// chapter, and the glossary check over the whole manuscript. Read-only.
+pub fn lint(repo: &Path) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let book = crate::storage::read_book(repo)?.unwrap_or_default();
+    let mut chapters = Vec::new();
+    let mut total = 0;
+    for n in 1..=config.chapter_count {
+        let Ok(outline) = std::fs::read_to_string(repo.join(Unit::chapter(n).path())) else {
+            continue;
+        };
+        let Ok((Some(front), _)) = front_matter::split(&outline) else {
+            continue;
+        };
+        if front.perspective.is_none() {
+            continue;
+        }
+        let Some(prose) = crate::chapters::chapter_prose(&book, n, config.book_storage) else {
+            continue;
+        };
+        let warnings = check_pov(&front, &prose);
+        total += warnings.len();
+        chapters.push(json!({
+            "chapter": n,
+            "pov": front.pov,
+            "perspective": front.perspective,
+            "pov_warnings": warnings,
+        }));
+    }
+    let mut prose_hints = Vec::new();
+    for n in 1..=config.chapter_count {
+        let Some(prose) = crate::chapters::chapter_prose(&book, n, config.book_storage) else {
+            continue;
+        };
+        let hints = crate::prose_lint::check(&prose, &config.prose_lint);
+        if !hints.is_empty() {
+            total += hints.len();
+            prose_hints.push(json!({ "chapter": n, "hints": hints }));
+        }
+    }
+    let glossary_warnings = crate::glossary::check(
+        &crate::glossary::load(repo)?,
+        &crate::glossary::documented_words(repo),
+        &book,
+    );
+    total += glossary_warnings.len();
+    Ok(json!({
+        "status": if total == 0 { "clean" } else { "warnings" },
+        "warning_count": total,
+        "chapters": chapters,
+        "prose": prose_hints,
+        "glossary_warnings": glossary_warnings,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn front(pov: Option<&str>, perspective: &str) -> FrontMatter {
+        FrontMatter {
+            pov: pov.map(str::to_string),
+            perspective: Some(perspective.to_string()),
+            ..FrontMatter::default()
+        }
+    }
+
+    fn narrated() -> String {
+        "The harbour lay still under a low grey sky. ".repeat(12)
+    }
+
+    #[test]
+    fn first_person_inside_dialogue_is_fine() {
+        let third = front(Some("Mara"), "third person limited");
+        let prose = "Mara climbed the stair. \"I know my way,\" she said. “Give me the key.”";
+        assert!(check_pov(&third, prose).is_empty());
+    }
+
+    #[test]
+    fn first_person_narration_breaks_third_person() {
+        let third = front(Some("Mara"), "third person limited");
+        let warnings = check_pov(&third, "Mara climbed. I followed her up.");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].expected, "Mara, third person");
+        assert!(warnings[0].finding.contains("\"I\""));
+    }
+
+    #[test]
+    fn first_person_chapters_need_an_i_narrator() {
+        let first = front(Some("Mara"), "first");
+        let findings: Vec<String> =
+            check_pov(&first, &format!("{} Mara thought of home.", narrated()))
+                .into_iter()
+                .map(|w| w.finding)
+                .collect();
+        assert_eq!(
+            findings,
+            [
+                "no first-person narration",
+                "narrator Mara described in the third person"
+            ]
+        );
+        assert!(check_pov(&first, &format!("{} I waited.", narrated())).is_empty());
+    }
+
+    #[test]
+    fn chapters_without_a_perspective_are_not_checked() {
+        assert!(check_pov(&FrontMatter::default(), "I ran.").is_empty());
+    }
+}
@@ -1,13 +1,60 @@
+mod anthology;
+mod audit;
+mod backup;
+mod beats;
 mod book;
+mod chapters;
+mod cmdlock;
 mod config;
+mod constraints;
 mod context;
+mod continuity;
+mod curl;
+mod dialogue;
+mod discover;
+mod edits;
+mod encoding;
+mod errors;
+mod export;
+mod front_matter;
 mod git;
+mod glossary;
+mod import;
 mod init;
+mod journal;
+mod lint;
 mod maintenance;
+mod metadata;
+mod metrics;
+mod notify;
+mod obsidian;
+mod pacing;
+mod patch;
+mod phrases;
+mod prose_lint;
+mod readability;
+mod release;
+mod rename;
+mod resync;
+mod retarget;
+mod review;
+mod schedule;
+mod sequence;
+mod series;
+mod signals;
+mod spellcheck;
 mod state;
+mod storage;
+mod threads;
+mod timeline;
+mod timings;
+mod titles;
+mod truncation;
+mod typography;
+mod wordcount;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use std::io::Read;
 use std::path::PathBuf;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
@@ -16,45 +63,246 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 #[command(
     name = "ink-cli",
     version,
-    about = "Ink Gateway CLI for AI-driven fiction writing sessions"
+    about = "Ink Gateway CLI for AI-driven fiction writing sessions",
+    after_help = errors::EXIT_CODES_HELP
 )]
 struct Cli {
+    /// Log errors only (overrides RUST_LOG)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Log more: -v info, -vv debug, -vvv trace (overrides RUST_LOG)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Format of the log lines written to stderr
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per event (timestamp, level, target, fields), for CI runners
+    Json,
+}
+
+impl Cli {
+    /// The log filter: `--quiet` / `-v` when given, else RUST_LOG (errors
+    /// only when unset).
+    fn log_filter(&self) -> EnvFilter {
+        let level = match (self.quiet, self.verbose) {
+            (true, _) => "error",
+            (false, 0) => return EnvFilter::from_default_env(),
+            (false, 1) => "info",
+            (false, 2) => "debug",
+            (false, _) => "trace",
+        };
+        EnvFilter::new(level)
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Open a writing session: git sync, load context, output JSON payload
     SessionOpen {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
     },
     /// Close a writing session: read prose from stdin, write files, push
     SessionClose {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+        /// `session_id` from the session-open payload; the close is refused if it does not match the lock
+        #[arg(long)]
+        session_id: String,
         /// One-paragraph narrative summary of this session (appended to Summary.md and Changelog)
         #[arg(long)]
         summary: Option<String>,
         /// Human-edited files from the session-open payload (repeatable)
         #[arg(long = "human-edit")]
         human_edits: Vec<String>,
+        /// How stdin is interpreted: full prose, or a unified diff against Review/current.md
+        #[arg(long, value_enum, default_value_t = ProseFormat::Full)]
+        format: ProseFormat,
+        /// Check lock and markers and print what would be written, without writing files or touching git
+        #[arg(long)]
+        dry_run: bool,
+        /// Close prose that looks cut off mid-generation even with `refuse_truncated: true`
+        #[arg(long)]
+        force: bool,
+        /// Beats of the session's outline completed this session, by number (e.g. 1,2); checked off in the outline
+        #[arg(long, value_delimiter = ',')]
+        beats_done: Vec<u32>,
+        /// In-story event to append to Global Material/Timeline.md, as "DATE | EVENT" (repeatable); without a date, the outline's `date` front matter is used
+        #[arg(long = "timeline-event")]
+        timeline_events: Vec<String>,
+        /// Invented term to add to Global Material/Glossary.md, as "TERM | DEFINITION" or "TERM | DEFINITION | VARIANT, …" (repeatable)
+        #[arg(long = "new-terms")]
+        new_terms: Vec<String>,
     },
     /// Mark book as complete and perform final push
     Complete {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
     },
     /// Wipe all book content and allow re-running init (requires confirmation)
     Reset {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+    },
+    /// Ask the engine to stop: commit and push .ink-kill (honoured by the next session-open)
+    Kill {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Poll origin until the engine acknowledges the kill request
+        #[arg(long)]
+        wait: bool,
+        /// Give up waiting after this many minutes
+        #[arg(long, default_value_t = 60)]
+        timeout_minutes: u64,
+    },
+    /// Renew the session lock (.ink-running) so a long session is not declared stale
+    Heartbeat {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+        /// Only renew the lock if it belongs to this session
+        #[arg(long)]
+        session_id: Option<String>,
+    },
+    /// Force-release a crashed session's lock (.ink-running) instead of waiting for the timeout
+    Unlock {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+        /// Skip the confirmation prompt (required when not running in a terminal)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Merge the prose queued in Review/pending.md into Full_Book.md (require_approval)
+    Approve {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+    },
+    /// Discard the prose queued in Review/pending.md (require_approval)
+    Reject {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
     },
     /// Revert to the state before the last writing session (requires confirmation)
     Rollback {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Skip the confirmation prompt and print the result as JSON
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Recover a main that has diverged from origin (fast-forward merge fails):
+    /// commit or stash local edits, rebase onto origin, resolve Full_Book.md /
+    /// Summary.md append conflicts, and push
+    Resync {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Stash uncommitted edits and re-apply them afterwards instead of committing them
+        #[arg(long)]
+        stash: bool,
+    },
+    /// Delete old ink-* snapshot tags locally and on the remotes, keeping those
+    /// Config.yml `tag_retention` asks for (the newest N plus one per month)
+    PruneTags {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Report what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run sessions unattended on the Config.yml `schedule:` (cron + agent command)
+    Schedule {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Run one session now and exit instead of waiting for the schedule
+        #[arg(long)]
+        once: bool,
+        /// Serve Prometheus metrics on http://<host:port>/metrics while waiting
+        #[arg(long, value_name = "HOST:PORT", conflicts_with = "once")]
+        metrics_addr: Option<String>,
+        /// File whose first line is the bearer token /metrics requires
+        /// (default: INK_METRICS_TOKEN, else no token)
+        #[arg(long, requires = "metrics_addr")]
+        metrics_token_file: Option<PathBuf>,
+    },
+    /// Write a timestamped backup of the whole book: a git bundle of every branch
+    /// and tag, or a tarball that also keeps uncommitted files and local state
+    Backup {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        #[arg(long, value_enum, default_value = "bundle")]
+        format: backup::BackupFormat,
+        /// Backup file (default: <repo>-YYYYMMDD-HHMMSS.bundle / .tar.gz here)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Verify a backup and unpack it into a new directory
+    Restore {
+        /// Backup file written by `backup` (.bundle or .tar.gz)
+        file: PathBuf,
+        /// Directory to restore into (must not exist or be empty)
+        dest: PathBuf,
+        /// Set origin to this URL (a restored bundle's origin is the bundle file)
+        #[arg(long)]
+        remote: Option<String>,
+    },
+    /// Clone a book repository and report what it needs next: doctor checks and
+    /// next steps, or — for an empty repository — init and its questions payload
+    Clone {
+        /// URL of the book repository
+        url: String,
+        /// Where to clone (default: the repository name in the current directory)
+        dest: Option<PathBuf>,
+        /// Book title, used only when the repository is empty and gets initialized
+        #[arg(long, default_value = "Untitled")]
+        title: String,
+        /// Author name, used only when the repository is empty and gets initialized
+        #[arg(long, default_value = "Unknown")]
+        author: String,
+        /// Output the init questions payload instead of running interactive prompts
+        #[arg(long)]
+        agent: bool,
     },
     /// Scaffold a new book repository with all required files and directories
     Init {
@@ -70,11 +318,111 @@ enum Commands {
         /// (forced automatically when stdout is not a TTY)
         #[arg(long)]
         agent: bool,
+        /// Scaffold a story of an anthology in Stories/<SLUG> and list it in Anthology.yml
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+    },
+    /// Start a sequel: initialize a new book repository like `init`, copying the
+    /// world and voice (Soul, Lore, Characters) from an existing book
+    Spinoff {
+        /// Path to the new book repository (must be an existing git repo)
+        repo_path: PathBuf,
+        /// The book to take the Global Material from
+        #[arg(long)]
+        from: PathBuf,
+        /// Global Material file to copy, repeatable (default: Soul.md, Lore.md,
+        /// Characters.md); Outline.md and Summary.md always start fresh
+        #[arg(long = "copy", value_name = "FILE")]
+        copy: Vec<String>,
+        /// Title of the new book
+        #[arg(long, default_value = "Untitled")]
+        title: String,
+        /// Author name
+        #[arg(long, default_value = "Unknown")]
+        author: String,
+    },
+    /// Import an existing manuscript (.md, .txt, or .docx via pandoc) into a freshly
+    /// initialized book: Full_Book.md, .ink-state.yml position, outline stubs
+    Import {
+        /// Path to the book repository (initialized, Full_Book.md still empty)
+        repo_path: PathBuf,
+        /// Manuscript file; `#`/`##` Chapter / Prologue / Interlude / Epilogue headings split it
+        manuscript: PathBuf,
+        /// Synopsis of the imported part, written to Summary.md
+        #[arg(long)]
+        synopsis: Option<PathBuf>,
+    },
+    /// Write Current version/Full_Book.md from the per-chapter files
+    /// (`book_storage: chapters`); git-ignored, regenerated on every run. At the
+    /// root of an anthology, write Current version/Anthology.md from its stories
+    Compile {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Compile one story of an anthology instead of the whole collection
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+    },
+    /// Switch the manuscript layout: split Full_Book.md into per-chapter files
+    /// at its chapter headings, or join them back. Commits and pushes
+    Migrate {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Layout to switch to
+        #[arg(long, value_enum, default_value = "chapters")]
+        to: config::BookStorage,
     },
     /// Advance to the next chapter, resetting the chapter word count
     AdvanceChapter {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+        /// Append the finished unit's words, sessions, and dates to Chapters material/_progress.md
+        #[arg(long)]
+        retrospective: bool,
+        /// Push the advance commit (best-effort) instead of leaving it for session-close
+        #[arg(long)]
+        push: bool,
+    },
+    /// Change the book's length mid-way: target_length, chapter_count,
+    /// words_per_chapter, and words_per_session rewritten together from the
+    /// words already written, with a Changelog note. Commits and pushes
+    Retarget {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// New length of the book, in words
+        #[arg(long)]
+        target_length: u32,
+        /// Chapters of the new book (default: enough to keep the chapter length)
+        #[arg(long)]
+        chapter_count: Option<u32>,
+        /// Words per session (default: as many sessions per chapter as before)
+        #[arg(long)]
+        words_per_session: Option<u32>,
+        /// Why the scope changed, for the Changelog note
+        #[arg(long)]
+        reason: Option<String>,
+        /// Print the new values without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rename the book: the new title and/or author in Config.yml, the Global
+    /// Material headers, README.md, and the manuscript's title page, as one commit
+    Rename {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// New title of the book
+        #[arg(long)]
+        title: Option<String>,
+        /// New author name
+        #[arg(long)]
+        author: Option<String>,
     },
     /// Write CLAUDE.md and GEMINI.md so any AI agent can auto-detect and init an empty repo
     Seed {
@@ -83,97 +431,1041 @@ enum Commands {
     },
     /// Show current book state: chapter, word counts, lock status, completion
     Status {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Story of an anthology to work on: Stories/<SLUG> of the repository
+        #[arg(long, value_name = "SLUG")]
+        story: Option<String>,
+        /// Print only chapter, pct_complete, lock, last_session_at, and health (cached; for frequent polling)
+        #[arg(long)]
+        ping: bool,
+    },
+    /// Query the local audit log (.ink/audit.log): every ink-cli and MCP command
+    /// run on this working tree, with arguments, outcome, duration, and commit
+    Audit {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Only entries since a date (2026-03-30), an RFC 3339 time, or an age (12h, 3d)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only commands starting with this (`session` matches session-open and session-close)
+        #[arg(long)]
+        command: Option<String>,
+        /// Only requests from this caller (MCP client `name/version`, `$INK_AGENT`, or user)
+        #[arg(long)]
+        caller: Option<String>,
+        /// Only commands that failed
+        #[arg(long)]
+        errors: bool,
+        /// Newest entries to show (0 = all)
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Print a payload for an agent to re-plan the chapters after the current
+    /// one; with --apply, write the outlines it returns (JSON on stdin)
+    Reoutline {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Read {"outlines": [{"chapter", "content"}]} from stdin and write them. Commits and pushes
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Check written chapters against their outlines: narration that contradicts
+    /// the `perspective` (and `pov`) declared in the outline front matter
+    Lint {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+    },
+    /// Report which chapters have a planned outline, a template stub, or none,
+    /// and whether the engine is about to outrun the author's planning
+    OutlineStatus {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
     },
     /// Refresh AGENTS.md (and CLAUDE.md/GEMINI.md) from the latest embedded template
     UpdateAgents {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
     },
     /// Validate repository structure, config, git remote, and session state
     Doctor {
-        /// Path to the book repository
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Repair a wrong branch, detached HEAD, unfinished rebase/merge, or
+        /// abandoned draft before checking (unsaved commits go to a rescue branch)
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Write an author read-through packet (new prose, summaries, open instructions) to Review/
+    ReviewPacket {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
         repo_path: PathBuf,
+        /// How many days back the packet covers
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+        /// Write the packet as HTML instead of Markdown
+        #[arg(long)]
+        html: bool,
+    },
+    /// Export book content in other formats, or run a named profile from Config.yml
+    #[command(args_conflicts_with_subcommands = true)]
+    Export {
+        #[command(subcommand)]
+        format: Option<ExportFormat>,
+        /// Path to the book repository (with --profile; default: found from the current directory)
+        repo_path: Option<PathBuf>,
+        /// Export profile from the `exports:` section of Config.yml
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Read or change Config.yml values with validation and lint checks
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Read or change the book's publishing metadata in Global Material/Metadata.yml
+    Metadata {
+        #[command(subcommand)]
+        action: MetadataAction,
+    },
+    /// Analysis reports over the manuscript
+    Report {
+        #[command(subcommand)]
+        kind: ReportKind,
+    },
+    /// Restructure chapters: outlines, manuscript headings, state, and chapter_count together
+    Chapter {
+        #[command(subcommand)]
+        action: ChapterAction,
     },
     /// Apply format patches to Full_Book.md (title, author, chapter headings). Reads JSON patch from stdin.
     ApplyFormat {
-        #[arg(value_name = "REPO_PATH")]
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(value_name = "REPO_PATH", default_value = ".")]
+        repo_path: PathBuf,
+    },
+}
+
+impl Commands {
+    /// The book a command works on, for the audit log.
+    fn repo(&mut self) -> Option<PathBuf> {
+        if let Commands::Export {
+            format: None,
+            repo_path,
+            ..
+        } = self
+        {
+            return repo_path.clone();
+        }
+        if let Some(repo_path) = self.repo_path_mut() {
+            return Some(repo_path.clone());
+        }
+        self.mutates().map(|(repo, _)| repo.to_path_buf())
+    }
+
+    /// Repository path of the commands that default it to `.`.
+    fn repo_path_mut(&mut self) -> Option<&mut PathBuf> {
+        match self {
+            Commands::SessionOpen { repo_path, .. }
+            | Commands::SessionClose { repo_path, .. }
+            | Commands::Complete { repo_path, .. }
+            | Commands::Reset { repo_path }
+            | Commands::Kill { repo_path, .. }
+            | Commands::Heartbeat { repo_path, .. }
+            | Commands::Unlock { repo_path, .. }
+            | Commands::Approve { repo_path, .. }
+            | Commands::Reject { repo_path, .. }
+            | Commands::Rollback { repo_path, .. }
+            | Commands::Resync { repo_path, .. }
+            | Commands::PruneTags { repo_path, .. }
+            | Commands::Schedule { repo_path, .. }
+            | Commands::Backup { repo_path, .. }
+            | Commands::Compile { repo_path, .. }
+            | Commands::Migrate { repo_path, .. }
+            | Commands::AdvanceChapter { repo_path, .. }
+            | Commands::Retarget { repo_path, .. }
+            | Commands::Rename { repo_path, .. }
+            | Commands::Status { repo_path, .. }
+            | Commands::Audit { repo_path, .. }
+            | Commands::Reoutline { repo_path, .. }
+            | Commands::Lint { repo_path }
+            | Commands::OutlineStatus { repo_path }
+            | Commands::UpdateAgents { repo_path }
+            | Commands::Doctor { repo_path, .. }
+            | Commands::ReviewPacket { repo_path, .. }
+            | Commands::ApplyFormat { repo_path }
+            | Commands::Export {
+                format:
+                    Some(
+                        ExportFormat::Audio { repo_path, .. }
+                        | ExportFormat::Scrivener { repo_path, .. },
+                    ),
+                ..
+            }
+            | Commands::Report {
+                kind:
+                    ReportKind::Dialogue { repo_path }
+                    | ReportKind::Pacing { repo_path }
+                    | ReportKind::Phrases { repo_path, .. },
+            }
+            | Commands::Chapter {
+                action: ChapterAction::Insert { repo_path, .. },
+            } => Some(repo_path),
+            _ => None,
+        }
+    }
+
+    /// Commands that stop at step checkpoints and undo their partial work on
+    /// SIGINT / SIGTERM. Every other command keeps the default handling, so
+    /// Ctrl-C stops it at once.
+    fn cleans_up_on_interrupt(&self) -> bool {
+        matches!(
+            self,
+            Commands::SessionOpen { .. }
+                | Commands::SessionClose { dry_run: false, .. }
+                | Commands::Kill { wait: true, .. }
+                | Commands::Schedule { .. }
+        )
+    }
+
+    /// Repository path and `--story` of the commands that take one.
+    fn story_mut(&mut self) -> Option<(&mut PathBuf, Option<&str>)> {
+        match self {
+            Commands::SessionOpen { repo_path, story }
+            | Commands::SessionClose {
+                repo_path, story, ..
+            }
+            | Commands::Heartbeat {
+                repo_path, story, ..
+            }
+            | Commands::Unlock {
+                repo_path, story, ..
+            }
+            | Commands::Status {
+                repo_path, story, ..
+            }
+            | Commands::Complete { repo_path, story }
+            | Commands::Approve { repo_path, story }
+            | Commands::Reject { repo_path, story }
+            | Commands::AdvanceChapter {
+                repo_path, story, ..
+            }
+            | Commands::Compile { repo_path, story } => Some((repo_path, story.as_deref())),
+            _ => None,
+        }
+    }
+
+    /// Repository and command name for commands that change the working tree or
+    /// its git state; these take the local command lock. Read-only commands,
+    /// exports, `kill` (which locks only around its write, not while it
+    /// waits), and `schedule` (which locks each run, not the wait between
+    /// runs) return None.
+    fn mutates(&self) -> Option<(&std::path::Path, &'static str)> {
+        let (repo_path, name) = match self {
+            Commands::SessionOpen { repo_path, .. } => (repo_path, "session-open"),
+            Commands::SessionClose {
+                repo_path,
+                dry_run: false,
+                ..
+            } => (repo_path, "session-close"),
+            Commands::Complete { repo_path, .. } => (repo_path, "complete"),
+            Commands::Heartbeat { repo_path, .. } => (repo_path, "heartbeat"),
+            Commands::Unlock { repo_path, .. } => (repo_path, "unlock"),
+            Commands::Approve { repo_path, .. } => (repo_path, "approve"),
+            Commands::Reject { repo_path, .. } => (repo_path, "reject"),
+            Commands::Reset { repo_path } => (repo_path, "reset"),
+            Commands::Rollback { repo_path, .. } => (repo_path, "rollback"),
+            Commands::Resync { repo_path, .. } => (repo_path, "resync"),
+            Commands::PruneTags {
+                repo_path,
+                dry_run: false,
+            } => (repo_path, "prune-tags"),
+            Commands::Init { repo_path, .. } => (repo_path, "init"),
+            Commands::Spinoff { repo_path, .. } => (repo_path, "spinoff"),
+            Commands::AdvanceChapter { repo_path, .. } => (repo_path, "advance-chapter"),
+            Commands::Retarget {
+                repo_path,
+                dry_run: false,
+                ..
+            } => (repo_path, "retarget"),
+            Commands::Rename { repo_path, .. } => (repo_path, "rename"),
+            Commands::Import { repo_path, .. } => (repo_path, "import"),
+            Commands::Migrate { repo_path, .. } => (repo_path, "migrate"),
+            Commands::Reoutline {
+                repo_path,
+                apply: true,
+            } => (repo_path, "reoutline --apply"),
+            Commands::Seed { repo_path } => (repo_path, "seed"),
+            Commands::UpdateAgents { repo_path } => (repo_path, "update-agents"),
+            Commands::ReviewPacket { repo_path, .. } => (repo_path, "review-packet"),
+            Commands::Config {
+                action: ConfigAction::Set { repo_path, .. },
+            } => (repo_path, "config set"),
+            Commands::Metadata {
+                action: MetadataAction::Set { repo_path, .. },
+            } => (repo_path, "metadata set"),
+            Commands::Metadata {
+                action: MetadataAction::AddAuthor { repo_path, .. },
+            } => (repo_path, "metadata add-author"),
+            Commands::Metadata {
+                action: MetadataAction::RemoveAuthor { repo_path, .. },
+            } => (repo_path, "metadata remove-author"),
+            Commands::Report {
+                kind:
+                    ReportKind::Phrases {
+                        repo_path,
+                        update: true,
+                        ..
+                    },
+            } => (repo_path, "report phrases --update"),
+            Commands::Chapter {
+                action: ChapterAction::Insert { repo_path, .. },
+            } => (repo_path, "chapter insert"),
+            Commands::Chapter {
+                action: ChapterAction::Delete { repo_path, .. },
+            } => (repo_path, "chapter delete"),
+            Commands::Chapter {
+                action: ChapterAction::Merge { repo_path, .. },
+            } => (repo_path, "chapter merge"),
+            Commands::ApplyFormat { repo_path } => (repo_path, "apply-format"),
+            Commands::Doctor {
+                repo_path,
+                fix: true,
+            } => (repo_path, "doctor --fix"),
+            _ => return None,
+        };
+        Some((repo_path.as_path(), name))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ProseFormat {
+    /// stdin is the complete new current.md (REWORKED + NEW blocks)
+    Full,
+    /// stdin is a unified diff against Review/current.md
+    Patch,
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// Render prose added since a snapshot to per-chapter audio files in Assets/audio/
+    Audio {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Snapshot tag to diff against (default: most recent ink-* tag)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Write a Scrivener import bundle: one MultiMarkdown document per Full_Book.md
+    /// section plus an OPML outline with synopsis cards from Summary.md
+    Scrivener {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Repo-relative output directory (default: Exports/scrivener)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Set one Config.yml value; rejected if the result fails validation or lint
+    Set {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Top-level Config.yml key (e.g. words_per_session)
+        key: String,
+        /// New value, written verbatim after `key: `
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetadataAction {
+    /// Print one metadata value, or all of them; title and author fall back to Config.yml
+    Get {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Metadata key (title, subtitle, author, pen_name, isbn, publisher, blurb,
+        /// keywords, cover); all keys when omitted
+        key: Option<String>,
+    },
+    /// Set one metadata value and commit it; an empty value removes the key
+    Set {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Metadata key (e.g. isbn)
+        key: String,
+        /// New value: comma-separated for keywords, a repo-relative image path for cover
+        value: String,
+    },
+    /// Add an author of a co-written book (or update the one with that name); the joined
+    /// byline replaces the author name in Config.yml, README.md, and the title page
+    AddAuthor {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Real name of the author
+        name: String,
+        /// Name printed on the book instead of the real name
+        #[arg(long)]
+        pen_name: Option<String>,
+        /// Credit: author (default), co-author, translator, illustrator, editor, …
+        #[arg(long)]
+        role: Option<String>,
+        /// Email for the Co-authored-by trailer of session commits
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Remove an author from the list; the last one stays
+    RemoveAuthor {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Real name of the author, as listed
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportKind {
+    /// Dialogue versus narration per chapter, tagged lines per speaker, and
+    /// speakers silent for three chapters or more
+    Dialogue {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+    },
+    /// Words, scenes and dialogue density per chapter against the Outline.md
+    /// acts, with sparklines
+    Pacing {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+    },
+    /// Most repeated phrases across Full_Book.md and use counts of the
+    /// Global Material/Crutches.yml crutch list
+    Phrases {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Number of top offenders to list
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+        /// Add the listed offenders to Crutches.yml and commit it
+        #[arg(long)]
+        update: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChapterAction {
+    /// Add a chapter after chapter N, renumbering every later chapter
+    Insert {
+        /// Path to the book repository (default: found from the current directory)
+        #[arg(default_value = ".")]
+        repo_path: PathBuf,
+        /// Chapter the new one follows (0 = before chapter 1)
+        #[arg(long)]
+        after: u32,
+        /// Title for the new outline's heading
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Delete chapter N (outline and prose, archived first), renumbering every later chapter
+    Delete {
+        /// Path to the book repository
+        repo_path: PathBuf,
+        /// Chapter to delete
+        chapter: u32,
+    },
+    /// Merge two adjacent chapters into the first, renumbering every later chapter
+    Merge {
+        /// Path to the book repository
         repo_path: PathBuf,
+        /// Chapter that absorbs the next one
+        a: u32,
+        /// Chapter folded into it (must be a + 1)
+        b: u32,
     },
 }
 
-fn main() -> Result<()> {
-    // Initialize structured logging to stderr with env-filter
+fn main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command = command_name(&matches);
+
+    // Initialize structured logging to stderr, as text or JSON lines
+    let json = cli.log_format == LogFormat::Json;
     tracing_subscriber::registry()
-        .with(fmt::layer().with_writer(std::io::stderr))
-        .with(EnvFilter::from_default_env())
+        .with((!json).then(|| fmt::layer().with_writer(std::io::stderr)))
+        .with(json.then(|| fmt::layer().json().with_writer(std::io::stderr)))
+        .with(cli.log_filter())
         .init();
 
-    let cli = Cli::parse();
+    if let Err(e) = run(cli, &command) {
+        // One JSON error object on stdout, where callers read every result;
+        // the exit status tells the error kinds apart without parsing it.
+        let (report, status) = errors::report(&e);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_else(|_| report.to_string())
+        );
+        if json {
+            tracing::error!(code = report["error"]["code"].as_str(), "{:#}", e);
+        } else {
+            eprintln!("Error: {:#}", e);
+        }
+        std::process::exit(status);
+    }
+}
+
+/// `report phrases` for `ink-cli report phrases --top 5`.
+fn command_name(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut matches = matches;
+    while let Some((name, sub)) = matches.subcommand() {
+        names.push(name);
+        matches = sub;
+    }
+    names.join(" ")
+}
+
+fn run(mut cli: Cli, command: &str) -> Result<()> {
+    // Interrupts are turned into errors at session step boundaries so that
+    // session-open / session-close can undo their partial work before exiting.
+    if cli.command.cleans_up_on_interrupt() {
+        signals::install("ink-cli");
+    }
+
+    // An omitted repo path (`.`) is the book enclosing the current directory
+    if let Some(repo_path) = cli.command.repo_path_mut() {
+        if repo_path.as_path() == std::path::Path::new(".") {
+            *repo_path = discover::book_root()?;
+        }
+    }
+    if let Commands::Export {
+        format: None,
+        repo_path: repo_path @ None,
+        profile: Some(_),
+    } = &mut cli.command
+    {
+        *repo_path = Some(discover::book_root()?);
+    }
+
+    // `--story <slug>` runs the command on Stories/<slug> of an anthology.
+    // The stories share one working tree, so the collection is locked too.
+    let mut collection = None;
+    if let Some((repo_path, story)) = cli.command.story_mut() {
+        match story {
+            Some(slug) => {
+                let root = repo_path.clone();
+                *repo_path = anthology::story_root(&root, slug)?;
+                collection = Some((root, slug.to_string()));
+            }
+            None if anthology::is_anthology(repo_path)
+                && !repo_path.join("Global Material/Config.yml").exists()
+                && command != "compile" =>
+            {
+                return Err(errors::InkError::InvalidInput(format!(
+                    "{} is an anthology — pass --story <slug> (stories: {})",
+                    repo_path.display(),
+                    anthology::stories(repo_path).join(", ")
+                ))
+                .into());
+            }
+            None => {}
+        }
+    }
+    let _collection_lock = match (&collection, cli.command.mutates()) {
+        (Some((root, slug)), Some((_, name))) => {
+            if let (Commands::SessionOpen { .. }, Some(other)) =
+                (&cli.command, anthology::open_elsewhere(root, slug))
+            {
+                return Err(errors::InkError::Busy {
+                    holder: format!("a session on story {} (close or unlock it first)", other),
+                }
+                .into());
+            }
+            Some(cmdlock::acquire(root, name)?)
+        }
+        _ => None,
+    };
+
+    // Every command on a book lands in its .ink/audit.log, except `audit`
+    let audit = match cli.command.repo() {
+        Some(repo) if command != "audit" => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            Some(audit::Audit::start(
+                &repo,
+                "cli",
+                command,
+                &serde_json::json!(args),
+            ))
+        }
+        _ => None,
+    };
+    let result = execute(cli.command);
+    if let Some(audit) = audit {
+        audit.finish(&result);
+    }
+    result
+}
 
-    match cli.command {
-        Commands::SessionOpen { repo_path } => {
+fn execute(command: Commands) -> Result<()> {
+    // Held until the command returns: one mutating command per working tree
+    // at a time
+    let _command_lock = match command.mutates() {
+        Some((repo, name)) => Some(cmdlock::acquire(repo, name)?),
+        None => None,
+    };
+
+    match command {
+        Commands::SessionOpen { repo_path, .. } => {
             let payload = context::session_open(&repo_path)?;
             println!("{}", serde_json::to_string_pretty(&payload)?);
         }
         Commands::SessionClose {
             repo_path,
+            session_id,
             summary,
             human_edits,
+            format,
+            dry_run,
+            force,
+            beats_done,
+            timeline_events,
+            new_terms,
+            ..
         } => {
-            let mut prose = String::new();
+            let mut input = String::new();
             std::io::stdin()
-                .read_to_string(&mut prose)
+                .read_to_string(&mut input)
                 .context("Failed to read prose from stdin")?;
-            let result =
-                maintenance::close_session(&repo_path, &prose, summary.as_deref(), &human_edits)?;
+            let prose = match format {
+                ProseFormat::Full => input,
+                ProseFormat::Patch => maintenance::prose_from_patch(&repo_path, &input)?,
+            };
+            if dry_run {
+                let preview = maintenance::preview_close(
+                    &repo_path,
+                    &session_id,
+                    &prose,
+                    summary.as_deref(),
+                    force,
+                )?;
+                println!("{}", serde_json::to_string_pretty(&preview)?);
+                return Ok(());
+            }
+            let result = maintenance::close_session(
+                &repo_path,
+                &session_id,
+                &prose,
+                summary.as_deref(),
+                &human_edits,
+                &maintenance::StoryUpdates {
+                    beats_done,
+                    timeline_events: timeline_events
+                        .iter()
+                        .map(|e| timeline::TimelineEvent::parse(e))
+                        .collect::<Result<_>>()?,
+                    new_terms: new_terms
+                        .iter()
+                        .map(|t| glossary::GlossaryEntry::parse(t))
+                        .collect::<Result<_>>()?,
+                },
+                force,
+            )?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
-        Commands::Complete { repo_path } => {
+        Commands::Complete { repo_path, .. } => {
             let result = maintenance::complete_session(&repo_path)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
+        Commands::Kill {
+            repo_path,
+            wait,
+            timeout_minutes,
+        } => {
+            let result = maintenance::request_kill(&repo_path, wait, timeout_minutes)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Heartbeat {
+            repo_path,
+            session_id,
+            ..
+        } => {
+            let result = context::heartbeat(&repo_path, session_id.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Unlock { repo_path, yes, .. } => {
+            let result = maintenance::unlock_session(&repo_path, yes)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Approve { repo_path, .. } => {
+            let result = maintenance::approve_pending(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Reject { repo_path, .. } => {
+            let result = maintenance::reject_pending(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
         Commands::Reset { repo_path } => {
             init::run_reset(&repo_path)?;
         }
-        Commands::Rollback { repo_path } => {
+        Commands::Rollback {
+            repo_path,
+            yes: false,
+        } => {
             maintenance::rollback_session(&repo_path)?;
         }
+        Commands::Rollback {
+            repo_path,
+            yes: true,
+        } => {
+            let result = maintenance::rollback_latest(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Resync { repo_path, stash } => {
+            let edits = if stash {
+                resync::LocalEdits::Stash
+            } else {
+                resync::LocalEdits::Commit
+            };
+            let result = resync::resync(&repo_path, edits)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::PruneTags { repo_path, dry_run } => {
+            let result = maintenance::prune_tags(&repo_path, dry_run)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Schedule {
+            repo_path,
+            once,
+            metrics_addr,
+            metrics_token_file,
+        } => {
+            if once {
+                let result = schedule::run_once(&repo_path)?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                if let Some(addr) = metrics_addr {
+                    let token = metrics::token(metrics_token_file.as_deref())?;
+                    let addr = metrics::serve(&addr, token)?;
+                    tracing::info!("Serving metrics on http://{}/metrics", addr);
+                }
+                schedule::run_daemon(&repo_path)?;
+            }
+        }
         Commands::Init {
             repo_path,
             title,
             author,
             agent,
+            story,
         } => {
+            let repo_path = match story {
+                Some(slug) => anthology::add_story(&repo_path, &slug, &title)?,
+                None => repo_path,
+            };
             let result = init::run_init(&repo_path, &title, &author)?;
-            let is_tty = std::io::IsTerminal::is_terminal(&std::io::stdout());
-            if is_tty && !agent {
-                // Human at a terminal without --agent: run interactive Q&A
-                init::run_interactive_qa(&repo_path, &result)?;
+            finish_init(&repo_path, &result, agent)?;
+        }
+        Commands::Spinoff {
+            repo_path,
+            from,
+            copy,
+            title,
+            author,
+        } => {
+            let result = init::run_spinoff(&repo_path, &from, &copy, &title, &author)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Import {
+            repo_path,
+            manuscript,
+            synopsis,
+        } => {
+            let result = import::import_manuscript(&repo_path, &manuscript, synopsis.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Compile { repo_path, story } => {
+            let result = match story.is_none() && anthology::is_anthology(&repo_path) {
+                true => anthology::compile(&repo_path)?,
+                false => storage::compile(&repo_path)?,
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Migrate { repo_path, to } => {
+            let result = storage::migrate(&repo_path, to)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Backup {
+            repo_path,
+            format,
+            output,
+        } => {
+            let result = backup::backup(&repo_path, format, output.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Restore { file, dest, remote } => {
+            let result = backup::restore(&file, &dest, remote.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Clone {
+            url,
+            dest,
+            title,
+            author,
+            agent,
+        } => {
+            let repo_path = init::clone_repo(&url, dest.as_deref())?;
+            if git::has_commits(&repo_path) {
+                let report = init::onboarding_report(&repo_path)?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
             } else {
-                // Called by agent, piped, or with --agent flag: output JSON payload
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                let _lock = cmdlock::acquire(&repo_path, "clone")?;
+                let result = init::run_init(&repo_path, &title, &author)?;
+                finish_init(&repo_path, &result, agent)?;
             }
         }
-        Commands::AdvanceChapter { repo_path } => {
-            let result = maintenance::advance_chapter(&repo_path)?;
+        Commands::AdvanceChapter {
+            repo_path,
+            retrospective,
+            push,
+            ..
+        } => {
+            let result = maintenance::advance_chapter(&repo_path, retrospective, push)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Retarget {
+            repo_path,
+            target_length,
+            chapter_count,
+            words_per_session,
+            reason,
+            dry_run,
+        } => {
+            let result = retarget::retarget(
+                &repo_path,
+                &retarget::Retarget {
+                    target_length,
+                    chapter_count,
+                    words_per_session,
+                    reason: reason.as_deref(),
+                    dry_run,
+                },
+            )?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Rename {
+            repo_path,
+            title,
+            author,
+        } => {
+            let result = rename::rename(&repo_path, title.as_deref(), author.as_deref())?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
         Commands::Seed { repo_path } => {
             let result = init::run_seed(&repo_path)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
-        Commands::Status { repo_path } => {
-            let result = maintenance::book_status(&repo_path)?;
+        Commands::Status {
+            repo_path, ping, ..
+        } => {
+            if ping {
+                let result = maintenance::status_ping(&repo_path)?;
+                println!("{}", serde_json::to_string(&result)?);
+            } else {
+                let result = maintenance::book_status(&repo_path)?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+        }
+        Commands::Reoutline { repo_path, apply } => {
+            let result = if apply {
+                let mut input = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)
+                    .with_context(|| "Failed to read reoutline JSON from stdin")?;
+                chapters::apply_reoutline(&repo_path, &input)?
+            } else {
+                chapters::reoutline_payload(&repo_path)?
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Lint { repo_path } => {
+            let result = lint::lint(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::OutlineStatus { repo_path } => {
+            let result = chapters::outline_status(&repo_path)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
         Commands::UpdateAgents { repo_path } => {
             let result = init::update_agents(&repo_path)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
-        Commands::Doctor { repo_path } => {
-            let result = maintenance::doctor(&repo_path)?;
+        Commands::Doctor { repo_path, fix } => {
+            let result = maintenance::doctor(&repo_path, fix)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::ReviewPacket {
+            repo_path,
+            days,
+            html,
+        } => {
+            let result = review::review_packet(&repo_path, days, html)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Export {
+            format: Some(ExportFormat::Audio { repo_path, since }),
+            ..
+        } => {
+            let result = export::export_audio(&repo_path, since.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Export {
+            format: Some(ExportFormat::Scrivener { repo_path, output }),
+            ..
+        } => {
+            let result = export::export_scrivener(&repo_path, output.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Export {
+            format: None,
+            repo_path,
+            profile,
+        } => {
+            let (Some(repo_path), Some(profile)) = (repo_path, profile) else {
+                anyhow::bail!("usage: ink-cli export <repo-path> --profile <name>, or ink-cli export audio|scrivener <repo-path>");
+            };
+            let result = export::export_profile(&repo_path, &profile)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Config {
+            action:
+                ConfigAction::Set {
+                    repo_path,
+                    key,
+                    value,
+                },
+        } => {
+            let result = maintenance::config_set(&repo_path, &key, &value)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Metadata {
+            action: MetadataAction::Get { repo_path, key },
+        } => {
+            let result = metadata::get(&repo_path, key.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Metadata {
+            action:
+                MetadataAction::Set {
+                    repo_path,
+                    key,
+                    value,
+                },
+        } => {
+            let result = metadata::set(&repo_path, &key, &value)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Metadata {
+            action:
+                MetadataAction::AddAuthor {
+                    repo_path,
+                    name,
+                    pen_name,
+                    role,
+                    email,
+                },
+        } => {
+            let author = metadata::Author {
+                name,
+                pen_name,
+                role,
+                email,
+            };
+            let result = metadata::add_author(&repo_path, author)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Metadata {
+            action: MetadataAction::RemoveAuthor { repo_path, name },
+        } => {
+            let result = metadata::remove_author(&repo_path, &name)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Report {
+            kind: ReportKind::Dialogue { repo_path },
+        } => {
+            let result = dialogue::report(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Report {
+            kind: ReportKind::Pacing { repo_path },
+        } => {
+            let result = pacing::report(&repo_path)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Report {
+            kind:
+                ReportKind::Phrases {
+                    repo_path,
+                    top,
+                    update,
+                },
+        } => {
+            let result = phrases::report(&repo_path, top, update)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Chapter {
+            action:
+                ChapterAction::Insert {
+                    repo_path,
+                    after,
+                    title,
+                },
+        } => {
+            let result = chapters::insert_chapter(&repo_path, after, title.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Chapter {
+            action: ChapterAction::Delete { repo_path, chapter },
+        } => {
+            let result = chapters::delete_chapter(&repo_path, chapter)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Chapter {
+            action: ChapterAction::Merge { repo_path, a, b },
+        } => {
+            let result = chapters::merge_chapters(&repo_path, a, b)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Audit {
+            repo_path,
+            since,
+            command,
+            caller,
+            errors,
+            limit,
+        } => {
+            let query = audit::AuditQuery {
+                since: since
+                    .map(|s| audit::parse_since(&s, chrono::Utc::now()))
+                    .transpose()?,
+                command,
+                caller,
+                errors_only: errors,
+                limit,
+            };
+            let result = audit::query(&repo_path, &query)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
         Commands::ApplyFormat { repo_path } => {
@@ -189,3 +1481,83 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// After `init` (or `clone` of an empty repository): interactive Q&A for a
+/// human at a terminal, otherwise the JSON questions payload.
+fn finish_init(repo_path: &std::path::Path, result: &init::InitPayload, agent: bool) -> Result<()> {
+    let is_tty = std::io::IsTerminal::is_terminal(&std::io::stdout());
+    if is_tty && !agent {
+        init::run_interactive_qa(repo_path, result)?;
+    } else {
+        println!("{}", serde_json::to_string_pretty(result)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("ink-cli").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn session_commands_clean_up_on_interrupt() {
+        for args in [
+            &["session-open", "/book"][..],
+            &["session-close", "/book", "--session-id", "s1"],
+            &["kill", "/book", "--wait"],
+        ] {
+            assert!(parse(args).command.cleans_up_on_interrupt(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn other_commands_stop_at_once_on_interrupt() {
+        for args in [
+            &["session-close", "/book", "--session-id", "s1", "--dry-run"][..],
+            &["kill", "/book"],
+            &["status", "/book"],
+            &["compile", "/book"],
+        ] {
+            assert!(!parse(args).command.cleans_up_on_interrupt(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn quiet_logs_errors_only() {
+        assert_eq!(
+            parse(&["-q", "status", "/book"]).log_filter().to_string(),
+            "error"
+        );
+    }
+
+    #[test]
+    fn each_verbose_flag_raises_the_log_level() {
+        for (flag, level) in [
+            ("-v", "info"),
+            ("-vv", "debug"),
+            ("-vvv", "trace"),
+            ("-vvvv", "trace"),
+        ] {
+            assert_eq!(
+                parse(&[flag, "status", "/book"]).log_filter().to_string(),
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn quiet_and_verbose_conflict() {
+        assert!(Cli::try_parse_from(["ink-cli", "-q", "-v", "status", "/book"]).is_err());
+    }
+
+    #[test]
+    fn logging_flags_are_accepted_after_the_command() {
+        let cli = parse(&["status", "/book", "--log-format", "json", "--verbose"]);
+        assert!(cli.log_format == LogFormat::Json);
+        assert_eq!(cli.log_filter().to_string(), "info");
+        assert!(parse(&["status", "/book"]).log_format == LogFormat::Text);
+    }
+}
@@ -1,22 +1,43 @@
 use anyhow::{anyhow, Context, Result};
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use inquire::Confirm;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use tracing::info;
 
 use crate::book::{
-    append_to_full_book, check_full_book_format, count_prose_words, strip_author_ink_instructions,
-    strip_engine_markers,
+    append_paginated, apply_reworked_replacements, apply_reworked_replacements_with,
+    check_full_book_format, normalize_scene_breaks, strip_author_ink_instructions,
+    strip_engine_markers, validate_engine_markers, MarkerIssue, FULL_BOOK_HEADER,
 };
-use crate::config::Config;
+use crate::config::{BookStorage, Config, TagRetention};
+use crate::config::{MarkerValidation, QuotaMode};
+use crate::constraints::{self, ConstraintViolation};
 use crate::context::{extract_anchor, ink_re};
+use crate::continuity::ContinuityIssue;
+use crate::errors::InkError;
 use crate::git;
-use crate::state::InkState;
+use crate::glossary::{self, GlossaryEntry, TermWarning};
+use crate::journal;
+use crate::lint::PovWarning;
+use crate::metrics;
+use crate::notify::{self, Event};
+use crate::obsidian;
+use crate::readability::{self, Readability};
+use crate::sequence::{self, Next, Unit, UnitKind};
+use crate::signals;
+use crate::spellcheck::Misspelling;
+use crate::state::{InkState, LastClose};
+use crate::storage;
+use crate::timeline::{self, TimelineEntry, TimelineEvent};
+use crate::timings::StepTimer;
+use crate::truncation::TruncationSign;
+use crate::wordcount::WordCounter;
 
 // ─── Output types ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ClosePayload {
     pub session_word_count: u32,
     pub expected_words_per_session: u32,
@@ -24,7 +45,120 @@ pub struct ClosePayload {
     pub target_length: u32,
     pub completion_ready: bool,
     pub current_chapter_word_count: u32,
+    /// The book's length in pages of `words_per_page`.
+    pub total_pages: u32,
+    /// The book's reading time at `reading_words_per_minute`.
+    pub reading_minutes: u32,
+    /// Mean length of the chapters before the current one; null until the
+    /// first chapter closes.
+    pub average_chapter_words: Option<u32>,
+    pub replacements_applied: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub marker_issues: Vec<MarkerIssue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraint_violations: Vec<ConstraintViolation>,
+    /// Narration contradicting the outline's declared `perspective`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pov_warnings: Vec<PovWarning>,
+    /// Set when require_approval queued this session's prose instead of merging it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_file: Option<String>,
+    /// Checked and total beats of the session's outline, when it lists any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beats: Option<crate::beats::Coverage>,
+    /// Rows this close appended to Global Material/Timeline.md.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timeline_events: Vec<TimelineEntry>,
+    /// Terms this close added to Global Material/Glossary.md.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub glossary_added: Vec<String>,
+    /// Invented terms missing from the glossary, or spelled off a glossary term.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub glossary_warnings: Vec<TermWarning>,
+    /// Unknown speakers and future-chapter summary references; only with
+    /// `continuity_on_close`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub continuity_warnings: Vec<ContinuityIssue>,
+    /// Readability of the session prose; omitted when it holds no words.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readability: Option<Readability>,
+    /// Words hunspell rejects that the book's vocabulary does not hold; only
+    /// with `spellcheck:`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spelling_warnings: Vec<Misspelling>,
+    /// Session length outside the `session_quota:` bounds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaIssue>,
+    /// Signs the prose was cut off mid-generation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub truncation_warnings: Vec<TruncationSign>,
+    pub status: &'static str,
+    /// Milliseconds spent in each step (`recover`, `checks`, `spellcheck`,
+    /// `write_files`, `commit`, `push`, `mirrors`, `notify`) and `total`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub timings: BTreeMap<String, u64>,
+}
+
+/// Session prose outside the `session_quota:` bounds.
+#[derive(Debug, Serialize)]
+pub struct QuotaIssue {
+    /// `under` or `over`.
+    pub problem: &'static str,
+    pub words: u32,
+    pub min_words: u32,
+    /// None without an upper bound (`max_ratio: 0`).
+    pub max_words: Option<u32>,
+}
+
+/// What `session-close --dry-run` reports: the close it would perform.
+#[derive(Debug, Serialize)]
+pub struct DryRunPayload {
+    /// `"ready"`, or `"invalid_markers"` / `"quota_violation"` / `"truncated"`
+    /// when strict marker validation, a rejecting `session_quota`, or
+    /// `refuse_truncated` would refuse the prose.
     pub status: &'static str,
+    pub session_word_count: u32,
+    pub expected_words_per_session: u32,
+    /// Paginated block that would be appended to Full_Book.md.
+    pub full_book_append: String,
+    /// Reworked passages that would be replaced in place in Full_Book.md.
+    pub replacements_applied: u32,
+    /// Words the close would insert (see `plan_merge`).
+    pub words_added: u32,
+    pub total_word_count: u32,
+    pub current_chapter_word_count: u32,
+    /// Set when require_approval would queue the prose instead of merging it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_file: Option<String>,
+    /// Paragraph that would be appended to Summary.md.
+    pub summary_append: String,
+    /// Words in the new Review/current.md.
+    pub current_md_word_count: u32,
+    /// True when unanswered INK instructions would be carried forward.
+    pub instructions_carried_forward: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub marker_issues: Vec<MarkerIssue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraint_violations: Vec<ConstraintViolation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pov_warnings: Vec<PovWarning>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub glossary_warnings: Vec<TermWarning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaIssue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub truncation_warnings: Vec<TruncationSign>,
+}
+
+/// What the engine reports about the story alongside the session prose.
+#[derive(Debug, Default)]
+pub struct StoryUpdates {
+    /// Outline beats completed, by number (`--beats-done`).
+    pub beats_done: Vec<u32>,
+    /// In-story events for Timeline.md (`--timeline-event`).
+    pub timeline_events: Vec<TimelineEvent>,
+    /// Invented terms for Glossary.md (`--new-terms`).
+    pub new_terms: Vec<GlossaryEntry>,
 }
 
 // ─── Helpers ──────────────────────────────────────────────────────────────────
@@ -66,11 +200,23 @@ fn extract_author_instructions(content: &str) -> (String, Vec<(String, String)>)
     (stripped, instructions)
 }
 
+/// Split current.md at the first author INK instruction into
+/// `(validated, pending)`. With no instructions, everything is validated.
+fn split_current(content: &str) -> (String, Option<String>) {
+    match find_first_ink_instruction(content) {
+        Some(pos) => (
+            content[..pos].trim_end().to_string(),
+            Some(content[pos..].trim_start().to_string()),
+        ),
+        None => (content.trim_end().to_string(), None),
+    }
+}
+
 // ─── README helpers ────────────────────────────────────────────────────────────
 
 /// Extract the first Markdown heading from `content` as a plain string.
-/// Falls back to "Chapter N" if no heading is found.
-fn extract_chapter_title(content: &str, chapter_num: u32) -> String {
+/// Falls back to the unit label ("Chapter N", "Prologue", …) if no heading is found.
+fn extract_chapter_title(content: &str, label: &str) -> String {
     content
         .lines()
         .find_map(|line| {
@@ -81,21 +227,22 @@ fn extract_chapter_title(content: &str, chapter_num: u32) -> String {
                 None
             }
         })
-        .unwrap_or_else(|| format!("Chapter {}", chapter_num))
+        .unwrap_or_else(|| label.to_string())
 }
 
 /// Rebuild the chapter list section in README.md.
 ///
-/// Chapters 1..=`completed_through` are marked ✓.
-/// `in_progress` (if Some) is marked *(in progress)*.
-/// Chapters beyond `in_progress` (or `completed_through` when None) are not listed.
+/// `listed` is the book sequence up to and including the current unit
+/// (prologue, chapters, interludes, epilogue). Every entry is marked ✓, except
+/// the last one, which is marked *(in progress)* when `in_progress` is true.
+/// Units after the current one are not listed.
 ///
 /// The section is delimited by the `<!-- INK:README:CHAPTERS -->` marker and the
 /// next `\n---` separator. Non-fatal if README.md is absent or the marker is missing.
-fn update_readme_chapters(
+pub(crate) fn update_readme_chapters(
     repo: &Path,
-    completed_through: u32,
-    in_progress: Option<u32>,
+    listed: &[Unit],
+    in_progress: bool,
 ) -> Result<()> {
     let readme_path = repo.join("README.md");
     if !readme_path.exists() {
@@ -111,24 +258,21 @@ fn update_readme_chapters(
     };
 
     // Build the chapter list
-    let last = in_progress.unwrap_or(completed_through);
     let mut list = String::new();
-    for i in 1..=last {
-        let chapter_path = repo
-            .join("Chapters material")
-            .join(format!("Chapter_{:02}.md", i));
+    for (i, unit) in listed.iter().enumerate() {
+        let chapter_path = repo.join(unit.path());
         let title = if chapter_path.exists() {
             let ch = std::fs::read_to_string(&chapter_path).unwrap_or_default();
-            extract_chapter_title(&ch, i)
+            extract_chapter_title(&ch, &unit.label)
         } else {
-            format!("Chapter {}", i)
+            unit.label.clone()
         };
-        let suffix = if Some(i) == in_progress {
+        let suffix = if in_progress && i + 1 == listed.len() {
             " *(in progress)*"
         } else {
             " ✓"
         };
-        list.push_str(&format!("{}. **{}**{}\n", i, title, suffix));
+        list.push_str(&format!("{}. **{}**{}\n", i + 1, title, suffix));
     }
 
     // Replace from the marker line to the next \n--- separator (kept intact).
@@ -178,24 +322,593 @@ fn update_readme_status(repo: &Path, new_status: &str) -> Result<()> {
     Ok(())
 }
 
+/// Repo-relative path of the prose queue used by the two-stage close.
+pub const PENDING_PROSE_FILE: &str = "Review/pending.md";
+
+/// Result of merging validated prose into Full_Book.md.
+#[derive(Debug, Default)]
+struct MergeOutcome {
+    total_word_count: u32,
+    words_added: u32,
+    replacements_applied: u32,
+}
+
+/// Mean length of the chapters before the current one — the book's words less
+/// the current chapter's; None while the first chapter is still being written.
+fn average_chapter_words(total_word_count: u32, state: &InkState) -> Option<u32> {
+    let finished = state.current_chapter.checked_sub(1).filter(|&n| n > 0)?;
+    Some(total_word_count.saturating_sub(state.current_chapter_word_count) / finished)
+}
+
+fn current_book_word_count(repo: &Path) -> Result<u32> {
+    let counter = WordCounter::for_repo(repo);
+    Ok(storage::compiled_book(repo)?.map_or(0, |text| counter.count(&text)))
+}
+
+/// Full_Book.md after a merge, computed without writing anything.
+struct MergePlan {
+    /// The whole new Full_Book.md text.
+    book: String,
+    /// The paginated block appended at the end (empty if nothing is appended).
+    appended: String,
+    outcome: MergeOutcome,
+}
+
+/// Plan merging validated prose (engine markers still present) into `existing`
+/// Full_Book.md text (None if the file does not exist yet).
+///
+/// With `typography:`, the prose is normalized first — original passages
+/// included, so they still match the normalized book.
+///
+/// Reworked passages whose original text is already in the book replace it in
+/// place; everything else (NEW blocks, plain prose, unmatched reworks) is
+/// appended. words_added counts the text the session inserted: the appended
+/// prose plus what each rework adds over its original — a rework that shortens
+/// a passage adds nothing, rather than taking words off the session.
+fn plan_merge(existing: Option<String>, validated: &str, config: &Config) -> MergePlan {
+    let exists = existing.is_some();
+    let mut book = existing.unwrap_or_default();
+    let counter = WordCounter::new(config);
+    let validated = match &config.typography {
+        Some(typography) => crate::typography::apply(validated, typography, &config.language),
+        None => validated.to_string(),
+    };
+    let mut rework_growth = 0;
+    let (validated, replacements_applied) =
+        apply_reworked_replacements_with(&mut book, &validated, |original, reworked| {
+            rework_growth += counter
+                .count(reworked)
+                .saturating_sub(counter.count(original));
+        });
+
+    // Strip engine markers before appending — they belong only in current.md.
+    let validated = normalize_scene_breaks(&strip_engine_markers(&validated), &config.scene_break);
+    let appended = if validated.trim().is_empty() {
+        String::new()
+    } else {
+        if !exists {
+            book = format!("{}\n", FULL_BOOK_HEADER);
+        }
+        append_paginated(&mut book, validated.trim(), config.words_per_page)
+    };
+
+    let total_word_count = counter.count(&book);
+    MergePlan {
+        book,
+        appended,
+        outcome: MergeOutcome {
+            total_word_count,
+            words_added: rework_growth + counter.count(&validated),
+            replacements_applied,
+        },
+    }
+}
+
+/// Merge validated prose into Full_Book.md, or the unit files with
+/// per-chapter storage (see `plan_merge`).
+fn merge_into_full_book(repo: &Path, validated: &str, config: &Config) -> Result<MergeOutcome> {
+    let plan = plan_merge(storage::read_book(repo)?, validated, config);
+    if plan.outcome.replacements_applied > 0 {
+        info!(
+            "Applied {} in-place rework replacement(s)",
+            plan.outcome.replacements_applied
+        );
+    }
+    if plan.outcome.replacements_applied > 0 || !plan.appended.is_empty() {
+        storage::write_book(repo, &plan.book)?;
+    }
+    Ok(plan.outcome)
+}
+
+/// Append validated prose to Review/pending.md. Sessions closed before the
+/// author gets to `approve` accumulate in order; markers are kept so in-place
+/// rework replacements still apply when the queue is merged.
+fn queue_pending_prose(repo: &Path, validated: &str) -> Result<()> {
+    let path = repo.join(PENDING_PROSE_FILE);
+    let mut queued = if path.exists() {
+        std::fs::read_to_string(&path).with_context(|| "Failed to read Review/pending.md")?
+    } else {
+        String::new()
+    };
+    if !queued.trim().is_empty() {
+        queued = format!("{}\n\n", queued.trim_end());
+    }
+    queued.push_str(validated.trim());
+    queued.push('\n');
+    std::fs::create_dir_all(repo.join("Review")).with_context(|| "Failed to create Review/")?;
+    std::fs::write(&path, queued).with_context(|| "Failed to write Review/pending.md")
+}
+
+/// Stable 64-bit FNV-1a hash of the submitted prose, hex-encoded. Unlike std's
+/// hasher it does not change between Rust releases, so it is safe to persist.
+fn prose_hash(prose: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in prose.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Publish a committed session: push draft, fast-forward main onto it, push main.
+/// Every step is a no-op when already done, so a retried close can re-run it.
+pub(crate) fn publish_session(repo: &Path) -> Result<()> {
+    // Leased force: origin/draft may still hold heartbeats of a session whose
+    // stale lock was taken over, and draft is reset to main at every open.
+    let branches = git::branches(repo);
+    git::run_git(
+        repo,
+        &[
+            "push",
+            "--force-with-lease",
+            &branches.remote,
+            &branches.draft,
+        ],
+    )
+    .with_context(|| format!("Failed to push {}", branches.draft))
+    .inspect_err(|e| notify_push_failed(repo, e))?;
+
+    info!(
+        "Fast-forward merging {} into {} and pushing",
+        branches.draft, branches.main
+    );
+    git::run_git(repo, &["checkout", &branches.main])
+        .with_context(|| format!("Failed to checkout {}", branches.main))?;
+    if let Err(e) = git::run_git(repo, &["merge", "--ff-only", &branches.draft]) {
+        git::abort_merge_if_in_progress(repo);
+        return Err(e).with_context(|| {
+            format!(
+                "Failed to fast-forward merge {} into {}",
+                branches.draft, branches.main
+            )
+        });
+    }
+    git::push_main(repo)
+        .with_context(|| "Failed to push main")
+        .inspect_err(|e| notify_push_failed(repo, e))?;
+    Ok(())
+}
+
+/// Update the backup remotes and report each one under `mirrors` (omitted
+/// when Config.yml lists no backups), plus `network_retries` when a fetch or
+/// push of this command had to be retried.
+fn with_remote_report(repo: &Path, payload: &mut serde_json::Value) {
+    let mirrors = git::push_mirrors(repo);
+    if !mirrors.is_empty() {
+        payload["mirrors"] = serde_json::json!(mirrors);
+    }
+    let retries = git::take_network_retries();
+    if retries > 0 {
+        payload["network_retries"] = serde_json::json!(retries);
+    }
+}
+
+/// Whether `dir` (recursively) holds any file besides `.gitkeep`.
+fn has_assets(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.filter_map(|e| e.ok()).any(|e| {
+            let path = e.path();
+            if path.is_dir() {
+                has_assets(&path)
+            } else {
+                e.file_name() != ".gitkeep"
+            }
+        })
+    })
+}
+
+fn branch_exists(repo: &Path, branch: &str) -> bool {
+    git::run_git(
+        repo,
+        &["show-ref", "--verify", &format!("refs/heads/{}", branch)],
+    )
+    .is_ok()
+}
+
+pub(crate) fn notify_push_failed(repo: &Path, error: &anyhow::Error) {
+    notify::send(
+        repo,
+        Event::PushFailed,
+        &format!(
+            "{:#}\n\nThe commits are kept locally; the next ink command retries the push.",
+            error
+        ),
+    );
+}
+
+/// Handle a close for a session that was already closed. Identical prose is
+/// treated as a retry: the publish step is re-run (it may be what failed) and
+/// the original payload is returned. Anything else is refused.
+fn replay_close(repo: &Path, last: LastClose, hash: &str) -> Result<serde_json::Value> {
+    if last.prose_hash != hash {
+        return Err(InkError::SessionClosed {
+            session_id: last.session_id,
+        }
+        .into());
+    }
+    info!(
+        "Duplicate session-close for {} — returning the original payload",
+        last.session_id
+    );
+    // Only republish when the original commit exists (clean tree); if a new
+    // session holds the lock, main has already moved on.
+    let clean = git::run_git(repo, &["status", "--porcelain"])
+        .map(|s| s.is_empty())
+        .unwrap_or(false);
+    if !repo.join(".ink-running").exists() {
+        anyhow::ensure!(
+            clean,
+            "previous close of session {} stopped before its commit — inspect the working tree",
+            last.session_id
+        );
+        publish_session(repo)?;
+    }
+    Ok(last.payload)
+}
+
+/// Guards shared by session-close and its dry run: the lock must exist and
+/// belong to `session_id`. Stops a retried or stray agent call from closing a
+/// session it did not open. Locks written before session IDs existed carry
+/// none and are accepted.
+fn check_session_lock(repo: &Path, session_id: &str) -> Result<()> {
+    if !repo.join(".ink-running").exists() {
+        return Err(InkError::NoSession.into());
+    }
+    if let Some(lock_id) = crate::context::read_lock(repo).and_then(|l| l.session_id) {
+        if lock_id != session_id {
+            return Err(InkError::SessionMismatch {
+                session_id: session_id.to_string(),
+            }
+            .into());
+        }
+    } else {
+        tracing::warn!("Lock has no session ID (written by an older ink-cli) — skipping check");
+    }
+    Ok(())
+}
+
+/// Outline of the unit the session opened (recorded in the lock), else of
+/// the current unit for locks written before it was recorded.
+fn session_outline(repo: &Path) -> Result<String> {
+    match crate::context::read_lock(repo).and_then(|l| l.outline) {
+        Some(rel) => Ok(rel),
+        None => Ok(
+            sequence::current_unit(&sequence::load_sequence(repo)?, &InkState::load(repo)?).path(),
+        ),
+    }
+}
+
+/// Timeline.md rows for the events a session-close submits, credited to the
+/// unit whose outline is at `rel`. An event without a date takes the
+/// outline's `date` front matter; with neither, the close is refused.
+fn timeline_entries(
+    repo: &Path,
+    rel: &str,
+    events: &[TimelineEvent],
+) -> Result<Vec<TimelineEntry>> {
+    if events.is_empty() {
+        return Ok(vec![]);
+    }
+    let chapter = sequence::load_sequence(repo)?
+        .into_iter()
+        .find(|u| u.path() == rel)
+        .map(|u| u.label)
+        .unwrap_or_else(|| rel.trim_start_matches("Chapters material/").to_string());
+    let outline_date = std::fs::read_to_string(repo.join(rel))
+        .ok()
+        .and_then(|o| crate::front_matter::split(&o).ok().and_then(|(f, _)| f))
+        .and_then(|f| f.date);
+    events
+        .iter()
+        .map(|e| {
+            let date = e
+                .date
+                .clone()
+                .or_else(|| outline_date.clone())
+                .ok_or_else(|| {
+                    InkError::InvalidInput(format!(
+                        "timeline event '{}' has no date and {} sets no `date` front matter",
+                        e.event, rel
+                    ))
+                })?;
+            Ok(TimelineEntry {
+                date,
+                chapter: chapter.clone(),
+                event: e.event.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// New Review/current.md: the engine prose with any echoed author instructions
+/// removed. If old current.md had pending INK instructions but the engine
+/// produced no REWORKED blocks, the rework was silently skipped — the pending
+/// section is carried forward (second value `true`) so the instructions surface
+/// again next session instead of being discarded.
+fn next_current_md(prose: &str, pending: Option<&str>) -> (String, bool) {
+    let prose_clean = strip_author_ink_instructions(prose);
+    match pending {
+        Some(pending) if !prose.contains("<!-- INK:REWORKED:START -->") => {
+            let instruction_count = ink_re().find_iter(pending).count();
+            tracing::warn!(
+                "Engine produced 0 REWORKED blocks despite {} pending INK instruction(s); \
+                 carrying pending section forward to next session",
+                instruction_count
+            );
+            // Strip stale engine markers from pending before re-appending so they
+            // don't accumulate across sessions (markers belong only in current.md
+            // when freshly generated, not when preserved from a prior session).
+            let pending_clean = strip_engine_markers(pending);
+            (
+                format!("{}\n\n{}", prose_clean.trim_end(), pending_clean.trim()),
+                true,
+            )
+        }
+        _ => (prose_clean, false),
+    }
+}
+
+/// The paragraph session-close appends to Summary.md.
+fn summary_entry(summary: Option<&str>, now: &chrono::DateTime<Local>, words: u32) -> String {
+    let text = summary.map(|s| s.to_string()).unwrap_or_else(|| {
+        format!(
+            "Session {} — {} words written.",
+            now.format("%Y-%m-%d %H:%M"),
+            words
+        )
+    });
+    text.trim().to_string()
+}
+
 // ─── session-close ─────────────────────────────────────────────────────────────
 
+/// `force` closes prose that `refuse_truncated` would refuse as cut off.
 pub fn close_session(
     repo: &Path,
+    session_id: &str,
     prose: &str,
     summary: Option<&str>,
     human_edits: &[String],
-) -> Result<ClosePayload> {
-    let lock_path = repo.join(".ink-running");
-
-    // Guard: lock must exist
-    if !lock_path.exists() {
-        return Err(anyhow!("no active session — run session-open first"));
+    updates: &StoryUpdates,
+    force: bool,
+) -> Result<serde_json::Value> {
+    let beats_done = &updates.beats_done;
+    // A previous close that crashed or failed to push is finished (or undone)
+    // first, so the checks below see a consistent repo.
+    git::take_network_retries();
+    let mut timer = StepTimer::start();
+    timer.step("recover");
+    journal::recover(repo)?;
+
+    // Retried close: the same session was already closed (lock released in the
+    // session commit). Checked before the lock guard for exactly that reason.
+    let hash = prose_hash(prose);
+    if let Some(last) = InkState::load(repo)?.last_close {
+        if last.session_id == session_id {
+            return replay_close(repo, last, &hash);
+        }
     }
 
+    check_session_lock(repo, session_id)?;
+    timer.step("checks");
+
+    // Everything written below derives from NFC, LF-only text
+    let prose: &str = &crate::encoding::normalize(prose);
+    let summary = summary.map(crate::encoding::normalize);
+    let summary = summary.as_deref();
+
     let config = Config::load(repo)?;
     let now = Local::now();
-    let session_word_count = crate::book::count_prose_words(prose);
+    let session_word_count = WordCounter::new(&config).count(prose);
+    let changelog_rel = format!("Changelog/{}.md", now.format("%Y-%m-%d-%H-%M"));
+
+    // Validate engine markers before touching anything. Strict mode rejects
+    // malformed prose with the issue list; the lock stays so the engine can retry.
+    let marker_issues = validate_engine_markers(prose);
+    if !marker_issues.is_empty() {
+        if config.marker_validation == MarkerValidation::Strict {
+            tracing::warn!(
+                "Rejecting session prose: {} marker issue(s)",
+                marker_issues.len()
+            );
+            return Err(InkError::InvalidMarkers {
+                issues: marker_issues,
+            }
+            .into());
+        }
+        tracing::warn!(
+            "{} marker issue(s) in session prose (marker_validation: warn)",
+            marker_issues.len()
+        );
+    }
+
+    let quota = match check_session_quota(session_word_count, &config) {
+        Some(issue) if quota_rejects(&config) => {
+            tracing::warn!(
+                "Rejecting session prose: {} words, {} the session quota",
+                session_word_count,
+                issue.problem
+            );
+            return Err(InkError::QuotaViolation { quota: issue }.into());
+        }
+        quota => quota,
+    };
+    if let Some(issue) = &quota {
+        tracing::warn!(
+            "Session prose is {} the session quota: {} words, {} expected",
+            issue.problem,
+            session_word_count,
+            config.words_per_session
+        );
+    }
+
+    let truncation_warnings = crate::truncation::check(prose);
+    if !truncation_warnings.is_empty() {
+        if config.refuse_truncated && !force {
+            tracing::warn!(
+                "Rejecting session prose: it looks cut off ({} sign(s)); --force closes it anyway",
+                truncation_warnings.len()
+            );
+            return Err(InkError::Truncated {
+                signs: truncation_warnings,
+            }
+            .into());
+        }
+        tracing::warn!(
+            "Session prose may be cut off: {}",
+            truncation_warnings
+                .iter()
+                .map(|s| s.check)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    // Narrative constraints are checked heuristically and only reported (payload
+    // + Changelog): a false positive must never block a session.
+    let constraint_violations = constraints::check(
+        &config.constraints,
+        &strip_engine_markers(prose),
+        InkState::load(repo)?.current_chapter,
+    );
+    if !constraint_violations.is_empty() {
+        tracing::warn!(
+            "{} possible constraint violation(s) in session prose",
+            constraint_violations.len()
+        );
+    }
+    // Same for the point of view the session's outline declares
+    let outline_rel = session_outline(repo)?;
+    let pov_warnings = crate::lint::check_outline_pov(repo, &outline_rel, prose);
+    if !pov_warnings.is_empty() {
+        tracing::warn!(
+            "{} possible POV slip(s) in session prose",
+            pov_warnings.len()
+        );
+    }
+
+    // Beats are checked off in the outline of the unit the session opened.
+    // An unknown beat number is refused here, before any write.
+    let checked_outline = if beats_done.is_empty() {
+        None
+    } else {
+        let outline = std::fs::read_to_string(repo.join(&outline_rel))
+            .with_context(|| format!("--beats-done: failed to read {}", outline_rel))?;
+        Some(
+            crate::beats::mark_done(&outline, beats_done)
+                .with_context(|| format!("--beats-done: {}", outline_rel))?,
+        )
+    };
+
+    // Timeline events are dated now too: an event without a date needs one in
+    // the outline's front matter.
+    let timeline_entries = timeline_entries(repo, &outline_rel, &updates.timeline_events)?;
+
+    // Invented terms are checked against the glossary, this session's new
+    // terms included — reported, never blocking.
+    let mut glossary_entries = glossary::load(repo).unwrap_or_else(|e| {
+        tracing::warn!("{:#}", e);
+        vec![]
+    });
+    glossary_entries.extend(updates.new_terms.iter().cloned());
+    let documented = glossary::documented_words(repo);
+    let glossary_warnings = glossary::check(&glossary_entries, &documented, prose);
+    if !glossary_warnings.is_empty() {
+        tracing::warn!(
+            "{} possible glossary issue(s) in session prose",
+            glossary_warnings.len()
+        );
+    }
+
+    // Spellcheck with the glossary and the book's material as a custom
+    // dictionary — reported, never blocking, and skipped if hunspell fails.
+    let spelling_warnings = match &config.spellcheck {
+        Some(spellcheck) => {
+            timer.step("spellcheck");
+            let mut known = documented;
+            known.extend(glossary_entries.iter().flat_map(GlossaryEntry::words));
+            crate::spellcheck::check(spellcheck, &config.language, &known, prose).unwrap_or_else(
+                |e| {
+                    tracing::warn!("spellcheck skipped: {:#}", e);
+                    vec![]
+                },
+            )
+        }
+        None => vec![],
+    };
+    timer.step("checks");
+
+    let continuity_warnings = if config.continuity_on_close {
+        crate::continuity::check_session(
+            repo,
+            prose,
+            summary,
+            InkState::load(repo)?.current_chapter,
+        )
+    } else {
+        vec![]
+    };
+
+    // Write-ahead journal: if the process dies from here on, the next command
+    // either publishes the session commit or restores these files.
+    let mut journaled: Vec<String> = storage::book_files(repo)?;
+    journaled.extend(
+        [
+            PENDING_PROSE_FILE,
+            ".ink-state.yml",
+            "Review/current.md",
+            "Global Material/Summary.md",
+            &changelog_rel,
+            ".ink-running",
+        ]
+        .iter()
+        .map(|f| f.to_string()),
+    );
+    if config.obsidian {
+        journaled.extend([obsidian::INDEX_NOTE.to_string(), ".gitignore".to_string()]);
+    }
+    if checked_outline.is_some() {
+        journaled.push(outline_rel.clone());
+    }
+    if !timeline_entries.is_empty() {
+        journaled.push(timeline::TIMELINE_FILE.to_string());
+    }
+    if !updates.new_terms.is_empty() {
+        journaled.push(glossary::GLOSSARY_FILE.to_string());
+    }
+    journal::begin(repo, session_id, journaled)?;
+
+    // Interrupt checkpoint: discard every uncommitted write made so far so the
+    // repo returns to its session-open state with the lock still held — the
+    // engine can simply re-run session-close with the same prose.
+    let checkpoint = |step: &str| -> Result<()> {
+        signals::checkpoint(step).inspect_err(|_| {
+            git::discard_uncommitted_changes(repo, &["Changelog", "Current version"]);
+            journal::finish(repo);
+        })
+    };
+    checkpoint("reading current.md")?;
+    timer.step("write_files");
 
     // ── Step 1: Read old current.md, split at first INK instruction ──────────
     info!("Reading Review/current.md to extract validated content");
@@ -203,8 +916,9 @@ pub fn close_session(
     let current_md_path = review_dir.join("current.md");
 
     let old_current = if current_md_path.exists() {
-        std::fs::read_to_string(&current_md_path)
-            .with_context(|| "Failed to read Review/current.md")?
+        let text = std::fs::read_to_string(&current_md_path)
+            .with_context(|| "Failed to read Review/current.md")?;
+        crate::encoding::normalize(&text).into_owned()
     } else {
         String::new()
     };
@@ -212,39 +926,30 @@ pub fn close_session(
     // Everything before the first author INK instruction is validated prose.
     // The pending section (from the first instruction onwards) is tracked separately:
     // if the engine skips rework, we carry it forward so instructions aren't silently lost.
-    let (validated, pending_opt) = match find_first_ink_instruction(&old_current) {
-        Some(pos) => (
-            old_current[..pos].trim_end().to_string(),
-            Some(old_current[pos..].trim_start().to_string()),
-        ),
-        None => (old_current.trim_end().to_string(), None), // no instructions → all is validated
-    };
+    let (validated, pending_opt) = split_current(&old_current);
 
     // ── Step 2: Append validated content to Full_Book.md ────────────────────
-    info!("Appending validated content to Full_Book.md");
-    let book_dir = repo.join("Current version");
-    std::fs::create_dir_all(&book_dir).with_context(|| "Failed to create 'Current version/'")?;
-    let book_path = book_dir.join("Full_Book.md");
-
-    // Strip engine markers before appending — they belong only in current.md.
-    let validated = strip_engine_markers(&validated);
-
-    // append_to_full_book returns (old_words, new_words) from a single file read,
-    // eliminating the separate pre-read that was needed before.
-    let (old_total, total_word_count) = if !validated.trim().is_empty() {
-        append_to_full_book(&book_path, validated.trim(), config.words_per_page)?
+    // With require_approval, the validated prose is queued in Review/pending.md
+    // instead and only reaches Full_Book.md through `approve`.
+    checkpoint("Full_Book.md append")?;
+    let pending_file = if config.require_approval && !validated.trim().is_empty() {
+        info!("Queueing validated content in Review/pending.md for approval");
+        queue_pending_prose(repo, &validated)?;
+        Some(PENDING_PROSE_FILE.to_string())
     } else {
-        // Nothing validated: no words added; report current book word count
-        let existing = if book_path.exists() {
-            count_prose_words(
-                &std::fs::read_to_string(&book_path)
-                    .with_context(|| "Failed to read Full_Book.md")?,
-            )
-        } else {
-            0
-        };
-        (existing, existing)
+        None
+    };
+    let merged = if pending_file.is_some() {
+        MergeOutcome {
+            total_word_count: current_book_word_count(repo)?,
+            ..MergeOutcome::default()
+        }
+    } else {
+        info!("Appending validated content to Full_Book.md");
+        merge_into_full_book(repo, &validated, &config)?
     };
+    let total_word_count = merged.total_word_count;
+    let replacements_applied = merged.replacements_applied;
 
     // ── Step 2b: Update chapter word count in .ink-state.yml ────────────────
     // NOTE: if the engine called advance-chapter before session-close (which the
@@ -258,37 +963,16 @@ pub fn close_session(
     // A clean fix would require passing the active chapter from session-open to
     // session-close (e.g. via the lock file), which is left as a future improvement.
     info!("Updating chapter word count in .ink-state.yml");
-    let words_added = total_word_count.saturating_sub(old_total);
+    let words_added = merged.words_added;
     let mut state = InkState::load(repo)?;
     state.current_chapter_word_count += words_added;
     state.save(repo)?;
 
     // ── Step 3: Write new current.md = engine prose (REWORKED + NEW blocks) ──
-    //
-    // Guard: if old current.md had pending INK instructions but the engine
-    // produced no REWORKED blocks, the rework was silently skipped.
-    // Carry the pending section forward so instructions surface again in the
-    // next session-open payload instead of being permanently discarded.
-    // Strip any author INK instructions the engine may have echoed back — they must
+    // Author INK instructions the engine echoed back are stripped — they must
     // never accumulate in current.md across sessions.
-    let prose_clean = strip_author_ink_instructions(prose);
-
-    let new_current = match pending_opt {
-        Some(ref pending) if !prose.contains("<!-- INK:REWORKED:START -->") => {
-            let instruction_count = ink_re().find_iter(pending).count();
-            tracing::warn!(
-                "Engine produced 0 REWORKED blocks despite {} pending INK instruction(s); \
-                 carrying pending section forward to next session",
-                instruction_count
-            );
-            // Strip stale engine markers from pending before re-appending so they
-            // don't accumulate across sessions (markers belong only in current.md
-            // when freshly generated, not when preserved from a prior session).
-            let pending_clean = strip_engine_markers(pending);
-            format!("{}\n\n{}", prose_clean.trim_end(), pending_clean.trim())
-        }
-        _ => prose_clean,
-    };
+    checkpoint("current.md rewrite")?;
+    let (new_current, _) = next_current_md(prose, pending_opt.as_deref());
 
     info!("Writing new Review/current.md");
     std::fs::create_dir_all(&review_dir).with_context(|| "Failed to create Review/")?;
@@ -298,14 +982,7 @@ pub fn close_session(
     // ── Step 4: Append to Summary.md ─────────────────────────────────────────
     info!("Appending to Summary.md");
     let summary_path = repo.join("Global Material").join("Summary.md");
-    let delta_text = summary.map(|s| s.to_string()).unwrap_or_else(|| {
-        format!(
-            "Session {} — {} words written.",
-            now.format("%Y-%m-%d %H:%M"),
-            session_word_count
-        )
-    });
-    let delta = format!("\n\n{}", delta_text.trim());
+    let delta = format!("\n\n{}", summary_entry(summary, &now, session_word_count));
     let mut existing_summary = if summary_path.exists() {
         std::fs::read_to_string(&summary_path).with_context(|| "Failed to read Summary.md")?
     } else {
@@ -315,12 +992,34 @@ pub fn close_session(
     std::fs::write(&summary_path, &existing_summary)
         .with_context(|| "Failed to write Summary.md")?;
 
+    // ── Step 4b: Check off completed beats in the outline ───────────────────
+    if let Some(outline) = &checked_outline {
+        info!("Checking off beats {:?} in {}", beats_done, outline_rel);
+        std::fs::write(repo.join(&outline_rel), outline)
+            .with_context(|| format!("Failed to write {}", outline_rel))?;
+    }
+    let beats = crate::beats::coverage(repo, &outline_rel)?;
+
+    // ── Step 4c: Append timeline events ──────────────────────────────────────
+    if !timeline_entries.is_empty() {
+        info!(
+            "Appending {} event(s) to Timeline.md",
+            timeline_entries.len()
+        );
+        timeline::append(repo, &timeline_entries)?;
+    }
+
+    // ── Step 4d: Add new terms to the glossary ───────────────────────────────
+    let glossary_added = glossary::append(repo, &updates.new_terms)?;
+    if !glossary_added.is_empty() {
+        info!("Added {} term(s) to Glossary.md", glossary_added.len());
+    }
+
     // ── Step 5: Write Changelog entry ────────────────────────────────────────
     info!("Writing changelog entry");
-    let changelog_dir = repo.join("Changelog");
-    std::fs::create_dir_all(&changelog_dir).with_context(|| "Failed to create Changelog/")?;
-    let changelog_filename = format!("{}.md", now.format("%Y-%m-%d-%H-%M"));
-    let changelog_path = changelog_dir.join(&changelog_filename);
+    std::fs::create_dir_all(repo.join("Changelog"))
+        .with_context(|| "Failed to create Changelog/")?;
+    let changelog_path = repo.join(&changelog_rel);
 
     let mut changelog = format!(
         "# Session {}\n\n**Words written:** {}\n",
@@ -328,9 +1027,84 @@ pub fn close_session(
         session_word_count
     );
     if !human_edits.is_empty() {
-        changelog.push_str("\n**Human edits:**\n");
-        for edit in human_edits {
-            changelog.push_str(&format!("- {}\n", edit));
+        let known = crate::context::read_lock(repo)
+            .map(|l| l.human_edits)
+            .unwrap_or_default();
+        changelog.push_str(&crate::edits::changelog_section(human_edits, &known));
+    }
+    if !truncation_warnings.is_empty() {
+        changelog.push_str("\n**Possibly truncated:**\n");
+        for s in &truncation_warnings {
+            changelog.push_str(&format!("- {}: \"{}\"\n", s.check, s.excerpt));
+        }
+    }
+    if let Some(q) = &quota {
+        changelog.push_str(&format!(
+            "\n**Session quota:** {} words, {} the {}–{} expected\n",
+            q.words,
+            q.problem,
+            q.min_words,
+            q.max_words.map_or("∞".to_string(), |m| m.to_string())
+        ));
+    }
+    if !constraint_violations.is_empty() {
+        changelog.push_str("\n**Constraint warnings:**\n");
+        for v in &constraint_violations {
+            changelog.push_str(&format!(
+                "- {} — {}: \"{}\"\n",
+                v.constraint, v.finding, v.excerpt
+            ));
+        }
+    }
+    if !pov_warnings.is_empty() {
+        changelog.push_str("\n**POV warnings:**\n");
+        for w in &pov_warnings {
+            changelog.push_str(&format!(
+                "- {} — {}: \"{}\"\n",
+                w.expected, w.finding, w.excerpt
+            ));
+        }
+    }
+    if !glossary_added.is_empty() {
+        changelog.push_str(&format!("\n**New terms:** {}\n", glossary_added.join(", ")));
+    }
+    if !glossary_warnings.is_empty() {
+        changelog.push_str("\n**Glossary warnings:**\n");
+        for w in &glossary_warnings {
+            changelog.push_str(&format!(
+                "- {} — {}: \"{}\"\n",
+                w.term, w.finding, w.excerpt
+            ));
+        }
+    }
+    if !spelling_warnings.is_empty() {
+        let words: Vec<&str> = spelling_warnings.iter().map(|m| m.word.as_str()).collect();
+        changelog.push_str(&format!("\n**Spelling:** {}\n", words.join(", ")));
+    }
+    let readability = readability::measure(prose, &config.language);
+    if let Some(r) = &readability {
+        changelog.push_str(&format!(
+            "\n**Readability:** ARI {}, LIX {}",
+            r.automated_readability_index, r.lix
+        ));
+        if let (Some(ease), Some(grade)) = (r.flesch_reading_ease, r.flesch_kincaid_grade) {
+            changelog.push_str(&format!(
+                ", Flesch {}, Flesch–Kincaid grade {}",
+                ease, grade
+            ));
+        }
+        changelog.push('\n');
+    }
+    if !continuity_warnings.is_empty() {
+        changelog.push_str("\n**Continuity warnings:**\n");
+        for w in &continuity_warnings {
+            changelog.push_str(&format!("- {}\n", w.detail));
+        }
+    }
+    if !timeline_entries.is_empty() {
+        changelog.push_str("\n**Timeline:**\n");
+        for e in &timeline_entries {
+            changelog.push_str(&format!("- {} — {}\n", e.date, e.event));
         }
     }
     if let Some(s) = summary {
@@ -340,51 +1114,375 @@ pub fn close_session(
     std::fs::write(&changelog_path, &changelog)
         .with_context(|| format!("Failed to write {}", changelog_path.display()))?;
 
-    // ── Step 6: Commit and push ───────────────────────────────────────────────
-    info!("Committing session on draft branch");
-    git::run_git(repo, &["rm", "-f", ".ink-running"])
-        .with_context(|| "Failed to git rm .ink-running")?;
-    git::run_git(repo, &["add", "-A"]).with_context(|| "Failed to git add session files")?;
-    git::run_git(repo, &["commit", "-m", "session: write prose"])
-        .with_context(|| "Failed to commit session files")?;
-    git::run_git(repo, &["push", "origin", "draft"]).with_context(|| "Failed to push draft")?;
-
-    info!("Fast-forward merging draft into main and pushing");
-    git::run_git(repo, &["checkout", "main"]).with_context(|| "Failed to checkout main")?;
-    git::run_git(repo, &["merge", "--ff-only", "draft"])
-        .with_context(|| "Failed to fast-forward merge draft into main")?;
-    git::run_git(repo, &["push", "origin", "main"]).with_context(|| "Failed to push main")?;
-
     let completion_ready = total_word_count >= (config.target_length as f64 * 0.9) as u32;
+    let status = if pending_file.is_some() {
+        "pending_approval"
+    } else {
+        "closed"
+    };
 
-    Ok(ClosePayload {
+    let mut payload = serde_json::to_value(ClosePayload {
         session_word_count,
         expected_words_per_session: config.words_per_session,
         total_word_count,
         target_length: config.target_length,
         completion_ready,
         current_chapter_word_count: state.current_chapter_word_count,
-        status: "closed",
+        total_pages: config.words_to_pages(total_word_count),
+        reading_minutes: config.reading_minutes(total_word_count),
+        average_chapter_words: average_chapter_words(total_word_count, &state),
+        replacements_applied,
+        marker_issues,
+        constraint_violations,
+        pov_warnings,
+        pending_file,
+        beats,
+        timeline_events: timeline_entries,
+        glossary_added,
+        glossary_warnings,
+        continuity_warnings,
+        readability,
+        spelling_warnings,
+        quota,
+        truncation_warnings,
+        status,
+        // Set once the session is published
+        timings: BTreeMap::new(),
+    })?;
+
+    // ── Step 6: Commit and push ───────────────────────────────────────────────
+    // Last checkpoint: once the draft commit exists, finishing the merge and
+    // push is safer than stopping halfway between branches.
+    // The payload is recorded in .ink-state.yml inside the session commit so a
+    // retry after a failed push can replay it instead of writing prose twice.
+    checkpoint("session commit")?;
+    timer.step("commit");
+    state.last_close = Some(LastClose {
+        session_id: session_id.to_string(),
+        prose_hash: hash,
+        payload: payload.clone(),
+        closed_at: Some(chrono::Utc::now()),
+    });
+    state.save(repo)?;
+    if config.obsidian {
+        obsidian::write_index(repo)?;
+    }
+    info!("Committing session on draft branch");
+    git::run_git(repo, &["rm", "-f", ".ink-running"])
+        .with_context(|| "Failed to git rm .ink-running")?;
+    git::run_git(repo, &["add", "-A"]).with_context(|| "Failed to git add session files")?;
+    let chapter = sequence::load_sequence(repo)
+        .map(|seq| sequence::current_unit(&seq, &state).label)
+        .unwrap_or_else(|_| format!("Chapter {}", state.current_chapter));
+    let messages = crate::metadata::with_co_authors(repo, &config.commit_messages);
+    let message = messages.render(
+        &messages.session,
+        &[
+            ("session_id", session_id),
+            ("words", &session_word_count.to_string()),
+            ("chapter", &chapter),
+            ("date", &now.format("%Y-%m-%d").to_string()),
+        ],
+    );
+    git::engine_commit(repo, &["-m", &message])
+        .with_context(|| "Failed to commit session files")?;
+    timer.step("push");
+    publish_session(repo)?;
+    journal::finish(repo);
+    timer.step("mirrors");
+    with_remote_report(repo, &mut payload);
+
+    // Notify once, on the close that crosses the threshold
+    timer.step("notify");
+    let threshold = (config.target_length as f64 * 0.9) as u32;
+    if completion_ready && total_word_count.saturating_sub(merged.words_added) < threshold {
+        notify::send(
+            repo,
+            Event::CompletionReady,
+            &format!(
+                "The book has {} of {} target words. Run `ink-cli complete` to seal it.",
+                total_word_count, config.target_length
+            ),
+        );
+    }
+
+    metrics::session_closed(session_word_count);
+    payload["timings"] = serde_json::json!(timer.timings());
+    Ok(payload)
+}
+
+/// Whether `words` falls outside the `session_quota:` bounds, if any.
+fn check_session_quota(words: u32, config: &Config) -> Option<QuotaIssue> {
+    let quota = config.session_quota.as_ref()?;
+    let expected = config.words_per_session as f64;
+    let min_words = (expected * quota.min_ratio).round() as u32;
+    let max_words = (quota.max_ratio > 0.0).then(|| (expected * quota.max_ratio).round() as u32);
+    let problem = if words < min_words {
+        "under"
+    } else if max_words.is_some_and(|max| words > max) {
+        "over"
+    } else {
+        return None;
+    };
+    Some(QuotaIssue {
+        problem,
+        words,
+        min_words,
+        max_words,
+    })
+}
+
+fn quota_rejects(config: &Config) -> bool {
+    config
+        .session_quota
+        .as_ref()
+        .is_some_and(|q| q.mode == QuotaMode::Reject)
+}
+
+/// `session-close --dry-run`: run every check and compute every change the
+/// close would make — Full_Book.md append and in-place replacements, word
+/// counts, Summary.md entry, new current.md — without writing files or
+/// running git. Lock and session ID are verified the same way.
+pub fn preview_close(
+    repo: &Path,
+    session_id: &str,
+    prose: &str,
+    summary: Option<&str>,
+    force: bool,
+) -> Result<DryRunPayload> {
+    check_session_lock(repo, session_id)?;
+    let prose: &str = &crate::encoding::normalize(prose);
+    let summary = summary.map(crate::encoding::normalize);
+    let summary = summary.as_deref();
+
+    let config = Config::load(repo)?;
+    let state = InkState::load(repo)?;
+    let counter = WordCounter::new(&config);
+    let session_word_count = counter.count(prose);
+
+    let marker_issues = validate_engine_markers(prose);
+    let quota = check_session_quota(session_word_count, &config);
+    let truncation_warnings = crate::truncation::check(prose);
+    let status =
+        if !marker_issues.is_empty() && config.marker_validation == MarkerValidation::Strict {
+            "invalid_markers"
+        } else if quota.is_some() && quota_rejects(&config) {
+            "quota_violation"
+        } else if !truncation_warnings.is_empty() && config.refuse_truncated && !force {
+            "truncated"
+        } else {
+            "ready"
+        };
+    let constraint_violations = constraints::check(
+        &config.constraints,
+        &strip_engine_markers(prose),
+        state.current_chapter,
+    );
+    let pov_warnings = crate::lint::check_outline_pov(repo, &session_outline(repo)?, prose);
+    let glossary_warnings = glossary::check(
+        &glossary::load(repo).unwrap_or_default(),
+        &glossary::documented_words(repo),
+        prose,
+    );
+
+    let current_md_path = repo.join("Review").join("current.md");
+    let old_current = if current_md_path.exists() {
+        let text = std::fs::read_to_string(&current_md_path)
+            .with_context(|| "Failed to read Review/current.md")?;
+        crate::encoding::normalize(&text).into_owned()
+    } else {
+        String::new()
+    };
+    let (validated, pending_opt) = split_current(&old_current);
+
+    let queue = config.require_approval && !validated.trim().is_empty();
+    let existing = storage::read_book(repo)?;
+    let plan = if queue {
+        MergePlan {
+            book: String::new(),
+            appended: String::new(),
+            outcome: MergeOutcome {
+                total_word_count: existing.as_deref().map_or(0, |b| counter.count(b)),
+                ..MergeOutcome::default()
+            },
+        }
+    } else {
+        plan_merge(existing, &validated, &config)
+    };
+
+    let (new_current, carried) = next_current_md(prose, pending_opt.as_deref());
+
+    Ok(DryRunPayload {
+        status,
+        session_word_count,
+        expected_words_per_session: config.words_per_session,
+        full_book_append: plan.appended,
+        replacements_applied: plan.outcome.replacements_applied,
+        words_added: plan.outcome.words_added,
+        total_word_count: plan.outcome.total_word_count,
+        current_chapter_word_count: state.current_chapter_word_count + plan.outcome.words_added,
+        pending_file: queue.then(|| PENDING_PROSE_FILE.to_string()),
+        summary_append: summary_entry(summary, &Local::now(), session_word_count),
+        current_md_word_count: counter.count(&new_current),
+        instructions_carried_forward: carried,
+        marker_issues,
+        constraint_violations,
+        pov_warnings,
+        glossary_warnings,
+        quota,
+        truncation_warnings,
     })
 }
 
+/// Turn a unified diff against Review/current.md into session-close prose.
+///
+/// The patched file must still begin with the validated section (everything
+/// before the first INK instruction) — that prose is about to be moved to
+/// Full_Book.md and a patch that rewrites it is rejected. What follows the
+/// validated section is the engine's output. A pending section left untouched
+/// by the patch is dropped here so the carry-forward guard in `close_session`
+/// re-adds it exactly once.
+pub fn prose_from_patch(repo: &Path, diff: &str) -> Result<String> {
+    let current_md_path = repo.join("Review").join("current.md");
+    let old_current = if current_md_path.exists() {
+        std::fs::read_to_string(&current_md_path)
+            .with_context(|| "Failed to read Review/current.md")?
+    } else {
+        String::new()
+    };
+
+    let patched = crate::patch::apply_unified_diff(&old_current, diff)?;
+    let (validated, pending) = split_current(&old_current);
+
+    let Some(rest) = patched.trim_start().strip_prefix(validated.trim_start()) else {
+        return Err(InkError::InvalidInput(
+            "patch modifies validated prose (text before the first INK instruction) — \
+             only the pending section and new prose may change"
+                .to_string(),
+        )
+        .into());
+    };
+    let rest = rest.trim_start();
+    let rest = match pending {
+        Some(ref p) => rest.strip_prefix(p.trim_end()).unwrap_or(rest),
+        None => rest,
+    };
+    Ok(rest.trim().to_string())
+}
+
+// ─── approve / reject ─────────────────────────────────────────────────────────
+
+/// Two-stage close, second stage: merge Review/pending.md into Full_Book.md,
+/// credit the words to the current chapter, delete the queue, and commit.
+/// Refused while a session is running — the session branch would conflict.
+pub fn approve_pending(repo: &Path) -> Result<serde_json::Value> {
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: "approve".to_string(),
+        }
+        .into());
+    }
+    let pending_path = repo.join(PENDING_PROSE_FILE);
+    if !pending_path.exists() {
+        return Ok(serde_json::json!({ "status": "nothing_pending" }));
+    }
+
+    let config = Config::load(repo)?;
+    let pending = std::fs::read_to_string(&pending_path)
+        .with_context(|| "Failed to read Review/pending.md")?;
+
+    info!("Merging Review/pending.md into Full_Book.md");
+    let merged = merge_into_full_book(repo, &pending, &config)?;
+    let mut state = InkState::load(repo)?;
+    state.current_chapter_word_count += merged.words_added;
+    state.save(repo)?;
+    std::fs::remove_file(&pending_path).with_context(|| "Failed to remove Review/pending.md")?;
+
+    git::run_git(
+        repo,
+        &[
+            "add",
+            "-A",
+            "--",
+            "Current version",
+            ".ink-state.yml",
+            "Review",
+        ],
+    )
+    .with_context(|| "Failed to git add approved prose")?;
+    git::run_git(repo, &["commit", "-m", "approve: merge pending prose"])
+        .with_context(|| "Failed to commit approved prose")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+
+    Ok(serde_json::json!({
+        "status": "approved",
+        "words_added": merged.words_added,
+        "replacements_applied": merged.replacements_applied,
+        "total_word_count": merged.total_word_count,
+        "current_chapter_word_count": state.current_chapter_word_count,
+    }))
+}
+
+/// Two-stage close, discard path: delete Review/pending.md without touching
+/// Full_Book.md. The rejected prose remains recoverable from git history.
+pub fn reject_pending(repo: &Path) -> Result<serde_json::Value> {
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: "reject".to_string(),
+        }
+        .into());
+    }
+    let pending_path = repo.join(PENDING_PROSE_FILE);
+    if !pending_path.exists() {
+        return Ok(serde_json::json!({ "status": "nothing_pending" }));
+    }
+
+    let pending = std::fs::read_to_string(&pending_path)
+        .with_context(|| "Failed to read Review/pending.md")?;
+    let words_discarded = WordCounter::for_repo(repo).count(&strip_engine_markers(&pending));
+
+    info!("Discarding Review/pending.md");
+    git::run_git(repo, &["rm", "-q", "--", PENDING_PROSE_FILE])
+        .with_context(|| "Failed to git rm Review/pending.md")?;
+    git::run_git(repo, &["commit", "-m", "reject: discard pending prose"])
+        .with_context(|| "Failed to commit rejected prose")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+
+    Ok(serde_json::json!({
+        "status": "rejected",
+        "words_discarded": words_discarded,
+    }))
+}
+
 // ─── complete ─────────────────────────────────────────────────────────────────
 
 /// Check for pending author INK instructions in current.md.
 /// Returns `needs_revision` JSON if any found, or finalizes and returns `complete` JSON.
 pub fn complete_session(repo: &Path) -> Result<serde_json::Value> {
+    git::take_network_retries();
+    journal::recover(repo)?;
     let complete_path = repo.join("COMPLETE");
 
     // Guard: COMPLETE must not already exist
     if complete_path.exists() {
-        return Err(anyhow!(
-            "book already complete — COMPLETE marker already exists"
-        ));
+        return Err(InkError::BookComplete.into());
+    }
+
+    // Queued prose would be left out of the finished book
+    if repo.join(PENDING_PROSE_FILE).exists() {
+        return Err(InkError::PendingApproval.into());
     }
 
     // Ensure we're on main
-    git::run_git(repo, &["checkout", "main"])
-        .with_context(|| "Failed to checkout main for complete")?;
+    let branches = git::branches(repo);
+    git::run_git(repo, &["checkout", &branches.main])
+        .with_context(|| format!("Failed to checkout {} for complete", branches.main))?;
 
     // Read current.md
     let current_md_path = repo.join("Review").join("current.md");
@@ -427,26 +1525,29 @@ pub fn complete_session(repo: &Path) -> Result<serde_json::Value> {
     // ── No instructions: finalize the book ───────────────────────────────────
     info!("No pending INK instructions — finalizing book");
 
-    // stripped_content already has INK comments removed; now strip engine markers too.
-    let current_content = strip_engine_markers(&stripped_content);
-
-    // Append entire current.md to Full_Book.md (it's all validated at this point)
+    // Append entire current.md to Full_Book.md (it's all validated at this point).
+    // Reworked passages already in the book are replaced in place first.
     let config = Config::load(repo)?;
-    let book_dir = repo.join("Current version");
-    std::fs::create_dir_all(&book_dir).with_context(|| "Failed to create 'Current version/'")?;
-    let book_path = book_dir.join("Full_Book.md");
-
-    let total_word_count = if !current_content.trim().is_empty() {
-        let (_, new_total) =
-            append_to_full_book(&book_path, &current_content, config.words_per_page)?;
-        new_total
-    } else if book_path.exists() {
-        let content = std::fs::read_to_string(&book_path)
-            .with_context(|| "Failed to read Full_Book.md for word count")?;
-        count_prose_words(&content)
-    } else {
-        0
-    };
+    let mut book_text = storage::read_book(repo)?.unwrap_or_default();
+    let (remainder, replacements_applied) =
+        apply_reworked_replacements(&mut book_text, &stripped_content);
+
+    // stripped_content already has INK comments removed; now strip engine markers too.
+    let current_content = strip_engine_markers(&remainder);
+    if !current_content.trim().is_empty() {
+        if book_text.is_empty() {
+            book_text = format!("{}\n", FULL_BOOK_HEADER);
+        }
+        append_paginated(&mut book_text, &current_content, config.words_per_page);
+    }
+    if replacements_applied > 0 || !current_content.trim().is_empty() {
+        storage::write_book(repo, &book_text)?;
+    }
+    let total_word_count = WordCounter::new(&config).count(&book_text);
+    // Per-chapter storage: the sealed book still gets its Full_Book.md
+    if config.book_storage == BookStorage::Chapters {
+        storage::compile(repo)?;
+    }
 
     // Write completion placeholder to current.md
     let placeholder =
@@ -472,7 +1573,13 @@ pub fn complete_session(repo: &Path) -> Result<serde_json::Value> {
     } else {
         "chapters"
     };
-    let _ = update_readme_chapters(repo, state.current_chapter, None);
+    let chapter_count = Config::load(repo)
+        .map(|c| c.chapter_count)
+        .unwrap_or(state.current_chapter);
+    let book_sequence = sequence::load_sequence(repo).unwrap_or_default();
+    let unit = sequence::current_unit(&book_sequence, &state);
+    let listed = sequence::units_through(&book_sequence, &unit, chapter_count);
+    let _ = update_readme_chapters(repo, &listed, false);
     let _ = update_readme_status(
         repo,
         &format!(
@@ -481,130 +1588,569 @@ pub fn complete_session(repo: &Path) -> Result<serde_json::Value> {
         ),
     );
 
+    // Designated export profile rides along in the final seal. The book is
+    // already sealed on disk, so a failing export is reported, not fatal.
+    let export = config.export_on_complete.as_deref().map(|profile| {
+        crate::export::export_profile(repo, profile).unwrap_or_else(|e| {
+            tracing::warn!("export_on_complete profile '{}' failed: {:#}", profile, e);
+            serde_json::json!({
+                "status": "failed",
+                "profile": profile,
+                "message": format!("{:#}", e),
+            })
+        })
+    });
+
     // Commit and push main + draft so both branches reflect the sealed book
     git::run_git(repo, &["add", "-A"]).with_context(|| "Failed to git add for final seal")?;
-    git::run_git(repo, &["commit", "-m", "book: complete — final seal"])
+    git::engine_commit(repo, &["-m", "book: complete — final seal"])
         .with_context(|| "Failed to commit completion")?;
-    git::run_git(repo, &["push", "origin", "main"])
-        .with_context(|| "Failed to push main for completion")?;
+    git::push_main(repo).with_context(|| "Failed to push main for completion")?;
 
     // Keep draft in sync — best-effort, not fatal if draft never existed
-    if git::run_git(repo, &["show-ref", "--verify", "refs/heads/draft"]).is_ok() {
-        git::run_git(repo, &["branch", "-f", "draft", "main"])
+    if branch_exists(repo, &branches.draft) {
+        git::run_git(repo, &["branch", "-f", &branches.draft, &branches.main])
             .with_context(|| "Failed to fast-forward draft to main")?;
-        if let Err(e) = git::run_git(repo, &["push", "origin", "draft"]) {
+        if let Err(e) = git::run_git(repo, &["push", &branches.remote, &branches.draft]) {
             tracing::warn!("Could not push draft after completion (non-fatal): {}", e);
         }
     }
 
-    Ok(serde_json::json!({
+    // Release of the sealed commit, with the manuscript attached. Like the
+    // export, a failure is reported, not fatal: the book is already pushed.
+    let release = config.release.as_ref().map(|release| {
+        let mut assets = vec!["Current version/Full_Book.md".to_string()];
+        if let Some(output) = export
+            .as_ref()
+            .filter(|e| e["status"] == "exported")
+            .and_then(|e| e["output"].as_str())
+        {
+            assets.push(output.to_string());
+        }
+        assets.extend(release.assets.iter().cloned());
+        let notes = format!(
+            "Complete — {} {}, {} words.",
+            state.current_chapter, chapter_word, total_word_count
+        );
+        crate::release::create_release(repo, release, &notes, &assets).unwrap_or_else(|e| {
+            tracing::warn!("GitHub release {} failed: {:#}", release.tag, e);
+            serde_json::json!({
+                "status": "failed",
+                "tag": release.tag,
+                "message": format!("{:#}", e),
+            })
+        })
+    });
+
+    // Setups never paid off are reported for the author; the book is sealed
+    // either way.
+    let payoff_audit = crate::threads::load(repo)
+        .map(|t| t.audit())
+        .unwrap_or_else(|e| {
+            Some(serde_json::json!({ "status": "failed", "message": format!("{:#}", e) }))
+        });
+
+    let mut result = serde_json::json!({
         "status": "complete",
         "total_word_count": total_word_count,
-    }))
+        "replacements_applied": replacements_applied,
+        "export": export,
+        "release": release,
+        "payoff_audit": payoff_audit,
+    });
+    with_remote_report(repo, &mut result);
+    Ok(result)
 }
 
 // ─── advance-chapter ──────────────────────────────────────────────────────────
 
-/// Advance to the next chapter by updating `.ink-state.yml`.
-/// Returns `needs_chapter_outline` if the next chapter file is missing,
-/// or `advanced` with the new chapter content on success.
-/// Does NOT push — session-close handles all pushes.
-pub fn advance_chapter(repo: &Path) -> Result<serde_json::Value> {
-    let config = Config::load(repo)?;
-    let mut state = InkState::load(repo)?;
+/// Per-unit ledger `advance-chapter --retrospective` appends to; the leading
+/// underscore keeps it out of the book sequence.
+const PROGRESS_LEDGER: &str = "Chapters material/_progress.md";
 
-    let next_chapter = state.current_chapter + 1;
+const PROGRESS_HEADER: &str = "# Chapter Progress\n\n\
+    *One row per finished unit, appended by `advance-chapter --retrospective`.*\n\n\
+    | Unit | Words | Sessions | Started | Finished |\n\
+    |------|------:|---------:|---------|----------|\n";
 
-    if next_chapter > config.chapter_count {
-        return Ok(serde_json::json!({
-            "status": "error",
-            "message": format!("Already at last chapter ({}/{})", state.current_chapter, config.chapter_count),
-        }));
+/// Append the row for `label` to the progress ledger: its words, and the
+/// sessions (Changelog entries) closed since the previous row finished.
+fn append_progress(
+    repo: &Path,
+    label: &str,
+    words: u32,
+    now: &DateTime<Local>,
+) -> Result<serde_json::Value> {
+    const ROW_TIME: &str = "%Y-%m-%d %H:%M";
+    let path = repo.join(PROGRESS_LEDGER);
+    let mut ledger = std::fs::read_to_string(&path).unwrap_or_else(|_| PROGRESS_HEADER.to_string());
+    let since = ledger
+        .lines()
+        .rev()
+        .filter_map(|line| line.trim().trim_matches('|').rsplit('|').next())
+        .find_map(|finished| chrono::NaiveDateTime::parse_from_str(finished.trim(), ROW_TIME).ok());
+    let mut sessions: Vec<chrono::NaiveDateTime> = std::fs::read_dir(repo.join("Changelog"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    let stamp = name.strip_suffix(".md")?.to_string();
+                    chrono::NaiveDateTime::parse_from_str(&stamp, "%Y-%m-%d-%H-%M").ok()
+                })
+                .filter(|at| since.is_none_or(|since| *at > since) && *at <= now.naive_local())
+                .collect()
+        })
+        .unwrap_or_default();
+    sessions.sort();
+    let started = sessions
+        .first()
+        .map_or("—".to_string(), |at| at.format(ROW_TIME).to_string());
+    let finished = now.format(ROW_TIME).to_string();
+
+    if !ledger.ends_with('\n') {
+        ledger.push('\n');
     }
+    ledger.push_str(&format!(
+        "| {} | {} | {} | {} | {} |\n",
+        label,
+        words,
+        sessions.len(),
+        started,
+        finished
+    ));
+    std::fs::write(&path, ledger)
+        .with_context(|| format!("Failed to write {}", PROGRESS_LEDGER))?;
+    Ok(serde_json::json!({
+        "unit": label,
+        "words": words,
+        "sessions": sessions.len(),
+        "started": started,
+        "finished": finished,
+    }))
+}
 
-    // Guard: chapter must have reached ≥ 90 % of words_per_chapter
-    let min_words = (config.words_per_chapter as f64 * 0.9) as u32;
+/// Advance to the next chapter by updating `.ink-state.yml`.
+/// Returns `needs_chapter_outline` if the next chapter file is missing (with
+/// `scaffold_outlines`, one is written instead), or `advanced` with the new
+/// chapter content on success.
+/// With `retrospective`, the finished unit gets a row in the progress ledger.
+/// Pushes only with `push` (best-effort) — otherwise session-close handles it.
+pub fn advance_chapter(repo: &Path, retrospective: bool, push: bool) -> Result<serde_json::Value> {
+    journal::recover(repo)?;
+    let config = Config::load(repo)?;
+    let mut state = InkState::load(repo)?;
+    let book_sequence = sequence::load_sequence(repo)?;
+    let unit = sequence::current_unit(&book_sequence, &state);
+
+    let next = match sequence::next_unit(&book_sequence, &unit, config.chapter_count) {
+        Next::End => {
+            return Ok(serde_json::json!({
+                "status": "error",
+                "message": format!("Already at last chapter ({}/{})", state.current_chapter, config.chapter_count),
+            }));
+        }
+        next => next,
+    };
+
+    // Guard: the unit must have reached ≥ 90 % of its target
+    let target_words = unit.target_words(&config);
+    let min_words = (target_words as f64 * 0.9) as u32;
     if state.current_chapter_word_count < min_words {
         return Ok(serde_json::json!({
             "status": "chapter_not_ready",
             "current_word_count": state.current_chapter_word_count,
-            "target_word_count": config.words_per_chapter,
+            "target_word_count": target_words,
             "min_words_to_advance": min_words,
         }));
     }
 
-    let chapter_filename = format!("Chapter_{:02}.md", next_chapter);
-    let chapter_rel = format!("Chapters material/{}", chapter_filename);
-    let chapter_path = repo.join(&chapter_rel);
-
-    if !chapter_path.exists() {
-        return Ok(serde_json::json!({
-            "status": "needs_chapter_outline",
-            "chapter": next_chapter,
-            "chapter_file": chapter_rel,
-        }));
-    }
+    let mut scaffolded = false;
+    let next_unit = match next {
+        Next::Unit(u) => u,
+        Next::MissingChapter(n) if config.scaffold_outlines => {
+            crate::chapters::scaffold_outline(repo, n, config.chapter_count)?;
+            scaffolded = true;
+            Unit::chapter(n)
+        }
+        Next::MissingChapter(n) => {
+            return Ok(serde_json::json!({
+                "status": "needs_chapter_outline",
+                "chapter": n,
+                "chapter_file": Unit::chapter(n).path(),
+            }));
+        }
+        Next::End => unreachable!(),
+    };
 
-    let chapter_content = std::fs::read_to_string(&chapter_path)
+    let chapter_rel = next_unit.path();
+    let chapter_content = std::fs::read_to_string(repo.join(&chapter_rel))
         .with_context(|| format!("Failed to read {}", chapter_rel))?;
 
     // Advance state
-    state.current_chapter = next_chapter;
+    let finished_words = state.current_chapter_word_count;
+    let now = Local::now();
+    let progress = match retrospective {
+        true => Some(append_progress(repo, &unit.label, finished_words, &now)?),
+        false => None,
+    };
+    sequence::set_current(&mut state, &next_unit, config.chapter_count);
     state.current_chapter_word_count = 0;
     state.save(repo)?;
 
-    // Update README: mark previous chapter ✓, new chapter in progress
-    update_readme_chapters(repo, next_chapter - 1, Some(next_chapter))?;
-    update_readme_status(repo, &format!("In progress — Chapter {}", next_chapter))?;
+    // Update README: mark previous units ✓, new unit in progress
+    let listed = sequence::units_through(&book_sequence, &next_unit, config.chapter_count);
+    update_readme_chapters(repo, &listed, true)?;
+    update_readme_status(repo, &format!("In progress — {}", next_unit.label))?;
 
-    // Commit the state update (and chapter file + README if present)
+    // Commit the state update (and outline file + README if present)
     let readme_exists = repo.join("README.md").exists();
     let mut add_args = vec!["add", ".ink-state.yml", &chapter_rel];
     if readme_exists {
         add_args.push("README.md");
     }
+    if progress.is_some() {
+        add_args.push(PROGRESS_LEDGER);
+    }
     git::run_git(repo, &add_args).with_context(|| "Failed to git add for chapter advance")?;
-    git::run_git(
-        repo,
+    // "chapter: advance to chapter 4" as before; other units keep their label
+    let subject = match next_unit.kind {
+        UnitKind::Chapter => next_unit.label.to_lowercase(),
+        _ => next_unit.label.clone(),
+    };
+    let session_id = crate::context::read_lock(repo)
+        .and_then(|l| l.session_id)
+        .unwrap_or_default();
+    let messages = crate::metadata::with_co_authors(repo, &config.commit_messages);
+    let message = messages.render(
+        &messages.chapter_advance,
         &[
-            "commit",
-            "-m",
-            &format!("chapter: advance to chapter {}", next_chapter),
+            ("session_id", &session_id),
+            ("words", &finished_words.to_string()),
+            ("chapter", &subject),
+            ("date", &now.format("%Y-%m-%d").to_string()),
         ],
-    )
-    .with_context(|| "Failed to commit chapter advance")?;
+    );
+    git::engine_commit(repo, &["-m", &message])
+        .with_context(|| "Failed to commit chapter advance")?;
 
-    info!("Advanced to chapter {}", next_chapter);
+    info!("Advanced to {}", next_unit.label);
 
-    Ok(serde_json::json!({
+    let mut result = serde_json::json!({
         "status": "advanced",
-        "new_chapter": next_chapter,
+        "new_chapter": state.current_chapter,
+        "unit": next_unit,
         "chapter_file": chapter_rel,
         "chapter_content": chapter_content,
+        "outline_scaffolded": scaffolded,
+    });
+    if let Some(progress) = progress {
+        result["progress"] = progress;
+    }
+    if push {
+        // Mid-session the commit sits on draft, which heartbeats push with a lease
+        let branches = git::branches(repo);
+        let head = git::run_git(repo, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+        let pushed = match head == branches.draft {
+            true => git::run_git(
+                repo,
+                &[
+                    "push",
+                    "--force-with-lease",
+                    &branches.remote,
+                    &branches.draft,
+                ],
+            ),
+            false => git::push_main(repo),
+        };
+        result["pushed"] = serde_json::json!(pushed.is_ok());
+        if let Err(e) = pushed {
+            tracing::warn!("git push skipped: {}", e);
+            result["push_error"] = serde_json::json!(format!("{:#}", e));
+        }
+    }
+    Ok(result)
+}
+
+// ─── kill ─────────────────────────────────────────────────────────────────────
+
+/// Seconds between origin polls in `kill --wait`.
+const KILL_POLL_SECONDS: u64 = 15;
+
+/// Request that the engine stop: write `.ink-kill`, commit it on main, push.
+/// The next `session-open` (the cron run, or one already starting) cancels
+/// itself and removes the file in a "chore: acknowledge kill request" commit.
+///
+/// With `wait`, origin is polled until that acknowledgement lands (status
+/// `acknowledged`) or `timeout_minutes` pass (status `timeout`).
+pub fn request_kill(repo: &Path, wait: bool, timeout_minutes: u64) -> Result<serde_json::Value> {
+    // Locked only while writing: the wait below is for a session-open that may
+    // run on this very working tree.
+    let command_lock = crate::cmdlock::acquire(repo, "kill")?;
+    git::preflight_fetch_and_checkout(repo)?;
+    git::merge_ff_origin_main(repo)?;
+
+    let already_requested = repo.join(".ink-kill").exists();
+    if !already_requested {
+        std::fs::write(
+            repo.join(".ink-kill"),
+            format!("requested_at: {}\n", Utc::now().to_rfc3339()),
+        )
+        .with_context(|| "Failed to write .ink-kill")?;
+        git::run_git(repo, &["add", ".ink-kill"]).with_context(|| "Failed to git add .ink-kill")?;
+        git::run_git(repo, &["commit", "-m", "chore: request kill"])
+            .with_context(|| "Failed to commit .ink-kill")?;
+        git::push_main(repo).with_context(|| "Failed to push .ink-kill")?;
+        info!("Kill requested");
+    }
+    let session_active = repo.join(".ink-running").exists();
+    drop(command_lock);
+
+    if !wait {
+        return Ok(serde_json::json!({
+            "status": if already_requested { "already_requested" } else { "kill_requested" },
+            "session_active": session_active,
+        }));
+    }
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_minutes * 60);
+    info!("Waiting for the engine to acknowledge the kill request...");
+    loop {
+        signals::checkpoint("kill acknowledgement")?;
+        let branches = git::branches(repo);
+        git::run_git(repo, &["fetch", &branches.remote])
+            .with_context(|| format!("Failed to fetch from {}", branches.remote))?;
+        let origin_main = branches.remote_main();
+        if !git::file_exists_at(repo, &origin_main, ".ink-kill") {
+            // Bring the author's clone up to date; best-effort
+            if let Err(e) = crate::cmdlock::acquire(repo, "kill")
+                .and_then(|_lock| git::merge_ff_origin_main(repo))
+            {
+                tracing::warn!("Could not fast-forward main after acknowledgement: {}", e);
+            }
+            return Ok(serde_json::json!({
+                "status": "acknowledged",
+                "session_active": git::file_exists_at(repo, &origin_main, ".ink-running"),
+            }));
+        }
+        if std::time::Instant::now() >= deadline {
+            return Ok(serde_json::json!({
+                "status": "timeout",
+                "message": format!(
+                    "no acknowledgement after {} minute(s) — .ink-kill stays on main and the next session-open will honour it",
+                    timeout_minutes
+                ),
+            }));
+        }
+        std::thread::sleep(std::time::Duration::from_secs(KILL_POLL_SECONDS));
+    }
+}
+
+// ─── unlock ───────────────────────────────────────────────────────────────────
+
+/// Force-release the session lock instead of waiting for the stale-lock
+/// timeout: show the lock's age, confirm (skipped with `yes`), then
+/// `git rm .ink-running`, commit, and push main.
+///
+/// Prompts go to stderr so stdout stays JSON. Without a terminal, `yes` is
+/// required.
+pub fn unlock_session(repo: &Path, yes: bool) -> Result<serde_json::Value> {
+    journal::recover(repo)?;
+    git::preflight_fetch_and_checkout(repo)?;
+    git::merge_ff_origin_main(repo)?;
+
+    let Some(lock) = crate::context::read_live_lock(repo) else {
+        return Ok(serde_json::json!({ "status": "not_locked" }));
+    };
+    let age_minutes = Utc::now()
+        .signed_duration_since(lock.started_at)
+        .num_minutes();
+    let idle_minutes = lock.idle_minutes();
+    let timeout = Config::load(repo)
+        .map(|c| c.session_timeout_minutes)
+        .unwrap_or(60);
+    let stale = idle_minutes > timeout;
+
+    if !yes {
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            return Err(InkError::InvalidInput(
+                "refusing to unlock without confirmation — pass --yes when running non-interactively"
+                    .to_string(),
+            )
+            .into());
+        }
+        eprintln!(
+            "\n  Session lock   : {}",
+            lock.session_id.as_deref().unwrap_or("(no session id)")
+        );
+        eprintln!(
+            "  Started        : {} ({} min ago)",
+            lock.started_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            age_minutes
+        );
+        eprintln!(
+            "  Last heartbeat : {} min ago (timeout {} min{})",
+            idle_minutes,
+            timeout,
+            if stale { ", stale" } else { "" }
+        );
+        if !stale {
+            eprintln!("\n  The session may still be running. Unlocking lets another");
+            eprintln!("  session start on top of it.");
+        }
+        let confirmed = Confirm::new("Release the session lock?")
+            .with_default(false)
+            .prompt()
+            .with_context(|| "Failed to read confirmation")?;
+        if !confirmed {
+            return Ok(serde_json::json!({ "status": "cancelled" }));
+        }
+    }
+
+    git::run_git(repo, &["rm", "-q", "--ignore-unmatch", ".ink-running"])
+        .with_context(|| "Failed to git rm .ink-running")?;
+    // An untracked lock (never pushed) is removed from disk only
+    if repo.join(".ink-running").exists() {
+        std::fs::remove_file(repo.join(".ink-running"))
+            .with_context(|| "Failed to remove .ink-running")?;
+    }
+    if !git::run_git(repo, &["diff", "--cached", "--name-only"])?.is_empty() {
+        git::run_git(repo, &["commit", "-m", "chore: force-release session lock"])
+            .with_context(|| "Failed to commit lock release")?;
+        git::push_main(repo).with_context(|| "Failed to push lock release")?;
+    }
+    info!("Session lock released ({} min old)", age_minutes);
+
+    Ok(serde_json::json!({
+        "status": "unlocked",
+        "session_id": lock.session_id,
+        "age_minutes": age_minutes,
+        "idle_minutes": idle_minutes,
+        "stale": stale,
     }))
 }
 
 // ─── status ───────────────────────────────────────────────────────────────────
 
+/// `status --ping`: fixed-shape, cheap enough for high-frequency polling.
+#[derive(Debug, Serialize)]
+pub struct PingPayload {
+    pub chapter: u32,
+    /// Full_Book.md words as a percentage of target_length, capped at 100.
+    pub pct_complete: u8,
+    /// A session lock (.ink-running) exists.
+    pub lock: bool,
+    /// When the last session-close committed, if known.
+    pub last_session_at: Option<DateTime<Utc>>,
+    /// `ok`, `stale_lock`, `interrupted_close`, or `uninitialized`.
+    pub health: &'static str,
+}
+
+/// Derived values `status --ping` would otherwise recompute from
+/// .ink-state.yml, Config.yml, and Full_Book.md on every poll.
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusCache {
+    chapter: u32,
+    pct_complete: u8,
+    last_session_at: Option<DateTime<Utc>>,
+    session_timeout_minutes: i64,
+}
+
+const STATUS_CACHE_FILE: &str = ".ink/status.json";
+
+fn modified(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The status cache, rebuilt when any of its source files is newer than it.
+/// Rebuilding is the slow path (word count of Full_Book.md); writing the cache
+/// is best-effort.
+fn load_status_cache(repo: &Path) -> Result<StatusCache> {
+    let cache_path = repo.join(STATUS_CACHE_FILE);
+    let mut sources = vec![
+        repo.join(".ink-state.yml"),
+        repo.join("Global Material").join("Config.yml"),
+    ];
+    sources.extend(storage::book_files(repo)?.iter().map(|rel| repo.join(rel)));
+    if let Some(cached_at) = modified(&cache_path) {
+        if sources
+            .iter()
+            .all(|p| modified(p).is_none_or(|m| m <= cached_at))
+        {
+            if let Some(cache) = std::fs::read_to_string(&cache_path)
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+            {
+                return Ok(cache);
+            }
+        }
+    }
+
+    let state = InkState::load(repo)?;
+    let config = Config::load(repo)?;
+    let total = current_book_word_count(repo)?;
+    let cache = StatusCache {
+        chapter: state.current_chapter,
+        pct_complete: (total as u64 * 100 / config.target_length.max(1) as u64).min(100) as u8,
+        last_session_at: state.last_close.and_then(|c| c.closed_at),
+        session_timeout_minutes: config.session_timeout_minutes,
+    };
+    if journal::ensure_ink_dir(repo).is_ok() {
+        if let Ok(json) = serde_json::to_string(&cache) {
+            let _ = std::fs::write(&cache_path, json);
+        }
+    }
+    Ok(cache)
+}
+
+/// Tiny status for dashboards: a few stats plus one small cached JSON file in
+/// the common case. Never touches git.
+pub fn status_ping(repo: &Path) -> Result<PingPayload> {
+    if !repo.join("Global Material").join("Config.yml").exists() {
+        return Ok(PingPayload {
+            chapter: 0,
+            pct_complete: 0,
+            lock: false,
+            last_session_at: None,
+            health: "uninitialized",
+        });
+    }
+    let cache = load_status_cache(repo)?;
+    let lock = repo.join(".ink-running").exists();
+    let health = if journal::is_pending(repo) {
+        "interrupted_close"
+    } else if lock
+        && crate::context::read_lock_age(repo)
+            .is_some_and(|age| age > cache.session_timeout_minutes)
+    {
+        "stale_lock"
+    } else {
+        "ok"
+    };
+    Ok(PingPayload {
+        chapter: cache.chapter,
+        pct_complete: cache.pct_complete,
+        lock,
+        last_session_at: cache.last_session_at,
+        health,
+    })
+}
+
 /// Return a lightweight read-only JSON snapshot of the book's current state.
 /// Reads only local files — no git operations, no network.
 pub fn book_status(repo: &Path) -> Result<serde_json::Value> {
     let state = InkState::load(repo)?;
     let config = Config::load(repo).ok();
 
-    let book_path = repo.join("Current version").join("Full_Book.md");
-    let total_word_count = if book_path.exists() {
-        let content =
-            std::fs::read_to_string(&book_path).with_context(|| "Failed to read Full_Book.md")?;
-        count_prose_words(&content)
-    } else {
-        0
-    };
+    let total_word_count = current_book_word_count(repo)?;
 
     let lock_path = repo.join(".ink-running");
     let lock_age_seconds = crate::context::read_lock_age(repo);
     let complete = repo.join("COMPLETE").exists();
     let initialized = repo.join("Global Material").join("Config.yml").exists();
+    let unit = sequence::current_unit(&sequence::load_sequence(repo)?, &state);
 
     let (
         target_length,
@@ -612,43 +2158,209 @@ pub fn book_status(repo: &Path) -> Result<serde_json::Value> {
         words_per_session,
         chapter_close_suggested,
         completion_ready,
+        total_pages,
+        target_pages,
+        reading_minutes,
     ) = match &config {
         Some(c) => (
             c.target_length,
             c.words_per_chapter,
             c.words_per_session,
-            state.current_chapter_word_count >= (c.words_per_chapter as f64 * 0.9) as u32,
+            state.current_chapter_word_count >= (unit.target_words(c) as f64 * 0.9) as u32,
             total_word_count >= (c.target_length as f64 * 0.9) as u32,
+            c.words_to_pages(total_word_count),
+            c.words_to_pages(c.target_length),
+            c.reading_minutes(total_word_count),
         ),
-        None => (0, 0, 0, false, false),
+        None => (0, 0, 0, false, false, 0, 0, 0),
     };
 
     Ok(serde_json::json!({
         "initialized": initialized,
         "complete": complete,
         "current_chapter": state.current_chapter,
+        "current_unit": unit.label,
+        "current_part": config.as_ref().and_then(|c| c.part_of(state.current_chapter)),
         "current_chapter_word_count": state.current_chapter_word_count,
         "words_per_chapter": words_per_chapter,
+        "beats": crate::beats::coverage(repo, &unit.path())?,
+        "unit_target_words": config.as_ref().map_or(0, |c| unit.target_words(c)),
         "chapter_close_suggested": chapter_close_suggested,
         "total_word_count": total_word_count,
         "target_length": target_length,
+        "total_pages": total_pages,
+        "target_pages": target_pages,
+        "reading_minutes": reading_minutes,
+        "average_chapter_words": average_chapter_words(total_word_count, &state),
         "words_per_session": words_per_session,
         "completion_ready": completion_ready,
         "session_active": lock_path.exists(),
         "session_age_seconds": lock_age_seconds,
+        "lock": crate::context::read_lock(repo).map(|l| l.holder()),
+        "pending_approval": repo.join(PENDING_PROSE_FILE).exists(),
     }))
 }
 
 // ─── doctor ───────────────────────────────────────────────────────────────────
 
+/// What is wrong with where HEAD stands, if anything. ink expects main — or
+/// draft while a session runs — and no rebase or merge left half-done.
+fn head_problem(repo: &Path, session_live: bool) -> Option<String> {
+    let branches = git::branches(repo);
+    if let Some(op) = git::operation_in_progress(repo) {
+        return Some(format!("a git {} was left unfinished", op));
+    }
+    match git::run_git(repo, &["symbolic-ref", "-q", "--short", "HEAD"]) {
+        Err(_) => Some(format!(
+            "detached HEAD at {}",
+            git::run_git(repo, &["rev-parse", "--short", "HEAD"]).unwrap_or_default()
+        )),
+        Ok(branch) if branch == branches.main => None,
+        Ok(branch) if branch == branches.draft && session_live => None,
+        Ok(branch) => Some(format!("on {} instead of {}", branch, branches.main)),
+    }
+}
+
+/// Commits reachable from `rev` that no other branch or remote-tracking
+/// branch holds — lost if `rev` moves. `own_branch` is left out of the search.
+fn unsaved_commits(repo: &Path, rev: &str, own_branch: Option<&str>) -> u32 {
+    let exclude = own_branch.map(|b| format!("--exclude={}", b));
+    let mut args = vec!["rev-list", "--count", rev, "--not"];
+    args.extend(exclude.as_deref());
+    args.extend(["--branches", "--remotes"]);
+    git::run_git(repo, &args)
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Commits on draft that main lacks, outside a session — left by one that
+/// died before session-close merged it. None when a journaled close is still
+/// to be recovered: those commits are its prose.
+fn abandoned_draft_commits(repo: &Path) -> Option<u32> {
+    let branches = git::branches(repo);
+    if !branch_exists(repo, &branches.draft) || !matches!(journal::pending(repo), Ok(None)) {
+        return None;
+    }
+    git::run_git(
+        repo,
+        &[
+            "rev-list",
+            "--count",
+            &format!("{}..{}", branches.main, branches.draft),
+        ],
+    )
+    .ok()
+    .and_then(|n| n.parse().ok())
+    .filter(|n| *n > 0)
+}
+
+/// Keep `rev` reachable on a new `ink-rescue-<time>-<what>` branch.
+fn rescue_branch(repo: &Path, rev: &str, what: &str) -> Result<String> {
+    let name = format!(
+        "ink-rescue-{}-{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        what
+    );
+    git::run_git(repo, &["branch", &name, rev])
+        .with_context(|| format!("Failed to create rescue branch {}", name))?;
+    Ok(name)
+}
+
+/// `doctor --fix`: finish what a failed rebase or merge left behind — abort
+/// the operation, stash stray changes, and return to main — then reset an
+/// abandoned draft to main. Commits no other branch holds are kept on a
+/// rescue branch first, so nothing is lost. Returns what was done.
+fn repair_head(repo: &Path) -> Result<Vec<String>> {
+    let branches = git::branches(repo);
+    let mut fixed = Vec::new();
+    if let Some(op) = git::operation_in_progress(repo) {
+        git::run_git(repo, &[op, "--abort"])
+            .with_context(|| format!("Failed to abort the unfinished {}", op))?;
+        fixed.push(format!("aborted the unfinished git {}", op));
+    }
+
+    let branch = git::run_git(repo, &["symbolic-ref", "-q", "--short", "HEAD"]).ok();
+    if branch.as_deref() != Some(branches.main.as_str()) {
+        let was = match &branch {
+            Some(b) => b.clone(),
+            None => format!(
+                "detached HEAD at {}",
+                git::run_git(repo, &["rev-parse", "--short", "HEAD"])?
+            ),
+        };
+        if !git::collect_modified_files(repo)?.is_empty() {
+            git::run_git(
+                repo,
+                &[
+                    "stash",
+                    "push",
+                    "--include-untracked",
+                    "-m",
+                    &format!("ink doctor --fix: changes left on {}", was),
+                ],
+            )?;
+            fixed.push(format!(
+                "stashed uncommitted changes left on {} (git stash list)",
+                was
+            ));
+        }
+        if branch.is_none() && unsaved_commits(repo, "HEAD", None) > 0 {
+            let rescue = rescue_branch(repo, "HEAD", "head")?;
+            fixed.push(format!("kept the detached commits on {}", rescue));
+        }
+        git::run_git(repo, &["checkout", &branches.main])
+            .with_context(|| format!("Failed to checkout {}", branches.main))?;
+        fixed.push(format!("checked out {} (was {})", branches.main, was));
+    }
+
+    if let Some(count) = abandoned_draft_commits(repo) {
+        if unsaved_commits(repo, &branches.draft, Some(&branches.draft)) > 0 {
+            let rescue = rescue_branch(repo, &branches.draft, &branches.draft)?;
+            fixed.push(format!(
+                "kept the abandoned {} on {}",
+                branches.draft, rescue
+            ));
+        }
+        git::run_git(repo, &["branch", "-f", &branches.draft, &branches.main])
+            .with_context(|| format!("Failed to reset {}", branches.draft))?;
+        fixed.push(format!(
+            "reset {} to {} ({} abandoned commit(s))",
+            branches.draft, branches.main, count
+        ));
+    }
+    Ok(fixed)
+}
+
 /// Validate the book repository structure and return a list of issues.
-/// Checks file presence, Config.yml validity, git remote, draft branch, and lock state.
+/// Checks file presence, Config.yml validity, git remote, branch, draft, and lock state.
 /// Note: the `git_remote_reachable` check makes a network call and may be slow on an
-/// unreachable remote — all other checks are local-only.
-pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
+/// unreachable remote — all other checks are local-only. With `fix`, first repairs
+/// a wrong branch, detached HEAD, or abandoned draft (see `repair_head`) unless a
+/// session is running or an interrupted close awaits recovery.
+pub fn doctor(repo: &Path, fix: bool) -> Result<serde_json::Value> {
     let mut checks: Vec<serde_json::Value> = Vec::new();
     let mut all_ok = true;
 
+    let session_timeout = Config::load(repo)
+        .map(|c| c.session_timeout_minutes)
+        .unwrap_or(60);
+    // Same rule as the session_lock check below: a lock is live until it goes stale
+    let session_live = repo.join(".ink-running").exists()
+        && crate::context::read_lock_age(repo).is_none_or(|age| age <= session_timeout);
+    let mut repair = serde_json::Value::Null;
+    if fix {
+        repair = if session_live {
+            serde_json::json!({ "skipped": "a session is in progress — its lock is live" })
+        } else if !matches!(journal::pending(repo), Ok(None)) {
+            serde_json::json!({
+                "skipped": "an interrupted session-close awaits recovery — run session-open first"
+            })
+        } else {
+            serde_json::json!({ "fixed": repair_head(repo)? })
+        };
+    }
+
     macro_rules! check {
         ($name:expr, $ok:expr, $detail:expr) => {{
             let ok: bool = $ok;
@@ -689,7 +2401,9 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
 
             // ── Current chapter outline exists ────────────────────────────
             let state = InkState::load(repo).unwrap_or_default();
-            let chapter_file = format!("Chapters material/Chapter_{:02}.md", state.current_chapter);
+            let chapter_file = sequence::load_sequence(repo)
+                .map(|seq| sequence::current_unit(&seq, &state).path())
+                .unwrap_or_else(|_| Unit::chapter(state.current_chapter).path());
             let chapter_path = repo.join(&chapter_file);
             check!(
                 "current_chapter_outline",
@@ -701,6 +2415,18 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
                 }
             );
 
+            // ── Agent-derived value lint ──────────────────────────────────
+            let issues = cfg.lint();
+            check!(
+                "config_lint",
+                issues.is_empty(),
+                if issues.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::json!(issues)
+                }
+            );
+
             // ── Words-per-session sanity ──────────────────────────────────
             let sane = cfg.words_per_session >= 100 && cfg.words_per_session <= 10_000;
             check!(
@@ -715,6 +2441,56 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
                     ))
                 }
             );
+
+            // ── Chapter titles, once the book nears completion ────────────
+            let near_end = current_book_word_count(repo)
+                .is_ok_and(|words| words >= (cfg.target_length as f64 * 0.9) as u32);
+            match crate::titles::load(repo) {
+                Ok(titles) => {
+                    let untitled = match near_end {
+                        true => crate::titles::untitled(&titles, cfg.chapter_count),
+                        false => vec![],
+                    };
+                    check!(
+                        "chapter_titles",
+                        untitled.is_empty(),
+                        if untitled.is_empty() {
+                            serde_json::Value::Null
+                        } else {
+                            serde_json::json!(format!(
+                                "no title in {} for chapter(s) {}",
+                                crate::titles::TITLES_FILE,
+                                untitled
+                                    .iter()
+                                    .map(u32::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ))
+                        }
+                    );
+                }
+                Err(e) => check!(
+                    "chapter_titles",
+                    false,
+                    serde_json::json!(format!("{:#}", e))
+                ),
+            }
+
+            // ── Series canon: local sections redefining shared ones ───────
+            if let Some(series) = &cfg.series {
+                match crate::series::contradictions(repo, series) {
+                    Ok(found) => check!(
+                        "series_canon",
+                        found.is_empty(),
+                        if found.is_empty() {
+                            serde_json::Value::Null
+                        } else {
+                            serde_json::json!(found)
+                        }
+                    ),
+                    Err(e) => check!("series_canon", false, serde_json::json!(format!("{:#}", e))),
+                }
+            }
         }
         Err(e) => {
             check!("config_valid", false, serde_json::json!(e.to_string()));
@@ -722,6 +2498,57 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
         }
     }
 
+    // ── Metadata.yml: parses, a valid ISBN, the cover image present ──────────
+    if repo.join(crate::metadata::METADATA_FILE).exists() {
+        match crate::metadata::load(repo) {
+            Ok(metadata) => {
+                let problems = crate::metadata::problems(repo, &metadata.unwrap_or_default());
+                check!(
+                    "metadata",
+                    problems.is_empty(),
+                    if problems.is_empty() {
+                        serde_json::Value::Null
+                    } else {
+                        serde_json::json!(problems.join("; "))
+                    }
+                );
+            }
+            Err(e) => check!("metadata", false, serde_json::json!(format!("{:#}", e))),
+        }
+    }
+
+    // ── Continuity: speakers, chapter headings, Summary.md references ────────
+    match crate::continuity::check(repo) {
+        Ok(issues) => {
+            for kind in ["character_names", "chapter_headings", "summary_chapters"] {
+                let found: Vec<&crate::continuity::ContinuityIssue> =
+                    issues.iter().filter(|i| i.check == kind).collect();
+                check!(
+                    format!("continuity_{}", kind),
+                    found.is_empty(),
+                    if found.is_empty() {
+                        serde_json::Value::Null
+                    } else {
+                        serde_json::json!(found)
+                    }
+                );
+            }
+        }
+        Err(e) => check!("continuity", false, serde_json::json!(format!("{:#}", e))),
+    }
+
+    // ── Text encodings: UTF-8, NFC, LF ───────────────────────────────────────
+    let encoding_issues = crate::encoding::scan(repo);
+    check!(
+        "text_encoding",
+        encoding_issues.is_empty(),
+        if encoding_issues.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::json!(encoding_issues)
+        }
+    );
+
     // ── Review/current.md ────────────────────────────────────────────────────
     let current_md = repo.join("Review").join("current.md");
     check!(
@@ -735,7 +2562,8 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
     );
 
     // ── Git remote configured ─────────────────────────────────────────────────
-    let remote_url = git::run_git(repo, &["remote", "get-url", "origin"]);
+    let remote = git::branches(repo).remote;
+    let remote_url = git::run_git(repo, &["remote", "get-url", &remote]);
     check!(
         "git_remote_configured",
         remote_url.is_ok(),
@@ -747,7 +2575,7 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
 
     // ── Git remote reachable (network call) ───────────────────────────────────
     if remote_url.is_ok() {
-        match git::run_git(repo, &["ls-remote", "--exit-code", "--heads", "origin"]) {
+        match git::run_git(repo, &["ls-remote", "--exit-code", "--heads", &remote]) {
             Ok(_) => check!("git_remote_reachable", true, serde_json::Value::Null),
             Err(e) => check!(
                 "git_remote_reachable",
@@ -757,17 +2585,102 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
         }
     }
 
+    // ── Shallow / partial clone ───────────────────────────────────────────────
+    // Not a failure: session-open deepens a shallow clone itself, and git
+    // fetches a partial clone's missing objects on demand.
+    let clone_note = if git::is_shallow(repo) {
+        serde_json::json!(
+            "shallow clone — the next session-open fetches the full history and tags \
+             (git fetch --unshallow --tags) before touching branches"
+        )
+    } else if let Some(filter) = git::partial_clone_filter(repo, &remote) {
+        serde_json::json!(format!(
+            "partial clone (filter {}) — missing objects are fetched on demand, so \
+             the remote must stay reachable",
+            filter
+        ))
+    } else {
+        serde_json::Value::Null
+    };
+    check!("clone_depth", true, clone_note);
+
+    // ── Git LFS available when Assets/ holds files ───────────────────────────
+    if has_assets(&repo.join(crate::init::ASSETS_DIR)) {
+        let lfs = std::process::Command::new("git")
+            .args(["lfs", "version"])
+            .current_dir(repo)
+            .output()
+            .is_ok_and(|o| o.status.success());
+        let tracked = git::run_git(repo, &["check-attr", "filter", "--", "Assets/cover.png"])
+            .is_ok_and(|out| out.ends_with(": lfs"));
+        check!(
+            "git_lfs",
+            lfs && tracked,
+            match (lfs, tracked) {
+                (true, true) => serde_json::Value::Null,
+                (false, _) => serde_json::json!(
+                    "Assets/ holds files but git-lfs is not installed — they are committed \
+                     as regular blobs; install git-lfs and run `git lfs install`"
+                ),
+                (true, false) => serde_json::json!(
+                    "Assets/ is not routed through LFS — add \
+                     `Assets/** filter=lfs diff=lfs merge=lfs -text` to .gitattributes"
+                ),
+            }
+        );
+    }
+
+    // ── Commit signing works (when enabled) ──────────────────────────────────
+    if crate::config::read_git_settings(repo).sign_commits {
+        match git::check_signing(repo) {
+            Ok(()) => check!("commit_signing", true, serde_json::Value::Null),
+            Err(e) => check!(
+                "commit_signing",
+                false,
+                serde_json::json!(format!(
+                    "sign_commits is on but a test signature failed — check signing_key / \
+                     signing_format or git's user.signingkey: {}",
+                    e
+                ))
+            ),
+        }
+    }
+
+    // ── HEAD on main (draft during a session), nothing half-done ─────────────
+    let head = head_problem(repo, session_live);
+    check!(
+        "head_branch",
+        head.is_none(),
+        match head {
+            Some(problem) => serde_json::json!(format!(
+                "{} — session-open expects a clean {}; run doctor --fix",
+                problem,
+                git::branches(repo).main
+            )),
+            None => serde_json::Value::Null,
+        }
+    );
+
     // ── Draft branch exists locally ───────────────────────────────────────────
-    let draft_exists = git::run_git(repo, &["show-ref", "--verify", "refs/heads/draft"]).is_ok();
+    let draft = git::branches(repo).draft;
+    let draft_exists = branch_exists(repo, &draft);
+    let abandoned = (!session_live)
+        .then(|| abandoned_draft_commits(repo))
+        .flatten();
     check!(
         "draft_branch",
-        draft_exists,
-        if draft_exists {
-            serde_json::Value::Null
-        } else {
-            serde_json::json!(
-                "draft branch not found locally — will be created at next session-open"
-            )
+        draft_exists && abandoned.is_none(),
+        match abandoned {
+            _ if !draft_exists => serde_json::json!(format!(
+                "{} branch not found locally — will be created at next session-open",
+                draft
+            )),
+            Some(count) => serde_json::json!(format!(
+                "{} has {} commit(s) not on main, left by an abandoned session — \
+                 run doctor --fix to reset it (unsaved commits are kept on a rescue branch)",
+                draft, count
+            )),
+            None => serde_json::Value::Null,
         }
     );
 
@@ -775,10 +2688,7 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
     let lock_path = repo.join(".ink-running");
     if lock_path.exists() {
         let age = crate::context::read_lock_age(repo);
-        let timeout = loaded_config
-            .as_ref()
-            .map(|c| c.session_timeout_minutes)
-            .unwrap_or(60);
+        let timeout = session_timeout;
         let stale = age.map(|a| a > timeout).unwrap_or(false);
         check!(
             "session_lock",
@@ -798,29 +2708,197 @@ pub fn doctor(repo: &Path) -> Result<serde_json::Value> {
         check!("session_lock", true, serde_json::Value::Null);
     }
 
-    Ok(serde_json::json!({
+    // ── Interrupted session-close ─────────────────────────────────────────────
+    match journal::pending(repo) {
+        Ok(Some(entry)) => check!(
+            "close_journal",
+            false,
+            serde_json::json!(format!(
+                "session-close of {} (started {}) was interrupted — the next command will {}",
+                entry.session_id,
+                entry.started_at.format("%Y-%m-%d %H:%M UTC"),
+                if journal::is_committed(repo, &entry) {
+                    "publish its commit"
+                } else {
+                    "roll back its writes"
+                }
+            ))
+        ),
+        Ok(None) => check!(
+            "close_journal",
+            true,
+            match journal::last_recovery(repo) {
+                Some(r) => serde_json::json!(r),
+                None => serde_json::Value::Null,
+            }
+        ),
+        Err(e) => check!("close_journal", false, serde_json::json!(e.to_string())),
+    }
+
+    let mut result = serde_json::json!({
         "status": if all_ok { "healthy" } else { "issues" },
         "checks": checks,
+    });
+    if let serde_json::Value::Object(repair) = repair {
+        result.as_object_mut().unwrap().extend(repair);
+    }
+    Ok(result)
+}
+
+// ─── config set ───────────────────────────────────────────────────────────────
+
+/// Config.yml `content` with the top-level `key` set to `value` (appended
+/// when absent); comments and every other line are kept as they are.
+pub(crate) fn with_config_value(content: &str, key: &str, value: &str) -> String {
+    let prefix = format!("{}:", key);
+    let new_line = format!("{}: {}", key, value.trim());
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !found && line.starts_with(&prefix) {
+                found = true;
+                new_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(new_line);
+    }
+    format!("{}\n", lines.join("\n"))
+}
+
+/// Set a single top-level `key: value` in Config.yml, preserving comments and
+/// the order of every other line. The candidate file is parsed, validated, and
+/// linted before anything is written — a value that fails to parse or validate,
+/// or that brings lint issues the file did not have, is rejected with the list
+/// of issues and Config.yml is left untouched. Issues already there do not
+/// block a change, so a file with several can be fixed one key at a time.
+/// Commits on success; the push is best-effort (same as init).
+pub fn config_set(repo: &Path, key: &str, value: &str) -> Result<serde_json::Value> {
+    anyhow::ensure!(
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        "invalid config key '{}'",
+        key
+    );
+    // One line per key: a line break would smuggle in other keys
+    if value.contains(['\n', '\r']) {
+        return Err(
+            InkError::InvalidInput(format!("the value of {} must be a single line", key)).into(),
+        );
+    }
+
+    let config_rel = "Global Material/Config.yml";
+    let config_path = repo.join(config_rel);
+    let content = std::fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {}", config_rel))?;
+
+    let updated = with_config_value(&content, key, value);
+    let candidate =
+        Config::parse(&updated).map_err(|e| InkError::ConfigInvalid(format!("{:#}", e)))?;
+    let existing = Config::parse(&content)
+        .map(|current| current.lint())
+        .unwrap_or_default();
+    let issues: Vec<String> = candidate
+        .lint()
+        .into_iter()
+        .filter(|issue| !existing.contains(issue))
+        .collect();
+    if !issues.is_empty() {
+        return Err(InkError::ConfigInvalid(format!(
+            "Config.yml: refusing to set {} — {}",
+            key,
+            issues.join("; ")
+        ))
+        .into());
+    }
+
+    std::fs::write(&config_path, &updated)
+        .with_context(|| format!("Failed to write {}", config_rel))?;
+    git::run_git(repo, &["add", config_rel]).with_context(|| "Failed to git add Config.yml")?;
+    git::run_git(repo, &["commit", "-m", &format!("config: set {}", key)])
+        .with_context(|| "Failed to commit Config.yml")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+
+    Ok(serde_json::json!({
+        "status": "updated",
+        "key": key,
+        "value": value.trim(),
     }))
 }
 
 // ─── rollback ─────────────────────────────────────────────────────────────────
 
-/// Revert main (and draft) to the snapshot tag created at the start of the
-/// last writing session, undoing all prose generated in that session.
-pub fn rollback_session(repo_path: &Path) -> Result<()> {
-    // Collect all ink-* tags and sort reverse-chronologically.
-    let raw = git::run_git(repo_path, &["tag", "-l", "ink-*"])?;
-    let mut tags: Vec<&str> = raw
+/// `ink-*` snapshot tags, newest first.
+fn snapshot_tags(repo: &Path) -> Result<Vec<String>> {
+    let raw = git::run_git(repo, &["tag", "-l", "ink-*"])?;
+    let mut tags: Vec<String> = raw
         .lines()
         .map(|l| l.trim())
         .filter(|l| !l.is_empty())
+        .map(String::from)
         .collect();
     tags.sort_by(|a, b| b.cmp(a));
+    Ok(tags)
+}
+
+/// Hard-reset main to `target` and force-push it; draft follows if it exists.
+fn reset_to_snapshot(repo: &Path, target: &str) -> Result<()> {
+    // Ensure we're on main before resetting
+    let branches = git::branches(repo);
+    git::run_git(repo, &["checkout", &branches.main])
+        .with_context(|| format!("Failed to checkout {}", branches.main))?;
+
+    // Hard reset main to the snapshot tag
+    git::run_git(repo, &["reset", "--hard", target])
+        .with_context(|| format!("Failed to reset to {}", target))?;
+
+    // Force-push main
+    git::run_git(repo, &["push", "--force", &branches.remote, &branches.main])
+        .with_context(|| format!("Failed to force-push {}", branches.main))?;
+
+    // Reset draft to main if it exists
+    if branch_exists(repo, &branches.draft) {
+        git::run_git(repo, &["branch", "-f", &branches.draft, &branches.main])
+            .with_context(|| "Failed to reset draft branch")?;
+        git::run_git(
+            repo,
+            &["push", "--force", &branches.remote, &branches.draft],
+        )
+        .with_context(|| format!("Failed to force-push {}", branches.draft))?;
+    }
+    Ok(())
+}
+
+/// Revert to the most recent `ink-*` snapshot tag without prompting (MCP
+/// `rollback`, `rollback --yes`). Same effect as the confirmed interactive command.
+pub fn rollback_latest(repo: &Path) -> Result<serde_json::Value> {
+    journal::recover(repo)?;
+    let tags = snapshot_tags(repo)?;
+    let target = tags
+        .first()
+        .ok_or_else(|| anyhow!("No ink-* snapshot tags found — nothing to roll back"))?;
+    reset_to_snapshot(repo, target)?;
+    info!("Rolled back to {}", target);
+    Ok(serde_json::json!({
+        "status": "rolled_back",
+        "target": target,
+        "previous_snapshot": tags.get(1),
+    }))
+}
 
+/// Revert main (and draft) to the snapshot tag created at the start of the
+/// last writing session, undoing all prose generated in that session.
+pub fn rollback_session(repo_path: &Path) -> Result<()> {
+    journal::recover(repo_path)?;
+    let tags = snapshot_tags(repo_path)?;
     let target = tags
         .first()
-        .ok_or_else(|| anyhow::anyhow!("No ink-* snapshot tags found — nothing to roll back"))?;
+        .ok_or_else(|| anyhow!("No ink-* snapshot tags found — nothing to roll back"))?;
 
     println!("\n  Rollback target : {}", target);
     if let Some(prev) = tags.get(1) {
@@ -840,24 +2918,7 @@ pub fn rollback_session(repo_path: &Path) -> Result<()> {
         return Ok(());
     }
 
-    // Ensure we're on main before resetting
-    git::run_git(repo_path, &["checkout", "main"]).with_context(|| "Failed to checkout main")?;
-
-    // Hard reset main to the snapshot tag
-    git::run_git(repo_path, &["reset", "--hard", target])
-        .with_context(|| format!("Failed to reset to {}", target))?;
-
-    // Force-push main
-    git::run_git(repo_path, &["push", "--force", "origin", "main"])
-        .with_context(|| "Failed to force-push main")?;
-
-    // Reset draft to main if it exists
-    if git::run_git(repo_path, &["show-ref", "--verify", "refs/heads/draft"]).is_ok() {
-        git::run_git(repo_path, &["branch", "-f", "draft", "main"])
-            .with_context(|| "Failed to reset draft branch")?;
-        git::run_git(repo_path, &["push", "--force", "origin", "draft"])
-            .with_context(|| "Failed to force-push draft")?;
-    }
+    reset_to_snapshot(repo_path, target)?;
 
     println!("\n  Rolled back to {}.", target);
     println!("  The last session's prose has been removed.");
@@ -866,6 +2927,132 @@ pub fn rollback_session(repo_path: &Path) -> Result<()> {
     Ok(())
 }
 
+// ─── prune-tags ───────────────────────────────────────────────────────────────
+
+/// Month (`YYYY-MM`) of a session snapshot tag, None for any other `ink-*` tag.
+fn snapshot_month(tag: &str) -> Option<&str> {
+    let stamp = tag.strip_prefix("ink-")?;
+    let parses = |fmt| chrono::NaiveDateTime::parse_from_str(stamp, fmt).is_ok();
+    if parses("%Y-%m-%d-%H-%M-%S") || parses("%Y-%m-%d-%H-%M") {
+        stamp.get(..7)
+    } else {
+        None
+    }
+}
+
+/// Split snapshot tags (newest first) into those `retention` keeps and those
+/// it prunes. Tags that are not session snapshots are never pruned.
+fn apply_retention(tags: &[String], retention: &TagRetention) -> (Vec<String>, Vec<String>) {
+    let mut months_kept: Vec<&str> = Vec::new();
+    let mut snapshots = 0;
+    let (mut keep, mut prune) = (Vec::new(), Vec::new());
+    for tag in tags {
+        let Some(month) = snapshot_month(tag) else {
+            keep.push(tag.clone());
+            continue;
+        };
+        snapshots += 1;
+        let newest_of_month =
+            !months_kept.contains(&month) && months_kept.len() < retention.keep_monthly;
+        if newest_of_month {
+            months_kept.push(month);
+        }
+        if snapshots <= retention.keep_last || newest_of_month {
+            keep.push(tag.clone());
+        } else {
+            prune.push(tag.clone());
+        }
+    }
+    (keep, prune)
+}
+
+/// `git push <remote> --delete` for those of `tags` the remote has; returns
+/// how many were deleted there.
+fn delete_remote_tags(repo: &Path, remote: &str, tags: &[String]) -> Result<usize> {
+    let listed = git::run_git(repo, &["ls-remote", "--tags", "--refs", remote, "ink-*"])?;
+    let on_remote: Vec<&str> = listed
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .filter_map(|r| r.strip_prefix("refs/tags/"))
+        .collect();
+    let refs: Vec<String> = tags
+        .iter()
+        .filter(|t| on_remote.contains(&t.as_str()))
+        .map(|t| format!("refs/tags/{}", t))
+        .collect();
+    if !refs.is_empty() {
+        let mut args = vec!["push", remote, "--delete"];
+        args.extend(refs.iter().map(String::as_str));
+        git::run_git(repo, &args)?;
+    }
+    Ok(refs.len())
+}
+
+/// Delete the snapshot tags Config.yml `tag_retention` no longer keeps — on the
+/// primary remote first (a failure there stops before anything local is
+/// touched), then locally, then best-effort on the backup remotes.
+pub fn prune_tags(repo: &Path, dry_run: bool) -> Result<serde_json::Value> {
+    let config = Config::load(repo)?;
+    let (keep, prune) = apply_retention(&snapshot_tags(repo)?, &config.tag_retention);
+    if dry_run || prune.is_empty() {
+        return Ok(serde_json::json!({
+            "status": if dry_run { "dry_run" } else { "nothing_to_prune" },
+            "kept": keep.len(),
+            "removed": prune,
+        }));
+    }
+
+    let branches = git::branches(repo);
+    let has_remote = git::run_git(repo, &["remote", "get-url", &branches.remote]).is_ok();
+    let removed_remote = if has_remote {
+        delete_remote_tags(repo, &branches.remote, &prune).with_context(|| {
+            format!(
+                "Failed to delete tags on {} — nothing was pruned",
+                branches.remote
+            )
+        })?
+    } else {
+        0
+    };
+    let mut args = vec!["tag", "-d"];
+    args.extend(prune.iter().map(String::as_str));
+    git::run_git(repo, &args).with_context(|| "Failed to delete local tags")?;
+    info!(
+        "Pruned {} snapshot tag(s), kept {}",
+        prune.len(),
+        keep.len()
+    );
+
+    let mirrors: Vec<git::MirrorResult> = git::mirrors(repo)
+        .into_iter()
+        .map(|remote| match delete_remote_tags(repo, &remote, &prune) {
+            Ok(_) => git::MirrorResult {
+                remote,
+                pushed: true,
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!("Could not prune tags on {}: {}", remote, e);
+                git::MirrorResult {
+                    remote,
+                    pushed: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        })
+        .collect();
+    let mut result = serde_json::json!({
+        "status": "pruned",
+        "kept": keep.len(),
+        "removed": prune,
+        "removed_on_remote": removed_remote,
+    });
+    if !mirrors.is_empty() {
+        result["mirrors"] = serde_json::json!(mirrors);
+    }
+    Ok(result)
+}
+
 // ─── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -900,10 +3087,194 @@ mod tests {
     #[test]
     fn session_close_guard_returns_err_without_lock() {
         let tmp = tempfile::tempdir().unwrap();
-        let err = close_session(tmp.path(), "prose", None, &[]).unwrap_err();
+        let err = close_session(
+            tmp.path(),
+            "id",
+            "prose",
+            None,
+            &[],
+            &StoryUpdates::default(),
+            false,
+        )
+        .unwrap_err();
         assert!(err.to_string().contains("no active session"));
     }
 
+    #[test]
+    fn session_close_guard_rejects_foreign_session_id() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(".ink-running"),
+            "started_at: 2026-01-01T00:00:00Z\nsession_id: 20260101T000000Z-aaaaaaaa\n",
+        )
+        .unwrap();
+        let err = close_session(
+            tmp.path(),
+            "20260101T000000Z-bbbbbbbb",
+            "prose",
+            None,
+            &[],
+            &StoryUpdates::default(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("session ID mismatch"));
+    }
+
+    /// A locked book whose Config.yml adds `extra`, and a close of `prose` on it.
+    fn refused_close(extra: &str, prose: &str) -> (tempfile::TempDir, anyhow::Error) {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("Global Material")).unwrap();
+        std::fs::write(
+            tmp.path().join("Global Material/Config.yml"),
+            format!(
+                "target_length: 90000\nchapter_count: 20\nchapter_structure: linear\n\
+                 words_per_session: 1500\n{}",
+                extra
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join(".ink-running"),
+            "started_at: 2026-01-01T00:00:00Z\nsession_id: s1\n",
+        )
+        .unwrap();
+        let err = close_session(
+            tmp.path(),
+            "s1",
+            prose,
+            None,
+            &[],
+            &StoryUpdates::default(),
+            false,
+        )
+        .unwrap_err();
+        // Nothing written: the lock is still held for a retry
+        assert!(tmp.path().join(".ink-running").exists());
+        (tmp, err)
+    }
+
+    #[test]
+    fn strict_markers_refuse_the_close_as_an_error() {
+        let (_tmp, err) = refused_close("marker_validation: strict\n", "Prose without markers.\n");
+        let (json, exit) = crate::errors::report(&err);
+        assert_eq!(json["error"]["code"], "invalid_markers");
+        assert!(!json["error"]["marker_issues"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert_eq!(exit, crate::errors::EXIT_INVALID_INPUT);
+    }
+
+    #[test]
+    fn rejecting_quota_refuses_the_close_as_an_error() {
+        let (_tmp, err) = refused_close(
+            "session_quota: { mode: reject }\n",
+            "<!-- INK:NEW:START -->\nFar too short.\n<!-- INK:NEW:END -->\n",
+        );
+        let (json, exit) = crate::errors::report(&err);
+        assert_eq!(json["error"]["code"], "quota_violation");
+        assert_eq!(json["error"]["quota"]["problem"], "under");
+        assert_eq!(json["error"]["quota"]["words"], 3);
+        assert_eq!(exit, crate::errors::EXIT_INVALID_INPUT);
+    }
+
+    #[test]
+    fn cut_off_prose_refuses_the_close_as_an_error() {
+        let (_tmp, err) = refused_close(
+            "refuse_truncated: true\n",
+            "<!-- INK:NEW:START -->\nShe opened the door and\n<!-- INK:NEW:END -->\n",
+        );
+        let (json, exit) = crate::errors::report(&err);
+        assert_eq!(json["error"]["code"], "truncated");
+        assert_eq!(
+            json["error"]["truncation_warnings"][0]["check"],
+            "unterminated_sentence"
+        );
+        assert_eq!(exit, crate::errors::EXIT_INVALID_INPUT);
+    }
+
+    #[test]
+    fn session_quota_bounds() {
+        let config = |quota: &str| -> Config {
+            serde_yaml::from_str(&format!(
+                "target_length: 90000\nchapter_count: 20\nchapter_structure: linear\n\
+                 words_per_session: 1500\n{}",
+                quota
+            ))
+            .unwrap()
+        };
+        assert!(check_session_quota(300, &config("")).is_none());
+        let warn = config("session_quota: {}");
+        assert_eq!(check_session_quota(300, &warn).unwrap().problem, "under");
+        assert!(check_session_quota(1500, &warn).is_none());
+        let over = check_session_quota(3100, &warn).unwrap();
+        assert_eq!(
+            (over.problem, over.min_words, over.max_words),
+            ("over", 750, Some(3000))
+        );
+        assert!(!quota_rejects(&warn));
+        let reject = config("session_quota: { mode: reject, max_ratio: 0 }");
+        assert!(check_session_quota(9000, &reject).is_none());
+        assert!(quota_rejects(&reject));
+    }
+
+    #[test]
+    fn average_chapter_words_leaves_out_the_current_chapter() {
+        let mut state = InkState {
+            current_chapter: 1,
+            current_chapter_word_count: 800,
+            ..InkState::default()
+        };
+        assert_eq!(average_chapter_words(800, &state), None);
+        state.current_chapter = 4;
+        assert_eq!(average_chapter_words(9800, &state), Some(3000));
+    }
+
+    #[test]
+    fn session_close_replays_duplicate_and_refuses_changed_prose() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = InkState {
+            last_close: Some(LastClose {
+                session_id: "s1".to_string(),
+                prose_hash: prose_hash("same prose"),
+                payload: serde_json::json!({ "status": "closed", "session_word_count": 2 }),
+                closed_at: None,
+            }),
+            ..InkState::default()
+        };
+        state.save(tmp.path()).unwrap();
+        // A newer session holds the lock, so the replay must not touch git
+        std::fs::write(
+            tmp.path().join(".ink-running"),
+            "started_at: 2026-01-01T00:00:00Z\nsession_id: s2\n",
+        )
+        .unwrap();
+
+        let payload = close_session(
+            tmp.path(),
+            "s1",
+            "same prose",
+            None,
+            &[],
+            &StoryUpdates::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(payload["session_word_count"], 2);
+        let err = close_session(
+            tmp.path(),
+            "s1",
+            "other prose",
+            None,
+            &[],
+            &StoryUpdates::default(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("already closed"));
+    }
+
     #[test]
     fn complete_guard_returns_err_when_already_complete() {
         let tmp = tempfile::tempdir().unwrap();
@@ -912,6 +3283,94 @@ mod tests {
         assert!(err.to_string().contains("already complete"));
     }
 
+    #[test]
+    fn approve_guard_returns_err_during_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(".ink-running"), "").unwrap();
+        let err = approve_pending(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("session in progress"));
+    }
+
+    fn merge_config() -> Config {
+        serde_yaml::from_str(
+            "target_length: 1000\nchapter_count: 1\nchapter_structure: linear\n\
+             words_per_session: 100\nwords_per_page: 0\n",
+        )
+        .unwrap()
+    }
+
+    fn rework(original: &str, reworked: &str) -> String {
+        format!(
+            "<!-- INK:REWORKED:START -->\n{}\n<!-- INK:ORIGINAL:START -->\n{}\n\
+             <!-- INK:ORIGINAL:END -->\n<!-- INK:REWORKED:END -->\n",
+            reworked, original
+        )
+    }
+
+    #[test]
+    fn shortening_rework_credits_only_the_new_prose() {
+        let book = format!(
+            "{}\n\nThe rain fell on the old grey town all night.\n",
+            FULL_BOOK_HEADER
+        );
+        let validated = format!(
+            "{}<!-- INK:NEW:START -->\nMorning came.\n<!-- INK:NEW:END -->\n",
+            rework(
+                "The rain fell on the old grey town all night.",
+                "It rained."
+            )
+        );
+        let plan = plan_merge(Some(book), &validated, &merge_config());
+        assert_eq!(plan.outcome.replacements_applied, 1);
+        // The book lost words net, but the session wrote two new ones
+        assert_eq!(plan.outcome.words_added, 2);
+    }
+
+    #[test]
+    fn lengthening_rework_credits_its_growth() {
+        let book = format!("{}\n\nIt rained.\n\nThen it stopped.\n", FULL_BOOK_HEADER);
+        let validated = format!(
+            "{}{}",
+            rework("It rained.", "It rained on the old grey town."),
+            rework("Then it stopped.", "It stopped.")
+        );
+        let plan = plan_merge(Some(book), &validated, &merge_config());
+        assert_eq!(plan.outcome.replacements_applied, 2);
+        // +5 from the first rework; the second one shrinking takes nothing off
+        assert_eq!(plan.outcome.words_added, 5);
+        assert!(plan.appended.is_empty());
+    }
+
+    #[test]
+    fn queued_prose_accumulates_then_merges_into_book() {
+        let tmp = tempfile::tempdir().unwrap();
+        queue_pending_prose(tmp.path(), "First session.\n").unwrap();
+        queue_pending_prose(
+            tmp.path(),
+            "<!-- INK:NEW:START -->\nSecond session.\n<!-- INK:NEW:END -->",
+        )
+        .unwrap();
+        let queued = std::fs::read_to_string(tmp.path().join(PENDING_PROSE_FILE)).unwrap();
+        assert!(queued.find("First").unwrap() < queued.find("Second").unwrap());
+
+        std::fs::create_dir_all(tmp.path().join("Current version")).unwrap();
+        std::fs::write(
+            tmp.path().join("Current version/Full_Book.md"),
+            "Opening.\n",
+        )
+        .unwrap();
+        let config: Config = serde_yaml::from_str(
+            "target_length: 1000\nchapter_count: 1\nchapter_structure: linear\n\
+             words_per_session: 100\nwords_per_page: 0\n",
+        )
+        .unwrap();
+        let merged = merge_into_full_book(tmp.path(), &queued, &config).unwrap();
+        assert_eq!(merged.words_added, 4);
+        let book =
+            std::fs::read_to_string(tmp.path().join("Current version/Full_Book.md")).unwrap();
+        assert!(book.contains("Second session.") && !book.contains("INK:NEW"));
+    }
+
     // ── advance-chapter guard helpers ─────────────────────────────────────────
 
     fn write_test_config(dir: &std::path::Path, words_per_chapter: u32) {
@@ -933,6 +3392,34 @@ mod tests {
         std::fs::write(dir.join(".ink-state.yml"), content).unwrap();
     }
 
+    // ── status --ping ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn status_ping_uses_cache_until_sources_change() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(status_ping(tmp.path()).unwrap().health, "uninitialized");
+
+        write_test_config(tmp.path(), 3000);
+        write_test_state(tmp.path(), 1, 0);
+        let book_dir = tmp.path().join("Current version");
+        std::fs::create_dir_all(&book_dir).unwrap();
+        std::fs::write(book_dir.join("Full_Book.md"), "word ".repeat(8000)).unwrap();
+        let ping = status_ping(tmp.path()).unwrap();
+        assert_eq!(
+            (ping.chapter, ping.pct_complete, ping.health),
+            (1, 10, "ok")
+        );
+
+        // A state change newer than the cache is picked up
+        write_test_state(tmp.path(), 2, 0);
+        let cache = std::fs::File::options()
+            .write(true)
+            .open(tmp.path().join(STATUS_CACHE_FILE))
+            .unwrap();
+        cache.set_modified(std::time::UNIX_EPOCH).unwrap();
+        assert_eq!(status_ping(tmp.path()).unwrap().chapter, 2);
+    }
+
     // ── advance-chapter guard tests ───────────────────────────────────────────
 
     #[test]
@@ -941,7 +3428,7 @@ mod tests {
         write_test_config(tmp.path(), 3000);
         write_test_state(tmp.path(), 1, 100);
 
-        let result = advance_chapter(tmp.path()).unwrap();
+        let result = advance_chapter(tmp.path(), false, false).unwrap();
         assert_eq!(result["status"], "chapter_not_ready");
         assert_eq!(result["current_word_count"], 100);
         assert_eq!(result["target_word_count"], 3000);
@@ -954,11 +3441,39 @@ mod tests {
         write_test_config(tmp.path(), 3000);
         write_test_state(tmp.path(), 1, 0);
 
-        let result = advance_chapter(tmp.path()).unwrap();
+        let result = advance_chapter(tmp.path(), false, false).unwrap();
         assert_eq!(result["status"], "chapter_not_ready");
         assert_eq!(result["current_word_count"], 0);
     }
 
+    #[test]
+    fn progress_ledger_counts_sessions_since_the_last_row() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("Chapters material")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("Changelog")).unwrap();
+        for stamp in ["2026-03-01-09-00", "2026-03-02-09-00", "2026-03-05-21-30"] {
+            std::fs::write(tmp.path().join(format!("Changelog/{}.md", stamp)), "x").unwrap();
+        }
+        let at = |s: &str| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+        };
+        let row = append_progress(tmp.path(), "Chapter 1", 2900, &at("2026-03-03 08:00")).unwrap();
+        assert_eq!(
+            (row["sessions"].as_u64(), row["started"].as_str()),
+            (Some(2), Some("2026-03-01 09:00"))
+        );
+        let row = append_progress(tmp.path(), "Interlude A", 600, &at("2026-03-06 08:00")).unwrap();
+        assert_eq!(row["sessions"], 1);
+        let ledger = std::fs::read_to_string(tmp.path().join(PROGRESS_LEDGER)).unwrap();
+        assert!(ledger.ends_with(
+            "| Chapter 1 | 2900 | 2 | 2026-03-01 09:00 | 2026-03-03 08:00 |\n\
+             | Interlude A | 600 | 1 | 2026-03-05 21:30 | 2026-03-06 08:00 |\n"
+        ));
+    }
+
     // ── chapter_close_suggested formula tests (pure arithmetic, no I/O) ──────
 
     #[test]
@@ -1010,7 +3525,7 @@ mod tests {
         );
         std::fs::write(tmp.path().join("README.md"), readme).unwrap();
 
-        update_readme_chapters(tmp.path(), 1, Some(2)).unwrap();
+        update_readme_chapters(tmp.path(), &[Unit::chapter(1), Unit::chapter(2)], true).unwrap();
 
         let updated = std::fs::read_to_string(tmp.path().join("README.md")).unwrap();
         assert!(
@@ -1041,7 +3556,7 @@ mod tests {
         std::fs::write(tmp.path().join("README.md"), readme).unwrap();
 
         // Should return Ok(()) without writing
-        update_readme_chapters(tmp.path(), 1, Some(2)).unwrap();
+        update_readme_chapters(tmp.path(), &[Unit::chapter(1), Unit::chapter(2)], true).unwrap();
 
         let after = std::fs::read_to_string(tmp.path().join("README.md")).unwrap();
         assert_eq!(
@@ -1049,4 +3564,340 @@ mod tests {
             "file must be unchanged when separator is absent"
         );
     }
+
+    // ── config lint / config set ──────────────────────────────────────────────
+
+    #[test]
+    fn config_lint_flags_page_count_and_oversized_session() {
+        let cfg = Config::parse(
+            "target_length: 250\nchapter_count: 1\nchapter_structure: linear\n\
+             words_per_session: 4000\nwords_per_chapter: 3000\n",
+        )
+        .unwrap();
+        let issues = cfg.lint();
+        assert!(
+            issues.iter().any(|i| i.contains("page count")),
+            "{issues:?}"
+        );
+        assert!(issues.iter().any(|i| i.contains("exceeds")), "{issues:?}");
+    }
+
+    #[test]
+    fn commit_message_templates_render_and_validate() {
+        let base = "target_length: 80000\nchapter_count: 20\nchapter_structure: linear\n\
+                    words_per_session: 2000\nwords_per_chapter: 4000\n";
+        let defaults = Config::parse(base).unwrap().commit_messages;
+        assert_eq!(
+            defaults.render(&defaults.chapter_advance, &[("chapter", "chapter 4")]),
+            "chapter: advance to chapter 4"
+        );
+
+        let cfg = Config::parse(&format!(
+            "{base}commit_messages:\n  session: \"{{chapter}}: +{{words}} words ({{session_id}}, {{date}})\"\n  \
+             co_authored_by: [\"Ada <ada@example.com>\"]\n"
+        ))
+        .unwrap();
+        let messages = &cfg.commit_messages;
+        assert_eq!(
+            messages.render(
+                &messages.session,
+                &[
+                    ("session_id", "s1"),
+                    ("words", "1200"),
+                    ("chapter", "Chapter 3"),
+                    ("date", "2026-10-18"),
+                ],
+            ),
+            "Chapter 3: +1200 words (s1, 2026-10-18)\n\nCo-authored-by: Ada <ada@example.com>"
+        );
+
+        let err = Config::parse(&format!(
+            "{base}commit_messages:\n  session: \"{{wordz}}\"\n"
+        ))
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown placeholder {wordz}"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn doctor_checks_lfs_only_when_assets_exist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q"]).unwrap();
+        std::fs::create_dir_all(repo.join("Assets")).unwrap();
+        std::fs::write(repo.join("Assets/.gitkeep"), "").unwrap();
+        let lfs_check = |report: &serde_json::Value| {
+            report["checks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["name"] == "git_lfs")
+                .cloned()
+        };
+        assert!(lfs_check(&doctor(repo, false).unwrap()).is_none());
+
+        std::fs::write(repo.join("Assets/cover.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        let check = lfs_check(&doctor(repo, false).unwrap()).expect("git_lfs check");
+        assert_eq!(check["ok"], false);
+
+        assert!(crate::init::add_lfs_attributes(repo).unwrap());
+        assert!(
+            !crate::init::add_lfs_attributes(repo).unwrap(),
+            "written once"
+        );
+        let installed = std::process::Command::new("git")
+            .args(["lfs", "version"])
+            .output()
+            .is_ok_and(|o| o.status.success());
+        let check = lfs_check(&doctor(repo, false).unwrap()).unwrap();
+        assert_eq!(check["ok"], installed, "{check}");
+    }
+
+    #[test]
+    fn doctor_fix_returns_to_main_and_rescues_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "--allow-empty", "-m", "root"]).unwrap();
+        // An abandoned session's commit on draft, then a detached HEAD with
+        // its own commit and an uncommitted file
+        git::run_git(repo, &["checkout", "-q", "-b", "draft"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "--allow-empty", "-m", "lost prose"]).unwrap();
+        git::run_git(repo, &["checkout", "-q", "--detach", "main"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "--allow-empty", "-m", "detached"]).unwrap();
+        std::fs::write(repo.join("stray.md"), "stray").unwrap();
+        let check = |report: &serde_json::Value, name: &str| {
+            report["checks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["name"] == name)
+                .unwrap()["ok"]
+                .clone()
+        };
+
+        let report = doctor(repo, false).unwrap();
+        assert_eq!(check(&report, "head_branch"), false);
+        assert_eq!(check(&report, "draft_branch"), false);
+
+        let report = doctor(repo, true).unwrap();
+        assert_eq!(check(&report, "head_branch"), true, "{report}");
+        assert_eq!(check(&report, "draft_branch"), true, "{report}");
+        assert_eq!(report["fixed"].as_array().unwrap().len(), 5, "{report}");
+        assert_eq!(
+            git::run_git(repo, &["symbolic-ref", "--short", "HEAD"]).unwrap(),
+            "main"
+        );
+        assert!(!repo.join("stray.md").exists());
+        let rescued = git::run_git(repo, &["branch", "--list", "ink-rescue-*"]).unwrap();
+        assert_eq!(rescued.lines().count(), 2, "{rescued}");
+        assert_eq!(
+            git::run_git(repo, &["rev-parse", "draft"]).unwrap(),
+            git::run_git(repo, &["rev-parse", "main"]).unwrap()
+        );
+        assert_eq!(doctor(repo, true).unwrap()["fixed"], serde_json::json!([]));
+    }
+
+    /// A repository at `<tmp>/book` with one commit per message, main
+    /// pushed to a bare origin, and draft alongside.
+    fn pushed_repo(tmp: &Path, commits: &[&str]) -> std::path::PathBuf {
+        git::run_git(tmp, &["init", "-q", "--bare", "-b", "main", "origin.git"]).unwrap();
+        let repo = tmp.join("book");
+        std::fs::create_dir_all(&repo).unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "t@t"],
+            &["config", "user.name", "t"],
+            &["remote", "add", "origin", "../origin.git"],
+        ] {
+            git::run_git(&repo, args).unwrap();
+        }
+        for message in commits {
+            git::run_git(&repo, &["commit", "-q", "--allow-empty", "-m", message]).unwrap();
+        }
+        git::run_git(&repo, &["branch", "draft"]).unwrap();
+        git::run_git(&repo, &["push", "-q", "origin", "main", "draft"]).unwrap();
+        repo
+    }
+
+    #[test]
+    fn rollback_latest_returns_main_and_draft_to_the_newest_snapshot() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = pushed_repo(tmp.path(), &["one"]);
+        git::run_git(&repo, &["tag", "ink-2026-01-01-09-00"]).unwrap();
+        git::run_git(&repo, &["commit", "-q", "--allow-empty", "-m", "two"]).unwrap();
+        git::run_git(&repo, &["tag", "ink-2026-01-02-09-00"]).unwrap();
+        let snapshot = git::run_git(&repo, &["rev-parse", "HEAD"]).unwrap();
+        git::run_git(&repo, &["commit", "-q", "--allow-empty", "-m", "session"]).unwrap();
+        git::run_git(&repo, &["push", "-q", "origin", "main", "main:draft"]).unwrap();
+
+        let result = rollback_latest(&repo).unwrap();
+        assert_eq!(result["status"], "rolled_back");
+        assert_eq!(result["target"], "ink-2026-01-02-09-00");
+        assert_eq!(result["previous_snapshot"], "ink-2026-01-01-09-00");
+        git::run_git(&repo, &["fetch", "-q", "origin"]).unwrap();
+        for rev in ["main", "draft", "origin/main", "origin/draft"] {
+            assert_eq!(
+                git::run_git(&repo, &["rev-parse", rev]).unwrap(),
+                snapshot,
+                "{rev}"
+            );
+        }
+    }
+
+    #[test]
+    fn rollback_latest_without_a_snapshot_changes_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = pushed_repo(tmp.path(), &["one", "two"]);
+        let head = git::run_git(&repo, &["rev-parse", "HEAD"]).unwrap();
+        let err = rollback_latest(&repo).unwrap_err();
+        assert!(err.to_string().contains("nothing to roll back"), "{err}");
+        assert_eq!(git::run_git(&repo, &["rev-parse", "HEAD"]).unwrap(), head);
+    }
+
+    #[test]
+    fn config_set_rejects_inconsistent_chapter_count() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_test_config(tmp.path(), 3000);
+        let before =
+            std::fs::read_to_string(tmp.path().join("Global Material/Config.yml")).unwrap();
+
+        let err = config_set(tmp.path(), "chapter_count", "200").unwrap_err();
+        assert!(err.to_string().contains("chapter_count"), "{err}");
+
+        let after = std::fs::read_to_string(tmp.path().join("Global Material/Config.yml")).unwrap();
+        assert_eq!(before, after, "rejected value must not be written");
+    }
+
+    #[test]
+    fn config_set_rejects_only_new_lint_issues() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        write_test_config(repo, 3000);
+        let config_path = repo.join("Global Material/Config.yml");
+        // An issue the file already has
+        let mut content = std::fs::read_to_string(&config_path).unwrap();
+        content.push_str("git_timeout_seconds: 0\n");
+        std::fs::write(&config_path, content).unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "init"]).unwrap();
+
+        let result = config_set(repo, "words_per_session", "900").unwrap();
+        assert_eq!(result["status"], "updated");
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains("words_per_session: 900"), "{content}");
+
+        // A new issue is still refused, without listing the old one
+        let err = config_set(repo, "words_per_session", "5000").unwrap_err();
+        assert!(err.to_string().contains("words_per_session=5000"), "{err}");
+        assert!(!err.to_string().contains("git_timeout_seconds"), "{err}");
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), content);
+    }
+
+    #[test]
+    fn config_set_rejects_multiline_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_test_config(tmp.path(), 3000);
+        let config_path = tmp.path().join("Global Material/Config.yml");
+        let before = std::fs::read_to_string(&config_path).unwrap();
+
+        for value in ["900\nrequire_approval: true", "900\rrequire_approval: true"] {
+            let err = config_set(tmp.path(), "words_per_session", value).unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<InkError>(),
+                    Some(InkError::InvalidInput(_))
+                ),
+                "{err}"
+            );
+        }
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), before);
+    }
+
+    // ── patch-mode session-close ──────────────────────────────────────────────
+
+    #[test]
+    fn prose_from_patch_returns_engine_output_after_validated_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("Review")).unwrap();
+        std::fs::write(
+            tmp.path().join("Review/current.md"),
+            "Validated line.\n\n<!-- INK: darker -->\nPending line.\n",
+        )
+        .unwrap();
+        let diff = "\
+--- a/Review/current.md
++++ b/Review/current.md
+@@ -1,4 +1,5 @@
+ Validated line.
+ 
+-<!-- INK: darker -->
+-Pending line.
++<!-- INK:REWORKED:START -->
++Darker pending line.
++<!-- INK:REWORKED:END -->
+";
+        let prose = prose_from_patch(tmp.path(), diff).unwrap();
+        assert!(prose.starts_with("<!-- INK:REWORKED:START -->"), "{prose}");
+        assert!(!prose.contains("Validated line."));
+    }
+
+    #[test]
+    fn prose_from_patch_reports_conflicting_hunk() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("Review")).unwrap();
+        std::fs::write(tmp.path().join("Review/current.md"), "One.\nTwo.\n").unwrap();
+        let diff = "@@ -1,1 +1,1 @@\n-Three.\n+Four.\n";
+        let err = prose_from_patch(tmp.path(), diff).unwrap_err();
+        assert!(err.to_string().contains("hunk 1"), "{err}");
+    }
+
+    // ── prune-tags ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn retention_keeps_recent_and_monthly_snapshots() {
+        let tags: Vec<String> = [
+            "ink-2026-03-02-09-00-00",
+            "ink-2026-03-01-09-00-00",
+            "ink-2026-02-20-09-00",
+            "ink-2026-02-10-09-00-00",
+            "ink-release-candidate",
+            "ink-2026-01-31-09-00-00",
+            "ink-2025-12-31-09-00-00",
+        ]
+        .map(String::from)
+        .to_vec();
+        let retention = TagRetention {
+            keep_last: 2,
+            keep_monthly: 3,
+        };
+        let (keep, prune) = apply_retention(&tags, &retention);
+        assert_eq!(
+            keep,
+            [
+                "ink-2026-03-02-09-00-00",
+                "ink-2026-03-01-09-00-00",
+                "ink-2026-02-20-09-00",
+                "ink-release-candidate",
+                "ink-2026-01-31-09-00-00",
+            ]
+        );
+        assert_eq!(
+            prune,
+            ["ink-2026-02-10-09-00-00", "ink-2025-12-31-09-00-00"]
+        );
+
+        let retention = TagRetention {
+            keep_last: 1,
+            keep_monthly: 0,
+        };
+        assert_eq!(apply_retention(&tags, &retention).1.len(), 5);
+    }
 }
@@ -2,18 +2,72 @@
 // subset of their functions. Suppress dead-code lint for this binary target.
 #![allow(dead_code)]
 
+mod anthology;
+mod audit;
+mod backup;
+mod beats;
 mod book;
+mod chapters;
+mod cmdlock;
 mod config;
+mod constraints;
 mod context;
+mod continuity;
+mod curl;
+mod dialogue;
+mod discover;
+mod edits;
+mod encoding;
+mod errors;
+mod export;
+mod front_matter;
 mod git;
+mod glossary;
+mod import;
 mod init;
+mod journal;
+mod lint;
 mod maintenance;
+mod metadata;
+mod metrics;
+mod notify;
+mod obsidian;
+mod pacing;
+mod patch;
+mod phrases;
+mod prose_lint;
+mod readability;
+mod release;
+mod rename;
+mod resync;
+mod retarget;
+mod review;
+mod schedule;
+mod sequence;
+mod series;
+mod signals;
+mod spellcheck;
 mod state;
+mod storage;
+mod threads;
+mod timeline;
+mod timings;
+mod titles;
+mod truncation;
+mod typography;
+mod wordcount;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use errors::InkError;
 
 // ── JSON-RPC 2.0 types ──────────────────────────────────────────────────────
 
@@ -65,20 +119,134 @@ impl RpcResponse {
     }
 }
 
+// ── Default repository ──────────────────────────────────────────────────────
+
+/// Repository used when a tool call omits `repo_path`: `--repo <path>` or
+/// `INK_REPO`, validated once at startup.
+static DEFAULT_REPO: OnceLock<PathBuf> = OnceLock::new();
+
+/// Command-line options, each also `--flag=value`: `--repo <path>` (else
+/// `INK_REPO`), `--metrics-addr <host:port>` (else `INK_METRICS_ADDR`),
+/// `--metrics-token-file <path>` (else `INK_METRICS_TOKEN` holds the token),
+/// `--max-prose-bytes <n>` (else `INK_MAX_PROSE_BYTES`), and
+/// `--max-writes-per-minute <n>` (else `INK_MAX_WRITES_PER_MINUTE`).
+#[derive(Debug, Default)]
+struct ServerArgs {
+    repo: Option<String>,
+    metrics_addr: Option<String>,
+    metrics_token_file: Option<String>,
+    max_prose_bytes: Option<String>,
+    max_writes_per_minute: Option<String>,
+}
+
+fn server_args() -> Result<ServerArgs, String> {
+    let usage = |arg: &str| {
+        format!(
+            "unexpected argument {:?} (usage: ink-gateway-mcp [--repo <path>] \
+             [--metrics-addr <host:port> [--metrics-token-file <path>]] \
+             [--max-prose-bytes <n>] [--max-writes-per-minute <n>])",
+            arg
+        )
+    };
+    let mut parsed = ServerArgs::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let slot = match flag {
+            "--repo" => &mut parsed.repo,
+            "--metrics-addr" => &mut parsed.metrics_addr,
+            "--metrics-token-file" => &mut parsed.metrics_token_file,
+            "--max-prose-bytes" => &mut parsed.max_prose_bytes,
+            "--max-writes-per-minute" => &mut parsed.max_writes_per_minute,
+            _ => return Err(usage(&arg)),
+        };
+        *slot = Some(match value {
+            Some(value) => value,
+            None => args.next().ok_or_else(|| usage(&arg))?,
+        });
+    }
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+    parsed.repo = parsed.repo.or_else(|| env("INK_REPO"));
+    parsed.metrics_addr = parsed.metrics_addr.or_else(|| env("INK_METRICS_ADDR"));
+    parsed.max_prose_bytes = parsed
+        .max_prose_bytes
+        .or_else(|| env("INK_MAX_PROSE_BYTES"));
+    parsed.max_writes_per_minute = parsed
+        .max_writes_per_minute
+        .or_else(|| env("INK_MAX_WRITES_PER_MINUTE"));
+    if parsed.metrics_token_file.is_some() && parsed.metrics_addr.is_none() {
+        return Err("--metrics-token-file needs --metrics-addr".to_string());
+    }
+    Ok(parsed)
+}
+
+/// The default repository must be an existing git work tree; checked at boot
+/// so a typo fails loudly instead of on every tool call.
+fn validate_default_repo(path: &str) -> Result<PathBuf, String> {
+    let repo = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("default repo {path}: {e}"))?;
+    git::run_git(&repo, &["rev-parse", "--is-inside-work-tree"])
+        .map_err(|_| format!("default repo {} is not a git repository", repo.display()))?;
+    Ok(repo)
+}
+
+/// `repo_path` from the tool arguments, else the server's default repository.
+fn tool_repo(args: &Value) -> Option<PathBuf> {
+    args.get("repo_path")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .or_else(|| DEFAULT_REPO.get().cloned())
+}
+
+/// The book a tool works on: `repo`, or `Stories/<story>` of it when the call
+/// names a story of an anthology.
+fn tool_book(repo: PathBuf, args: &Value) -> anyhow::Result<PathBuf> {
+    match args.get("story").and_then(|v| v.as_str()) {
+        Some(slug) => anthology::story_root(&repo, slug),
+        None => Ok(repo),
+    }
+}
+
 // ── Tool schema ─────────────────────────────────────────────────────────────
 
 fn tools_list() -> Value {
+    let mut list = tool_definitions();
+    // With a default repository, repo_path becomes optional everywhere
+    if let Some(repo) = DEFAULT_REPO.get() {
+        for tool in list["tools"].as_array_mut().into_iter().flatten() {
+            let schema = &mut tool["inputSchema"];
+            if let Some(required) = schema["required"].as_array_mut() {
+                required.retain(|r| r != "repo_path");
+            }
+            schema["properties"]["repo_path"]["description"] = json!(format!(
+                "Absolute path to the book repository (default: {})",
+                repo.display()
+            ));
+        }
+    }
+    list
+}
+
+fn tool_definitions() -> Value {
     json!({
         "tools": [
             {
                 "name": "session_open",
-                "description": "Open a writing session: pre-flight git sync, snapshot tag, draft branch, load all book context. Returns a full JSON payload ready for the writing engine.",
+                "description": "Open a writing session: pre-flight git sync, snapshot tag, draft branch, load all book context. Returns a full JSON payload ready for the writing engine, including the session_id that session_close requires.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "repo_path": {
                             "type": "string",
                             "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
                         }
                     },
                     "required": ["repo_path"]
@@ -86,7 +254,7 @@ fn tools_list() -> Value {
             },
             {
                 "name": "session_close",
-                "description": "Close a writing session: split current.md (validated prose → Full_Book.md, new prose → current.md), update Summary.md, write Changelog entry, push. Returns word counts and completion_ready flag.",
+                "description": "Close a writing session: split current.md (validated prose → Full_Book.md, new prose → current.md), reworked passages whose original is already in Full_Book.md are replaced in place, update Summary.md, write Changelog entry, push. Returns word counts, replacements_applied, and completion_ready flag. With require_approval in Config.yml, validated prose goes to Review/pending.md instead and status is pending_approval.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -94,9 +262,22 @@ fn tools_list() -> Value {
                             "type": "string",
                             "description": "Absolute path to the book repository"
                         },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        },
+                        "session_id": {
+                            "type": "string",
+                            "description": "session_id from the session_open payload; the close is refused if it does not match the active lock"
+                        },
                         "prose": {
                             "type": "string",
-                            "description": "New prose for this session — reworked blocks and new continuation, wrapped in INK:REWORKED/INK:NEW markers"
+                            "description": "New prose for this session — reworked blocks and new continuation, wrapped in INK:REWORKED/INK:NEW markers. With format=patch: a unified diff against Review/current.md"
+                        },
+                        "format": {
+                            "type": "string",
+                            "enum": ["full", "patch"],
+                            "description": "How 'prose' is interpreted (default: full)"
                         },
                         "summary": {
                             "type": "string",
@@ -106,9 +287,47 @@ fn tools_list() -> Value {
                             "type": "array",
                             "items": { "type": "string" },
                             "description": "Filenames the human edited between sessions (from session_open payload)"
+                        },
+                        "beats_completed": {
+                            "type": "array",
+                            "items": { "type": "integer", "minimum": 1 },
+                            "description": "Numbers of the outline beats this session completed; checked off in the session's chapter outline"
+                        },
+                        "timeline_events": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "date": { "type": "string", "description": "In-story date; defaults to the outline's `date` front matter" },
+                                    "event": { "type": "string" }
+                                },
+                                "required": ["event"]
+                            },
+                            "description": "In-story events of this session, appended to Global Material/Timeline.md"
+                        },
+                        "new_terms": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "term": { "type": "string" },
+                                    "definition": { "type": "string" },
+                                    "spelling": { "type": "array", "items": { "type": "string" }, "description": "Accepted variant forms" }
+                                },
+                                "required": ["term", "definition"]
+                            },
+                            "description": "Invented terms this session introduced, added to Global Material/Glossary.md"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Check lock and markers and report what would be appended, replaced, and summarised — without writing files or touching git (default: false)"
+                        },
+                        "force": {
+                            "type": "boolean",
+                            "description": "Close prose that looks cut off mid-generation even when refuse_truncated is set (default: false)"
                         }
                     },
-                    "required": ["repo_path", "prose"]
+                    "required": ["repo_path", "session_id", "prose"]
                 }
             },
             {
@@ -120,6 +339,86 @@ fn tools_list() -> Value {
                         "repo_path": {
                             "type": "string",
                             "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        }
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            {
+                "name": "kill",
+                "description": "Ask the engine to stop: commit and push .ink-kill on main. The next session_open cancels itself and removes the file. With wait=true, polls origin until that acknowledgement lands or timeout_minutes pass.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        },
+                        "wait": {
+                            "type": "boolean",
+                            "description": "Block until the engine acknowledges the kill request (default: false)"
+                        },
+                        "timeout_minutes": {
+                            "type": "integer",
+                            "description": "Give up waiting after this many minutes (default: 60)"
+                        }
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            {
+                "name": "unlock",
+                "description": "Force-release the session lock (.ink-running) left by a crashed session: git rm, commit, and push main. Use only when no session is running; returns not_locked if there is no lock.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        }
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            {
+                "name": "approve",
+                "description": "Merge the prose queued in Review/pending.md (require_approval) into Full_Book.md, credit the words to the current chapter, and commit. Returns nothing_pending if there is no queue.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        }
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            {
+                "name": "reject",
+                "description": "Discard the prose queued in Review/pending.md (require_approval) without touching Full_Book.md, and commit. Returns nothing_pending if there is no queue.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
                         }
                     },
                     "required": ["repo_path"]
@@ -127,13 +426,25 @@ fn tools_list() -> Value {
             },
             {
                 "name": "advance_chapter",
-                "description": "Advance to the next chapter. Verifies the next chapter outline file exists (returns needs_chapter_outline if missing), updates .ink-state.yml, and commits. Does NOT push.",
+                "description": "Advance to the next chapter. Verifies the next chapter outline file exists (returns needs_chapter_outline if missing, unless scaffold_outlines is set in Config.yml — then it is scaffolded from Outline.md), updates .ink-state.yml, and commits. Pushes only with push=true.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "repo_path": {
                             "type": "string",
                             "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        },
+                        "retrospective": {
+                            "type": "boolean",
+                            "description": "Append the finished unit's words, session count, and dates to Chapters material/_progress.md (default false)"
+                        },
+                        "push": {
+                            "type": "boolean",
+                            "description": "Push the advance commit, best-effort (default false: session-close pushes)"
                         }
                     },
                     "required": ["repo_path"]
@@ -141,7 +452,7 @@ fn tools_list() -> Value {
             },
             {
                 "name": "init",
-                "description": "Scaffold a new book repository with all required files and directories. Returns a JSON payload containing a `questions` array. IMPORTANT — after calling this tool you MUST: (1) Act as the interactive setup wizard: ask the user one question at a time — show the `hint` as context, show the available `options` for select-type questions, wait for the answer, then move on to the next question. Do not ask the next question until the current one is answered. (2) Once all answers are collected, extrapolate each brief answer into rich, detailed content — do not copy the raw answer verbatim; infer characters' deeper motivations, expand the world-building, flesh out the plot arc beats, enrich the prose style description, etc. (3) Read each template file first (they contain structured sections with `[...]` placeholders), then replace ONLY the placeholder sections with the extrapolated content. CRITICAL: preserve the template structure exactly — do not add, remove, or rename any section heading. File mapping: Q1–4 → Config.yml: read the file, then update ONLY these four lines in-place: `language:`, `target_length:` (pages×250), `words_per_session:` (pages×250), `chapter_count:` (ceil(target_length/3000)) — do NOT touch any other field, do NOT remove comments, do NOT rewrite the file from scratch; Q5–6 → Soul.md; Q7–8 → Characters.md; Q9–11 → Outline.md; Q12 → Lore.md; Q13 → Chapter_01.md. (4) After all files are written: git -C <repo_path> add -A && git -C <repo_path> commit -m 'init: populate global material from author Q&A' && git -C <repo_path> push origin main. Notify the author the book is ready.",
+                "description": "Scaffold a new book repository with all required files and directories. Returns a JSON payload containing a `questions` array. IMPORTANT — after calling this tool you MUST: (1) Act as the interactive setup wizard: ask the user one question at a time — show the `hint` as context, show the available `options` for select-type questions, wait for the answer, then move on to the next question. Do not ask the next question until the current one is answered. (2) Once all answers are collected, extrapolate each brief answer into rich, detailed content — do not copy the raw answer verbatim; infer characters' deeper motivations, expand the world-building, flesh out the plot arc beats, enrich the prose style description, etc. (3) Read each template file first (they contain structured sections with `[...]` placeholders), then replace ONLY the placeholder sections with the extrapolated content. CRITICAL: preserve the template structure exactly — do not add, remove, or rename any section heading. File mapping: Q1–4 → Config.yml: read the file, then update ONLY these four lines in-place: `language:`, `target_length:` (pages×words_per_page), `words_per_session:` (pages×words_per_page) — words_per_page is already in Config.yml (default 250), `chapter_count:` (ceil(target_length/3000)) — do NOT touch any other field, do NOT remove comments, do NOT rewrite the file from scratch; Q5–6 → Soul.md; Q7–8 → Characters.md; Q9–11 → Outline.md; Q12 → Lore.md; Q13 → Chapter_01.md. (4) After all files are written: git -C <repo_path> add -A && git -C <repo_path> commit -m 'init: populate global material from author Q&A' && git -C <repo_path> push origin main. Notify the author the book is ready.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -184,11 +495,32 @@ fn tools_list() -> Value {
                         "repo_path": {
                             "type": "string",
                             "description": "Absolute path to the book repository"
+                        },
+                        "story": {
+                            "type": "string",
+                            "description": "Story of an anthology to work on: Stories/<story> of the repository"
+                        },
+                        "ping": {
+                            "type": "boolean",
+                            "description": "Return only { chapter, pct_complete, lock, last_session_at, health } from a local cache — for frequent polling (default: false)"
                         }
                     },
                     "required": ["repo_path"]
                 }
             },
+            {
+                "name": "health",
+                "description": "Check that the server can work: reports its version and uptime, whether git is installed, and — with repo_path or a default repository — whether that path is a git work tree, holds a book, and has a session lock. Read-only and fast: no fetch, no writes. Status is healthy or unhealthy.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository (optional)"
+                        }
+                    }
+                }
+            },
             {
                 "name": "update_agents",
                 "description": "Refresh AGENTS.md (and CLAUDE.md/GEMINI.md if present) with the latest engine instructions embedded in this ink-gateway-mcp build. Commits and pushes. Idempotent.",
@@ -205,18 +537,54 @@ fn tools_list() -> Value {
             },
             {
                 "name": "doctor",
-                "description": "Validate the book repository: checks required files, Config.yml validity, git remote configuration and reachability, draft branch, and session lock state. Returns a list of named checks each with ok/detail. Run this before registering a cron job.",
+                "description": "Validate the book repository: checks required files, Config.yml validity, git remote configuration and reachability, current branch, draft branch, and session lock state. Returns a list of named checks each with ok/detail. Run this before registering a cron job. With fix=true, first returns the repo to main from a wrong branch, detached HEAD, or unfinished rebase/merge and resets an abandoned draft (unsaved commits are kept on an ink-rescue-* branch); skipped while a session runs.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "repo_path": {
                             "type": "string",
                             "description": "Absolute path to the book repository"
+                        },
+                        "fix": {
+                            "type": "boolean",
+                            "description": "Repair the branch state before checking (default false)"
                         }
                     },
                     "required": ["repo_path"]
                 }
             },
+            {
+                "name": "rollback",
+                "description": "Revert main and draft to the most recent ink-* snapshot tag (taken at the start of the last session) and force-push both, removing that session's prose, Summary.md entry, and Changelog entry. No prompt — only call this when the author asked to undo the last session.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        }
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            {
+                "name": "reset",
+                "description": "Wipe all book content (git history is kept) so the repository can be re-initialized with init. Destructive: refused unless confirmation is exactly the repository directory name, which the author must supply.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {
+                            "type": "string",
+                            "description": "Absolute path to the book repository"
+                        },
+                        "confirmation": {
+                            "type": "string",
+                            "description": "The repository directory name, typed by the author (same check as the CLI prompt)"
+                        }
+                    },
+                    "required": ["repo_path", "confirmation"]
+                }
+            },
             {
                 "name": "apply_format",
                 "description": "Apply format patches to Full_Book.md (title, author, missing chapter headings). Accepts a 'patch' object with optional 'prepend' string and 'insert_headings' array of {before_anchor, heading}. Commits and pushes.",
@@ -239,27 +607,224 @@ fn tools_list() -> Value {
     })
 }
 
+// ── Limits ──────────────────────────────────────────────────────────────────
+
+/// Guards against a runaway agent: the largest `prose` one call may send,
+/// and the mutating calls one repository accepts per minute. 0 disables.
+#[derive(Debug)]
+struct Limits {
+    max_prose_bytes: usize,
+    max_writes_per_minute: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_prose_bytes: 2 * 1024 * 1024,
+            max_writes_per_minute: 30,
+        }
+    }
+}
+
+static LIMITS: OnceLock<Limits> = OnceLock::new();
+
+fn limits() -> &'static Limits {
+    LIMITS.get_or_init(Limits::default)
+}
+
+/// The limits set on the command line or environment, over the defaults.
+fn parse_limits(args: &ServerArgs) -> Result<Limits, String> {
+    fn number<T: std::str::FromStr>(
+        flag: &str,
+        value: &Option<String>,
+    ) -> Result<Option<T>, String> {
+        value
+            .as_deref()
+            .map(|v| {
+                v.trim()
+                    .parse()
+                    .map_err(|_| format!("{flag} {v:?}: expected a whole number"))
+            })
+            .transpose()
+    }
+    let defaults = Limits::default();
+    Ok(Limits {
+        max_prose_bytes: number("--max-prose-bytes", &args.max_prose_bytes)?
+            .unwrap_or(defaults.max_prose_bytes),
+        max_writes_per_minute: number("--max-writes-per-minute", &args.max_writes_per_minute)?
+            .unwrap_or(defaults.max_writes_per_minute),
+    })
+}
+
+/// Refuse a `param` over the prose size limit before any work starts.
+fn check_size(param: &str, value: &str) -> Result<(), InkError> {
+    check_size_within(param, value, limits().max_prose_bytes)
+}
+
+fn check_size_within(param: &str, value: &str, limit: usize) -> Result<(), InkError> {
+    if limit > 0 && value.len() > limit {
+        return Err(InkError::PayloadTooLarge {
+            param: param.to_string(),
+            bytes: value.len(),
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// Start times of the mutating calls of the last minute, per repository.
+static WRITES: Mutex<BTreeMap<PathBuf, VecDeque<Instant>>> = Mutex::new(BTreeMap::new());
+
+/// Count a mutating call on `repo`, or refuse it when the last minute already
+/// holds `max_writes_per_minute` of them. Refused calls do not count.
+fn take_write_slot(repo: &Path) -> Result<(), InkError> {
+    let limit = limits().max_writes_per_minute;
+    if limit == 0 {
+        return Ok(());
+    }
+    let mut writes = WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    take_slot(
+        writes.entry(repo_key(repo)).or_default(),
+        limit,
+        Instant::now(),
+    )
+}
+
+/// `take_write_slot` on one repository's call `starts`, at `now`.
+fn take_slot(starts: &mut VecDeque<Instant>, limit: u32, now: Instant) -> Result<(), InkError> {
+    let window = Duration::from_secs(60);
+    while starts.front().is_some_and(|t| now - *t >= window) {
+        starts.pop_front();
+    }
+    if let Some(oldest) = starts.front().filter(|_| starts.len() >= limit as usize) {
+        return Err(InkError::RateLimited {
+            limit,
+            retry_after_seconds: (window - (now - *oldest)).as_secs().max(1),
+        });
+    }
+    starts.push_back(now);
+    Ok(())
+}
+
 // ── Tool dispatch ────────────────────────────────────────────────────────────
 
-fn call_tool(name: &str, args: &Value) -> Result<Value, String> {
-    let repo_path = args
-        .get("repo_path")
-        .and_then(|v| v.as_str())
-        .map(PathBuf::from)
-        .ok_or("Missing required parameter: repo_path")?;
+/// An `invalid_input` error for a required parameter the call left out.
+fn missing(param: &str) -> InkError {
+    InkError::InvalidInput(format!("Missing required parameter: {param}"))
+}
+
+/// The MCP client, as it named itself in `initialize`; every audited call
+/// records it as its caller.
+static CLIENT: OnceLock<String> = OnceLock::new();
+
+/// `name/version` from the `clientInfo` of `initialize` params.
+fn client_name(params: Option<&Value>) -> Option<String> {
+    let info = params?.get("clientInfo")?;
+    let name = info.get("name")?.as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(match info.get("version").and_then(|v| v.as_str()) {
+        Some(version) => format!("{}/{}", name, version),
+        None => name.to_string(),
+    })
+}
+
+/// When this server started, for `health`'s `uptime_seconds`.
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// `health`: whether this server can do its work — git on PATH and, when a
+/// repository is given or defaulted, that it is a git work tree. Reads only:
+/// no git command that writes, no network, and no audit entry.
+fn health(repo: Option<PathBuf>) -> Value {
+    let git = std::process::Command::new("git")
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .output();
+    let git = match git {
+        Ok(out) if out.status.success() => json!({
+            "ok": true,
+            "version": String::from_utf8_lossy(&out.stdout).trim(),
+        }),
+        Ok(out) => json!({ "ok": false, "error": String::from_utf8_lossy(&out.stderr).trim() }),
+        Err(e) => json!({ "ok": false, "error": format!("git not found: {e}") }),
+    };
+    let repo = repo.map(|repo| {
+        let work_tree = repo.is_dir()
+            && git::run_git(&repo, &["rev-parse", "--is-inside-work-tree"])
+                .is_ok_and(|o| o == "true");
+        let mut check = json!({
+            "path": repo,
+            "ok": work_tree,
+            "book": repo.join("Global Material").join("Config.yml").exists(),
+            "lock": repo.join(".ink-running").exists(),
+        });
+        if !work_tree {
+            check["error"] = json!(if repo.is_dir() {
+                "not a git work tree"
+            } else {
+                "no such directory"
+            });
+        }
+        check
+    });
+    let healthy = git["ok"] == true && repo.as_ref().is_none_or(|r| r["ok"] == true);
+    json!({
+        "status": if healthy { "healthy" } else { "unhealthy" },
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_seconds": STARTED.get().map_or(0, |t| t.elapsed().as_secs()),
+        "git": git,
+        "repo": repo,
+    })
+}
+
+fn call_tool(name: &str, args: &Value) -> anyhow::Result<Value> {
+    if name == "health" {
+        return Ok(health(tool_repo(args)));
+    }
+    let repo = tool_repo(args).ok_or_else(|| missing("repo_path"))?;
+    let repo_path = tool_book(repo.clone(), args)?;
 
     match name {
         "session_open" => {
-            let payload = context::session_open(&repo_path).map_err(|e| e.to_string())?;
-            serde_json::to_value(payload).map_err(|e| e.to_string())
+            // Stories share the working tree: one session in the collection at a time
+            let story = args.get("story").and_then(|v| v.as_str());
+            if let Some(other) = story.and_then(|slug| anthology::open_elsewhere(&repo, slug)) {
+                return Err(InkError::Busy {
+                    holder: format!("a session on story {} (close or unlock it first)", other),
+                }
+                .into());
+            }
+            let payload = context::session_open(&repo_path)?;
+            Ok(serde_json::to_value(payload)?)
         }
 
         "session_close" => {
-            let prose = args
+            let session_id = args
+                .get("session_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| missing("session_id"))?;
+            let input = args
                 .get("prose")
                 .and_then(|v| v.as_str())
-                .ok_or("Missing required parameter: prose")?;
+                .ok_or_else(|| missing("prose"))?;
+            check_size("prose", input)?;
+            let prose = match args.get("format").and_then(|v| v.as_str()) {
+                None | Some("full") => input.to_string(),
+                Some("patch") => maintenance::prose_from_patch(&repo_path, input)?,
+                Some(other) => {
+                    return Err(
+                        InkError::InvalidInput(format!("Unknown prose format: {other}")).into(),
+                    )
+                }
+            };
             let summary = args.get("summary").and_then(|v| v.as_str());
+            let force = args.get("force").and_then(|v| v.as_bool()) == Some(true);
+            if args.get("dry_run").and_then(|v| v.as_bool()) == Some(true) {
+                let preview =
+                    maintenance::preview_close(&repo_path, session_id, &prose, summary, force)?;
+                return Ok(serde_json::to_value(preview)?);
+            }
             let human_edits: Vec<String> = args
                 .get("human_edits")
                 .and_then(|v| v.as_array())
@@ -269,15 +834,68 @@ fn call_tool(name: &str, args: &Value) -> Result<Value, String> {
                         .collect()
                 })
                 .unwrap_or_default();
+            let beats_done: Vec<u32> = args
+                .get("beats_completed")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_u64().map(|n| n as u32))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let timeline_events: Vec<timeline::TimelineEvent> = match args.get("timeline_events") {
+                Some(v) => serde_json::from_value(v.clone()).map_err(|e| {
+                    InkError::InvalidInput(format!("Invalid timeline_events: {}", e))
+                })?,
+                None => vec![],
+            };
+            let new_terms: Vec<glossary::GlossaryEntry> = match args.get("new_terms") {
+                Some(v) => serde_json::from_value(v.clone())
+                    .map_err(|e| InkError::InvalidInput(format!("Invalid new_terms: {}", e)))?,
+                None => vec![],
+            };
+
+            let payload = maintenance::close_session(
+                &repo_path,
+                session_id,
+                &prose,
+                summary,
+                &human_edits,
+                &maintenance::StoryUpdates {
+                    beats_done,
+                    timeline_events,
+                    new_terms,
+                },
+                force,
+            )?;
+            Ok(serde_json::to_value(payload)?)
+        }
+
+        "complete" => maintenance::complete_session(&repo_path),
 
-            let payload = maintenance::close_session(&repo_path, prose, summary, &human_edits)
-                .map_err(|e| e.to_string())?;
-            serde_json::to_value(payload).map_err(|e| e.to_string())
+        "advance_chapter" => {
+            let retrospective = args
+                .get("retrospective")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let push = args.get("push").and_then(|v| v.as_bool()).unwrap_or(false);
+            maintenance::advance_chapter(&repo_path, retrospective, push)
         }
 
-        "complete" => maintenance::complete_session(&repo_path).map_err(|e| e.to_string()),
+        "kill" => {
+            let wait = args.get("wait").and_then(|v| v.as_bool()).unwrap_or(false);
+            let timeout_minutes = args
+                .get("timeout_minutes")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(60);
+            maintenance::request_kill(&repo_path, wait, timeout_minutes)
+        }
+
+        "unlock" => maintenance::unlock_session(&repo_path, true),
+
+        "approve" => maintenance::approve_pending(&repo_path),
 
-        "advance_chapter" => maintenance::advance_chapter(&repo_path).map_err(|e| e.to_string()),
+        "reject" => maintenance::reject_pending(&repo_path),
 
         "init" => {
             let title = args
@@ -288,30 +906,50 @@ fn call_tool(name: &str, args: &Value) -> Result<Value, String> {
                 .get("author")
                 .and_then(|v| v.as_str())
                 .unwrap_or("Unknown");
-            let payload = init::run_init(&repo_path, title, author).map_err(|e| e.to_string())?;
-            serde_json::to_value(payload).map_err(|e| e.to_string())
+            let payload = init::run_init(&repo_path, title, author)?;
+            Ok(serde_json::to_value(payload)?)
         }
 
         "seed" => {
-            let payload = init::run_seed(&repo_path).map_err(|e| e.to_string())?;
-            serde_json::to_value(payload).map_err(|e| e.to_string())
+            let payload = init::run_seed(&repo_path)?;
+            Ok(serde_json::to_value(payload)?)
         }
 
-        "status" => maintenance::book_status(&repo_path).map_err(|e| e.to_string()),
+        "status" => {
+            if args.get("ping").and_then(|v| v.as_bool()) == Some(true) {
+                let ping = maintenance::status_ping(&repo_path)?;
+                Ok(serde_json::to_value(ping)?)
+            } else {
+                maintenance::book_status(&repo_path)
+            }
+        }
+
+        "update_agents" => init::update_agents(&repo_path),
 
-        "update_agents" => init::update_agents(&repo_path).map_err(|e| e.to_string()),
+        "doctor" => {
+            let fix = args.get("fix").and_then(|v| v.as_bool()).unwrap_or(false);
+            maintenance::doctor(&repo_path, fix)
+        }
 
-        "doctor" => maintenance::doctor(&repo_path).map_err(|e| e.to_string()),
+        "rollback" => maintenance::rollback_latest(&repo_path),
+
+        "reset" => {
+            let confirmation = args
+                .get("confirmation")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| missing("confirmation"))?;
+            init::reset_book(&repo_path, confirmation)
+        }
 
         "apply_format" => {
             let patch = args
                 .get("patch")
                 .cloned()
                 .unwrap_or(serde_json::Value::Object(Default::default()));
-            book::apply_format_patch(&repo_path, patch).map_err(|e| e.to_string())
+            book::apply_format_patch(&repo_path, patch)
         }
 
-        _ => Err(format!("Unknown tool: {name}")),
+        _ => Err(InkError::InvalidInput(format!("Unknown tool: {name}")).into()),
     }
 }
 
@@ -319,9 +957,422 @@ fn call_tool(name: &str, args: &Value) -> Result<Value, String> {
 
 fn send(resp: &RpcResponse) {
     let line = serde_json::to_string(resp).expect("serialization cannot fail");
-    println!("{line}");
-    if let Err(e) = io::stdout().flush() {
-        eprintln!("ink-gateway-mcp: stdout flush error: {e}");
+    // One locked write per response: workers never interleave lines
+    let mut out = io::stdout().lock();
+    if let Err(e) = writeln!(out, "{line}").and_then(|_| out.flush()) {
+        eprintln!("ink-gateway-mcp: stdout write error: {e}");
+    }
+}
+
+// ── Request dispatch ────────────────────────────────────────────────────────
+
+/// Worker threads for read-only tools/call (status, doctor, dry runs), so they
+/// answer while a slow session_open is still running.
+const WORKERS: usize = 4;
+
+/// A tools/call request: JSON-RPC id, params, and its cancellation flag.
+type Job = (Value, Value, Arc<AtomicBool>);
+
+/// A repository's queue and the thread draining it.
+type RepoQueue = (mpsc::Sender<Job>, JoinHandle<()>);
+
+/// One mutex per repository (canonical path), held while a mutating tool runs
+/// and tried by the heartbeat thread before it commits.
+static REPO_LOCKS: Mutex<BTreeMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(BTreeMap::new());
+
+/// Per-repository queues for mutating tools: one thread per repo runs them in
+/// the order they arrived; different repos proceed in parallel.
+static REPO_QUEUES: Mutex<BTreeMap<PathBuf, RepoQueue>> = Mutex::new(BTreeMap::new());
+
+/// Cancellation flags of queued and running tools/call, keyed by JSON-RPC id.
+static IN_FLIGHT: Mutex<BTreeMap<String, Arc<AtomicBool>>> = Mutex::new(BTreeMap::new());
+
+fn repo_key(repo: &Path) -> PathBuf {
+    repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf())
+}
+
+fn repo_lock(repo: &Path) -> Arc<Mutex<()>> {
+    REPO_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(repo_key(repo))
+        .or_default()
+        .clone()
+}
+
+/// Tools that only read the repo. `kill` writes, but takes the command lock
+/// itself and only while writing, so it is not serialized here either.
+fn is_mutating(name: &str, args: &Value) -> bool {
+    match name {
+        "status" | "kill" | "health" => false,
+        "doctor" => args.get("fix").and_then(|v| v.as_bool()) == Some(true),
+        "session_close" => args.get("dry_run").and_then(|v| v.as_bool()) != Some(true),
+        _ => true,
+    }
+}
+
+/// Run a tool with the locking it needs: mutating tools hold the repo's mutex
+/// (against the heartbeat thread) and the local command lock shared with
+/// ink-cli (against other processes), failing fast if a CLI command holds it.
+fn run_tool(name: &str, args: &Value) -> anyhow::Result<Value> {
+    let (Some(repo), true) = (tool_repo(args), is_mutating(name, args)) else {
+        let result = call_tool(name, args);
+        if let (Ok(payload), Some(repo)) = (&result, tool_repo(args)) {
+            let book = tool_book(repo.clone(), args)?;
+            track_session(&mut heartbeats(), name, &repo, &book, payload);
+        }
+        return result;
+    };
+    take_write_slot(&repo)?;
+    let repo_mutex = repo_lock(&repo);
+    let _repo = repo_mutex.lock().unwrap_or_else(|e| e.into_inner());
+    let _command_lock = cmdlock::acquire(&repo, name)?;
+    let result = call_tool(name, args);
+    if let Ok(payload) = &result {
+        let book = tool_book(repo.clone(), args)?;
+        track_session(&mut heartbeats(), name, &repo, &book, payload);
+    }
+    result
+}
+
+fn handle_tool_call((id, params, cancel): Job) {
+    let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let args = params.get("arguments").unwrap_or(&Value::Null);
+
+    // Health probes come often and change nothing; they stay out of the log
+    let audit = tool_repo(args).filter(|_| name != "health").map(|repo| {
+        audit::Audit::start(&repo, "mcp", name, args)
+            .caller(CLIENT.get().cloned())
+            .request_id(id.to_string())
+    });
+    let started = Instant::now();
+    // A call cancelled while still queued never starts; one cancelled while
+    // running stops at its next checkpoint, which releases what it created.
+    let result = match refusal(&cancel, signals::interrupted()) {
+        Some(refused) => Err(refused.into()),
+        None => signals::with_cancellation(Arc::clone(&cancel), || run_tool(name, args)),
+    };
+    metrics::record(name, &result, started.elapsed());
+    if let Some(audit) = audit {
+        audit.finish(&result);
+    }
+
+    // Failures carry the CLI's error object, naming the tool
+    let (content, is_error) = match result {
+        Ok(result) => (result, false),
+        Err(e) => {
+            let (mut body, _) = errors::report(&e);
+            body["tool"] = json!(name);
+            (body, true)
+        }
+    };
+    let content_text =
+        serde_json::to_string_pretty(&content).unwrap_or_else(|_| content.to_string());
+
+    // Answered before it leaves IN_FLIGHT, which shutdown waits on
+    let key = id.to_string();
+    send(&RpcResponse::ok(
+        id,
+        json!({
+            "content": [{ "type": "text", "text": content_text }],
+            "isError": is_error
+        }),
+    ));
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&key);
+}
+
+/// Why a call is refused before it starts: the server is shutting down (calls
+/// still queued are refused, not started), or the client cancelled it while
+/// it waited.
+fn refusal(cancel: &AtomicBool, interrupted: bool) -> Option<InkError> {
+    let step = "start".to_string();
+    if interrupted {
+        Some(InkError::Interrupted { step })
+    } else if cancel.load(Ordering::SeqCst) {
+        Some(InkError::Cancelled { step })
+    } else {
+        None
+    }
+}
+
+/// A thread running jobs from `rx` one after another until every sender is gone.
+fn spawn_runner(rx: Arc<Mutex<mpsc::Receiver<Job>>>) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        let job = rx.lock().unwrap_or_else(|e| e.into_inner()).recv();
+        match job {
+            Ok(job) => handle_tool_call(job),
+            Err(_) => break, // stdin closed and queue drained
+        }
+    })
+}
+
+/// Shared pool for read-only tools.
+fn spawn_workers() -> (mpsc::Sender<Job>, Vec<JoinHandle<()>>) {
+    let (tx, rx) = mpsc::channel();
+    let rx = Arc::new(Mutex::new(rx));
+    let handles = (0..WORKERS)
+        .map(|_| spawn_runner(Arc::clone(&rx)))
+        .collect();
+    (tx, handles)
+}
+
+/// Queue a tools/call: mutating tools on their repo's queue, everything else
+/// (and calls without a repo_path, which fail validation) on the shared pool.
+fn dispatch(pool: &mpsc::Sender<Job>, job: Job) {
+    let name = job.1.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let args = job.1.get("arguments").unwrap_or(&Value::Null);
+    let queue = match tool_repo(args) {
+        Some(repo) if is_mutating(name, args) => {
+            let mut queues = REPO_QUEUES.lock().unwrap_or_else(|e| e.into_inner());
+            let (tx, _) = queues.entry(repo_key(&repo)).or_insert_with(|| {
+                let (tx, rx) = mpsc::channel();
+                (tx, spawn_runner(Arc::new(Mutex::new(rx))))
+            });
+            tx.clone()
+        }
+        _ => pool.clone(),
+    };
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(job.0.to_string(), Arc::clone(&job.2));
+    if queue.send(job).is_err() {
+        eprintln!("ink-gateway-mcp: tool runner stopped");
+    }
+}
+
+/// Flag the request named by a `notifications/cancelled`; unknown or already
+/// answered ids are ignored, as the protocol allows.
+fn cancel(params: Option<&Value>) {
+    let Some(request_id) = params.and_then(|p| p.get("requestId")) else {
+        return;
+    };
+    if let Some(flag) = IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&request_id.to_string())
+    {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Let in-flight and queued tool calls finish before exiting.
+fn drain(pool: mpsc::Sender<Job>, workers: Vec<JoinHandle<()>>) {
+    drop(pool);
+    let queues = std::mem::take(&mut *REPO_QUEUES.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut handles = workers;
+    for (_, (tx, handle)) in queues {
+        drop(tx);
+        handles.push(handle);
+    }
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+// ── Lock heartbeat ──────────────────────────────────────────────────────────
+
+/// A session opened through this server whose lock the heartbeat thread renews.
+struct Heartbeat {
+    /// Repository the tool was called on: the book, or the collection of an
+    /// anthology story — the working tree `run_tool` locks
+    root: PathBuf,
+    session_id: String,
+    last_renewed: Instant,
+}
+
+/// Sessions whose locks are kept alive, by book (canonical path).
+type Heartbeats = BTreeMap<PathBuf, Heartbeat>;
+
+static HEARTBEATS: Mutex<Heartbeats> = Mutex::new(BTreeMap::new());
+
+/// Held while the heartbeat thread renews locks, so shutdown waits for a
+/// commit under way. HEARTBEATS itself is never held across git.
+static RENEWING: Mutex<()> = Mutex::new(());
+
+const HEARTBEAT_TICK_SECONDS: u64 = 30;
+
+fn heartbeats() -> std::sync::MutexGuard<'static, Heartbeats> {
+    HEARTBEATS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Follow a successful tool call on `book` (of the repository `root`): a
+/// `session_open` starts renewing its lock, a `kill` stops (the engine is told
+/// to stop), and a `session_close` or `unlock` stops once the lock is no
+/// longer that session's — a close refused for its prose keeps the lock, and
+/// the beat.
+fn track_session(sessions: &mut Heartbeats, tool: &str, root: &Path, book: &Path, payload: &Value) {
+    let key = repo_key(book);
+    match tool {
+        "session_open" => {
+            if let Some(session_id) = payload["session_id"].as_str() {
+                sessions.insert(
+                    key,
+                    Heartbeat {
+                        root: root.to_path_buf(),
+                        session_id: session_id.to_string(),
+                        last_renewed: Instant::now(),
+                    },
+                );
+            }
+        }
+        "kill" => {
+            sessions.remove(&key);
+        }
+        "session_close" | "unlock" => {
+            let holder = context::read_lock(book).and_then(|lock| lock.session_id);
+            if sessions
+                .get(&key)
+                .is_some_and(|beat| holder.as_deref() != Some(beat.session_id.as_str()))
+            {
+                sessions.remove(&key);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `(book, root, session_id)` of the sessions whose last renewal is at least
+/// their `interval` ago, copied out so the renewals run without the map locked.
+fn due_sessions(
+    sessions: &Heartbeats,
+    interval: impl Fn(&Path) -> Duration,
+) -> Vec<(PathBuf, PathBuf, String)> {
+    sessions
+        .iter()
+        .filter(|(book, beat)| beat.last_renewed.elapsed() >= interval(book))
+        .map(|(book, beat)| (book.clone(), beat.root.clone(), beat.session_id.clone()))
+        .collect()
+}
+
+/// Renew the lock of `book`, unless a mutating tool (here or in another
+/// process) holds its repository `root`: None then, and the beat is retried
+/// next tick, so a heartbeat commit never lands mid-session_close (whose crash
+/// recovery keys off the draft head). The stories of an anthology share the
+/// collection's working tree, so theirs are locked at the root like tools are.
+fn renew(book: &Path, root: &Path, session_id: &str) -> Option<anyhow::Result<Value>> {
+    let repo_mutex = repo_lock(root);
+    let Ok(_repo) = repo_mutex.try_lock() else {
+        return None;
+    };
+    let Ok(_command_lock) = cmdlock::acquire(root, "heartbeat") else {
+        return None;
+    };
+    Some(context::heartbeat(book, Some(session_id)))
+}
+
+fn spawn_heartbeat() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(Duration::from_secs(HEARTBEAT_TICK_SECONDS));
+        let due = due_sessions(&heartbeats(), context::heartbeat_interval);
+        let _renewing = RENEWING.lock().unwrap_or_else(|e| e.into_inner());
+        for (book, root, session_id) in due {
+            let Some(result) = renew(&book, &root, &session_id) else {
+                continue;
+            };
+            let gone = match result {
+                Ok(r) => r["status"] == "no_lock" || r["status"] == "not_owner",
+                Err(e) => {
+                    tracing::warn!("Session heartbeat failed: {e:#}");
+                    false
+                }
+            };
+            // The session may have closed, or another opened, meanwhile
+            let mut sessions = heartbeats();
+            if sessions
+                .get(&book)
+                .is_none_or(|beat| beat.session_id != session_id)
+            {
+                continue;
+            }
+            if gone {
+                sessions.remove(&book);
+            } else if let Some(beat) = sessions.get_mut(&book) {
+                beat.last_renewed = Instant::now();
+            }
+        }
+    });
+}
+
+/// On SIGINT / SIGTERM, exit once every tool call has been answered: running
+/// ones stop at their next step checkpoint and undo their partial work (a
+/// `session_open` releases the lock it pushed), queued ones are refused, and
+/// a heartbeat commit under way finishes first.
+fn spawn_shutdown() {
+    std::thread::spawn(|| {
+        while !signals::interrupted() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        while !IN_FLIGHT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+        {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let _renewing = RENEWING.lock().unwrap_or_else(|e| e.into_inner());
+        tracing::info!("Interrupted — exiting");
+        std::process::exit(errors::EXIT_INTERRUPTED);
+    });
+}
+
+/// The response to `req` that is due at once: None for notifications and for
+/// `tools/call`, which is queued and answers when it has run.
+fn handle_request(req: RpcRequest, pool: &mpsc::Sender<Job>) -> Option<RpcResponse> {
+    let id = req.id.clone().unwrap_or(Value::Null);
+
+    match req.method.as_str() {
+        "initialize" => {
+            if let Some(client) = client_name(req.params.as_ref()) {
+                let _ = CLIENT.set(client);
+            }
+            Some(RpcResponse::ok(
+                id,
+                json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": "ink-gateway",
+                        "version": env!("CARGO_PKG_VERSION")
+                    }
+                }),
+            ))
+        }
+
+        // Liveness probe: answered at once, never queued behind a tool
+        "ping" => Some(RpcResponse::ok(id, json!({}))),
+
+        // Notification — no response
+        "notifications/initialized" => None,
+
+        // Notification — flag the request; it answers with a cancelled result
+        "notifications/cancelled" => {
+            cancel(req.params.as_ref());
+            None
+        }
+
+        "tools/list" => Some(RpcResponse::ok(id, tools_list())),
+
+        "tools/call" => {
+            dispatch(
+                pool,
+                (
+                    id,
+                    req.params.unwrap_or(Value::Null),
+                    Arc::new(AtomicBool::new(false)),
+                ),
+            );
+            None
+        }
+
+        _ => Some(RpcResponse::err(
+            id,
+            -32601,
+            format!("Method not found: {}", req.method),
+        )),
     }
 }
 
@@ -335,6 +1386,46 @@ fn main() {
         )
         .init();
 
+    let args = server_args().unwrap_or_else(|e| {
+        eprintln!("ink-gateway-mcp: {e}");
+        std::process::exit(2);
+    });
+    match parse_limits(&args) {
+        Ok(limits) => {
+            let _ = LIMITS.set(limits);
+        }
+        Err(e) => {
+            eprintln!("ink-gateway-mcp: {e}");
+            std::process::exit(2);
+        }
+    }
+    match args.repo.map(|p| validate_default_repo(&p)).transpose() {
+        Ok(Some(repo)) => {
+            let _ = DEFAULT_REPO.set(repo);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("ink-gateway-mcp: {e}");
+            std::process::exit(2);
+        }
+    }
+    if let Some(addr) = args.metrics_addr {
+        let token = metrics::token(args.metrics_token_file.as_deref().map(Path::new));
+        match token.and_then(|token| metrics::serve(&addr, token)) {
+            Ok(addr) => tracing::info!("Serving metrics on http://{addr}/metrics"),
+            Err(e) => {
+                eprintln!("ink-gateway-mcp: {e:#}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let _ = STARTED.set(Instant::now());
+    signals::install("ink-gateway-mcp");
+    spawn_shutdown();
+    spawn_heartbeat();
+    let (pool, workers) = spawn_workers();
+
     let stdin = io::stdin();
     for line in stdin.lock().lines() {
         let line = match line {
@@ -358,60 +1449,318 @@ fn main() {
             }
         };
 
-        let id = req.id.clone().unwrap_or(Value::Null);
+        if let Some(resp) = handle_request(req, &pool) {
+            send(&resp);
+        }
+    }
 
-        match req.method.as_str() {
-            "initialize" => {
-                send(&RpcResponse::ok(
-                    id,
-                    json!({
-                        "protocolVersion": "2024-11-05",
-                        "capabilities": { "tools": {} },
-                        "serverInfo": {
-                            "name": "ink-gateway",
-                            "version": env!("CARGO_PKG_VERSION")
-                        }
-                    }),
-                ));
-            }
+    drain(pool, workers);
+}
 
-            // Notification — no response
-            "notifications/initialized" => {}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            "tools/list" => {
-                send(&RpcResponse::ok(id, tools_list()));
-            }
+    fn hold_lock(book: &Path, session_id: &str) {
+        std::fs::write(
+            book.join(".ink-running"),
+            format!("started_at: 2026-03-30T07:15:00Z\nsession_id: {session_id}\n"),
+        )
+        .unwrap();
+    }
 
-            "tools/call" => {
-                let params = req.params.as_ref().unwrap_or(&Value::Null);
-                let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                let args = params.get("arguments").unwrap_or(&Value::Null);
-
-                let (content_text, is_error) = match call_tool(name, args) {
-                    Ok(result) => (
-                        serde_json::to_string_pretty(&result)
-                            .unwrap_or_else(|_| result.to_string()),
-                        false,
-                    ),
-                    Err(e) => (e, true),
-                };
-
-                send(&RpcResponse::ok(
-                    id,
-                    json!({
-                        "content": [{ "type": "text", "text": content_text }],
-                        "isError": is_error
-                    }),
-                ));
-            }
+    #[test]
+    fn heartbeats_are_tracked_per_book() {
+        let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut sessions = Heartbeats::new();
+        for (book, id) in [(a.path(), "s-a"), (b.path(), "s-b")] {
+            let payload = json!({ "session_id": id });
+            track_session(&mut sessions, "session_open", book, book, &payload);
+        }
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[&repo_key(a.path())].session_id, "s-a");
+        assert_eq!(sessions[&repo_key(b.path())].session_id, "s-b");
 
-            _ => {
-                send(&RpcResponse::err(
-                    id,
-                    -32601,
-                    format!("Method not found: {}", req.method),
-                ));
-            }
+        // Both are due once their interval has passed, neither before
+        assert!(due_sessions(&sessions, |_| Duration::from_secs(3600)).is_empty());
+        assert_eq!(due_sessions(&sessions, |_| Duration::ZERO).len(), 2);
+    }
+
+    #[test]
+    fn closing_one_book_keeps_the_other_beating() {
+        let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut sessions = Heartbeats::new();
+        for (book, id) in [(a.path(), "s-a"), (b.path(), "s-b")] {
+            let payload = json!({ "session_id": id });
+            track_session(&mut sessions, "session_open", book, book, &payload);
         }
+
+        // A close refused for its prose keeps the lock, and the beat
+        hold_lock(a.path(), "s-a");
+        track_session(
+            &mut sessions,
+            "session_close",
+            a.path(),
+            a.path(),
+            &json!({}),
+        );
+        assert_eq!(sessions.len(), 2);
+
+        std::fs::remove_file(a.path().join(".ink-running")).unwrap();
+        track_session(
+            &mut sessions,
+            "session_close",
+            a.path(),
+            a.path(),
+            &json!({}),
+        );
+        assert_eq!(
+            sessions.keys().collect::<Vec<_>>(),
+            vec![&repo_key(b.path())]
+        );
+
+        track_session(&mut sessions, "kill", b.path(), b.path(), &json!({}));
+        assert!(sessions.is_empty());
+    }
+
+    #[test]
+    fn story_heartbeat_waits_for_tools_on_the_collection() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (root, story) = (tmp.path(), tmp.path().join("Stories/first"));
+        std::fs::create_dir_all(&story).unwrap();
+        let mut sessions = Heartbeats::new();
+        let payload = json!({ "session_id": "s-1" });
+        track_session(&mut sessions, "session_open", root, &story, &payload);
+        let (book, lock_root, session_id) = due_sessions(&sessions, |_| Duration::ZERO).remove(0);
+        assert_eq!(lock_root, root);
+
+        // A tool running on the collection, in this server or another process
+        {
+            let repo_mutex = repo_lock(root);
+            let _repo = repo_mutex.lock().unwrap();
+            assert!(renew(&book, &lock_root, &session_id).is_none());
+        }
+        {
+            let _command_lock = cmdlock::acquire(root, "session_close").unwrap();
+            assert!(renew(&book, &lock_root, &session_id).is_none());
+        }
+
+        // Free: the beat runs (there is no lock left to renew here)
+        let result = renew(&book, &lock_root, &session_id).unwrap().unwrap();
+        assert_eq!(result["status"], "no_lock");
+    }
+
+    #[test]
+    fn client_name_comes_from_client_info() {
+        let params = json!({ "clientInfo": { "name": "claude-ai", "version": "0.1.0" } });
+        assert_eq!(
+            client_name(Some(&params)).as_deref(),
+            Some("claude-ai/0.1.0")
+        );
+        let unversioned = json!({ "clientInfo": { "name": "cron" } });
+        assert_eq!(client_name(Some(&unversioned)).as_deref(), Some("cron"));
+        assert_eq!(client_name(Some(&json!({}))), None);
+        assert_eq!(client_name(None), None);
+    }
+
+    #[test]
+    fn writes_over_the_limit_are_refused() {
+        let mut starts = VecDeque::new();
+        let now = Instant::now();
+        for _ in 0..3 {
+            take_slot(&mut starts, 3, now).unwrap();
+        }
+        let later = now + Duration::from_secs(20);
+        match take_slot(&mut starts, 3, later) {
+            Err(InkError::RateLimited {
+                limit,
+                retry_after_seconds,
+            }) => assert_eq!((limit, retry_after_seconds), (3, 40)),
+            other => panic!("expected rate_limited, got {other:?}"),
+        }
+        // Refused calls do not count
+        assert_eq!(starts.len(), 3);
+    }
+
+    #[test]
+    fn write_slots_free_up_once_the_minute_passes() {
+        let mut starts = VecDeque::new();
+        let now = Instant::now();
+        take_slot(&mut starts, 2, now).unwrap();
+        take_slot(&mut starts, 2, now + Duration::from_secs(30)).unwrap();
+        assert!(take_slot(&mut starts, 2, now + Duration::from_secs(59)).is_err());
+        // The first call leaves the window; the second still holds a slot
+        take_slot(&mut starts, 2, now + Duration::from_secs(60)).unwrap();
+        assert!(take_slot(&mut starts, 2, now + Duration::from_secs(61)).is_err());
+        assert_eq!(starts.len(), 2);
+    }
+
+    #[test]
+    fn oversized_prose_is_refused() {
+        assert!(check_size_within("prose", "four", 4).is_ok());
+        match check_size_within("prose", "five!", 4) {
+            Err(InkError::PayloadTooLarge {
+                param,
+                bytes,
+                limit,
+            }) => assert_eq!((param.as_str(), bytes, limit), ("prose", 5, 4)),
+            other => panic!("expected payload_too_large, got {other:?}"),
+        }
+        // 0 disables the cap
+        assert!(check_size_within("prose", &"x".repeat(10_000), 0).is_ok());
+    }
+
+    #[test]
+    fn book_tools_accept_a_story() {
+        let list = tool_definitions();
+        let takes_story = |name: &str| {
+            list["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|tool| tool["name"] == name)
+                .map(|tool| tool["inputSchema"]["properties"]["story"].is_object())
+                .unwrap()
+        };
+        for name in [
+            "session_open",
+            "session_close",
+            "unlock",
+            "status",
+            "complete",
+            "approve",
+            "reject",
+            "advance_chapter",
+        ] {
+            assert!(takes_story(name), "{name} has no story argument");
+        }
+    }
+
+    fn request(method: &str, id: u64) -> RpcRequest {
+        serde_json::from_value(json!({ "jsonrpc": "2.0", "id": id, "method": method })).unwrap()
+    }
+
+    #[test]
+    fn ping_is_answered_at_once() {
+        let (pool, queued) = mpsc::channel();
+        let resp = handle_request(request("ping", 7), &pool).unwrap();
+        assert_eq!((resp.id, resp.result), (json!(7), Some(json!({}))));
+        assert!(queued.try_recv().is_err());
+    }
+
+    #[test]
+    fn unknown_methods_are_not_found() {
+        let (pool, _queued) = mpsc::channel();
+        let resp = handle_request(request("tools/frobnicate", 8), &pool).unwrap();
+        assert_eq!(resp.error.unwrap().code, -32601);
+    }
+
+    #[test]
+    fn health_of_a_book_repository_is_healthy() {
+        let tmp = tempfile::tempdir().unwrap();
+        git::run_git(tmp.path(), &["init", "-q", "-b", "main"]).unwrap();
+        std::fs::create_dir_all(tmp.path().join("Global Material")).unwrap();
+        std::fs::write(tmp.path().join("Global Material/Config.yml"), "").unwrap();
+        hold_lock(tmp.path(), "s1");
+
+        let report = health(Some(tmp.path().to_path_buf()));
+        assert_eq!(report["status"], "healthy");
+        assert_eq!(report["git"]["ok"], true);
+        assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+        let repo = &report["repo"];
+        assert_eq!(
+            (&repo["ok"], &repo["book"], &repo["lock"]),
+            (&json!(true), &json!(true), &json!(true))
+        );
+        assert!(repo.get("error").is_none());
+    }
+
+    #[test]
+    fn health_of_a_directory_outside_git_is_unhealthy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let report = health(Some(tmp.path().to_path_buf()));
+        assert_eq!(report["status"], "unhealthy");
+        assert_eq!(report["repo"]["book"], false);
+        assert_eq!(report["repo"]["error"], "not a git work tree");
+    }
+
+    #[test]
+    fn health_of_a_missing_path_is_unhealthy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let report = health(Some(tmp.path().join("gone")));
+        assert_eq!(report["status"], "unhealthy");
+        assert_eq!(report["repo"]["error"], "no such directory");
+    }
+
+    #[test]
+    fn health_without_a_repository_checks_git_alone() {
+        let report = health(None);
+        assert_eq!(report["status"], "healthy");
+        assert!(report["repo"].is_null());
+        assert!(!is_mutating("health", &json!({})));
+    }
+
+    #[test]
+    fn queued_calls_are_refused_once_shutting_down() {
+        let cancel = AtomicBool::new(false);
+        assert!(refusal(&cancel, false).is_none());
+        let refused = refusal(&cancel, true).unwrap();
+        assert_eq!(
+            (refused.code(), refused.to_string().as_str()),
+            ("interrupted", "interrupted by signal before start")
+        );
+    }
+
+    #[test]
+    fn cancelled_queued_calls_never_start() {
+        let cancel = AtomicBool::new(true);
+        assert_eq!(refusal(&cancel, false).unwrap().code(), "cancelled");
+        // Shutting down takes precedence
+        assert_eq!(refusal(&cancel, true).unwrap().code(), "interrupted");
+    }
+
+    #[test]
+    fn a_call_stays_in_flight_until_answered() {
+        let in_flight = |key: &str| {
+            IN_FLIGHT
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains_key(key)
+        };
+        let id = json!("in-flight-test");
+        let (pool, queued) = mpsc::channel();
+        dispatch(
+            &pool,
+            (
+                id.clone(),
+                json!({ "name": "health", "arguments": {} }),
+                Arc::new(AtomicBool::new(false)),
+            ),
+        );
+        // Shutdown waits on this: the queued call is still owed an answer
+        assert!(in_flight(&id.to_string()));
+        handle_tool_call(queued.try_recv().unwrap());
+        assert!(!in_flight(&id.to_string()));
+    }
+
+    #[test]
+    fn reset_needs_the_authors_confirmation() {
+        let tmp = tempfile::tempdir().unwrap();
+        let args = json!({ "repo_path": tmp.path() });
+        let err = call_tool("reset", &args).unwrap_err();
+        assert_eq!(errors::code_of(&err), "invalid_input");
+        assert!(err.to_string().contains("confirmation"), "{err}");
+    }
+
+    #[test]
+    fn rollback_reset_and_doctor_fix_run_on_the_repository_queue() {
+        let args = json!({ "repo_path": "/book" });
+        assert!(is_mutating("rollback", &args));
+        assert!(is_mutating("reset", &args));
+        assert!(is_mutating(
+            "doctor",
+            &json!({ "repo_path": "/book", "fix": true })
+        ));
+        assert!(!is_mutating("doctor", &args));
     }
 }
@@ -0,0 +1,745 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use tracing::warn;
+
+use crate::config::{CommitMessages, Config};
+use crate::errors::InkError;
+use crate::git;
+use crate::import::unit_heading_re;
+use crate::journal;
+
+/// Publishing metadata of the book, written by `metadata set` and read by
+/// `compile`, `export --profile`, and session-open.
+pub const METADATA_FILE: &str = "Global Material/Metadata.yml";
+
+const METADATA_HEADER: &str = "# Book metadata — managed by `ink-cli metadata set`; read by \
+                               compile, export --profile, and session-open (read-only).\n";
+
+/// Keys of Metadata.yml, in file order.
+pub const KEYS: [&str; 10] = [
+    "title",
+    "subtitle",
+    "author",
+    "pen_name",
+    "authors",
+    "isbn",
+    "publisher",
+    "blurb",
+    "keywords",
+    "cover",
+];
+
+/// Global Material/Metadata.yml. Every key is optional; unknown keys are
+/// rejected so a typo does not silently drop a value from the exports.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Metadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    /// Legal name of the author.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Name the book is published under; the byline when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pen_name: Option<String>,
+    /// Every author of a co-written (or translated, illustrated, …) book, in
+    /// credit order; replaces `author` / `pen_name` when not empty. Managed by
+    /// `metadata add-author` / `remove-author`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<Author>,
+    /// ISBN-10 or ISBN-13, hyphens allowed; checked on `metadata set`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blurb: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Repo-relative path of the cover image (e.g. `Assets/cover.jpg`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+}
+
+/// One entry of `authors:`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Author {
+    /// Real name: the legal author, and the git identity with `email`.
+    pub name: String,
+    /// Name printed on the book instead of `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pen_name: Option<String>,
+    /// `author` (the default), `co-author`, `translator`, `illustrator`,
+    /// `editor`, or any other credit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// With `name`, a `Co-authored-by:` trailer on session commits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+impl Author {
+    /// The name printed on the book.
+    pub fn printed_name(&self) -> &str {
+        self.pen_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Whether the author is on the byline rather than in a credit line.
+    fn on_byline(&self) -> bool {
+        self.role.as_deref().is_none_or(|role| {
+            matches!(
+                role.trim().to_lowercase().as_str(),
+                "author" | "co-author" | "coauthor" | "co-writer"
+            )
+        })
+    }
+
+    /// `Translated by …` and the like, for the other roles.
+    fn credit(&self) -> Option<String> {
+        if self.on_byline() {
+            return None;
+        }
+        let role = self.role.as_deref().unwrap_or_default().trim();
+        Some(match role.to_lowercase().as_str() {
+            "translator" => format!("Translated by {}", self.printed_name()),
+            "illustrator" => format!("Illustrated by {}", self.printed_name()),
+            "editor" => format!("Edited by {}", self.printed_name()),
+            _ => format!("{}: {}", role, self.printed_name()),
+        })
+    }
+}
+
+/// `A`, `A and B`, `A, B and C`.
+fn join_names(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+impl Metadata {
+    /// Printed names of the authors on the byline: the `authors:` with an
+    /// author role, else the pen name or the author.
+    pub fn byline_names(&self) -> Vec<&str> {
+        if !self.authors.is_empty() {
+            return self
+                .authors
+                .iter()
+                .filter(|a| a.on_byline())
+                .map(Author::printed_name)
+                .collect();
+        }
+        self.pen_name
+            .as_deref()
+            .or(self.author.as_deref())
+            .into_iter()
+            .collect()
+    }
+
+    /// The name(s) on the title page, joined.
+    pub fn byline(&self) -> Option<String> {
+        let names = self.byline_names();
+        (!names.is_empty()).then(|| join_names(&names))
+    }
+
+    /// Title, subtitle, byline, other credits, and rule, in the layout
+    /// `apply-format` uses; None without a title.
+    pub fn title_page(&self) -> Option<String> {
+        let mut page = format!("# {}\n\n", self.title.as_deref()?);
+        if let Some(subtitle) = &self.subtitle {
+            page.push_str(&format!("*{}*\n\n", subtitle));
+        }
+        if let Some(byline) = self.byline() {
+            page.push_str(&format!("*by {}*\n\n", byline));
+        }
+        for credit in self.authors.iter().filter_map(Author::credit) {
+            page.push_str(&format!("*{}*\n\n", credit));
+        }
+        page.push_str("---\n\n");
+        Some(page)
+    }
+
+    /// `Name <email>` of every author with an email — real names, never
+    /// pen names.
+    pub fn git_identities(&self) -> Vec<String> {
+        self.authors
+            .iter()
+            .filter_map(|a| Some(format!("{} <{}>", a.name, a.email.as_deref()?)))
+            .collect()
+    }
+}
+
+/// Metadata.yml as written; None when the file does not exist.
+pub fn load(repo: &Path) -> Result<Option<Metadata>> {
+    let path = repo.join(METADATA_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", METADATA_FILE))?;
+    let metadata: Option<Metadata> = serde_yaml::from_str(&content)
+        .map_err(|e| InkError::ConfigInvalid(format!("{} is not valid: {}", METADATA_FILE, e)))?;
+    Ok(Some(metadata.unwrap_or_default()))
+}
+
+/// Metadata.yml with `title` and `author` falling back to Config.yml, for
+/// the commands that print them.
+pub fn effective(repo: &Path) -> Result<Metadata> {
+    let mut metadata = load(repo)?.unwrap_or_default();
+    if metadata.title.is_none() || metadata.author.is_none() {
+        if let Ok(config) = Config::load(repo) {
+            metadata.title = metadata.title.or(config.title);
+            // With `authors:`, Config.yml holds their joined byline
+            if metadata.authors.is_empty() {
+                metadata.author = metadata.author.or(config.author);
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+/// Whether `isbn` (hyphens and spaces ignored) is an ISBN-10 or ISBN-13
+/// with a valid check digit.
+fn valid_isbn(isbn: &str) -> bool {
+    let chars: Vec<char> = isbn.chars().filter(|c| *c != '-' && *c != ' ').collect();
+    match chars.len() {
+        10 => {
+            let mut sum = 0;
+            for (i, c) in chars.iter().enumerate() {
+                let digit = match (i, c) {
+                    (9, 'X' | 'x') => 10,
+                    _ => match c.to_digit(10) {
+                        Some(d) => d,
+                        None => return false,
+                    },
+                };
+                sum += (10 - i as u32) * digit;
+            }
+            sum % 11 == 0
+        }
+        13 => {
+            let mut sum = 0;
+            for (i, c) in chars.iter().enumerate() {
+                let Some(digit) = c.to_digit(10) else {
+                    return false;
+                };
+                sum += if i % 2 == 0 { digit } else { 3 * digit };
+            }
+            sum % 10 == 0
+        }
+        _ => false,
+    }
+}
+
+/// What is wrong with `metadata`: an invalid ISBN, a missing cover image,
+/// an author without a name, listed twice, or with an unusable email.
+pub fn problems(repo: &Path, metadata: &Metadata) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (i, author) in metadata.authors.iter().enumerate() {
+        if author.name.trim().is_empty() {
+            problems.push(format!("authors[{}] has no name", i));
+        } else if metadata.authors[..i].iter().any(|a| a.name == author.name) {
+            problems.push(format!("authors lists '{}' twice", author.name));
+        }
+        if let Some(email) = &author.email {
+            let usable = email.contains('@')
+                && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>');
+            if !usable {
+                problems.push(format!(
+                    "authors: email '{}' of '{}' is not an email address",
+                    email, author.name
+                ));
+            }
+        }
+    }
+    if let Some(isbn) = &metadata.isbn {
+        if !valid_isbn(isbn) {
+            problems.push(format!("isbn '{}' is not a valid ISBN-10 or ISBN-13", isbn));
+        }
+    }
+    if let Some(cover) = &metadata.cover {
+        if !repo.join(cover).is_file() {
+            problems.push(format!("cover '{}' does not exist", cover));
+        }
+    }
+    problems
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if !KEYS.contains(&key) {
+        return Err(InkError::InvalidInput(format!(
+            "unknown metadata key '{}' — keys: {}",
+            key,
+            KEYS.join(", ")
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// One key of the book's metadata, or all of them (null when unset).
+/// `title` and `author` fall back to Config.yml.
+pub fn get(repo: &Path, key: Option<&str>) -> Result<Value> {
+    let metadata = serde_json::to_value(effective(repo)?)?;
+    let value = |key: &str| metadata.get(key).cloned().unwrap_or(Value::Null);
+    match key {
+        Some(key) => {
+            check_key(key)?;
+            Ok(json!({ "key": key, "value": value(key) }))
+        }
+        None => Ok(Value::Object(
+            KEYS.iter().map(|k| (k.to_string(), value(k))).collect(),
+        )),
+    }
+}
+
+/// Set one Metadata.yml key — an empty value removes it; `keywords` takes a
+/// comma-separated list — rejecting an invalid ISBN or a cover image that
+/// does not exist. A new byline (`author`, `pen_name`) also replaces the
+/// author name in Config.yml, README.md, and the title page, as `rename`
+/// does. Commits; the push is best-effort.
+pub fn set(repo: &Path, key: &str, value: &str) -> Result<Value> {
+    check_key(key)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: "metadata set".to_string(),
+        }
+        .into());
+    }
+    let value = value.trim();
+    let before = load(repo)?.unwrap_or_default();
+    if key == "authors" || (!before.authors.is_empty() && matches!(key, "author" | "pen_name")) {
+        return Err(InkError::InvalidInput(format!(
+            "{} — use metadata add-author / remove-author",
+            match key {
+                "authors" => "authors is a list".to_string(),
+                _ => format!("{} lists the book's authors", METADATA_FILE),
+            }
+        ))
+        .into());
+    }
+    let mut fields = serde_json::to_value(&before)?;
+    let fields_map = fields
+        .as_object_mut()
+        .expect("Metadata serializes to an object");
+    if value.is_empty() {
+        fields_map.remove(key);
+    } else if key == "keywords" {
+        let mut keywords: Vec<&str> = Vec::new();
+        for keyword in value.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            if !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+        fields_map.insert(key.to_string(), json!(keywords));
+    } else {
+        fields_map.insert(key.to_string(), json!(value));
+    }
+    let mut metadata: Metadata = serde_json::from_value(fields)?;
+    let printed = Config::load(repo).ok().and_then(|c| c.author);
+    if key == "pen_name" && metadata.author.is_none() {
+        // The real name stays on record once a pen name takes the byline
+        metadata.author = printed
+            .clone()
+            .filter(|a| Some(a) != metadata.pen_name.as_ref());
+    }
+    let byline = metadata
+        .byline()
+        .filter(|b| matches!(key, "author" | "pen_name") && Some(b) != printed.as_ref());
+    if byline.is_some() {
+        check_authors_editable(repo, "metadata set")?;
+    }
+    // Only the key being set is checked: a stale cover is doctor's to report
+    let problems: Vec<String> = problems(repo, &metadata)
+        .into_iter()
+        .filter(|p| p.starts_with(key))
+        .collect();
+    if !problems.is_empty() {
+        return Err(InkError::InvalidInput(problems.join("; ")).into());
+    }
+    let new_value = serde_json::to_value(&metadata)?
+        .get(key)
+        .cloned()
+        .unwrap_or(Value::Null);
+    if metadata == before {
+        return Ok(json!({ "status": "unchanged", "key": key, "value": new_value }));
+    }
+
+    save(repo, &metadata)?;
+    let mut add_files = vec![METADATA_FILE.to_string()];
+    if let Some(byline) = &byline {
+        add_files.extend(crate::rename::write_names(repo, None, Some(byline))?);
+    }
+    let mut add = vec!["add", "--"];
+    add.extend(add_files.iter().map(String::as_str));
+    git::run_git(repo, &add).with_context(|| format!("Failed to git add {}", METADATA_FILE))?;
+    let files: Vec<String> = git::run_git(repo, &["diff", "--cached", "--name-only"])?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let verb = if value.is_empty() { "unset" } else { "set" };
+    git::run_git(
+        repo,
+        &["commit", "-m", &format!("metadata: {} {}", verb, key)],
+    )
+    .with_context(|| format!("Failed to commit {}", METADATA_FILE))?;
+    if let Err(e) = git::push_main(repo) {
+        warn!("git push skipped: {}", e);
+    }
+    Ok(json!({ "status": "updated", "key": key, "value": new_value, "files": files }))
+}
+
+/// Add an author to `authors:`, or update the entry with the same name. The
+/// first one added to a book with a single `author` lists that author first.
+/// The joined byline replaces the author name in Config.yml, README.md, and
+/// the manuscript's title page, in the same commit.
+pub fn add_author(repo: &Path, author: Author) -> Result<Value> {
+    check_authors_editable(repo, "metadata add-author")?;
+    let mut metadata = load(repo)?.unwrap_or_default();
+    let name = author.name.trim().to_string();
+    if name.is_empty() {
+        return Err(InkError::InvalidInput("an author needs a name".to_string()).into());
+    }
+    let given = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let author = Author {
+        name,
+        pen_name: given(author.pen_name),
+        role: given(author.role),
+        email: given(author.email),
+    };
+    if metadata.authors.is_empty() {
+        // The book's single author becomes the first of the list
+        let sole = metadata
+            .author
+            .take()
+            .or_else(|| Config::load(repo).ok().and_then(|c| c.author));
+        let pen_name = metadata.pen_name.take();
+        if let Some(sole) = sole.filter(|s| *s != author.name) {
+            metadata.authors.push(Author {
+                name: sole,
+                pen_name,
+                role: None,
+                email: None,
+            });
+        }
+    }
+    let status = match metadata.authors.iter_mut().find(|a| a.name == author.name) {
+        Some(entry) => {
+            *entry = author.clone();
+            "updated"
+        }
+        None => {
+            metadata.authors.push(author.clone());
+            "added"
+        }
+    };
+    let problems = problems(repo, &metadata)
+        .into_iter()
+        .filter(|p| p.starts_with("authors"))
+        .collect::<Vec<_>>();
+    if !problems.is_empty() {
+        return Err(InkError::InvalidInput(problems.join("; ")).into());
+    }
+    commit_authors(
+        repo,
+        &metadata,
+        status,
+        &format!(
+            "metadata: {} author {}",
+            if status == "added" { "add" } else { "update" },
+            author.name
+        ),
+    )
+}
+
+/// Remove the author named `name` from `authors:`; the last one stays.
+pub fn remove_author(repo: &Path, name: &str) -> Result<Value> {
+    check_authors_editable(repo, "metadata remove-author")?;
+    let mut metadata = load(repo)?.unwrap_or_default();
+    let Some(at) = metadata.authors.iter().position(|a| a.name == name.trim()) else {
+        let names: Vec<&str> = metadata.authors.iter().map(|a| a.name.as_str()).collect();
+        return Err(InkError::InvalidInput(format!(
+            "no author '{}' in {} (authors: {})",
+            name.trim(),
+            METADATA_FILE,
+            match names.is_empty() {
+                true => "none".to_string(),
+                false => names.join(", "),
+            }
+        ))
+        .into());
+    };
+    if metadata.authors.len() == 1 {
+        return Err(InkError::InvalidInput(format!(
+            "'{}' is the book's only author — add another before removing them",
+            name.trim()
+        ))
+        .into());
+    }
+    let removed = metadata.authors.remove(at);
+    commit_authors(
+        repo,
+        &metadata,
+        "removed",
+        &format!("metadata: remove author {}", removed.name),
+    )
+}
+
+/// Author changes rewrite the manuscript's title page: refused during a
+/// session and on a complete book, like `rename`.
+fn check_authors_editable(repo: &Path, action: &str) -> Result<()> {
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: action.to_string(),
+        }
+        .into());
+    }
+    if repo.join("COMPLETE").exists() {
+        return Err(InkError::BookComplete.into());
+    }
+    Ok(())
+}
+
+fn commit_authors(repo: &Path, metadata: &Metadata, status: &str, message: &str) -> Result<Value> {
+    save(repo, metadata)?;
+    let byline = metadata.byline();
+    let mut add_files = vec![METADATA_FILE.to_string()];
+    if let Some(byline) = &byline {
+        add_files.extend(crate::rename::write_names(repo, None, Some(byline))?);
+    }
+    let mut add = vec!["add", "--"];
+    add.extend(add_files.iter().map(String::as_str));
+    git::run_git(repo, &add).with_context(|| "Failed to git add the authors")?;
+    let files: Vec<String> = git::run_git(repo, &["diff", "--cached", "--name-only"])?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let status = match files.is_empty() {
+        true => "unchanged",
+        false => {
+            git::run_git(repo, &["commit", "-m", message])
+                .with_context(|| "Failed to commit the authors")?;
+            if let Err(e) = git::push_main(repo) {
+                warn!("git push skipped: {}", e);
+            }
+            status
+        }
+    };
+    Ok(json!({
+        "status": status,
+        "authors": metadata.authors,
+        "byline": byline,
+        "files": files,
+    }))
+}
+
+/// `commit_messages` with a `Co-authored-by:` trailer for each author of
+/// Metadata.yml with an email, after the configured ones. A Metadata.yml that
+/// does not parse adds none; it never blocks a commit.
+pub fn with_co_authors(repo: &Path, messages: &CommitMessages) -> CommitMessages {
+    let mut messages = messages.clone();
+    let identities = match load(repo) {
+        Ok(metadata) => metadata.map(|m| m.git_identities()).unwrap_or_default(),
+        Err(e) => {
+            warn!("{:#}", e);
+            Vec::new()
+        }
+    };
+    for identity in identities {
+        if !messages.co_authored_by.contains(&identity) {
+            messages.co_authored_by.push(identity);
+        }
+    }
+    messages
+}
+
+pub fn save(repo: &Path, metadata: &Metadata) -> Result<()> {
+    let yaml = serde_yaml::to_string(metadata)
+        .with_context(|| format!("Failed to serialize {}", METADATA_FILE))?;
+    let yaml = if yaml.trim() == "{}" {
+        String::new()
+    } else {
+        yaml
+    };
+    std::fs::write(
+        repo.join(METADATA_FILE),
+        format!("{}{}", METADATA_HEADER, yaml),
+    )
+    .with_context(|| format!("Failed to write {}", METADATA_FILE))
+}
+
+/// `book` with the metadata title page after its managed header, unless the
+/// text before its first chapter heading already has a `# ` title.
+pub fn with_title_page(book: &str, metadata: &Metadata) -> String {
+    let Some(page) = metadata.title_page() else {
+        return book.to_string();
+    };
+    let has_title = book
+        .lines()
+        .take_while(|l| !unit_heading_re().is_match(l.trim()))
+        .any(|l| l.starts_with("# "));
+    if has_title {
+        return book.to_string();
+    }
+    let after_header = match book.trim_start().starts_with("<!--") {
+        true => book.find("-->").map_or(0, |pos| pos + "-->".len()),
+        false => 0,
+    };
+    let rest = book[after_header..].trim_start_matches('\n');
+    match after_header {
+        0 => format!("{}{}", page, rest),
+        _ => format!("{}\n\n{}{}", &book[..after_header], page, rest),
+    }
+}
+
+/// A YAML metadata block for the head of a Markdown export, in the keys
+/// pandoc reads for EPUB and PDF output; None without metadata.
+pub fn yaml_block(metadata: &Metadata) -> Option<String> {
+    let text = |value: Option<&String>| value.map(|v| serde_yaml::Value::from(v.as_str()));
+    let list = |values: &[&str]| match values {
+        [] => None,
+        [one] => Some(serde_yaml::Value::from(*one)),
+        many => serde_yaml::to_value(many).ok(),
+    };
+    let keywords: Vec<&str> = metadata.keywords.iter().map(String::as_str).collect();
+    let block: serde_yaml::Mapping = [
+        ("title", text(metadata.title.as_ref())),
+        ("subtitle", text(metadata.subtitle.as_ref())),
+        ("author", list(&metadata.byline_names())),
+        ("publisher", text(metadata.publisher.as_ref())),
+        ("identifier", text(metadata.isbn.as_ref())),
+        ("description", text(metadata.blurb.as_ref())),
+        ("cover-image", text(metadata.cover.as_ref())),
+        ("keywords", serde_yaml::to_value(keywords).ok()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key.into(), value?)))
+    .filter(|(_, value): &(serde_yaml::Value, serde_yaml::Value)| {
+        value.as_sequence().is_none_or(|s| !s.is_empty())
+    })
+    .collect();
+    if block.is_empty() {
+        return None;
+    }
+    let yaml = serde_yaml::to_string(&block).ok()?;
+    Some(format!("---\n{}---\n", yaml))
+}
+
+/// `<meta>` name/content pairs for the head of an HTML export.
+pub fn html_meta(metadata: &Metadata) -> Vec<(&'static str, String)> {
+    let mut meta = Vec::new();
+    if let Some(byline) = metadata.byline() {
+        meta.push(("author", byline));
+    }
+    if let Some(blurb) = &metadata.blurb {
+        meta.push(("description", blurb.trim().to_string()));
+    }
+    if !metadata.keywords.is_empty() {
+        meta.push(("keywords", metadata.keywords.join(", ")));
+    }
+    if let Some(publisher) = &metadata.publisher {
+        meta.push(("publisher", publisher.clone()));
+    }
+    if let Some(isbn) = &metadata.isbn {
+        meta.push(("isbn", isbn.clone()));
+    }
+    meta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_page_and_isbn_checks() {
+        assert!(valid_isbn("978-0-306-40615-7"));
+        assert!(valid_isbn("0-306-40615-2"));
+        assert!(valid_isbn("0 8044 2957 X"));
+        assert!(!valid_isbn("978-0-306-40615-6"));
+        assert!(!valid_isbn("12345"));
+
+        let metadata = Metadata {
+            title: Some("The Salt Road".to_string()),
+            subtitle: Some("A Novel".to_string()),
+            author: Some("Jane Doe".to_string()),
+            pen_name: Some("J. D. Salt".to_string()),
+            keywords: vec!["sea".to_string(), "grief".to_string()],
+            ..Metadata::default()
+        };
+        let book = "<!-- ⚠ INK-GATEWAY:MANAGED — Do not edit. -->\n\n## Chapter 1\n\nThe tide.\n";
+        let titled = with_title_page(book, &metadata);
+        assert_eq!(
+            titled,
+            "<!-- ⚠ INK-GATEWAY:MANAGED — Do not edit. -->\n\n# The Salt Road\n\n*A Novel*\n\n\
+             *by J. D. Salt*\n\n---\n\n## Chapter 1\n\nThe tide.\n"
+        );
+        // A book that has its own title page keeps it
+        assert_eq!(with_title_page(&titled, &metadata), titled);
+        assert_eq!(
+            with_title_page(book, &Metadata::default()),
+            book.to_string()
+        );
+
+        let block = yaml_block(&metadata).unwrap();
+        assert!(block.starts_with("---\ntitle: The Salt Road\n"));
+        assert!(block.contains("author: J. D. Salt\n"));
+        assert!(block.contains("keywords:\n- sea\n- grief\n"));
+        assert_eq!(yaml_block(&Metadata::default()), None);
+
+        let typo: Result<Metadata, _> = serde_yaml::from_str("isbm: 123\n");
+        assert!(typo.is_err());
+    }
+
+    #[test]
+    fn co_written_books_print_pen_names_and_commit_as_real_names() {
+        let metadata: Metadata = serde_yaml::from_str(
+            "title: Tidewater\nauthor: ignored\nauthors:\n\
+             - name: Jane Doe\n  pen_name: J. D. Salt\n  email: jane@example.com\n\
+             - name: Tom Roe\n  role: co-author\n\
+             - name: Ana Ruiz\n  role: translator\n  email: ana@example.com\n",
+        )
+        .unwrap();
+        assert_eq!(metadata.byline().as_deref(), Some("J. D. Salt and Tom Roe"));
+        assert_eq!(
+            metadata.title_page().unwrap(),
+            "# Tidewater\n\n*by J. D. Salt and Tom Roe*\n\n*Translated by Ana Ruiz*\n\n---\n\n"
+        );
+        assert_eq!(
+            metadata.git_identities(),
+            ["Jane Doe <jane@example.com>", "Ana Ruiz <ana@example.com>"]
+        );
+        assert!(yaml_block(&metadata)
+            .unwrap()
+            .contains("author:\n- J. D. Salt\n- Tom Roe\n"));
+        assert_eq!(join_names(&["A", "B", "C"]), "A, B and C");
+
+        let tmp = tempfile::tempdir().unwrap();
+        let twice = Metadata {
+            authors: vec![metadata.authors[0].clone(), metadata.authors[0].clone()],
+            ..Metadata::default()
+        };
+        assert_eq!(
+            problems(tmp.path(), &twice),
+            ["authors lists 'Jane Doe' twice"]
+        );
+
+        std::fs::create_dir_all(tmp.path().join("Global Material")).unwrap();
+        save(tmp.path(), &metadata).unwrap();
+        let configured = CommitMessages {
+            co_authored_by: vec!["Ana Ruiz <ana@example.com>".to_string()],
+            ..CommitMessages::default()
+        };
+        let messages = with_co_authors(tmp.path(), &configured);
+        assert_eq!(
+            messages.co_authored_by,
+            ["Ana Ruiz <ana@example.com>", "Jane Doe <jane@example.com>"]
+        );
+    }
+}
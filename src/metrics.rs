@@ -0,0 +1,425 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::errors;
+
+/// Counters of this process since it started, served as `GET /metrics` in
+/// the Prometheus text format by `ink-gateway-mcp --metrics-addr` and
+/// `ink-cli schedule --metrics-addr`. Nothing is persisted: a restart starts
+/// from zero, which Prometheus counters allow for.
+#[derive(Debug, Default)]
+struct Metrics {
+    /// Runs per (command or MCP tool, outcome: `ok` or the error code).
+    commands: BTreeMap<(String, String), u64>,
+    /// Seconds spent and runs per command.
+    durations: BTreeMap<String, (f64, u64)>,
+    /// Commands that failed with `git_failed` / `git_timeout`.
+    git_failures: BTreeMap<&'static str, u64>,
+    sessions_opened: u64,
+    sessions_closed: u64,
+    words_written: u64,
+    last_close: Option<i64>,
+}
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics {
+    commands: BTreeMap::new(),
+    durations: BTreeMap::new(),
+    git_failures: BTreeMap::new(),
+    sessions_opened: 0,
+    sessions_closed: 0,
+    words_written: 0,
+    last_close: None,
+});
+
+fn with_metrics<T>(f: impl FnOnce(&mut Metrics) -> T) -> T {
+    f(&mut METRICS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+impl Metrics {
+    fn record<T>(&mut self, command: &str, result: &Result<T>, elapsed: Duration) {
+        let outcome = match result {
+            Ok(_) => "ok",
+            Err(e) => errors::code_of(e),
+        };
+        *self
+            .commands
+            .entry((command.to_string(), outcome.to_string()))
+            .or_default() += 1;
+        let (seconds, runs) = self.durations.entry(command.to_string()).or_default();
+        *seconds += elapsed.as_secs_f64();
+        *runs += 1;
+        if matches!(outcome, "git_failed" | "git_timeout") {
+            *self.git_failures.entry(outcome).or_default() += 1;
+        }
+    }
+
+    fn session_closed(&mut self, words: u32, at: i64) {
+        self.sessions_closed += 1;
+        self.words_written += u64::from(words);
+        self.last_close = Some(at);
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+            for (series, value) in samples {
+                let _ = writeln!(out, "{} {}", series, value);
+            }
+        };
+        family(
+            "ink_commands_total",
+            "counter",
+            "Commands and MCP tool calls run, by outcome (ok or the error code).",
+            self.commands
+                .iter()
+                .map(|((command, outcome), n)| {
+                    (
+                        format!(
+                            "ink_commands_total{{command=\"{}\",outcome=\"{}\"}}",
+                            label(command),
+                            outcome
+                        ),
+                        n.to_string(),
+                    )
+                })
+                .collect(),
+        );
+        family(
+            "ink_command_duration_seconds",
+            "summary",
+            "Wall-clock time of commands and MCP tool calls.",
+            self.durations
+                .iter()
+                .flat_map(|(command, (seconds, runs))| {
+                    let command = label(command);
+                    [
+                        (
+                            format!(
+                                "ink_command_duration_seconds_sum{{command=\"{}\"}}",
+                                command
+                            ),
+                            format!("{:.3}", seconds),
+                        ),
+                        (
+                            format!(
+                                "ink_command_duration_seconds_count{{command=\"{}\"}}",
+                                command
+                            ),
+                            runs.to_string(),
+                        ),
+                    ]
+                })
+                .collect(),
+        );
+        family(
+            "ink_git_failures_total",
+            "counter",
+            "Commands that failed on a git command, by code.",
+            ["git_failed", "git_timeout"]
+                .iter()
+                .map(|code| {
+                    (
+                        format!("ink_git_failures_total{{code=\"{}\"}}", code),
+                        self.git_failures.get(code).unwrap_or(&0).to_string(),
+                    )
+                })
+                .collect(),
+        );
+        family(
+            "ink_sessions_opened_total",
+            "counter",
+            "Sessions opened.",
+            vec![(
+                "ink_sessions_opened_total".to_string(),
+                self.sessions_opened.to_string(),
+            )],
+        );
+        family(
+            "ink_sessions_closed_total",
+            "counter",
+            "Sessions closed (published, or queued for approval).",
+            vec![(
+                "ink_sessions_closed_total".to_string(),
+                self.sessions_closed.to_string(),
+            )],
+        );
+        family(
+            "ink_words_written_total",
+            "counter",
+            "Words of prose in closed sessions.",
+            vec![(
+                "ink_words_written_total".to_string(),
+                self.words_written.to_string(),
+            )],
+        );
+        if let Some(at) = self.last_close {
+            family(
+                "ink_last_session_close_timestamp_seconds",
+                "gauge",
+                "When the last session closed, in Unix seconds.",
+                vec![(
+                    "ink_last_session_close_timestamp_seconds".to_string(),
+                    at.to_string(),
+                )],
+            );
+        }
+        out
+    }
+}
+
+/// A label value with `\`, `"`, and newlines escaped.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Count a finished command or MCP tool call.
+pub fn record<T>(command: &str, result: &Result<T>, elapsed: Duration) {
+    with_metrics(|m| m.record(command, result, elapsed));
+}
+
+pub fn session_opened() {
+    with_metrics(|m| m.sessions_opened += 1);
+}
+
+/// A session closed with `words` of prose; a replayed close is not counted.
+pub fn session_closed(words: u32) {
+    with_metrics(|m| m.session_closed(words, Utc::now().timestamp()));
+}
+
+/// The bearer token `/metrics` requires: the first line of `file`, else
+/// `INK_METRICS_TOKEN`; None serves it to anyone who can connect.
+pub fn token(file: Option<&Path>) -> Result<Option<String>> {
+    let token = match file {
+        Some(file) => std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        None => match std::env::var("INK_METRICS_TOKEN") {
+            Ok(token) => token.trim().to_string(),
+            Err(_) => return Ok(None),
+        },
+    };
+    if token.is_empty() {
+        return Err(errors::InkError::InvalidInput(match file {
+            Some(file) => format!("metrics token file {} is empty", file.display()),
+            None => "INK_METRICS_TOKEN is set but empty".to_string(),
+        })
+        .into());
+    }
+    Ok(Some(token))
+}
+
+/// Serve `GET /metrics` on `addr` (`host:port`) from a background thread,
+/// requiring `Authorization: Bearer <token>` when `token` is set. Binds
+/// before returning, so a taken port fails at startup; returns the bound
+/// address (port 0 picks one).
+pub fn serve(addr: &str, token: Option<String>) -> Result<SocketAddr> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+    let local = listener.local_addr()?;
+    if token.is_none() && !local.ip().is_loopback() {
+        tracing::warn!(
+            "Metrics on {} are served without a token to anyone who can connect",
+            local
+        );
+    }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = respond(stream, token.as_deref()) {
+                tracing::debug!("Metrics request failed: {}", e);
+            }
+        }
+    });
+    Ok(local)
+}
+
+/// Whether `presented` equals `token`, comparing every byte so the time
+/// taken does not reveal how much of a guess was right.
+fn token_matches(presented: &str, token: &str) -> bool {
+    presented.len() == token.len()
+        && presented
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The most a request line and its headers may take; anything longer is
+/// refused unread, so one client cannot stall or swell the listener.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Request line and bearer token of a request read from `input`, or None
+/// when it runs past `MAX_REQUEST_BYTES`.
+fn read_request(input: impl Read) -> std::io::Result<Option<(String, Option<String>)>> {
+    let mut reader = BufReader::new(input.take(MAX_REQUEST_BYTES));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Read the headers out so closing the socket does not reset it
+    let mut header = String::new();
+    let mut bearer = None;
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(str::to_string);
+            }
+        }
+        header.clear();
+    }
+    if reader.get_ref().limit() == 0 {
+        return Ok(None);
+    }
+    Ok(Some((request, bearer)))
+}
+
+fn respond(mut stream: TcpStream, token: Option<&str>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let Some((request, bearer)) = read_request(&stream)? else {
+        let body = "Request too large\n";
+        return write!(
+            stream,
+            "HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Type: text/plain\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+    };
+    let authorized =
+        token.is_none_or(|token| bearer.is_some_and(|b| token_matches(b.trim(), token)));
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        _ if !authorized => {
+            tracing::warn!(
+                "Refused a metrics request from {} without a valid token",
+                stream
+                    .peer_addr()
+                    .map_or("?".to_string(), |a| a.to_string())
+            );
+            ("401 Unauthorized", "Unauthorized\n".to_string())
+        }
+        (Some("GET"), Some("/metrics")) => ("200 OK", with_metrics(|m| m.render())),
+        _ => ("404 Not Found", "Not found: try GET /metrics\n".to_string()),
+    };
+    let challenge = if authorized {
+        ""
+    } else {
+        "WWW-Authenticate: Bearer realm=\"ink-gateway\"\r\n"
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n{}Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        challenge,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn recorded() -> String {
+        let mut metrics = Metrics::default();
+        metrics.record("session_open", &Ok(()), Duration::from_millis(1500));
+        let failed: Result<()> = Err(errors::InkError::GitFailed {
+            command: "git push".to_string(),
+            message: "rejected".to_string(),
+        }
+        .into());
+        metrics.record("session_close", &failed, Duration::from_millis(250));
+        metrics.session_closed(1200, 1_770_000_000);
+        metrics.render()
+    }
+
+    #[test]
+    fn commands_are_counted_by_outcome_and_timed() {
+        let text = recorded();
+        assert!(text.contains("ink_commands_total{command=\"session_open\",outcome=\"ok\"} 1\n"));
+        assert!(text
+            .contains("ink_commands_total{command=\"session_close\",outcome=\"git_failed\"} 1\n"));
+        assert!(text.contains("ink_command_duration_seconds_sum{command=\"session_open\"} 1.500\n"));
+    }
+
+    #[test]
+    fn git_failures_are_counted_by_code() {
+        let text = recorded();
+        assert!(text.contains("ink_git_failures_total{code=\"git_failed\"} 1\n"));
+        assert!(text.contains("ink_git_failures_total{code=\"git_timeout\"} 0\n"));
+    }
+
+    #[test]
+    fn closed_sessions_add_words_and_a_timestamp() {
+        let text = recorded();
+        assert!(text.contains("ink_words_written_total 1200\n"));
+        assert!(text.contains("# TYPE ink_last_session_close_timestamp_seconds gauge\n"));
+    }
+
+    fn get(addr: SocketAddr, path: &str, auth: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+            path, auth
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn metrics_are_served_with_the_token() {
+        let addr = serve("127.0.0.1:0", Some("s3cret".to_string())).unwrap();
+        let response = get(addr, "/metrics", "Authorization: Bearer s3cret\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("# TYPE ink_sessions_opened_total counter\n"));
+    }
+
+    #[test]
+    fn other_paths_are_not_found() {
+        let addr = serve("127.0.0.1:0", Some("s3cret".to_string())).unwrap();
+        assert!(get(addr, "/", "authorization: Bearer s3cret\r\n").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn a_missing_or_wrong_token_is_refused() {
+        let addr = serve("127.0.0.1:0", Some("s3cret".to_string())).unwrap();
+        assert!(get(addr, "/metrics", "").starts_with("HTTP/1.1 401"));
+        assert!(
+            get(addr, "/metrics", "Authorization: Bearer s3creT\r\n").starts_with("HTTP/1.1 401")
+        );
+    }
+
+    #[test]
+    fn request_reads_stop_at_the_size_cap() {
+        let request = "GET /metrics HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n";
+        let (line, bearer) = read_request(request.as_bytes()).unwrap().unwrap();
+        assert_eq!(line, "GET /metrics HTTP/1.1\r\n");
+        assert_eq!(bearer.as_deref(), Some("s3cret"));
+
+        // An endless request line is cut off at the cap and refused
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000));
+        assert!(read_request(long_line.as_bytes()).unwrap().is_none());
+        let long_headers = format!(
+            "GET /metrics HTTP/1.1\r\n{}\r\n",
+            "X-Pad: 1\r\n".repeat(1000)
+        );
+        assert!(read_request(long_headers.as_bytes()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+use crate::config::{Config, EmailConfig, NotificationsConfig, NtfyConfig};
+use crate::curl::SecretConfig;
+
+/// Something the author may want to hear about without watching the logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The book crossed 90% of `target_length`; `complete` can be run.
+    CompletionReady,
+    /// A push to origin failed; the session stays local until the next retry.
+    PushFailed,
+    /// session-open took over a lock left by a session that stopped renewing it.
+    StaleLockRecovered,
+}
+
+impl Event {
+    fn title(self) -> &'static str {
+        match self {
+            Event::CompletionReady => "Book ready for completion",
+            Event::PushFailed => "Push failed",
+            Event::StaleLockRecovered => "Stale session lock recovered",
+        }
+    }
+
+    /// ntfy priority: failures stand out, milestones do not.
+    fn priority(self) -> &'static str {
+        match self {
+            Event::PushFailed => "high",
+            Event::CompletionReady | Event::StaleLockRecovered => "default",
+        }
+    }
+
+    fn enabled(self, config: &NotificationsConfig) -> bool {
+        match self {
+            Event::CompletionReady => config.on.completion_ready,
+            Event::PushFailed => config.on.push_failed,
+            Event::StaleLockRecovered => config.on.stale_lock_recovered,
+        }
+    }
+}
+
+/// Send `event` through every channel configured in Config.yml. Best-effort:
+/// a notification must never fail the command that triggered it, so problems
+/// (unreadable config, curl errors) are only logged.
+pub fn send(repo: &Path, event: Event, message: &str) {
+    let Some(config) = Config::load(repo).ok().and_then(|c| c.notifications) else {
+        return;
+    };
+    if !event.enabled(&config) {
+        return;
+    }
+    let book = crate::init::repo_name(repo);
+    let title = format!("{} — {}", event.title(), book);
+    if let Some(ntfy) = &config.ntfy {
+        match send_ntfy(ntfy, event, &title, message) {
+            Ok(()) => info!("Sent ntfy notification: {}", event.title()),
+            Err(e) => warn!("ntfy notification failed: {:#}", e),
+        }
+    }
+    if let Some(email) = &config.email {
+        match send_email(email, &title, message) {
+            Ok(()) => info!("Sent email notification: {}", event.title()),
+            Err(e) => warn!("Email notification failed: {:#}", e),
+        }
+    }
+}
+
+fn send_ntfy(ntfy: &NtfyConfig, event: Event, title: &str, message: &str) -> Result<()> {
+    let token = match &ntfy.token_env {
+        Some(var) => Some(
+            std::env::var(var)
+                .with_context(|| format!("ntfy token variable {} is not set", var))?,
+        ),
+        None => None,
+    };
+    let (cmd, _secrets) = ntfy_command(ntfy, event, title, token.as_deref())?;
+    run_curl(cmd, message)
+}
+
+/// The ntfy request; the token goes to curl through a `SecretConfig`, never
+/// its arguments. The config must outlive the command.
+fn ntfy_command(
+    ntfy: &NtfyConfig,
+    event: Event,
+    title: &str,
+    token: Option<&str>,
+) -> Result<(Command, Option<SecretConfig>)> {
+    let url = format!("{}/{}", ntfy.server.trim_end_matches('/'), ntfy.topic);
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "--fail", "-X", "POST", &url])
+        .args(["-H", &format!("Title: {}", title)])
+        .args(["-H", &format!("Priority: {}", event.priority())]);
+    let secrets = match token {
+        Some(token) => {
+            let secrets =
+                SecretConfig::new(&[("header", &format!("Authorization: Bearer {}", token))])?;
+            cmd.arg("-K").arg(secrets.path());
+            Some(secrets)
+        }
+        None => None,
+    };
+    cmd.args(["--data-binary", "@-"]);
+    Ok((cmd, secrets))
+}
+
+fn send_email(email: &EmailConfig, subject: &str, message: &str) -> Result<()> {
+    let credentials = match &email.username_env {
+        Some(user_var) => {
+            let user = std::env::var(user_var)
+                .with_context(|| format!("SMTP username variable {} is not set", user_var))?;
+            let password = std::env::var(&email.password_env).with_context(|| {
+                format!("SMTP password variable {} is not set", email.password_env)
+            })?;
+            Some(format!("{}:{}", user, password))
+        }
+        None => None,
+    };
+    let (cmd, _secrets) = email_command(email, credentials.as_deref())?;
+    run_curl(cmd, &email_body(email, subject, message))
+}
+
+/// The SMTP upload; `user:password` goes to curl through a `SecretConfig`,
+/// never its arguments. The config must outlive the command.
+fn email_command(
+    email: &EmailConfig,
+    credentials: Option<&str>,
+) -> Result<(Command, Option<SecretConfig>)> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "--ssl-reqd", "--url", &email.smtp_url])
+        .args(["--mail-from", &email.from]);
+    for rcpt in &email.to {
+        cmd.args(["--mail-rcpt", rcpt]);
+    }
+    let secrets = match credentials {
+        Some(credentials) => {
+            let secrets = SecretConfig::new(&[("user", credentials)])?;
+            cmd.arg("-K").arg(secrets.path());
+            Some(secrets)
+        }
+        None => None,
+    };
+    cmd.args(["--upload-file", "-"]);
+    Ok((cmd, secrets))
+}
+
+/// RFC 5322 message curl uploads as-is: headers, blank line, text.
+fn email_body(email: &EmailConfig, subject: &str, message: &str) -> String {
+    format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+        email.from,
+        email.to.join(", "),
+        subject,
+        chrono::Local::now().to_rfc2822(),
+        message.replace('\n', "\r\n")
+    )
+}
+
+fn run_curl(mut cmd: Command, input: &str) -> Result<()> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| "Failed to spawn curl")?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .with_context(|| "Failed to send notification to curl")?;
+    let out = child
+        .wait_with_output()
+        .with_context(|| "Failed to wait for curl")?;
+    if !out.status.success() {
+        bail!("{}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_default_on_and_email_has_headers() {
+        let config: NotificationsConfig = serde_yaml::from_str(
+            "email:\n  smtp_url: smtps://smtp.example.com:465\n  from: ink@example.com\n  to: [a@example.com, b@example.com]\non:\n  push_failed: false\n",
+        )
+        .unwrap();
+        assert!(Event::CompletionReady.enabled(&config));
+        assert!(Event::StaleLockRecovered.enabled(&config));
+        assert!(!Event::PushFailed.enabled(&config));
+
+        let body = email_body(
+            config.email.as_ref().unwrap(),
+            "Push failed",
+            "line 1\nline 2",
+        );
+        assert!(body.starts_with("From: ink@example.com\r\nTo: a@example.com, b@example.com\r\n"));
+        assert!(body.contains("Subject: Push failed\r\n"));
+        assert!(body.ends_with("\r\n\r\nline 1\r\nline 2\r\n"));
+    }
+
+    #[test]
+    fn secrets_stay_out_of_curl_arguments() {
+        let args = |cmd: &Command| -> String {
+            cmd.get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let config: NotificationsConfig = serde_yaml::from_str(
+            "ntfy:\n  topic: my-book\n  token_env: NTFY_TOKEN\n\
+             email:\n  smtp_url: smtps://smtp.example.com:465\n  from: ink@example.com\n  to: [a@example.com]\n",
+        )
+        .unwrap();
+
+        let ntfy = config.ntfy.as_ref().unwrap();
+        let (cmd, secrets) =
+            ntfy_command(ntfy, Event::PushFailed, "Push failed", Some("tk_s3cret")).unwrap();
+        let secrets = secrets.unwrap();
+        assert!(!args(&cmd).contains("tk_s3cret"), "{}", args(&cmd));
+        assert!(args(&cmd).contains(&format!("-K {}", secrets.path().display())));
+        assert!(std::fs::read_to_string(secrets.path())
+            .unwrap()
+            .contains("Authorization: Bearer tk_s3cret"));
+
+        let email = config.email.as_ref().unwrap();
+        let (cmd, secrets) = email_command(email, Some("ada:hunter2")).unwrap();
+        assert!(!args(&cmd).contains("hunter2"), "{}", args(&cmd));
+        assert!(std::fs::read_to_string(secrets.unwrap().path())
+            .unwrap()
+            .contains("user = \"ada:hunter2\""));
+        let (_, none) = email_command(email, None).unwrap();
+        assert!(none.is_none());
+    }
+}
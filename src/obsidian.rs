@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::context::FileContent;
+
+/// Index note written at the repo root in Obsidian mode.
+pub const INDEX_NOTE: &str = "Index.md";
+
+/// Directories never searched for notes: git and Obsidian internals, binaries,
+/// and local engine state.
+const SKIP_DIRS: &[&str] = &[".git", ".obsidian", ".trash", ".ink", "Assets"];
+
+/// Managed files whose links resolve but whose text is never inlined as a
+/// note: the manuscript, review, and session records already have their own
+/// place in the payload (or none on purpose).
+const NOT_NOTES: &[&str] = &[
+    "Current version/",
+    "Review/",
+    "Changelog/",
+    "Chapters material/",
+    INDEX_NOTE,
+];
+
+/// Obsidian writes these on every click; committed, they would show up as
+/// human edits at each session-open.
+const GITIGNORE_LINES: &[&str] = &[
+    ".obsidian/workspace.json",
+    ".obsidian/workspace-mobile.json",
+    ".obsidian/cache",
+    ".trash/",
+];
+
+/// The `[[wiki-links]]` of the Global Material as the engine sees them: every
+/// target resolved to its file, and the text of the linked notes that are not
+/// already in `global_material`.
+#[derive(Debug, Default, Serialize)]
+pub struct WikiLinks {
+    /// Link target as written (`Mara`, `Places/Old Town`) → repo-relative file.
+    pub resolved: BTreeMap<String, String>,
+    /// Targets no note in the vault matches, in first-seen order.
+    pub unresolved: Vec<String>,
+    /// Linked notes, followed transitively, by repo-relative path.
+    pub notes: Vec<FileContent>,
+}
+
+fn link_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // [[target]], [[target|alias]], [[target#heading]]; embeds (![[…]]) too
+    RE.get_or_init(|| Regex::new(r"\[\[([^\]\|#\^]+)[^\]]*\]\]").unwrap())
+}
+
+/// Link targets in `text`, in order, without duplicates. Links to
+/// attachments (`cover.png`) are left out; `.md` is implied.
+fn wiki_links(text: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for caps in link_re().captures_iter(text) {
+        let target = caps[1].trim();
+        let target = target.strip_suffix(".md").unwrap_or(target);
+        let attachment = Path::new(target)
+            .extension()
+            .is_some_and(|ext| ext.len() <= 4 && !ext.to_string_lossy().contains(' '));
+        if !target.is_empty() && !attachment && !targets.iter().any(|t| t == target) {
+            targets.push(target.to_string());
+        }
+    }
+    targets
+}
+
+/// Every Markdown file in the vault, repo-relative with `/`, sorted.
+fn vault_notes(repo: &Path) -> Vec<String> {
+    fn walk(dir: &Path, rel: &str, out: &mut Vec<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = format!("{}{}", rel, name);
+            if entry.path().is_dir() {
+                if !SKIP_DIRS.contains(&name.as_str()) {
+                    walk(&entry.path(), &format!("{}/", path), out);
+                }
+            } else if name.ends_with(".md") {
+                out.push(path);
+            }
+        }
+    }
+    let mut notes = Vec::new();
+    walk(repo, "", &mut notes);
+    notes.sort();
+    notes
+}
+
+/// Obsidian's rule: a bare name matches a note of that name anywhere in the
+/// vault (case-insensitive, shortest path first); a name with `/` matches the
+/// end of a path.
+fn resolve<'a>(notes: &'a [String], target: &str) -> Option<&'a String> {
+    let wanted = format!("{}.md", target.trim_start_matches('/')).to_lowercase();
+    notes
+        .iter()
+        .filter(|note| {
+            let note = note.to_lowercase();
+            note == wanted || note.ends_with(&format!("/{}", wanted))
+        })
+        .min_by_key(|note| (note.matches('/').count(), note.len()))
+}
+
+/// Resolve the wiki-links of `material` (Global Material, names relative to
+/// it) and of every note they reach.
+pub fn resolve_links(repo: &Path, material: &[FileContent]) -> Result<WikiLinks> {
+    let notes = vault_notes(repo);
+    let mut links = WikiLinks::default();
+    let loaded: Vec<String> = material
+        .iter()
+        .map(|f| format!("Global Material/{}", f.filename))
+        .collect();
+    let mut queue: Vec<String> = material.iter().map(|f| f.content.clone()).collect();
+    while let Some(text) = queue.pop() {
+        for target in wiki_links(&text) {
+            if links.resolved.contains_key(&target) || links.unresolved.contains(&target) {
+                continue;
+            }
+            let Some(path) = resolve(&notes, &target) else {
+                links.unresolved.push(target);
+                continue;
+            };
+            links.resolved.insert(target, path.clone());
+            let inline = !loaded.contains(path)
+                && !NOT_NOTES.iter().any(|p| path.starts_with(p))
+                && !links.notes.iter().any(|n| &n.filename == path);
+            if inline {
+                let content = std::fs::read_to_string(repo.join(path))
+                    .with_context(|| format!("Failed to read linked note {}", path))?;
+                queue.push(content.clone());
+                links.notes.push(FileContent {
+                    filename: path.clone(),
+                    content,
+                });
+            }
+        }
+    }
+    links.notes.sort_by(|a, b| a.filename.cmp(&b.filename));
+    Ok(links)
+}
+
+/// `[[path/without .md|label]]` for a repo-relative note.
+fn link_to(path: &str) -> String {
+    let target = path.strip_suffix(".md").unwrap_or(path);
+    let label = target
+        .rsplit('/')
+        .next()
+        .unwrap_or(target)
+        .replace('_', " ");
+    format!("[[{}|{}]]", target, label)
+}
+
+/// Write `Index.md`, a note linking every managed file grouped by folder, and
+/// git-ignore Obsidian's per-device workspace files. Returns the files written.
+pub fn write_index(repo: &Path) -> Result<Vec<String>> {
+    let notes = vault_notes(repo);
+    let mut index = String::from(
+        "<!-- ⚠ INK-GATEWAY:MANAGED — regenerated at every session-close; edits are overwritten. -->\n\n\
+         # Index\n",
+    );
+    for (heading, dir) in [
+        ("Book", "Current version/"),
+        ("Review", "Review/"),
+        ("Global Material", "Global Material/"),
+        ("Chapters", "Chapters material/"),
+        ("Changelog", "Changelog/"),
+    ] {
+        let mut in_dir: Vec<&String> = notes.iter().filter(|n| n.starts_with(dir)).collect();
+        if in_dir.is_empty() {
+            continue;
+        }
+        if dir == "Changelog/" {
+            // Newest session first
+            in_dir.reverse();
+        }
+        index.push_str(&format!("\n## {}\n\n", heading));
+        for note in in_dir {
+            index.push_str(&format!("- {}\n", link_to(note)));
+        }
+    }
+    std::fs::write(repo.join(INDEX_NOTE), &index)
+        .with_context(|| format!("Failed to write {}", INDEX_NOTE))?;
+    let mut written = vec![INDEX_NOTE.to_string()];
+
+    let gitignore = repo.join(".gitignore");
+    let mut ignore = std::fs::read_to_string(&gitignore).unwrap_or_default();
+    let missing: Vec<&str> = GITIGNORE_LINES
+        .iter()
+        .copied()
+        .filter(|line| !ignore.lines().any(|l| l.trim() == *line))
+        .collect();
+    if !missing.is_empty() {
+        if !ignore.is_empty() && !ignore.ends_with('\n') {
+            ignore.push('\n');
+        }
+        ignore.push_str("# Obsidian per-device state\n");
+        for line in missing {
+            ignore.push_str(line);
+            ignore.push('\n');
+        }
+        std::fs::write(&gitignore, ignore).with_context(|| "Failed to write .gitignore")?;
+        written.push(".gitignore".to_string());
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for (rel, content) in [
+            (
+                "Global Material/Characters.md",
+                "[[Mara|our heroine]] fears [[the conclave#Rites]]. ![[cover.png]] [[Nobody]]",
+            ),
+            ("Global Material/Lore.md", "See [[Characters]]."),
+            ("Notes/People/Mara.md", "Sister of [[Ilse]]."),
+            ("Notes/Ilse.md", "Back to [[Mara]]."),
+            ("Notes/The Conclave.md", "An order."),
+            ("Current version/Full_Book.md", "prose"),
+            (".obsidian/Mara.md", "not a note"),
+        ] {
+            std::fs::create_dir_all(repo.join(rel).parent().unwrap()).unwrap();
+            std::fs::write(repo.join(rel), content).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn wiki_links_resolve_across_the_vault() {
+        let tmp = vault();
+        let repo = tmp.path();
+        let material = ["Characters.md", "Lore.md"].map(|f| FileContent {
+            filename: f.to_string(),
+            content: std::fs::read_to_string(repo.join("Global Material").join(f)).unwrap(),
+        });
+
+        let links = resolve_links(repo, &material).unwrap();
+        assert_eq!(links.resolved["Mara"], "Notes/People/Mara.md");
+        assert_eq!(links.resolved["the conclave"], "Notes/The Conclave.md");
+        assert_eq!(links.resolved["Ilse"], "Notes/Ilse.md");
+        assert_eq!(
+            links.resolved["Characters"],
+            "Global Material/Characters.md"
+        );
+        assert_eq!(links.unresolved, ["Nobody"]);
+        let inlined: Vec<&str> = links.notes.iter().map(|n| n.filename.as_str()).collect();
+        assert_eq!(
+            inlined,
+            [
+                "Notes/Ilse.md",
+                "Notes/People/Mara.md",
+                "Notes/The Conclave.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn index_links_the_book_and_its_material() {
+        let tmp = vault();
+        let repo = tmp.path();
+        assert_eq!(write_index(repo).unwrap(), [INDEX_NOTE, ".gitignore"]);
+        let index = std::fs::read_to_string(repo.join(INDEX_NOTE)).unwrap();
+        assert!(index.contains("## Book\n\n- [[Current version/Full_Book|Full Book]]\n"));
+        assert!(index.contains("- [[Global Material/Lore|Lore]]"));
+        assert!(!index.contains("Notes/"));
+    }
+
+    #[test]
+    fn index_adds_its_ignore_lines_once() {
+        let tmp = vault();
+        let repo = tmp.path();
+        write_index(repo).unwrap();
+        assert_eq!(write_index(repo).unwrap(), [INDEX_NOTE]);
+    }
+}
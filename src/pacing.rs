@@ -0,0 +1,193 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::book::{is_scene_break, strip_html_comments};
+use crate::chapters::act_section;
+use crate::config::Config;
+use crate::wordcount::WordCounter;
+
+/// Sparkline levels, lowest first.
+const LEVELS: &[u8] = b"_.-:=+*#";
+
+/// Share below the book's mean chapter length at which an act is flagged.
+const SAG_THRESHOLD: f64 = 0.2;
+
+#[derive(Debug, Serialize)]
+pub struct ChapterPacing {
+    pub chapter: u32,
+    /// Outline.md act heading covering the chapter; None without acts.
+    pub act: Option<String>,
+    pub words: u32,
+    pub scenes: u32,
+    pub dialogue_pct: u8,
+}
+
+/// Scenes of a chapter: one, plus one per separator line between them.
+fn scenes(prose: &str, separator: &str) -> u32 {
+    let text = strip_html_comments(prose);
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let is_break = |l: &&str| is_scene_break(l) || (!separator.is_empty() && *l == separator);
+    // Breaks at either end separate nothing
+    let start = lines
+        .iter()
+        .position(|l| !is_break(l))
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !is_break(l))
+        .map_or(start, |i| i + 1);
+    if start >= end {
+        return 0;
+    }
+    1 + lines[start..end].iter().filter(|l| is_break(l)).count() as u32
+}
+
+/// One character per value, scaled between the smallest and the largest;
+/// `|` where the act changes.
+fn sparkline(values: &[(Option<&str>, f64)]) -> String {
+    let min = values.iter().map(|v| v.1).fold(f64::INFINITY, f64::min);
+    let max = values.iter().map(|v| v.1).fold(f64::NEG_INFINITY, f64::max);
+    let top = (LEVELS.len() - 1) as f64;
+    let mut line = String::new();
+    for (i, (act, value)) in values.iter().enumerate() {
+        if i > 0 && values[i - 1].0 != *act {
+            line.push('|');
+        }
+        let level = if max > min {
+            ((value - min) / (max - min) * top).round() as usize
+        } else {
+            LEVELS.len() / 2
+        };
+        line.push(LEVELS[level] as char);
+    }
+    line
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0), |(s, n), v| (s + v, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+/// `report pacing`: words, scenes and dialogue share of every written
+/// chapter, grouped by the Outline.md act that covers it, with a sparkline
+/// per measure and the acts whose chapters run well short of the book's mean.
+pub fn report(repo: &Path) -> Result<Value> {
+    let config = Config::load(repo)?;
+    let book = crate::storage::read_book(repo)?.unwrap_or_default();
+    let outline =
+        std::fs::read_to_string(repo.join("Global Material/Outline.md")).unwrap_or_default();
+    let separator = config.scene_break.trim();
+    let counter = WordCounter::new(&config);
+
+    let mut chapters = Vec::new();
+    for n in 1..=config.chapter_count {
+        let Some(prose) = crate::chapters::chapter_prose(&book, n, config.book_storage) else {
+            continue;
+        };
+        chapters.push(ChapterPacing {
+            chapter: n,
+            act: act_section(&outline, n, config.chapter_count).map(|(heading, _)| heading),
+            words: counter.count(&prose),
+            scenes: scenes(&prose, separator),
+            dialogue_pct: crate::dialogue::measure(n, &prose, &mut BTreeMap::new()).dialogue_pct,
+        });
+    }
+
+    let book_mean = mean(chapters.iter().map(|c| c.words as f64));
+    let mut acts: Vec<Value> = Vec::new();
+    let mut flags: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chapters.len() {
+        let act = &chapters[i].act;
+        let group: Vec<&ChapterPacing> =
+            chapters[i..].iter().take_while(|c| &c.act == act).collect();
+        i += group.len();
+        let Some(heading) = act else {
+            continue;
+        };
+        let mean_words = mean(group.iter().map(|c| c.words as f64));
+        if book_mean > 0.0 && mean_words < book_mean * (1.0 - SAG_THRESHOLD) {
+            flags.push(format!(
+                "{}: chapters average {:.0} words, {:.0}% below the book's {:.0}",
+                heading,
+                mean_words,
+                (1.0 - mean_words / book_mean) * 100.0,
+                book_mean
+            ));
+        }
+        acts.push(json!({
+            "act": heading,
+            "chapters": [group[0].chapter, group[group.len() - 1].chapter],
+            "words": group.iter().map(|c| c.words).sum::<u32>(),
+            "mean_chapter_words": mean_words.round(),
+            "mean_scenes": (mean(group.iter().map(|c| c.scenes as f64)) * 10.0).round() / 10.0,
+            "mean_dialogue_pct": mean(group.iter().map(|c| c.dialogue_pct as f64)).round(),
+        }));
+    }
+
+    let line = |measure: fn(&ChapterPacing) -> f64| {
+        sparkline(
+            &chapters
+                .iter()
+                .map(|c| (c.act.as_deref(), measure(c)))
+                .collect::<Vec<_>>(),
+        )
+    };
+    Ok(json!({
+        "chapters": chapters,
+        "acts": acts,
+        "sparkline": {
+            "words": line(|c| c.words as f64),
+            "scenes": line(|c| c.scenes as f64),
+            "dialogue": line(|c| c.dialogue_pct as f64),
+        },
+        "flags": flags,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenes_are_split_at_markdown_and_custom_breaks() {
+        assert_eq!(
+            scenes("***\n\nOne.\n\n* * *\n\nTwo.\n\n⁂\n\nThree.\n\n---", "⁂"),
+            3
+        );
+        assert_eq!(scenes("One.\n\n%%\n\nTwo.", "%%"), 2);
+    }
+
+    #[test]
+    fn a_chapter_without_prose_has_no_scenes() {
+        assert_eq!(scenes("<!-- PAGE 2 -->", ""), 0);
+    }
+
+    #[test]
+    fn sparkline_marks_act_boundaries() {
+        let values = [
+            (Some("Act 1"), 3000.0),
+            (Some("Act 1"), 2800.0),
+            (Some("Act 2"), 1000.0),
+            (Some("Act 2"), 1200.0),
+            (Some("Act 3"), 3200.0),
+        ];
+        assert_eq!(sparkline(&values), "**|_.|#");
+    }
+
+    #[test]
+    fn flat_sparkline_sits_mid_height() {
+        assert_eq!(sparkline(&[(None, 5.0), (None, 5.0)]), "==");
+    }
+}
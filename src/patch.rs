@@ -0,0 +1,167 @@
+use anyhow::{bail, Result};
+
+// ─── Unified diff application ──────────────────────────────────────────────────
+
+/// One `@@ -a,b +c,d @@` hunk: the lines it expects (context + removals)
+/// and the lines it produces (context + additions).
+struct Hunk {
+    header: String,
+    old_start: usize,
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+}
+
+fn parse_hunks(diff: &str) -> Result<Vec<Hunk>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("@@ -") {
+            // "@@ -a,b +c,d @@ optional section" → old_start = a
+            let old_start = rest
+                .split([',', ' '])
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| anyhow::anyhow!("malformed hunk header: {}", line))?;
+            hunks.push(Hunk {
+                header: line.to_string(),
+                old_start,
+                old_lines: Vec::new(),
+                new_lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            // File headers (diff --git, ---, +++, index) precede the first hunk
+            continue;
+        };
+        if let Some(ctx) = line.strip_prefix(' ') {
+            hunk.old_lines.push(ctx.to_string());
+            hunk.new_lines.push(ctx.to_string());
+        } else if line.is_empty() {
+            // Some generators drop the leading space on blank context lines
+            hunk.old_lines.push(String::new());
+            hunk.new_lines.push(String::new());
+        } else if let Some(del) = line.strip_prefix('-') {
+            hunk.old_lines.push(del.to_string());
+        } else if let Some(add) = line.strip_prefix('+') {
+            hunk.new_lines.push(add.to_string());
+        }
+        // "\ No newline at end of file" and anything else: ignored
+    }
+    if hunks.is_empty() {
+        bail!("patch contains no hunks");
+    }
+    Ok(hunks)
+}
+
+/// Apply a unified diff to `original` and return the patched text.
+///
+/// Hunks are applied in order. Each hunk is tried at its declared line first,
+/// then anywhere after the previous hunk (tolerates shifted line numbers).
+/// Every hunk whose expected lines cannot be found is reported — the original
+/// is never partially patched.
+pub fn apply_unified_diff(original: &str, diff: &str) -> Result<String> {
+    let hunks = parse_hunks(diff)?;
+    let lines: Vec<&str> = original.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut cursor = 0usize;
+    let mut conflicts: Vec<String> = Vec::new();
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let matches_at = |pos: usize| {
+            pos + hunk.old_lines.len() <= lines.len()
+                && hunk
+                    .old_lines
+                    .iter()
+                    .zip(&lines[pos..])
+                    .all(|(want, have)| want.trim_end() == have.trim_end())
+        };
+        let declared = hunk.old_start.saturating_sub(1).max(cursor);
+        let pos = if matches_at(declared) {
+            Some(declared)
+        } else {
+            (cursor..=lines.len()).find(|&p| matches_at(p))
+        };
+        match pos {
+            Some(p) => {
+                out.extend(lines[cursor..p].iter().map(|l| l.to_string()));
+                out.extend(hunk.new_lines.iter().cloned());
+                cursor = p + hunk.old_lines.len();
+            }
+            None => {
+                let expected = hunk
+                    .old_lines
+                    .iter()
+                    .find(|l| !l.trim().is_empty())
+                    .map(|l| l.chars().take(80).collect::<String>())
+                    .unwrap_or_default();
+                conflicts.push(format!(
+                    "hunk {} ({}) does not match Review/current.md near \"{}\"",
+                    i + 1,
+                    hunk.header,
+                    expected
+                ));
+            }
+        }
+    }
+
+    if !conflicts.is_empty() {
+        bail!("patch does not apply: {}", conflicts.join("; "));
+    }
+
+    out.extend(lines[cursor..].iter().map(|l| l.to_string()));
+    let mut patched = out.join("\n");
+    if original.ends_with('\n') || original.is_empty() {
+        patched.push('\n');
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = "# Chapter 1\n\nThe tide came in.\n\nMara waited.\n";
+
+    #[test]
+    fn hunks_apply_at_their_declared_line() {
+        let diff = "--- a/Review/current.md\n+++ b/Review/current.md\n\
+                    @@ -3,3 +3,3 @@\n The tide came in.\n \n-Mara waited.\n+Mara waited by the gate.\n";
+        assert_eq!(
+            apply_unified_diff(CURRENT, diff).unwrap(),
+            "# Chapter 1\n\nThe tide came in.\n\nMara waited by the gate.\n"
+        );
+    }
+
+    #[test]
+    fn shifted_line_numbers_are_tolerated() {
+        let diff = "@@ -40,1 +40,2 @@\n Mara waited.\n+The gate opened.\n";
+        assert_eq!(
+            apply_unified_diff(CURRENT, diff).unwrap(),
+            "# Chapter 1\n\nThe tide came in.\n\nMara waited.\nThe gate opened.\n"
+        );
+    }
+
+    #[test]
+    fn every_mismatched_hunk_is_reported() {
+        let diff = "@@ -1,1 +1,1 @@\n-# Chapter 9\n+# Chapter 2\n\
+                    @@ -3,1 +3,1 @@\n-The tide came in.\n+The tide went out.\n\
+                    @@ -5,1 +5,1 @@\n-Tomas waited.\n+Tomas left.\n";
+        let err = apply_unified_diff(CURRENT, diff).unwrap_err().to_string();
+        assert!(err.contains("hunk 1 (@@ -1,1 +1,1 @@)"), "{err}");
+        assert!(err.contains("near \"# Chapter 9\""), "{err}");
+        assert!(err.contains("hunk 3"), "{err}");
+        assert!(!err.contains("hunk 2"), "{err}");
+    }
+
+    #[test]
+    fn a_patch_without_hunks_is_refused() {
+        let err = apply_unified_diff(CURRENT, "--- a/x\n+++ b/x\n").unwrap_err();
+        assert_eq!(err.to_string(), "patch contains no hunks");
+    }
+
+    #[test]
+    fn a_missing_final_newline_is_kept() {
+        let diff = "@@ -1,1 +1,1 @@\n-one\n+two\n";
+        assert_eq!(apply_unified_diff("one", diff).unwrap(), "two");
+    }
+}
@@ -0,0 +1,268 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::{prose_paragraphs, strip_engine_markers};
+use crate::git;
+
+/// Per-book crutch list: phrases the engine must avoid, kept by the author
+/// and by `report phrases --update`.
+pub const CRUTCHES_FILE: &str = "Global Material/Crutches.yml";
+
+pub const CRUTCHES_TEMPLATE: &str =
+    "# Crutch phrases of this book, one per line: - she let out a breath\n\
+    # Session-open sends them to the engine as phrases_to_avoid;\n\
+    # `ink-cli report phrases --update` adds the manuscript's top offenders.\n";
+
+/// Phrase lengths counted, in words.
+const PHRASE_LENGTHS: std::ops::RangeInclusive<usize> = 3..=5;
+
+/// Uses from which a phrase is an offender.
+pub const MIN_OCCURRENCES: u32 = 4;
+
+/// Function words; a phrase needs two words outside this list to count —
+/// `in the middle of the` is grammar, `let out a breath` is a tic.
+const STOPWORDS: &[&str] = &[
+    "the", "a", "an", "and", "or", "but", "of", "to", "in", "on", "at", "for", "with", "as", "by",
+    "from", "it", "its", "is", "was", "are", "were", "be", "been", "he", "she", "they", "i", "you",
+    "we", "his", "her", "their", "my", "your", "our", "him", "them", "me", "us", "that", "this",
+    "had", "have", "has", "not", "into", "out", "up", "down", "so", "if", "then", "there", "what",
+    "all", "did", "do", "would", "could", "no",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhraseCount {
+    pub phrase: String,
+    pub count: u32,
+    /// Uses per 10,000 words of the manuscript.
+    pub per_10k: f64,
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\p{L}+(?:['’]\p{L}+)*").unwrap())
+}
+
+/// Sentences of `text` as lowercased word lists; phrases never span two.
+fn sentences(text: &str) -> Vec<Vec<String>> {
+    prose_paragraphs(&strip_engine_markers(text))
+        .iter()
+        .flat_map(|p| {
+            p.split(['.', '!', '?', '…', ';'])
+                .map(|s| {
+                    word_re()
+                        .find_iter(s)
+                        .map(|m| m.as_str().to_lowercase().replace('’', "'"))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn normalize(phrase: &str) -> String {
+    word_re()
+        .find_iter(phrase)
+        .map(|m| m.as_str().to_lowercase().replace('’', "'"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Phrases of `text` used at least `MIN_OCCURRENCES` times, most used first.
+/// A phrase inside a longer one with the same count is left out for it.
+pub fn overused(text: &str) -> (u32, Vec<PhraseCount>) {
+    let sentences = sentences(text);
+    let words: u32 = sentences.iter().map(|s| s.len() as u32).sum();
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for sentence in &sentences {
+        for n in PHRASE_LENGTHS {
+            for gram in sentence.windows(n) {
+                let content = gram
+                    .iter()
+                    .filter(|w| !STOPWORDS.contains(&w.as_str()))
+                    .count();
+                if content >= 2 {
+                    *counts.entry(gram.join(" ")).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut candidates: Vec<(String, u32)> = counts
+        .into_iter()
+        .filter(|(_, c)| *c >= MIN_OCCURRENCES)
+        .collect();
+    // Longest first, so a subsumed shorter phrase finds its container kept
+    candidates.sort_by(|a, b| {
+        b.0.split(' ')
+            .count()
+            .cmp(&a.0.split(' ').count())
+            .then(b.1.cmp(&a.1))
+            .then(a.0.cmp(&b.0))
+    });
+    let mut kept: Vec<(String, u32)> = Vec::new();
+    for (phrase, count) in candidates {
+        let padded = format!(" {} ", phrase);
+        let subsumed = kept
+            .iter()
+            .any(|(k, c)| *c == count && format!(" {} ", k).contains(&padded));
+        if !subsumed {
+            kept.push((phrase, count));
+        }
+    }
+    kept.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let per_10k = |count: u32| (count as f64 * 100_000.0 / words.max(1) as f64).round() / 10.0;
+    let phrases = kept
+        .into_iter()
+        .map(|(phrase, count)| PhraseCount {
+            phrase,
+            count,
+            per_10k: per_10k(count),
+        })
+        .collect();
+    (words, phrases)
+}
+
+/// Uses of `phrase` in `text`, matched word by word, case-insensitively.
+fn occurrences(text: &str, phrase: &str) -> u32 {
+    let target: Vec<String> = normalize(phrase).split(' ').map(str::to_string).collect();
+    if target.iter().all(String::is_empty) {
+        return 0;
+    }
+    sentences(text)
+        .iter()
+        .map(|s| {
+            s.windows(target.len())
+                .filter(|w| *w == target.as_slice())
+                .count() as u32
+        })
+        .sum()
+}
+
+// ─── Crutch list ──────────────────────────────────────────────────────────────
+
+/// The crutch list; empty when the file does not exist.
+pub fn load_crutches(repo: &Path) -> Result<Vec<String>> {
+    let path = repo.join(CRUTCHES_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", CRUTCHES_FILE))?;
+    let list: Option<Vec<String>> = serde_yaml::from_str(&content).with_context(|| {
+        format!(
+            "Failed to parse {} — expected `- phrase` lines",
+            CRUTCHES_FILE
+        )
+    })?;
+    Ok(list
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect())
+}
+
+fn save_crutches(repo: &Path, phrases: &[String]) -> Result<()> {
+    let mut content = CRUTCHES_TEMPLATE.to_string();
+    for phrase in phrases {
+        content.push_str(&format!("- {}\n", json!(phrase)));
+    }
+    std::fs::write(repo.join(CRUTCHES_FILE), content)
+        .with_context(|| format!("Failed to write {}", CRUTCHES_FILE))
+}
+
+/// `phrases_to_avoid` of the session-open payload: the crutch list, then
+/// the manuscript's top offenders it does not hold, `limit` in all.
+pub fn to_avoid(repo: &Path, book: &str, limit: usize) -> Result<Vec<String>> {
+    if limit == 0 {
+        return Ok(vec![]);
+    }
+    let mut phrases = load_crutches(repo)?;
+    let listed: Vec<String> = phrases.iter().map(|p| normalize(p)).collect();
+    phrases.extend(
+        overused(book)
+            .1
+            .into_iter()
+            .map(|p| p.phrase)
+            .filter(|p| !listed.contains(p)),
+    );
+    phrases.truncate(limit);
+    Ok(phrases)
+}
+
+// ─── report phrases ───────────────────────────────────────────────────────────
+
+/// `report phrases`: the manuscript's most repeated phrases and the use count
+/// of each crutch. `update` adds the top `top` offenders to the crutch list
+/// and commits it.
+pub fn report(repo: &Path, top: usize, update: bool) -> Result<Value> {
+    let book = crate::storage::read_book(repo)?.unwrap_or_default();
+    let (words, mut offenders) = overused(&book);
+    offenders.truncate(top);
+    let mut crutches = load_crutches(repo)?;
+    let listed: Vec<String> = crutches.iter().map(|p| normalize(p)).collect();
+
+    let mut added = Vec::new();
+    if update {
+        added = offenders
+            .iter()
+            .map(|o| o.phrase.clone())
+            .filter(|p| !listed.contains(p))
+            .collect();
+        if !added.is_empty() {
+            crutches.extend(added.iter().cloned());
+            save_crutches(repo, &crutches)?;
+            git::run_git(repo, &["add", CRUTCHES_FILE])
+                .with_context(|| format!("Failed to git add {}", CRUTCHES_FILE))?;
+            let message = format!("phrases: add {} crutch phrase(s)", added.len());
+            git::run_git(repo, &["commit", "-m", &message])
+                .with_context(|| format!("Failed to commit {}", CRUTCHES_FILE))?;
+            if let Err(e) = git::push_main(repo) {
+                tracing::warn!("git push skipped: {}", e);
+            }
+        }
+    }
+
+    let crutch_counts: Vec<Value> = crutches
+        .iter()
+        .map(|p| json!({ "phrase": p, "count": occurrences(&book, p) }))
+        .collect();
+    Ok(json!({
+        "status": if update { "updated" } else { "report" },
+        "words": words,
+        "min_occurrences": MIN_OCCURRENCES,
+        "top": offenders,
+        "crutches": crutch_counts,
+        "added": added,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str = "She let out a breath. The sea was grey. \n\n\
+                        Mara let out a breath and waited. She let out a breath again. \
+                        He let out a breath! In the middle of the night. In the middle of the road. \
+                        In the middle of the day. In the middle of it all.";
+
+    #[test]
+    fn overused_phrases_are_counted_and_merged() {
+        let (words, top) = overused(BOOK);
+        assert_eq!(words, 51);
+        let phrases: Vec<(&str, u32)> = top.iter().map(|p| (p.phrase.as_str(), p.count)).collect();
+        // "let out a breath" subsumes "let out a"/"out a breath"; "in the middle of" is grammar
+        assert_eq!(phrases, [("let out a breath", 4)]);
+    }
+
+    #[test]
+    fn occurrences_ignore_case_and_punctuation() {
+        assert_eq!(occurrences(BOOK, "Let out a BREATH"), 4);
+        assert_eq!(occurrences(BOOK, "the sea was"), 1);
+    }
+}
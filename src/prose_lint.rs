@@ -0,0 +1,269 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::book::strip_engine_markers;
+use crate::config::ProseLintConfig;
+
+/// Filter words flagged when `prose_lint.filter_words` is unset: verbs that
+/// put a perceiving character between the reader and the scene.
+const DEFAULT_FILTER_WORDS: &[&str] = &[
+    "felt", "saw", "heard", "noticed", "realized", "realised", "wondered", "seemed", "watched",
+    "knew", "decided", "looked",
+];
+
+/// Doubled words that are often deliberate.
+const DOUBLING_ALLOWED: &[&str] = &["had", "that", "very", "no", "bye"];
+
+/// Common `-ly` words that are not adverbs.
+const NOT_ADVERBS: &[&str] = &[
+    "only", "family", "early", "holy", "reply", "belly", "fly", "ugly", "silly", "lonely",
+    "lovely", "friendly", "likely", "daily", "july", "italy", "lily", "ally", "bully", "jelly",
+    "rally", "supply", "apply", "imply", "curly", "elderly", "chilly", "hilly", "oily", "woolly",
+    "sly", "deadly", "costly", "kindly", "lively", "orderly", "wily",
+];
+
+/// Words that make a three-word run common phrasing rather than an echo.
+const STOPWORDS: &[&str] = &[
+    "the", "a", "an", "and", "or", "but", "of", "to", "in", "on", "at", "for", "with", "as", "by",
+    "from", "it", "is", "was", "he", "she", "they", "i", "you", "we", "his", "her", "their", "my",
+    "that", "this", "had", "have", "be", "been", "not", "into", "out", "up", "down", "him", "them",
+    "me", "were", "did", "do", "so", "if", "then", "there", "what", "all",
+];
+
+/// Fewest sentences the uniformity rule judges: a handful of lines says
+/// nothing about rhythm.
+const MIN_SENTENCES: usize = 8;
+
+/// Fewest words the adverb rule judges.
+const MIN_ADVERB_SAMPLE: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProseHint {
+    /// `repeated_words`, `filter_words`, `adverb_density`,
+    /// `sentence_uniformity`, or `echoes`.
+    pub rule: &'static str,
+    pub message: String,
+    /// Up to ~120 characters of prose around the first occurrence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\p{L}(?:[\p{L}'’-]*\p{L})?").unwrap())
+}
+
+fn sentence_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[^.!?…]+[.!?…]+").unwrap())
+}
+
+struct Word<'a> {
+    text: &'a str,
+    lower: String,
+    start: usize,
+    end: usize,
+}
+
+/// Run every rule `config` leaves enabled over `prose` (engine markers are
+/// stripped first).
+pub fn check(prose: &str, config: &ProseLintConfig) -> Vec<ProseHint> {
+    let prose = strip_engine_markers(prose);
+    let words: Vec<Word> = word_re()
+        .find_iter(&prose)
+        .map(|m| Word {
+            text: m.as_str(),
+            lower: m.as_str().to_lowercase(),
+            start: m.start(),
+            end: m.end(),
+        })
+        .collect();
+    let excerpt = |w: &Word| Some(crate::constraints::excerpt(&prose, w.start, w.end));
+
+    let mut hints = Vec::new();
+    let enabled = |rule: &str| !config.disabled.iter().any(|d| d == rule);
+
+    if enabled("repeated_words") {
+        for pair in words.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            if a.lower == b.lower
+                && prose[a.end..b.start].trim().is_empty()
+                && !DOUBLING_ALLOWED.contains(&a.lower.as_str())
+            {
+                hints.push(ProseHint {
+                    rule: "repeated_words",
+                    message: format!("\"{} {}\" — word repeated", a.text, b.text),
+                    excerpt: excerpt(b),
+                });
+            }
+        }
+    }
+
+    if enabled("filter_words") {
+        let list: Vec<String> = match &config.filter_words {
+            Some(list) => list.iter().map(|w| w.to_lowercase()).collect(),
+            None => DEFAULT_FILTER_WORDS.iter().map(|w| w.to_string()).collect(),
+        };
+        for filter in &list {
+            let uses: Vec<&Word> = words.iter().filter(|w| &w.lower == filter).collect();
+            if uses.len() >= 2 {
+                hints.push(ProseHint {
+                    rule: "filter_words",
+                    message: format!(
+                        "filter word \"{}\" used {} times — show what is perceived instead",
+                        filter,
+                        uses.len()
+                    ),
+                    excerpt: excerpt(uses[0]),
+                });
+            }
+        }
+    }
+
+    if enabled("adverb_density") && words.len() >= MIN_ADVERB_SAMPLE {
+        let adverbs: Vec<&Word> = words
+            .iter()
+            .filter(|w| {
+                w.lower.len() > 4
+                    && w.lower.ends_with("ly")
+                    && !NOT_ADVERBS.contains(&w.lower.as_str())
+            })
+            .collect();
+        let per_100 = adverbs.len() as f64 * 100.0 / words.len() as f64;
+        if per_100 > config.max_adverbs_per_100 {
+            let mut sample: Vec<&str> = adverbs.iter().map(|w| w.lower.as_str()).collect();
+            sample.dedup();
+            sample.truncate(5);
+            hints.push(ProseHint {
+                rule: "adverb_density",
+                message: format!(
+                    "{:.1} -ly adverbs per 100 words (max {}): {}",
+                    per_100,
+                    config.max_adverbs_per_100,
+                    sample.join(", ")
+                ),
+                excerpt: excerpt(adverbs[0]),
+            });
+        }
+    }
+
+    if enabled("sentence_uniformity") {
+        let lengths: Vec<f64> = sentence_re()
+            .find_iter(&prose)
+            .map(|s| word_re().find_iter(s.as_str()).count() as f64)
+            .filter(|&n| n > 0.0)
+            .collect();
+        if lengths.len() >= MIN_SENTENCES {
+            let mean = lengths.iter().sum::<f64>() / lengths.len() as f64;
+            let variance =
+                lengths.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / lengths.len() as f64;
+            let variation = variance.sqrt() / mean;
+            if variation < config.min_sentence_variation {
+                hints.push(ProseHint {
+                    rule: "sentence_uniformity",
+                    message: format!(
+                        "{} sentences of {:.0} words on average vary little (variation {:.2}, min {}) — mix short and long",
+                        lengths.len(),
+                        mean,
+                        variation,
+                        config.min_sentence_variation
+                    ),
+                    excerpt: None,
+                });
+            }
+        }
+    }
+
+    if enabled("echoes") {
+        let mut last_seen: HashMap<[&str; 3], usize> = HashMap::new();
+        let mut reported = Vec::new();
+        for (i, run) in words.windows(3).enumerate() {
+            let key = [
+                run[0].lower.as_str(),
+                run[1].lower.as_str(),
+                run[2].lower.as_str(),
+            ];
+            if key.iter().all(|w| STOPWORDS.contains(w)) {
+                continue;
+            }
+            if let Some(&prev) = last_seen.get(&key) {
+                if i - prev <= config.echo_window && !reported.contains(&key) {
+                    reported.push(key);
+                    hints.push(ProseHint {
+                        rule: "echoes",
+                        message: format!("\"{}\" echoes within {} words", key.join(" "), i - prev),
+                        excerpt: excerpt(&run[0]),
+                    });
+                }
+            }
+            last_seen.insert(key, i);
+        }
+    }
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(prose: &str, config: &ProseLintConfig) -> Vec<&'static str> {
+        check(prose, config).into_iter().map(|h| h.rule).collect()
+    }
+
+    const ECHO: &str = "The tide rolled over the black stones. Mara waited. \
+                        Later the tide rolled in again.";
+
+    #[test]
+    fn repeated_words_are_flagged() {
+        assert_eq!(
+            rules("She opened the the door.", &ProseLintConfig::default()),
+            ["repeated_words"]
+        );
+    }
+
+    #[test]
+    fn had_had_is_not_a_repeat() {
+        assert!(rules("He had had enough.", &ProseLintConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn filter_words_are_flagged() {
+        assert_eq!(
+            rules(
+                "She felt cold. He felt it too.",
+                &ProseLintConfig::default()
+            ),
+            ["filter_words"]
+        );
+    }
+
+    #[test]
+    fn dense_adverbs_and_uniform_sentences_are_flagged() {
+        let adverbs = "She walked slowly and quietly home. ".repeat(40);
+        let found = rules(&adverbs, &ProseLintConfig::default());
+        assert!(found.contains(&"adverb_density"));
+        assert!(found.contains(&"sentence_uniformity"));
+    }
+
+    #[test]
+    fn nearby_echoes_are_flagged() {
+        let hints = check(ECHO, &ProseLintConfig::default());
+        assert_eq!(hints.len(), 1);
+        assert_eq!(
+            hints[0].message,
+            "\"the tide rolled\" echoes within 10 words"
+        );
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let quiet = ProseLintConfig {
+            disabled: vec!["echoes".to_string()],
+            ..ProseLintConfig::default()
+        };
+        assert!(rules(ECHO, &quiet).is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use crate::book::{prose_paragraphs, strip_engine_markers};
+
+/// Readability of a session's prose, for tracking the engine's register
+/// from one close to the next.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Readability {
+    pub words: u32,
+    pub sentences: u32,
+    /// Flesch reading ease (higher is easier); English only — the syllable
+    /// count it rests on is English-specific.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flesch_reading_ease: Option<f64>,
+    /// Flesch–Kincaid US grade level; English only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flesch_kincaid_grade: Option<f64>,
+    /// Automated Readability Index (grade level, from characters per word).
+    pub automated_readability_index: f64,
+    /// Läsbarhetsindex: words per sentence plus the share of words over six
+    /// letters — comparable across languages.
+    pub lix: f64,
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[\p{L}\p{N}]+(?:['’-][\p{L}\p{N}]+)*").unwrap())
+}
+
+/// End of a sentence: terminal punctuation, closing quotes, then a break.
+fn sentence_end_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"[.!?…]+["”’»)]*(?:\s|$)"#).unwrap())
+}
+
+fn vowel_groups_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[aeiouy]+").unwrap())
+}
+
+/// English syllables by vowel groups, a silent final `e` discounted.
+fn syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let mut count = vowel_groups_re().find_iter(&word).count();
+    if count > 1 && word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+fn round1(x: f64) -> f64 {
+    (x * 10.0).round() / 10.0
+}
+
+/// Metrics of `prose` (engine markers and HTML comments ignored) written in
+/// `language` (Config.yml `language`); None when it holds no words.
+pub fn measure(prose: &str, language: &str) -> Option<Readability> {
+    let paragraphs = prose_paragraphs(&strip_engine_markers(prose));
+    let words: Vec<&str> = paragraphs
+        .iter()
+        .flat_map(|p| word_re().find_iter(p).map(|m| m.as_str()))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    // A paragraph without terminal punctuation (a heading, a cut-off line)
+    // still ends a sentence.
+    let sentences: usize = paragraphs
+        .iter()
+        .map(|p| {
+            let ends = sentence_end_re().find_iter(p).count();
+            let open_tail = !p.trim_end().ends_with(|c: char| ".!?…\"”’»)".contains(c));
+            ends + usize::from(open_tail || ends == 0)
+        })
+        .sum();
+
+    let w = words.len() as f64;
+    let s = sentences as f64;
+    let letters = words
+        .iter()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric()).count())
+        .sum::<usize>() as f64;
+    let long = words.iter().filter(|w| w.chars().count() > 6).count() as f64;
+    let english = {
+        let language = language.trim().to_lowercase();
+        language == "english" || language == "en" || language.starts_with("en-")
+    };
+    let (ease, grade) = if english {
+        let syl = words.iter().map(|w| syllables(w)).sum::<usize>() as f64;
+        (
+            Some(round1(206.835 - 1.015 * (w / s) - 84.6 * (syl / w))),
+            Some(round1(0.39 * (w / s) + 11.8 * (syl / w) - 15.59)),
+        )
+    } else {
+        (None, None)
+    };
+    Some(Readability {
+        words: words.len() as u32,
+        sentences: sentences as u32,
+        flesch_reading_ease: ease,
+        flesch_kincaid_grade: grade,
+        automated_readability_index: round1(4.71 * (letters / w) + 0.5 * (w / s) - 21.43),
+        lix: round1(w / s + 100.0 * long / w),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain() -> Readability {
+        measure(
+            "<!-- INK:NEW:START -->\nThe cat sat. The dog ran. We all went home.\n<!-- INK:NEW:END -->",
+            "English",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn plain_prose_scores_easy() {
+        let plain = plain();
+        assert_eq!((plain.words, plain.sentences), (10, 3));
+        assert!(plain.flesch_reading_ease.unwrap() > 90.0);
+        assert!(plain.flesch_kincaid_grade.unwrap() < 2.0);
+    }
+
+    #[test]
+    fn dense_prose_scores_harder_on_every_index() {
+        let plain = plain();
+        let dense = measure(
+            "Institutional considerations notwithstanding, the administration's \
+             comprehensive reorganization fundamentally jeopardized interdepartmental \
+             communication.",
+            "English",
+        )
+        .unwrap();
+        assert!(dense.flesch_kincaid_grade.unwrap() > 15.0);
+        assert!(dense.automated_readability_index > plain.automated_readability_index);
+        assert!(dense.lix > plain.lix);
+    }
+
+    #[test]
+    fn syllable_scores_are_english_only() {
+        let french = measure("« Viens », dit-elle. Il partit.", "French").unwrap();
+        assert_eq!(french.flesch_kincaid_grade, None);
+        assert_eq!(french.sentences, 2);
+    }
+
+    #[test]
+    fn text_without_prose_is_not_scored() {
+        assert!(measure("<!-- PAGE 3 -->", "English").is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Command;
+use tracing::info;
+
+use crate::config::{ReleaseConfig, ReleaseVia};
+use crate::curl::SecretConfig;
+use crate::git;
+
+/// `owner/repo` of a GitHub remote URL (`https://github.com/o/r(.git)`,
+/// `git@github.com:o/r.git`, `ssh://git@github.com/o/r`).
+fn github_slug(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("github.com")?;
+    let slug = rest
+        .trim_start_matches([':', '/'])
+        .trim_end_matches('/')
+        .trim_end_matches(".git");
+    let mut parts = slug.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(repo), None) if !owner.is_empty() && !repo.is_empty() => {
+            Some(format!("{}/{}", owner, repo))
+        }
+        _ => None,
+    }
+}
+
+/// Publish the sealed book as a GitHub release: tag HEAD of main with
+/// `release.tag`, push the tag, create the release, and upload `assets`
+/// (repo-relative files; missing ones are an error). Called by `complete`
+/// after the final push.
+pub fn create_release(
+    repo: &Path,
+    release: &ReleaseConfig,
+    notes: &str,
+    assets: &[String],
+) -> Result<Value> {
+    for asset in assets {
+        anyhow::ensure!(
+            repo.join(asset).is_file(),
+            "release asset {} does not exist",
+            asset
+        );
+    }
+    let name = release.name.clone().unwrap_or_else(|| release.tag.clone());
+    // Resolve the API target first, so a missing token never leaves a pushed
+    // tag without a release
+    let api = match release.via {
+        ReleaseVia::Api => Some(api_target(repo, release)?),
+        ReleaseVia::Gh => None,
+    };
+
+    info!("Tagging {} and pushing the tag", release.tag);
+    let branches = git::branches(repo);
+    git::run_git(
+        repo,
+        &["tag", "-a", &release.tag, "-m", &name, &branches.main],
+    )
+    .with_context(|| format!("Failed to create tag {}", release.tag))?;
+    git::run_git(repo, &["push", &branches.remote, &release.tag])
+        .with_context(|| format!("Failed to push tag {}", release.tag))?;
+
+    let url = match api {
+        Some((token, slug)) => {
+            release_with_api(repo, &token, &slug, release, &name, notes, assets)?
+        }
+        None => release_with_gh(repo, release, &name, notes, assets)?,
+    };
+    info!("Published release {}", release.tag);
+    Ok(json!({
+        "status": "released",
+        "tag": release.tag,
+        "url": url,
+        "assets": assets,
+    }))
+}
+
+fn release_with_gh(
+    repo: &Path,
+    release: &ReleaseConfig,
+    name: &str,
+    notes: &str,
+    assets: &[String],
+) -> Result<String> {
+    let out = Command::new("gh")
+        .args(["release", "create", &release.tag, "--verify-tag"])
+        .args(["--title", name, "--notes", notes])
+        .args(assets)
+        .current_dir(repo)
+        .output()
+        .with_context(|| "Failed to run gh — is the GitHub CLI installed?")?;
+    if !out.status.success() {
+        bail!(
+            "gh release create failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    // gh prints the release URL
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Token and `owner/repo` for `via: api`.
+fn api_target(repo: &Path, release: &ReleaseConfig) -> Result<(String, String)> {
+    let token = std::env::var(&release.token_env).map_err(|_| {
+        anyhow!(
+            "GitHub token not set — export it as ${} before running complete",
+            release.token_env
+        )
+    })?;
+    let remote = git::branches(repo).remote;
+    let url = git::run_git(repo, &["remote", "get-url", &remote])?;
+    let slug = github_slug(&url)
+        .ok_or_else(|| anyhow!("{} ({}) is not a GitHub repository", remote, url))?;
+    Ok((token, slug))
+}
+
+fn release_with_api(
+    repo: &Path,
+    token: &str,
+    slug: &str,
+    release: &ReleaseConfig,
+    name: &str,
+    notes: &str,
+    assets: &[String],
+) -> Result<String> {
+    let body = json!({ "tag_name": release.tag, "name": name, "body": notes }).to_string();
+    let created = curl_json(
+        token,
+        &[
+            "-X",
+            "POST",
+            &format!("https://api.github.com/repos/{}/releases", slug),
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            &body,
+        ],
+    )
+    .with_context(|| format!("Failed to create release {}", release.tag))?;
+
+    // upload_url is a URI template: ".../assets{?name,label}"
+    let upload_url = created["upload_url"]
+        .as_str()
+        .and_then(|u| u.split('{').next())
+        .ok_or_else(|| anyhow!("GitHub response has no upload_url"))?;
+    for asset in assets {
+        let file_name = Path::new(asset)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(asset);
+        curl_json(
+            token,
+            &[
+                "-X",
+                "POST",
+                &format!("{}?name={}", upload_url, file_name.replace(' ', "%20")),
+                "-H",
+                "Content-Type: application/octet-stream",
+                "--data-binary",
+                &format!("@{}", repo.join(asset).display()),
+            ],
+        )
+        .with_context(|| format!("Failed to upload release asset {}", asset))?;
+    }
+    Ok(created["html_url"].as_str().unwrap_or_default().to_string())
+}
+
+/// Call the GitHub API with curl and parse the JSON response.
+fn curl_json(token: &str, args: &[&str]) -> Result<Value> {
+    let (mut cmd, _secrets) = api_command(token, args)?;
+    let out = cmd
+        .output()
+        .with_context(|| "Failed to spawn curl for the GitHub API")?;
+    if !out.status.success() {
+        bail!(
+            "{} {}",
+            String::from_utf8_lossy(&out.stderr).trim(),
+            String::from_utf8_lossy(&out.stdout).trim()
+        );
+    }
+    serde_json::from_slice(&out.stdout).with_context(|| "GitHub API returned invalid JSON")
+}
+
+/// The API request; the token goes to curl through a `SecretConfig`, never
+/// its arguments. The config must outlive the command.
+fn api_command(token: &str, args: &[&str]) -> Result<(Command, SecretConfig)> {
+    let secrets = SecretConfig::new(&[("header", &format!("Authorization: Bearer {}", token))])?;
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "--fail-with-body"])
+        .arg("-K")
+        .arg(secrets.path())
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(args);
+    Ok((cmd, secrets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_slug_from_remote_urls() {
+        for url in [
+            "https://github.com/ada/my-book.git",
+            "https://github.com/ada/my-book",
+            "git@github.com:ada/my-book.git",
+            "ssh://git@github.com/ada/my-book/",
+        ] {
+            assert_eq!(github_slug(url).as_deref(), Some("ada/my-book"), "{url}");
+        }
+        assert_eq!(github_slug("https://gitlab.com/ada/my-book.git"), None);
+        assert_eq!(github_slug("/tmp/origin.git"), None);
+    }
+
+    #[test]
+    fn api_token_stays_out_of_curl_arguments() {
+        let (cmd, secrets) = api_command("ghp_s3cret", &["-X", "POST"]).unwrap();
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert!(args.iter().all(|a| !a.contains("ghp_s3cret")), "{args:?}");
+        assert!(args.contains(&secrets.path().to_string_lossy().into_owned()));
+        assert!(std::fs::read_to_string(secrets.path())
+            .unwrap()
+            .contains("Authorization: Bearer ghp_s3cret"));
+    }
+}
@@ -0,0 +1,348 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::config::{self, Config};
+use crate::errors::InkError;
+use crate::git;
+use crate::import::unit_heading_re;
+use crate::journal;
+use crate::maintenance::with_config_value;
+use crate::metadata;
+use crate::storage;
+
+const CONFIG_REL: &str = "Global Material/Config.yml";
+
+/// `# Soul — <title>`, the header init writes at the top of Global Material files.
+fn material_header_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(# .+? — ).+$").unwrap())
+}
+
+/// `content` of a Global Material file with `title` in its init header,
+/// unchanged when its first line is no such header.
+pub(crate) fn retitled(content: &str, title: &str) -> String {
+    let first = content.lines().next().unwrap_or_default();
+    match material_header_re().captures(first) {
+        Some(caps) => format!("{}{}{}", &caps[1], title, &content[first.len()..]),
+        None => content.to_string(),
+    }
+}
+
+/// A byline such as `*by Jane Doe*` or `_By Jane Doe_`.
+fn byline_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^([*_]*by\s+).+?([*_]*)$").unwrap())
+}
+
+/// `text` with its first line matching `find` rewritten by `replace`; None
+/// when no line matches or the line is unchanged.
+fn replace_first_line(
+    text: &str,
+    find: impl Fn(&str) -> bool,
+    replace: impl Fn(&str) -> String,
+) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut done = false;
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\n', '\r']);
+        if !done && find(body) {
+            done = true;
+            let new = replace(body);
+            changed = new != body;
+            out.push_str(&new);
+            out.push_str(&line[body.len()..]);
+        } else {
+            out.push_str(line);
+        }
+    }
+    changed.then_some(out)
+}
+
+/// The manuscript with the title (`# …`) and byline of its title page —
+/// the text before the first chapter heading — replaced.
+fn renamed_book(book: &str, title: Option<&str>, author: Option<&str>) -> Option<String> {
+    let front_end = book
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .find(|(_, line)| unit_heading_re().is_match(line.trim()))
+        .map_or(book.len(), |(start, _)| start);
+    let (front, rest) = book.split_at(front_end);
+    let mut front = front.to_string();
+    let mut changed = false;
+    if let Some(title) = title {
+        if let Some(new) =
+            replace_first_line(&front, |l| l.starts_with("# "), |_| format!("# {}", title))
+        {
+            front = new;
+            changed = true;
+        }
+    }
+    if let Some(author) = author {
+        if let Some(new) = replace_first_line(
+            &front,
+            |l| byline_re().is_match(l.trim()),
+            |l| {
+                byline_re()
+                    .replace(l.trim(), |c: &regex::Captures| {
+                        format!("{}{}{}", &c[1], author, &c[2])
+                    })
+                    .into_owned()
+            },
+        ) {
+            front = new;
+            changed = true;
+        }
+    }
+    changed.then(|| format!("{}{}", front, rest))
+}
+
+/// The title and author as the book records them: Config.yml, else the
+/// README.md init wrote.
+fn current(repo: &Path, config: &Config) -> (Option<String>, Option<String>) {
+    let readme = std::fs::read_to_string(repo.join("README.md")).unwrap_or_default();
+    let title = config.title.clone().or_else(|| {
+        readme
+            .lines()
+            .find_map(|l| l.strip_prefix("# "))
+            .map(|t| t.trim().to_string())
+    });
+    let author = config.author.clone().or_else(|| {
+        readme
+            .lines()
+            .find_map(|l| l.strip_prefix("- **Author:** "))
+            .map(|a| a.trim().to_string())
+    });
+    (title, author)
+}
+
+/// Write `title` and/or `author` to Config.yml, the Metadata.yml values
+/// they replace, the `# Soul — <title>` headers of Global Material,
+/// README.md, and the manuscript's title page. Returns the files changed;
+/// nothing is committed.
+pub(crate) fn write_names(
+    repo: &Path,
+    title: Option<&str>,
+    author: Option<&str>,
+) -> Result<Vec<String>> {
+    let mut files: Vec<String> = Vec::new();
+    let write = |rel: &str, content: &str, files: &mut Vec<String>| -> Result<()> {
+        std::fs::write(repo.join(rel), content)
+            .with_context(|| format!("Failed to write {}", rel))?;
+        files.push(rel.to_string());
+        Ok(())
+    };
+
+    let mut yaml = std::fs::read_to_string(repo.join(CONFIG_REL))
+        .with_context(|| format!("Failed to read {}", CONFIG_REL))?;
+    for (key, value) in [("title", title), ("author", author)] {
+        if let Some(value) = value {
+            yaml = with_config_value(&yaml, key, &config::yaml_quote(value));
+        }
+    }
+    Config::parse(&yaml).map_err(|e| InkError::ConfigInvalid(format!("{:#}", e)))?;
+    write(CONFIG_REL, &yaml, &mut files)?;
+
+    // Metadata.yml values that would otherwise keep the old name in exports
+    if let Some(mut book_metadata) = metadata::load(repo)? {
+        let before = book_metadata.clone();
+        if book_metadata.title.is_some() && title.is_some() {
+            book_metadata.title = title.map(str::to_string);
+        }
+        // The byline's source: the pen name when there is one; a list of
+        // authors is never rewritten from a single name
+        if book_metadata.authors.is_empty() && author.is_some() {
+            if book_metadata.pen_name.is_some() {
+                book_metadata.pen_name = author.map(str::to_string);
+            } else if book_metadata.author.is_some() {
+                book_metadata.author = author.map(str::to_string);
+            }
+        }
+        if book_metadata != before {
+            metadata::save(repo, &book_metadata)?;
+            files.push(metadata::METADATA_FILE.to_string());
+        }
+    }
+
+    if let Some(title) = title {
+        let mut material: Vec<_> = std::fs::read_dir(repo.join("Global Material"))
+            .with_context(|| "Failed to read Global Material/")?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .collect();
+        material.sort();
+        for path in material {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let renamed = retitled(&content, title);
+            if renamed != content {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                write(&format!("Global Material/{}", name), &renamed, &mut files)?;
+            }
+        }
+    }
+
+    if let Ok(readme) = std::fs::read_to_string(repo.join("README.md")) {
+        let mut renamed = readme.clone();
+        if let Some(title) = title {
+            if let Some(new) = replace_first_line(
+                &renamed,
+                |l| l.starts_with("# "),
+                |_| format!("# {}", title),
+            ) {
+                renamed = new;
+            }
+        }
+        if let Some(author) = author {
+            if let Some(new) = replace_first_line(
+                &renamed,
+                |l| l.starts_with("- **Author:** "),
+                |_| format!("- **Author:** {}", author),
+            ) {
+                renamed = new;
+            }
+        }
+        if renamed != readme {
+            write("README.md", &renamed, &mut files)?;
+        }
+    }
+
+    if let Some(book) = storage::read_book(repo)? {
+        if let Some(renamed) = renamed_book(&book, title, author) {
+            files.extend(storage::write_book(repo, &renamed)?);
+        }
+    }
+    Ok(files)
+}
+
+/// Rename the book: the new title and/or author written to Config.yml and
+/// the Metadata.yml values they replace, the `# Soul — <title>` headers of
+/// Global Material, README.md, and the manuscript's title page, committed
+/// together. The push is best-effort.
+pub fn rename(repo: &Path, title: Option<&str>, author: Option<&str>) -> Result<Value> {
+    let (title, author) = (
+        title.map(str::trim).filter(|t| !t.is_empty()),
+        author.map(str::trim).filter(|a| !a.is_empty()),
+    );
+    if title.is_none() && author.is_none() {
+        return Err(InkError::InvalidInput(
+            "nothing to rename — pass --title and/or --author".to_string(),
+        )
+        .into());
+    }
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: "rename".to_string(),
+        }
+        .into());
+    }
+    if repo.join("COMPLETE").exists() {
+        return Err(InkError::BookComplete.into());
+    }
+    let config = Config::load(repo)?;
+    if author.is_some() && metadata::load(repo)?.is_some_and(|m| !m.authors.is_empty()) {
+        return Err(InkError::InvalidInput(format!(
+            "{} lists the book's authors — change them with metadata add-author / remove-author",
+            metadata::METADATA_FILE
+        ))
+        .into());
+    }
+    let (old_title, old_author) = current(repo, &config);
+    let files = write_names(repo, title, author)?;
+
+    let mut add = vec!["add", "--"];
+    add.extend(files.iter().map(String::as_str));
+    git::run_git(repo, &add).with_context(|| "Failed to git add the rename")?;
+    let change = |from: Option<String>, to: Option<&str>| match to {
+        Some(to) => json!({ "from": from, "to": to }),
+        None => json!(null),
+    };
+    if git::run_git(repo, &["diff", "--cached", "--name-only"])?.is_empty() {
+        return Ok(json!({
+            "status": "unchanged",
+            "title": change(old_title, title),
+            "author": change(old_author, author),
+            "files": [],
+        }));
+    }
+    let message = match (title, author) {
+        (Some(title), _) => format!("rename: {}", title),
+        (None, Some(author)) => format!("rename: author {}", author),
+        (None, None) => unreachable!("checked above"),
+    };
+    git::run_git(repo, &["commit", "-m", &message])
+        .with_context(|| "Failed to commit the rename")?;
+    if let Err(e) = git::push_main(repo) {
+        warn!("git push skipped: {}", e);
+    }
+
+    Ok(json!({
+        "status": "renamed",
+        "title": change(old_title, title),
+        "author": change(old_author, author),
+        "files": files,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str =
+        "<!-- ⚠ INK-GATEWAY:MANAGED -->\n\n# The Lost Archive\n\n*by Jane Doe*\n\n---\n\n\
+         ## Chapter 1\n\n# The Lost Archive was a tavern.\n\nby the river\n";
+
+    #[test]
+    fn renames_the_title_page_and_nothing_after_it() {
+        let renamed = renamed_book(BOOK, Some("The Salt Road"), Some("J. Doe")).unwrap();
+        assert_eq!(
+            renamed,
+            "<!-- ⚠ INK-GATEWAY:MANAGED -->\n\n# The Salt Road\n\n*by J. Doe*\n\n---\n\n\
+             ## Chapter 1\n\n# The Lost Archive was a tavern.\n\nby the river\n"
+        );
+    }
+
+    #[test]
+    fn an_unchanged_title_page_is_left_alone() {
+        let renamed = renamed_book(BOOK, Some("The Salt Road"), None).unwrap();
+        assert_eq!(renamed_book(&renamed, Some("The Salt Road"), None), None);
+    }
+
+    #[test]
+    fn a_book_without_a_title_page_is_left_alone() {
+        assert_eq!(
+            renamed_book("## Chapter 1\n\nby the river\n", Some("X"), Some("Y")),
+            None
+        );
+    }
+
+    #[test]
+    fn material_headers_keep_their_prefix() {
+        let header = material_header_re()
+            .captures("# Soul — The Lost Archive")
+            .unwrap();
+        assert_eq!(&header[1], "# Soul — ");
+    }
+
+    #[test]
+    fn titles_are_quoted_for_config_yml() {
+        assert_eq!(config::yaml_quote("Say \"no\""), "\"Say \\\"no\\\"\"");
+        let parsed: Config = serde_yaml::from_str(&format!(
+            "title: {}\ntarget_length: 1\nchapter_count: 1\nchapter_structure: linear\n\
+             words_per_session: 1\n",
+            config::yaml_quote("Say \"no\": a \\ novel")
+        ))
+        .unwrap();
+        assert_eq!(parsed.title.as_deref(), Some("Say \"no\": a \\ novel"));
+    }
+}
@@ -0,0 +1,293 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::book;
+use crate::git;
+use crate::storage;
+
+const FULL_BOOK: &str = "Current version/Full_Book.md";
+const SUMMARY: &str = "Global Material/Summary.md";
+
+/// Files both sides only ever append to (as are the unit files of per-chapter
+/// storage); a rebase conflict in them is two sessions' additions, resolved
+/// by keeping both in order.
+const APPEND_ONLY: &[&str] = &[FULL_BOOK, SUMMARY];
+
+/// What to do with uncommitted local edits before rebasing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalEdits {
+    /// Commit them as `chore: human updates`, as session-open would.
+    Commit,
+    /// Stash them and re-apply them after the rebase.
+    Stash,
+}
+
+/// Bring a main that has diverged from the remote's back in line: commit or
+/// stash local edits, rebase main onto the remote main (resolving append-only
+/// conflicts in Full_Book.md and Summary.md), and push. Any other conflict
+/// aborts the rebase, leaving main as it was.
+pub fn resync(repo: &Path, edits: LocalEdits) -> Result<Value> {
+    let branches = git::branches(repo);
+    let origin_main = branches.remote_main();
+    if git::operation_in_progress(repo) == Some("rebase") {
+        warn!("Aborting a rebase left over from an earlier attempt");
+        git::run_git(repo, &["rebase", "--abort"])?;
+    }
+    git::abort_merge_if_in_progress(repo);
+
+    info!("Fetching {}...", branches.remote);
+    git::run_git(repo, &["fetch", &branches.remote])
+        .with_context(|| format!("Failed to fetch from {}", branches.remote))?;
+    git::run_git(repo, &["checkout", &branches.main])
+        .with_context(|| format!("Failed to checkout {}", branches.main))?;
+
+    let modified = git::collect_modified_files(repo)?;
+    let mut stashed = false;
+    if !modified.is_empty() {
+        match edits {
+            LocalEdits::Commit => git::commit_human_edits(repo, &modified)?,
+            LocalEdits::Stash => {
+                info!("Stashing {} local edit(s)...", modified.len());
+                git::run_git(
+                    repo,
+                    &["stash", "push", "--include-untracked", "-m", "ink resync"],
+                )?;
+                stashed = true;
+            }
+        }
+    }
+
+    let counts = git::run_git(
+        repo,
+        &[
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{}...{}", branches.main, origin_main),
+        ],
+    )?;
+    let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+    let (ahead, behind): (u32, u32) = (counts.next().unwrap_or(0), counts.next().unwrap_or(0));
+
+    let mut resolved: Vec<String> = Vec::new();
+    let status = if behind == 0 {
+        info!("{} already contains {}", branches.main, origin_main);
+        "up_to_date"
+    } else if ahead == 0 {
+        git::merge_ff_origin_main(repo)?;
+        "fast_forwarded"
+    } else {
+        info!(
+            "Rebasing {} local commit(s) onto {} ({} new there)...",
+            ahead, origin_main, behind
+        );
+        if let Err(e) = rebase_resolving_appends(repo, &origin_main, &mut resolved) {
+            if stashed {
+                restore_stash(repo);
+            }
+            return Err(e);
+        }
+        git::push_main(repo)
+            .with_context(|| format!("Rebased, but failed to push {}", branches.main))?;
+        "rebased"
+    };
+
+    let stash_conflict = stashed && !restore_stash(repo);
+    Ok(json!({
+        "status": status,
+        "local_edits": match (modified.is_empty(), edits) {
+            (true, _) => "none",
+            (false, LocalEdits::Commit) => "committed",
+            (false, LocalEdits::Stash) if stash_conflict => "left_in_stash",
+            (false, LocalEdits::Stash) => "restored",
+        },
+        "local_commits": ahead,
+        "remote_commits": behind,
+        "resolved": resolved,
+        "head": git::run_git(repo, &["rev-parse", "HEAD"]).ok(),
+    }))
+}
+
+/// `git rebase onto`, resolving each stop whose conflicts are all append-only
+/// files. Anything else aborts the rebase and fails with the conflicted files.
+fn rebase_resolving_appends(repo: &Path, onto: &str, resolved: &mut Vec<String>) -> Result<()> {
+    let mut step = git::run_git(repo, &["rebase", onto]);
+    while let Err(e) = step {
+        if git::operation_in_progress(repo) != Some("rebase") {
+            return Err(e.context(format!("Failed to rebase onto {}", onto)));
+        }
+        let conflicted = git::run_git(
+            repo,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--name-only",
+                "--diff-filter=U",
+            ],
+        )?;
+        let conflicted: Vec<&str> = conflicted.lines().filter(|l| !l.is_empty()).collect();
+        let mut unresolved = Vec::new();
+        for path in &conflicted {
+            let append_only = APPEND_ONLY.contains(path) || storage::is_unit_file(path);
+            if append_only && resolve_append(repo, path)? {
+                git::run_git(repo, &["add", "--", path])?;
+                if !resolved.iter().any(|r| r == path) {
+                    resolved.push(path.to_string());
+                }
+            } else {
+                unresolved.push(*path);
+            }
+        }
+        if conflicted.is_empty() || !unresolved.is_empty() {
+            git::run_git(repo, &["rebase", "--abort"])?;
+            bail!(
+                "Rebase onto {} stopped on conflicts that are not plain appends ({}) — \
+                 main is unchanged; run `git rebase {}` and resolve them by hand",
+                onto,
+                if unresolved.is_empty() {
+                    e.to_string()
+                } else {
+                    unresolved.join(", ")
+                },
+                onto
+            );
+        }
+        info!("Resolved append conflict(s) in {}", conflicted.join(", "));
+        step = git::run_git(repo, &["-c", "core.editor=true", "rebase", "--continue"]);
+    }
+    Ok(())
+}
+
+/// Resolve a conflicted append-only file: the upstream version followed by
+/// what the replayed commit appended to the common base. False when either
+/// side changed more than the end of the file.
+fn resolve_append(repo: &Path, path: &str) -> Result<bool> {
+    // Stage 1 is missing when both sides created the file
+    let base = git::run_git(repo, &["show", &format!(":1:{}", path)]).unwrap_or_default();
+    let ours = git::run_git(repo, &["show", &format!(":2:{}", path)])?;
+    let theirs = git::run_git(repo, &["show", &format!(":3:{}", path)])?;
+    let (true, Some(appended)) = (ours.starts_with(&base), theirs.strip_prefix(&base)) else {
+        return Ok(false);
+    };
+    let appended = appended.trim();
+    let mut merged = ours;
+    if path == FULL_BOOK {
+        // The replayed pages continue from upstream's word count, not the base's
+        let prose: Vec<&str> = appended
+            .split("\n\n")
+            .filter(|paragraph| !is_page_marker(paragraph))
+            .collect();
+        book::append_paginated(
+            &mut merged,
+            &prose.join("\n\n"),
+            crate::config::read_page_size(repo),
+        );
+    } else {
+        merged.push_str("\n\n");
+        merged.push_str(appended);
+    }
+    std::fs::write(repo.join(path), merged)
+        .with_context(|| format!("Failed to write resolved {}", path))?;
+    Ok(true)
+}
+
+fn is_page_marker(paragraph: &str) -> bool {
+    paragraph
+        .trim()
+        .strip_prefix("<!-- PAGE ")
+        .and_then(|rest| rest.strip_suffix(" -->"))
+        .is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+/// Pop the resync stash; false (stash kept) when it no longer applies cleanly.
+fn restore_stash(repo: &Path) -> bool {
+    match git::run_git(repo, &["stash", "pop"]) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!(
+                "Local edits kept in the stash — `git stash pop` conflicts: {}",
+                e
+            );
+            let _ = git::run_git(repo, &["checkout", "--", "."]);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_all(repo: &Path, message: &str) {
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", message]).unwrap();
+    }
+
+    #[test]
+    fn diverged_main_is_rebased_with_both_sessions_prose() {
+        let tmp = tempfile::tempdir().unwrap();
+        let author = tmp.path().join("author");
+        let agent = tmp.path().join("agent");
+        git::run_git(
+            tmp.path(),
+            &["init", "-q", "--bare", "-b", "main", "origin.git"],
+        )
+        .unwrap();
+        git::run_git(tmp.path(), &["clone", "-q", "origin.git", "author"]).unwrap();
+        git::run_git(&author, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(&author, &["config", "user.name", "t"]).unwrap();
+        std::fs::create_dir_all(author.join("Current version")).unwrap();
+        std::fs::create_dir_all(author.join("Global Material")).unwrap();
+        std::fs::write(
+            author.join("Global Material/Config.yml"),
+            "words_per_page: 4\n",
+        )
+        .unwrap();
+        std::fs::write(author.join(FULL_BOOK), "one two three\n").unwrap();
+        std::fs::write(author.join(SUMMARY), "start").unwrap();
+        commit_all(&author, "book");
+        git::run_git(&author, &["push", "-q", "origin", "main"]).unwrap();
+        git::run_git(tmp.path(), &["clone", "-q", "origin.git", "agent"]).unwrap();
+        git::run_git(&agent, &["config", "user.email", "a@a"]).unwrap();
+        git::run_git(&agent, &["config", "user.name", "a"]).unwrap();
+
+        // Both clones append a session; the agent's reaches origin first
+        std::fs::write(
+            agent.join(FULL_BOOK),
+            "one two three\n\n<!-- PAGE 2 -->\n\nfour five\n",
+        )
+        .unwrap();
+        std::fs::write(agent.join(SUMMARY), "start\n\nagent session").unwrap();
+        commit_all(&agent, "agent session");
+        git::run_git(&agent, &["push", "-q", "origin", "main"]).unwrap();
+        std::fs::write(
+            author.join(FULL_BOOK),
+            "one two three\n\n<!-- PAGE 2 -->\n\nsix seven eight nine\n",
+        )
+        .unwrap();
+        std::fs::write(author.join(SUMMARY), "start\n\nlocal session").unwrap();
+        commit_all(&author, "local session");
+        std::fs::write(author.join("notes.md"), "uncommitted").unwrap();
+
+        let result = resync(&author, LocalEdits::Stash).unwrap();
+        assert_eq!(result["status"], "rebased");
+        assert_eq!(result["local_edits"], "restored");
+        assert_eq!(result["resolved"], json!([FULL_BOOK, SUMMARY]));
+        assert_eq!(
+            std::fs::read_to_string(author.join(FULL_BOOK)).unwrap(),
+            "one two three\n\n<!-- PAGE 2 -->\n\nfour five\n\n<!-- PAGE 3 -->\n\nsix seven eight nine\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(author.join(SUMMARY)).unwrap(),
+            "start\n\nagent session\n\nlocal session"
+        );
+        assert!(author.join("notes.md").exists());
+        assert_eq!(
+            git::run_git(&author, &["rev-parse", "HEAD"]).unwrap(),
+            git::run_git(&author, &["rev-parse", "origin/main"]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::{json, Value};
+use std::path::Path;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::errors::InkError;
+use crate::git;
+use crate::journal;
+use crate::maintenance::with_config_value;
+use crate::state::InkState;
+use crate::storage;
+use crate::wordcount::WordCounter;
+
+const CONFIG_REL: &str = "Global Material/Config.yml";
+
+/// What `retarget` asks for; None derives the value from the new length.
+#[derive(Debug)]
+pub struct Retarget<'a> {
+    pub target_length: u32,
+    pub chapter_count: Option<u32>,
+    pub words_per_session: Option<u32>,
+    pub reason: Option<&'a str>,
+    pub dry_run: bool,
+}
+
+/// The new scope, derived from what is written so far.
+#[derive(Debug, PartialEq)]
+struct Plan {
+    chapter_count: u32,
+    words_per_chapter: u32,
+    words_per_session: u32,
+}
+
+/// Chapters before the current one are done: their words stay as written,
+/// and what the new length still needs is shared among the current chapter
+/// and those after it. Chapters with a `chapter_targets` entry keep theirs;
+/// the others get the new `words_per_chapter`. Without `--chapter-count`,
+/// enough chapters are planned to keep the current chapter length; without
+/// `--words-per-session`, sessions per chapter stay what they were.
+fn plan(config: &Config, state: &InkState, written: u32, request: &Retarget) -> Result<Plan> {
+    let done = state.current_chapter.saturating_sub(1);
+    let done_words = written.saturating_sub(state.current_chapter_word_count);
+    let remaining_words = request.target_length - done_words;
+    let chapter_count = request.chapter_count.unwrap_or_else(|| {
+        done + remaining_words
+            .div_ceil(config.words_per_chapter.max(1))
+            .max(1)
+    });
+    if chapter_count < state.current_chapter {
+        return Err(InkError::InvalidInput(format!(
+            "--chapter-count {} is below the current chapter ({})",
+            chapter_count, state.current_chapter
+        ))
+        .into());
+    }
+
+    let (mut fixed_words, mut fixed) = (0, 0);
+    for (_, words) in config
+        .chapter_targets
+        .range(state.current_chapter..=chapter_count)
+    {
+        fixed_words += words;
+        fixed += 1;
+    }
+    let open = chapter_count - done - fixed;
+    if open == 0 || remaining_words <= fixed_words {
+        return Err(InkError::InvalidInput(format!(
+            "{} words left to write do not fit chapters {}-{}: their chapter_targets \
+             already take {} — raise --chapter-count or edit chapter_targets",
+            remaining_words, state.current_chapter, chapter_count, fixed_words
+        ))
+        .into());
+    }
+    let words_per_chapter = (remaining_words - fixed_words).div_ceil(open);
+
+    let words_per_session = request.words_per_session.unwrap_or_else(|| {
+        let scaled = u64::from(config.words_per_session) * u64::from(words_per_chapter)
+            / u64::from(config.words_per_chapter.max(1));
+        // Round to 50 words, as init's page-based values are
+        (((scaled + 25) / 50 * 50).max(50) as u32).min(words_per_chapter)
+    });
+    Ok(Plan {
+        chapter_count,
+        words_per_chapter,
+        words_per_session,
+    })
+}
+
+fn changelog_note(
+    config: &Config,
+    plan: &Plan,
+    written: u32,
+    current_chapter: u32,
+    request: &Retarget,
+) -> String {
+    let pct = |target: u32| written * 100 / target.max(1);
+    let mut note = format!(
+        "# Retarget {}\n\n\
+         **Target length:** {} → {} words ({} written: {}% → {}%)\n\
+         **Chapter count:** {} → {} (chapter {} in progress)\n\
+         **Words per chapter:** {} → {} from chapter {} on\n\
+         **Words per session:** {} → {}\n",
+        Local::now().format("%Y-%m-%d %H:%M"),
+        config.target_length,
+        request.target_length,
+        written,
+        pct(config.target_length),
+        pct(request.target_length),
+        config.chapter_count,
+        plan.chapter_count,
+        current_chapter,
+        config.words_per_chapter,
+        plan.words_per_chapter,
+        current_chapter,
+        config.words_per_session,
+        plan.words_per_session,
+    );
+    if !config.chapter_targets.is_empty() {
+        note.push_str("\nChapters with a `chapter_targets` entry keep their length.\n");
+    }
+    if let Some(reason) = request.reason {
+        note.push_str(&format!("\n## Why\n\n{}\n", reason.trim()));
+    }
+    note
+}
+
+/// Change the book's scope mid-way: `target_length`, `chapter_count`,
+/// `words_per_chapter`, and `words_per_session` rewritten together in
+/// Config.yml (validated and linted like `config set`), with a Changelog note
+/// recording the old and new values. Commits; the push is best-effort.
+pub fn retarget(repo: &Path, request: &Retarget) -> Result<Value> {
+    journal::recover(repo)?;
+    if repo.join(".ink-running").exists() {
+        return Err(InkError::SessionActive {
+            action: "retarget".to_string(),
+        }
+        .into());
+    }
+    if repo.join("COMPLETE").exists() {
+        return Err(InkError::BookComplete.into());
+    }
+    let config = Config::load(repo)?;
+    let state = InkState::load(repo)?;
+    let written = storage::compiled_book(repo)?.map_or(0, |b| WordCounter::new(&config).count(&b));
+    if request.target_length <= written {
+        return Err(InkError::InvalidInput(format!(
+            "--target-length {} is not above the {} words already written",
+            request.target_length, written
+        ))
+        .into());
+    }
+    let plan = plan(&config, &state, written, request)?;
+
+    let content = std::fs::read_to_string(repo.join(CONFIG_REL))
+        .with_context(|| format!("Failed to read {}", CONFIG_REL))?;
+    let mut updated = content;
+    for (key, value) in [
+        ("target_length", request.target_length),
+        ("chapter_count", plan.chapter_count),
+        ("words_per_chapter", plan.words_per_chapter),
+        ("words_per_session", plan.words_per_session),
+    ] {
+        updated = with_config_value(&updated, key, &value.to_string());
+    }
+    let candidate =
+        Config::parse(&updated).map_err(|e| InkError::ConfigInvalid(format!("{:#}", e)))?;
+    let issues = candidate.lint();
+    if !issues.is_empty() {
+        return Err(InkError::ConfigInvalid(format!(
+            "Config.yml: refusing to retarget — {}",
+            issues.join("; ")
+        ))
+        .into());
+    }
+
+    let changelog_rel = format!(
+        "Changelog/{}-retarget.md",
+        Local::now().format("%Y-%m-%d-%H-%M")
+    );
+    let mut payload = json!({
+        "status": if request.dry_run { "dry_run" } else { "retargeted" },
+        "written_words": written,
+        "current_chapter": state.current_chapter,
+        "target_length": { "from": config.target_length, "to": request.target_length },
+        "chapter_count": { "from": config.chapter_count, "to": plan.chapter_count },
+        "words_per_chapter": { "from": config.words_per_chapter, "to": plan.words_per_chapter },
+        "words_per_session": { "from": config.words_per_session, "to": plan.words_per_session },
+        "chapter_targets_kept": config
+            .chapter_targets
+            .range(state.current_chapter..=plan.chapter_count)
+            .map(|(chapter, words)| json!({ "chapter": chapter, "words": words }))
+            .collect::<Vec<_>>(),
+        "pct_complete": written * 100 / request.target_length,
+    });
+    if request.dry_run {
+        return Ok(payload);
+    }
+
+    std::fs::write(repo.join(CONFIG_REL), &updated)
+        .with_context(|| format!("Failed to write {}", CONFIG_REL))?;
+    std::fs::create_dir_all(repo.join("Changelog"))
+        .with_context(|| "Failed to create Changelog/")?;
+    std::fs::write(
+        repo.join(&changelog_rel),
+        changelog_note(&config, &plan, written, state.current_chapter, request),
+    )
+    .with_context(|| format!("Failed to write {}", changelog_rel))?;
+    git::run_git(repo, &["add", CONFIG_REL, &changelog_rel])
+        .with_context(|| "Failed to git add the retarget")?;
+    git::run_git(
+        repo,
+        &[
+            "commit",
+            "-m",
+            &format!(
+                "retarget: {} → {} words",
+                config.target_length, request.target_length
+            ),
+        ],
+    )
+    .with_context(|| "Failed to commit the retarget")?;
+    if let Err(e) = git::push_main(repo) {
+        warn!("git push skipped: {}", e);
+    }
+    payload["changelog"] = json!(changelog_rel);
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn novella() -> (Config, InkState) {
+        let config = Config::parse(
+            "target_length: 30000\nchapter_count: 10\nchapter_structure: linear\n\
+             words_per_session: 1500\nwords_per_chapter: 3000\nchapter_targets:\n  9: 6000\n",
+        )
+        .unwrap();
+        let state = InkState {
+            current_chapter: 7,
+            current_chapter_word_count: 1000,
+            ..InkState::default()
+        };
+        (config, state)
+    }
+
+    fn to_novel(chapter_count: Option<u32>, words_per_session: Option<u32>) -> Retarget<'static> {
+        Retarget {
+            target_length: 90000,
+            chapter_count,
+            words_per_session,
+            reason: None,
+            dry_run: true,
+        }
+    }
+
+    #[test]
+    fn a_novella_becomes_a_novel() {
+        let (config, state) = novella();
+        // 18000 words in chapters 1-6 leave 72000: 24 chapters of 3000
+        let novel = plan(&config, &state, 19000, &to_novel(None, None)).unwrap();
+        assert_eq!(novel.chapter_count, 30);
+        // Chapter 9 keeps its 6000, the 23 others share 66000
+        assert_eq!(novel.words_per_chapter, 2870);
+        assert_eq!(novel.words_per_session, 1450);
+    }
+
+    #[test]
+    fn a_fixed_chapter_count_resizes_the_chapters() {
+        let (config, state) = novella();
+        let novel = plan(&config, &state, 19000, &to_novel(Some(20), Some(2000))).unwrap();
+        assert_eq!(
+            (novel.words_per_chapter, novel.words_per_session),
+            (5077, 2000)
+        );
+    }
+
+    #[test]
+    fn fewer_chapters_than_written_is_invalid() {
+        let (config, state) = novella();
+        let err = plan(&config, &state, 19000, &to_novel(Some(6), None)).unwrap_err();
+        assert_eq!(crate::errors::code_of(&err), "invalid_input");
+    }
+}
@@ -0,0 +1,451 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Local, NaiveDateTime};
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::info;
+
+use crate::book::{check_full_book_format, prose_paragraphs};
+use crate::context::extract_ink_instructions;
+use crate::git;
+use crate::wordcount::WordCounter;
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Paragraphs of Full_Book.md that did not exist `days` days ago, in book order.
+/// Compares against the last main commit before the cutoff; if the book did not
+/// exist then, every paragraph is new. Paragraph-set comparison (rather than a
+/// byte suffix) keeps in-place rework replacements in the packet too.
+fn new_prose_since(repo: &Path, days: u32) -> Result<Vec<String>> {
+    let Some(current) = crate::storage::compiled_book(repo)? else {
+        return Ok(vec![]);
+    };
+
+    let before = format!("--before={} days ago", days);
+    let old = git::run_git(repo, &["rev-list", "-1", &before, "HEAD"])
+        .ok()
+        .filter(|rev| !rev.is_empty())
+        .and_then(|rev| crate::storage::book_at(repo, &rev))
+        .unwrap_or_default();
+
+    let seen: HashSet<String> = prose_paragraphs(&old).into_iter().collect();
+    Ok(prose_paragraphs(&current)
+        .into_iter()
+        .filter(|p| !seen.contains(p))
+        .collect())
+}
+
+/// `(session label, summary)` for every Changelog entry newer than `days` days.
+/// Changelog filenames are `YYYY-MM-DD-HH-MM.md`; entries without a summary
+/// section are listed with their word count line instead.
+fn summaries_since(repo: &Path, days: u32) -> Result<Vec<(String, String)>> {
+    let dir = repo.join("Changelog");
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let cutoff = Local::now().naive_local() - Duration::days(days as i64);
+
+    let mut entries: Vec<(NaiveDateTime, String, String)> = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| "Failed to read Changelog/")? {
+        let path = entry?.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Ok(when) = NaiveDateTime::parse_from_str(stem, "%Y-%m-%d-%H-%M") else {
+            continue;
+        };
+        if when < cutoff {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let summary = match content.split_once("**Summary:**") {
+            Some((_, s)) => s.trim().to_string(),
+            None => content
+                .lines()
+                .find(|l| l.starts_with("**Words written:**"))
+                .unwrap_or("")
+                .to_string(),
+        };
+        entries.push((when, when.format("%Y-%m-%d %H:%M").to_string(), summary));
+    }
+    entries.sort_by_key(|e| e.0);
+    Ok(entries
+        .into_iter()
+        .map(|(_, label, s)| (label, s))
+        .collect())
+}
+
+/// Minimal Markdown → HTML for the packet and manuscript exports: headings,
+/// block quotes, list items, rules, and paragraphs. Everything is escaped; no
+/// inline formatting. `meta` becomes `<meta name content>` elements and
+/// `stylesheet` is inlined into a `<style>` element.
+pub(crate) fn to_html(
+    title: &str,
+    meta: &[(&str, String)],
+    markdown: &str,
+    stylesheet: Option<&str>,
+) -> String {
+    fn esc(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+    let mut body = String::new();
+    for block in markdown
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        let hashes = block.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&hashes) && block[hashes..].starts_with(' ') {
+            body.push_str(&format!(
+                "<h{0}>{1}</h{0}>\n",
+                hashes,
+                esc(block[hashes..].trim())
+            ));
+        } else if block == "---" {
+            body.push_str("<hr>\n");
+        } else if block.starts_with("- ") {
+            body.push_str("<ul>\n");
+            for item in block.lines() {
+                body.push_str(&format!(
+                    "<li>{}</li>\n",
+                    esc(item.trim_start_matches("- "))
+                ));
+            }
+            body.push_str("</ul>\n");
+        } else if block.starts_with("> ") {
+            let text: Vec<&str> = block
+                .lines()
+                .map(|l| l.trim_start_matches('>').trim())
+                .collect();
+            body.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                esc(&text.join(" "))
+            ));
+        } else {
+            body.push_str(&format!("<p>{}</p>\n", esc(block).replace('\n', "<br>\n")));
+        }
+    }
+    let meta: String = meta
+        .iter()
+        .map(|(name, content)| {
+            format!(
+                "<meta name=\"{}\" content=\"{}\">\n",
+                name,
+                esc(content).replace('"', "&quot;")
+            )
+        })
+        .collect();
+    let style = stylesheet
+        .map(|css| format!("<style>\n{}\n</style>\n", css.trim()))
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n{}{}</head>\n<body>\n{}</body>\n</html>\n",
+        esc(title),
+        meta,
+        style,
+        body
+    )
+}
+
+const FEEDBACK_TEMPLATE: &str = "\
+# Feedback — {{DATE}}
+
+Notes from the read-through of `{{PACKET}}`. Turn anything actionable into an
+`<!-- INK: ... -->` instruction in `Review/current.md` before the next session.
+
+## What works
+
+-
+
+## What doesn't
+
+-
+
+## Characters & voice
+
+-
+
+## Plot & pacing
+
+-
+
+## Instructions to add
+
+-
+";
+
+// ─── review-packet ────────────────────────────────────────────────────────────
+
+/// Assemble an author read-through packet covering the last `days` days:
+/// new validated prose (clean), session summaries, open INK instructions,
+/// Full_Book.md format warnings, plus a blank feedback template.
+/// Both files are written to `Review/`, committed, and pushed (best-effort),
+/// so they show up in the author's editor like every other managed file.
+pub fn review_packet(repo: &Path, days: u32, html: bool) -> Result<serde_json::Value> {
+    if repo.join(".ink-running").exists() {
+        return Err(anyhow!(
+            "session in progress — generate the review packet after session-close"
+        ));
+    }
+
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let prose = new_prose_since(repo, days)?;
+    let summaries = summaries_since(repo, days)?;
+
+    let current_md = repo.join("Review").join("current.md");
+    let raw_review = if current_md.exists() {
+        std::fs::read_to_string(&current_md).with_context(|| "Failed to read Review/current.md")?
+    } else {
+        String::new()
+    };
+    let (_, instructions) = extract_ink_instructions(&raw_review);
+
+    let format_issues: Vec<String> = check_full_book_format(repo)?
+        .and_then(|v| v.get("format_issues").cloned())
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    let prose_text = prose.join("\n\n");
+    let word_count = WordCounter::for_repo(repo).count(&prose_text);
+
+    let mut md = format!("# Review packet — {}\n\n", today);
+    md.push_str(&format!(
+        "Covering the last {} day(s): {} new words across {} session(s).\n\n",
+        days,
+        word_count,
+        summaries.len()
+    ));
+
+    md.push_str("## Session summaries\n\n");
+    if summaries.is_empty() {
+        md.push_str("No sessions in this period.\n\n");
+    } else {
+        for (label, summary) in &summaries {
+            md.push_str(&format!("### {}\n\n{}\n\n", label, summary));
+        }
+    }
+
+    md.push_str("## Open instructions\n\n");
+    if instructions.is_empty() {
+        md.push_str("None — current.md has no pending INK instructions.\n\n");
+    } else {
+        for ins in &instructions {
+            md.push_str(&format!("- {}\n", ins.instruction));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Warnings\n\n");
+    if format_issues.is_empty() {
+        md.push_str("None.\n\n");
+    } else {
+        for issue in &format_issues {
+            md.push_str(&format!("- Full_Book.md: {}\n", issue));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("---\n\n## New prose\n\n");
+    if prose.is_empty() {
+        md.push_str("No new validated prose in this period.\n");
+    } else {
+        md.push_str(&prose_text);
+        md.push('\n');
+    }
+
+    let (packet_name, packet_body) = if html {
+        (
+            format!("review-{}.html", today),
+            to_html(&format!("Review packet — {}", today), &[], &md, None),
+        )
+    } else {
+        (format!("review-{}.md", today), md)
+    };
+    let feedback_name = format!("feedback-{}.md", today);
+    let packet_rel = format!("Review/{}", packet_name);
+    let feedback_rel = format!("Review/{}", feedback_name);
+
+    std::fs::create_dir_all(repo.join("Review")).with_context(|| "Failed to create Review/")?;
+    std::fs::write(repo.join(&packet_rel), packet_body)
+        .with_context(|| format!("Failed to write {}", packet_rel))?;
+    // Never clobber a feedback file the author has already started filling in
+    let feedback_path = repo.join(&feedback_rel);
+    if !feedback_path.exists() {
+        let template = FEEDBACK_TEMPLATE
+            .replace("{{DATE}}", &today)
+            .replace("{{PACKET}}", &packet_rel);
+        std::fs::write(&feedback_path, template)
+            .with_context(|| format!("Failed to write {}", feedback_rel))?;
+    }
+
+    info!("Committing review packet {}", packet_rel);
+    git::run_git(repo, &["add", &packet_rel, &feedback_rel])
+        .with_context(|| "Failed to git add review packet")?;
+    if git::run_git(repo, &["diff", "--cached", "--quiet"]).is_err() {
+        git::engine_commit(repo, &["-m", &format!("review: packet {}", today)])
+            .with_context(|| "Failed to commit review packet")?;
+        if let Err(e) = git::push_main(repo) {
+            tracing::warn!("git push skipped: {}", e);
+        }
+    }
+
+    Ok(serde_json::json!({
+        "status": "generated",
+        "packet_file": packet_rel,
+        "feedback_file": feedback_rel,
+        "days": days,
+        "new_word_count": word_count,
+        "sessions": summaries.len(),
+        "open_instructions": instructions.len(),
+        "warnings": format_issues,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A git repository holding a Config.yml and `book` as Full_Book.md,
+    /// committed `days_ago`.
+    fn book_repo(tmp: &Path, book: &str, days_ago: u32) {
+        git::run_git(tmp, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(tmp, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(tmp, &["config", "user.name", "t"]).unwrap();
+        std::fs::create_dir_all(tmp.join("Global Material")).unwrap();
+        std::fs::write(
+            tmp.join("Global Material/Config.yml"),
+            "target_length: 90000\nchapter_count: 20\nchapter_structure: linear\n\
+             words_per_session: 1500\n",
+        )
+        .unwrap();
+        write_book(tmp, book);
+        let date = (Local::now() - Duration::days(days_ago as i64)).to_rfc3339();
+        let status = std::process::Command::new("git")
+            .args(["commit", "-q", "-m", "book"])
+            .current_dir(tmp)
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    fn write_book(repo: &Path, book: &str) {
+        std::fs::create_dir_all(repo.join("Current version")).unwrap();
+        std::fs::write(repo.join("Current version/Full_Book.md"), book).unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+    }
+
+    fn changelog(repo: &Path, ago: Duration, content: &str) {
+        let stem = (Local::now() - ago).format("%Y-%m-%d-%H-%M");
+        std::fs::create_dir_all(repo.join("Changelog")).unwrap();
+        std::fs::write(repo.join(format!("Changelog/{stem}.md")), content).unwrap();
+    }
+
+    #[test]
+    fn new_prose_is_what_the_book_gained_since_the_cutoff() {
+        let tmp = tempfile::tempdir().unwrap();
+        book_repo(tmp.path(), "The rain fell.\n\nThe river rose.\n", 10);
+        write_book(
+            tmp.path(),
+            "The storm broke.\n\nThe river rose.\n\nMara ran.\n",
+        );
+        git::run_git(tmp.path(), &["commit", "-q", "-m", "session"]).unwrap();
+
+        // The reworked paragraph counts as new, the untouched one does not
+        assert_eq!(
+            new_prose_since(tmp.path(), 7).unwrap(),
+            ["The storm broke.", "Mara ran."]
+        );
+    }
+
+    #[test]
+    fn a_book_younger_than_the_period_is_all_new() {
+        let tmp = tempfile::tempdir().unwrap();
+        book_repo(tmp.path(), "The rain fell.\n\nThe river rose.\n", 0);
+        assert_eq!(new_prose_since(tmp.path(), 7).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn summaries_cover_recent_sessions_oldest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        changelog(
+            tmp.path(),
+            Duration::hours(2),
+            "# Session\n\n**Summary:** Mara runs.\n",
+        );
+        changelog(
+            tmp.path(),
+            Duration::days(2),
+            "# Session\n\n**Words written:** 900\n",
+        );
+        changelog(tmp.path(), Duration::days(30), "**Summary:** Too old.\n");
+        std::fs::write(
+            tmp.path().join("Changelog/notes.md"),
+            "**Summary:** Not a session.\n",
+        )
+        .unwrap();
+
+        let summaries = summaries_since(tmp.path(), 7).unwrap();
+        let texts: Vec<&str> = summaries.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(texts, ["**Words written:** 900", "Mara runs."]);
+    }
+
+    #[test]
+    fn html_escapes_text_and_maps_blocks() {
+        let html = to_html(
+            "A & B",
+            &[("author", "Ada \"Lovelace\"".to_string())],
+            "# Title\n\n- one\n- two\n\n> quoted\n\n---\n\nFirst <line>\nsecond",
+            Some("p { margin: 0 }"),
+        );
+        for part in [
+            "<title>A &amp; B</title>",
+            "<meta name=\"author\" content=\"Ada &quot;Lovelace&quot;\">",
+            "<style>\np { margin: 0 }\n</style>",
+            "<h1>Title</h1>",
+            "<ul>\n<li>one</li>\n<li>two</li>\n</ul>",
+            "<blockquote>quoted</blockquote>",
+            "<hr>",
+            "<p>First &lt;line&gt;<br>\nsecond</p>",
+        ] {
+            assert!(html.contains(part), "{part} missing from {html}");
+        }
+    }
+
+    #[test]
+    fn packet_is_refused_during_a_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(".ink-running"), "").unwrap();
+        let err = review_packet(tmp.path(), 7, false).unwrap_err();
+        assert!(err.to_string().contains("session in progress"), "{err}");
+    }
+
+    #[test]
+    fn packet_is_committed_and_keeps_started_feedback() {
+        let tmp = tempfile::tempdir().unwrap();
+        book_repo(tmp.path(), "The rain fell.\n", 0);
+        let result = review_packet(tmp.path(), 7, false).unwrap();
+        assert_eq!(
+            (result["status"].as_str(), result["new_word_count"].as_u64()),
+            (Some("generated"), Some(3))
+        );
+        let packet =
+            std::fs::read_to_string(tmp.path().join(result["packet_file"].as_str().unwrap()))
+                .unwrap();
+        assert!(
+            packet.contains("## New prose\n\nThe rain fell."),
+            "{packet}"
+        );
+
+        let feedback = tmp.path().join(result["feedback_file"].as_str().unwrap());
+        std::fs::write(&feedback, "My notes.\n").unwrap();
+        let html = review_packet(tmp.path(), 7, true).unwrap();
+        assert!(html["packet_file"].as_str().unwrap().ends_with(".html"));
+        assert_eq!(std::fs::read_to_string(&feedback).unwrap(), "My notes.\n");
+        let last = git::run_git(tmp.path(), &["log", "-1", "--format=%s"]).unwrap();
+        assert!(last.starts_with("review: packet"), "{last}");
+    }
+}
@@ -0,0 +1,400 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, TimeZone, Timelike,
+};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::audit;
+use crate::cmdlock;
+use crate::config::{Config, ScheduleConfig};
+use crate::context;
+use crate::errors;
+use crate::maintenance;
+use crate::metrics;
+use crate::signals;
+
+// ─── Cron expressions ─────────────────────────────────────────────────────────
+
+/// A five-field cron expression (minute hour day-of-month month day-of-week)
+/// evaluated in local time. Each field takes `*`, numbers, `a-b` ranges,
+/// `,` lists and `/n` steps; day-of-week 0 and 7 are both Sunday.
+#[derive(Debug)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week restricted: either may match, as in cron(8).
+    day_or_weekday: bool,
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            bail!(
+                "cron expression '{}' needs 5 fields (minute hour day month weekday)",
+                expr
+            );
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1; // 7 is Sunday too
+        }
+        Ok(Cron {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            day_or_weekday: day != "*" && weekday != "*",
+        })
+    }
+
+    fn matches(&self, t: &NaiveDateTime) -> bool {
+        let bit = |set: u64, n: u32| set & (1 << n) != 0;
+        let day = bit(self.days, t.day());
+        let weekday = bit(self.weekdays, t.weekday().num_days_from_sunday());
+        let day_ok = if self.day_or_weekday {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        day_ok
+            && bit(self.minutes, t.minute())
+            && bit(self.hours, t.hour())
+            && bit(self.months, t.month())
+    }
+
+    /// First matching minute strictly after `after`, searched up to a year
+    /// ahead. Local times skipped by a DST change are skipped here too.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)?;
+        (1..=366 * 24 * 60)
+            .map(|m| start + ChronoDuration::minutes(m))
+            .filter(|t| self.matches(t))
+            .find_map(|t| Local.from_local_datetime(&t).earliest())
+    }
+}
+
+/// Bit set of the values a cron field allows, within `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (
+                r,
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| anyhow!("invalid step in cron field '{}'", field))?,
+            ),
+            None => (part, 1),
+        };
+        let num = |s: &str| {
+            s.parse::<u32>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "cron field '{}': '{}' is not a number in {}-{}",
+                        field,
+                        s,
+                        min,
+                        max
+                    )
+                })
+        };
+        let (first, last) = match range {
+            "*" => (min, max),
+            r => match r.split_once('-') {
+                Some((a, b)) => (num(a)?, num(b)?),
+                // `5/15` means "from 5, every 15"
+                None if part.contains('/') => (num(r)?, max),
+                None => (num(r)?, num(r)?),
+            },
+        };
+        if first > last {
+            bail!(
+                "cron field '{}': range {}-{} is reversed",
+                field,
+                first,
+                last
+            );
+        }
+        for n in (first..=last).step_by(step as usize) {
+            set |= 1 << n;
+        }
+    }
+    Ok(set)
+}
+
+// ─── Scheduled runs ───────────────────────────────────────────────────────────
+
+fn schedule_config(config: Config) -> Result<ScheduleConfig> {
+    config
+        .schedule
+        .ok_or_else(|| anyhow!("Config.yml has no `schedule:` section"))
+}
+
+/// One scheduled session: open, hand the payload to the agent command, close
+/// with its output. A run that cannot finish releases the lock it took, so the
+/// next run (or a human) starts from a clean state.
+pub fn run_once(repo: &Path) -> Result<Value> {
+    let schedule = schedule_config(Config::load(repo)?)?;
+    let _command_lock = cmdlock::acquire(repo, "schedule")?;
+
+    let payload = context::session_open(repo)?;
+    if payload.kill_requested {
+        return Ok(json!({ "status": "killed" }));
+    }
+    let Some(session_id) = payload.session_id.clone() else {
+        return Ok(json!({
+            "status": "skipped",
+            "reason": "session_already_run",
+            "lock_holder": payload.lock_holder,
+        }));
+    };
+
+    let output = serde_json::to_string(&payload)
+        .context("Failed to serialize session payload")
+        .and_then(|input| run_agent(repo, &schedule, &session_id, &input))
+        .and_then(|out| parse_agent_output(&out));
+    let (prose, summary) = match output {
+        Ok(output) => output,
+        Err(e) => {
+            context::release_interrupted_lock(repo);
+            return Err(e.context(format!("Scheduled session {} failed", session_id)));
+        }
+    };
+
+    let close = maintenance::close_session(
+        repo,
+        &session_id,
+        &prose,
+        summary.as_deref(),
+        &payload.human_edits,
+        &maintenance::StoryUpdates::default(),
+        false,
+    );
+    // Strict marker validation, a rejecting session quota, and
+    // `refuse_truncated` keep the lock for the agent to retry; nobody retries
+    // a scheduled run, so give the session up instead.
+    match close {
+        Ok(close) => Ok(json!({ "status": "closed", "session_id": session_id, "close": close })),
+        Err(e)
+            if matches!(
+                errors::code_of(&e),
+                "invalid_markers" | "quota_violation" | "truncated"
+            ) =>
+        {
+            context::release_interrupted_lock(repo);
+            let (report, _) = errors::report(&e);
+            Ok(json!({ "status": "rejected", "session_id": session_id, "error": report["error"] }))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Run `agent_command` through `sh -c` with the payload on stdin and return its
+/// stdout. The session lock is renewed while it runs; an interrupt or the
+/// schedule timeout kills it.
+fn run_agent(
+    repo: &Path,
+    schedule: &ScheduleConfig,
+    session_id: &str,
+    input: &str,
+) -> Result<String> {
+    info!("Running agent command: {}", schedule.agent_command);
+    let mut child = Command::new("sh")
+        .args(["-c", &schedule.agent_command])
+        .current_dir(repo)
+        .env("INK_SESSION_ID", session_id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to spawn agent command '{}'", schedule.agent_command))?;
+
+    // Feed stdin and drain stdout on their own threads so a large payload or
+    // output never deadlocks against the pipe buffers.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).map(|_| out)
+    });
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(schedule.timeout_minutes * 60);
+    let beat = context::heartbeat_interval(repo);
+    let mut last_beat = Instant::now();
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .context("Failed to wait for agent command")?
+        {
+            break status;
+        }
+        let stop = if signals::interrupted() {
+            Some("interrupted by signal".to_string())
+        } else if started.elapsed() >= timeout {
+            Some(format!(
+                "timed out after {} minute(s)",
+                schedule.timeout_minutes
+            ))
+        } else {
+            None
+        };
+        if let Some(reason) = stop {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("agent command {}", reason);
+        }
+        if last_beat.elapsed() >= beat {
+            if let Err(e) = context::heartbeat(repo, Some(session_id)) {
+                warn!("Heartbeat failed: {:#}", e);
+            }
+            last_beat = Instant::now();
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    };
+
+    // An agent that exits without reading all of stdin is not an error
+    let _ = writer.join();
+    let out = reader
+        .join()
+        .map_err(|_| anyhow!("agent output reader panicked"))?
+        .context("Failed to read agent command output")?;
+    if !status.success() {
+        bail!("agent command exited with {}", status);
+    }
+    Ok(out)
+}
+
+/// The agent's stdout is either the prose itself or a JSON object
+/// `{ "prose": ..., "summary": ... }`.
+fn parse_agent_output(out: &str) -> Result<(String, Option<String>)> {
+    let (prose, summary) = match serde_json::from_str::<Value>(out.trim()) {
+        Ok(Value::Object(obj)) => (
+            obj.get("prose")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("agent JSON output has no `prose` string"))?
+                .to_string(),
+            obj.get("summary")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        ),
+        _ => (out.to_string(), None),
+    };
+    if prose.trim().is_empty() {
+        bail!("agent command produced no prose");
+    }
+    Ok((prose, summary))
+}
+
+/// `ink-cli schedule`: wait for each time the `schedule.cron` expression
+/// matches and run a session, until interrupted. Failed runs are logged and
+/// the schedule continues; each run's result is printed as one JSON line.
+pub fn run_daemon(repo: &Path) -> Result<()> {
+    let schedule = schedule_config(Config::load(repo)?)?;
+    let cron = Cron::parse(&schedule.cron)?;
+    info!(
+        "Scheduler started for {} ({})",
+        repo.display(),
+        schedule.cron
+    );
+
+    loop {
+        let next = cron
+            .next_after(Local::now())
+            .ok_or_else(|| anyhow!("cron expression '{}' never matches", schedule.cron))?;
+        info!(
+            "Next scheduled session at {}",
+            next.format("%Y-%m-%d %H:%M")
+        );
+        while Local::now() < next {
+            if signals::interrupted() {
+                info!("Scheduler stopped");
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+
+        let audit = audit::Audit::start(repo, "schedule", "schedule", &json!([]));
+        let started = Instant::now();
+        let result = run_once(repo);
+        metrics::record("schedule", &result, started.elapsed());
+        audit.finish(&result);
+        let result = result.unwrap_or_else(|e| {
+            warn!("Scheduled run failed: {:#}", e);
+            json!({ "status": "failed", "error": format!("{:#}", e) })
+        });
+        println!("{}", serde_json::to_string(&result)?);
+        if signals::interrupted() {
+            info!("Scheduler stopped");
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        Cron::parse(expr)
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn cron_finds_next_run() {
+        // Nightly at 02:30: later the same night, else the next one
+        assert_eq!(next("30 2 * * *", "2026-03-10 01:00"), "2026-03-10 02:30");
+        assert_eq!(next("30 2 * * *", "2026-03-10 02:30"), "2026-03-11 02:30");
+        // Weekdays only (2026-03-13 is a Friday)
+        assert_eq!(next("0 6 * * 1-5", "2026-03-13 07:00"), "2026-03-16 06:00");
+        // Steps and lists; 7 is Sunday
+        assert_eq!(
+            next("*/20 9,21 * * *", "2026-03-10 09:45"),
+            "2026-03-10 21:00"
+        );
+        assert_eq!(next("0 0 * * 7", "2026-03-10 00:00"), "2026-03-15 00:00");
+        // Day-of-month and day-of-week both set: either matches
+        assert_eq!(next("0 0 1 * 3", "2026-03-10 00:00"), "2026-03-11 00:00");
+
+        assert!(Cron::parse("0 2 * *").is_err());
+        assert!(Cron::parse("60 2 * * *").is_err());
+        assert!(Cron::parse("0 5-2 * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn agent_output_is_prose_or_json() {
+        let (prose, summary) = parse_agent_output("Rain fell.\n").unwrap();
+        assert_eq!((prose.as_str(), summary), ("Rain fell.\n", None));
+        let (prose, summary) =
+            parse_agent_output(r#"{"prose": "Rain fell.", "summary": "Weather."}"#).unwrap();
+        assert_eq!(prose, "Rain fell.");
+        assert_eq!(summary.as_deref(), Some("Weather."));
+        assert!(parse_agent_output("  \n").is_err());
+        assert!(parse_agent_output(r#"{"text": "x"}"#).is_err());
+    }
+}
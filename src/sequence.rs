@@ -0,0 +1,426 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::state::InkState;
+
+/// Default order of an Interlude without an `INK:ORDER` marker: after every
+/// numbered chapter, before the Epilogue.
+const LATE_INTERLUDE_ORDER: f64 = 999_999.0;
+const EPILOGUE_ORDER: f64 = 1_000_000.0;
+
+// ─── Units ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitKind {
+    Prologue,
+    Chapter,
+    Interlude,
+    Epilogue,
+}
+
+/// One structural unit of the book, backed by an outline file in
+/// `Chapters material/`: `Prologue.md`, `Chapter_NN.md`, `Interlude_<name>.md`,
+/// or `Epilogue.md`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Unit {
+    pub kind: UnitKind,
+    /// File name inside `Chapters material/`, e.g. `Interlude_A.md`.
+    pub file: String,
+    /// Human-readable name: "Prologue", "Chapter 3", "Interlude A".
+    pub label: String,
+    /// Chapter number for `Chapter` units.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<u32>,
+    /// Sort key. Chapter N is N, Prologue 0, Epilogue last,
+    /// `Interlude_after_05.md` 5.5; an outline can override it with
+    /// `<!-- INK:ORDER 5.5 -->` (e.g. an interlude between chapters 5 and 6).
+    #[serde(skip)]
+    pub order: f64,
+    /// `target_words` from the outline's front matter, if set.
+    #[serde(skip)]
+    pub outline_target: Option<u32>,
+}
+
+fn file_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?:Chapter_(\d+)|(Prologue)|Interlude(?:_([\w-]+))?|(Epilogue))\.md$")
+            .unwrap()
+    })
+}
+
+/// Interlude name placing it by file name alone: `after_05`, `after_05_storm`.
+fn after_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^after_(\d+)(?:_([\w-]+))?$").unwrap())
+}
+
+fn order_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<!--\s*INK:ORDER\s+(\d+(?:\.\d+)?)\s*-->").unwrap())
+}
+
+impl Unit {
+    /// The unit for `Chapter_NN.md`, whether or not the outline exists yet.
+    pub fn chapter(n: u32) -> Self {
+        Unit {
+            kind: UnitKind::Chapter,
+            file: format!("Chapter_{:02}.md", n),
+            label: format!("Chapter {}", n),
+            number: Some(n),
+            order: n as f64,
+            outline_target: None,
+        }
+    }
+
+    /// Recognise an outline file name; `content` may carry an `INK:ORDER`
+    /// override and a `target_words` front matter.
+    pub fn from_file(file: &str, content: &str) -> Option<Self> {
+        let c = file_re().captures(file)?;
+        let mut unit = if let Some(n) = c.get(1) {
+            Unit::chapter(n.as_str().parse().ok()?)
+        } else if c.get(2).is_some() {
+            Unit {
+                kind: UnitKind::Prologue,
+                file: file.to_string(),
+                label: "Prologue".to_string(),
+                number: None,
+                order: 0.0,
+                outline_target: None,
+            }
+        } else if c.get(4).is_some() {
+            Unit {
+                kind: UnitKind::Epilogue,
+                file: file.to_string(),
+                label: "Epilogue".to_string(),
+                number: None,
+                order: EPILOGUE_ORDER,
+                outline_target: None,
+            }
+        } else {
+            let name = c.get(3).map(|m| m.as_str());
+            let (label, order) = match name.and_then(|n| after_re().captures(n)) {
+                // Interlude_after_05.md sits between chapters 5 and 6
+                Some(after) => {
+                    let n: u32 = after[1].parse().ok()?;
+                    let label = match after.get(2) {
+                        Some(rest) => format!(
+                            "Interlude after Chapter {} ({})",
+                            n,
+                            rest.as_str().replace('_', " ")
+                        ),
+                        None => format!("Interlude after Chapter {}", n),
+                    };
+                    (label, n as f64 + 0.5)
+                }
+                None => match name {
+                    Some(name) => (
+                        format!("Interlude {}", name.replace('_', " ")),
+                        LATE_INTERLUDE_ORDER,
+                    ),
+                    None => ("Interlude".to_string(), LATE_INTERLUDE_ORDER),
+                },
+            };
+            Unit {
+                kind: UnitKind::Interlude,
+                file: file.to_string(),
+                label,
+                number: None,
+                order,
+                outline_target: None,
+            }
+        };
+        if let Some(o) = order_re().captures(content) {
+            unit.order = o[1].parse().unwrap_or(unit.order);
+        }
+        // A malformed header is reported when the outline is loaded
+        if let Ok((Some(front), _)) = crate::front_matter::split(content) {
+            unit.outline_target = front.target_words;
+        }
+        Some(unit)
+    }
+
+    /// `Interlude_after_NN[_name].md` for an interlude placed by file name:
+    /// `(NN, name)`.
+    pub fn interlude_after(file: &str) -> Option<(u32, Option<String>)> {
+        let name = file.strip_prefix("Interlude_")?.strip_suffix(".md")?;
+        let caps = after_re().captures(name)?;
+        Some((
+            caps[1].parse().ok()?,
+            caps.get(2).map(|m| m.as_str().to_string()),
+        ))
+    }
+
+    /// File name of an interlude placed after chapter `n`.
+    pub fn interlude_after_file(n: u32, name: Option<&str>) -> String {
+        match name {
+            Some(name) => format!("Interlude_after_{:02}_{}.md", n, name),
+            None => format!("Interlude_after_{:02}.md", n),
+        }
+    }
+
+    /// Repo-relative path of the outline file.
+    pub fn path(&self) -> String {
+        format!("Chapters material/{}", self.file)
+    }
+
+    /// The chapter this unit counts under in `.ink-state.yml`: its own number,
+    /// or the last chapter before it (0 for a prologue), capped at `chapter_count`.
+    pub fn chapter_position(&self, chapter_count: u32) -> u32 {
+        self.number
+            .unwrap_or_else(|| (self.order.floor() as u32).min(chapter_count))
+    }
+
+    /// Words that make this unit complete: the outline's `target_words`,
+    /// else a chapter's `chapter_targets` entry or `words_per_chapter`;
+    /// prologues, interludes, and epilogues are one session long.
+    pub fn target_words(&self, config: &Config) -> u32 {
+        if let Some(words) = self.outline_target.filter(|&w| w > 0) {
+            return words;
+        }
+        match self.kind {
+            UnitKind::Chapter => config.chapter_target(self.number.unwrap_or(0)),
+            _ => config.words_per_session,
+        }
+    }
+}
+
+// ─── Sequence ─────────────────────────────────────────────────────────────────
+
+/// All outline files in `Chapters material/`, in reading order.
+/// Unrecognised file names are ignored.
+pub fn load_sequence(repo: &Path) -> Result<Vec<Unit>> {
+    let dir = repo.join("Chapters material");
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut units = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| "Failed to read Chapters material/")? {
+        let path = entry?.path();
+        let Some(file) = path.file_name().and_then(|f| f.to_str()) else {
+            continue;
+        };
+        if !file_re().is_match(file) {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Some(unit) = Unit::from_file(file, &content) {
+            units.push(unit);
+        }
+    }
+    units.sort_by(|a, b| a.order.total_cmp(&b.order).then(a.file.cmp(&b.file)));
+    Ok(units)
+}
+
+/// The unit currently being written: the file named by `current_unit` in
+/// state, otherwise the numbered `current_chapter`.
+pub fn current_unit(sequence: &[Unit], state: &InkState) -> Unit {
+    if let Some(file) = &state.current_unit {
+        return sequence
+            .iter()
+            .find(|u| &u.file == file)
+            .cloned()
+            .or_else(|| Unit::from_file(file, ""))
+            .unwrap_or_else(|| Unit::chapter(state.current_chapter));
+    }
+    Unit::chapter(state.current_chapter)
+}
+
+/// The unit a session opens on. Same as `current_unit`, except that a book
+/// with no prose yet whose sequence opens before Chapter 1 (a prologue)
+/// starts there.
+pub fn opening_unit(sequence: &[Unit], state: &InkState, book_words: u32) -> Unit {
+    if state.current_unit.is_none() && state.current_chapter <= 1 && book_words == 0 {
+        if let Some(first) = sequence.first().filter(|u| u.order < 1.0) {
+            return first.clone();
+        }
+    }
+    current_unit(sequence, state)
+}
+
+/// What follows the current unit.
+pub enum Next {
+    /// An outlined unit is next.
+    Unit(Unit),
+    /// The next numbered chapter has no outline yet.
+    MissingChapter(u32),
+    /// Nothing follows: last chapter (and any epilogue) reached.
+    End,
+}
+
+/// The unit after `current`: an interlude placed before the next chapter,
+/// the next chapter (outlined or not), then trailing interludes and the epilogue.
+pub fn next_unit(sequence: &[Unit], current: &Unit, chapter_count: u32) -> Next {
+    let candidate = sequence
+        .iter()
+        .find(|u| u.order > current.order && u.file != current.file);
+    let next_chapter = current.chapter_position(chapter_count) + 1;
+    if next_chapter <= chapter_count {
+        match candidate {
+            Some(u) if u.order <= next_chapter as f64 => Next::Unit(u.clone()),
+            _ => Next::MissingChapter(next_chapter),
+        }
+    } else {
+        match candidate {
+            Some(u) => Next::Unit(u.clone()),
+            None => Next::End,
+        }
+    }
+}
+
+/// The sequence from the start of the book through `current`, inclusive.
+/// Numbered chapters before `current` appear even if their outline file is gone.
+pub fn units_through(sequence: &[Unit], current: &Unit, chapter_count: u32) -> Vec<Unit> {
+    let mut units: Vec<Unit> = sequence
+        .iter()
+        .filter(|u| u.order < current.order && u.file != current.file)
+        .cloned()
+        .collect();
+    for n in 1..=chapter_count.min(current.chapter_position(chapter_count)) {
+        if (n as f64) < current.order && !units.iter().any(|u| u.number == Some(n)) {
+            units.push(Unit::chapter(n));
+        }
+    }
+    units.push(current.clone());
+    units.sort_by(|a, b| a.order.total_cmp(&b.order).then(a.file.cmp(&b.file)));
+    units
+}
+
+/// Point state at `unit`: numbered chapters are stored as `current_chapter`
+/// alone (the pre-sequence format), other units also set `current_unit`.
+pub fn set_current(state: &mut InkState, unit: &Unit, chapter_count: u32) {
+    state.current_chapter = unit.chapter_position(chapter_count);
+    state.current_unit = match unit.kind {
+        UnitKind::Chapter => None,
+        _ => Some(unit.file.clone()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(files: &[(&str, &str)]) -> Vec<Unit> {
+        let mut units: Vec<Unit> = files
+            .iter()
+            .filter_map(|(f, c)| Unit::from_file(f, c))
+            .collect();
+        units.sort_by(|a, b| a.order.total_cmp(&b.order));
+        units
+    }
+
+    fn next_file(sequence: &[Unit], current: &Unit, chapter_count: u32) -> String {
+        match next_unit(sequence, current, chapter_count) {
+            Next::Unit(u) => u.file,
+            Next::MissingChapter(n) => format!("missing {n}"),
+            Next::End => "end".to_string(),
+        }
+    }
+
+    #[test]
+    fn sequence_orders_prologue_interlude_and_epilogue() {
+        let s = seq(&[
+            ("Epilogue.md", ""),
+            ("Chapter_02.md", ""),
+            ("Interlude_A.md", "<!-- INK:ORDER 1.5 -->"),
+            ("Chapter_01.md", ""),
+            ("Prologue.md", ""),
+            ("notes.md", ""),
+        ]);
+        let files: Vec<&str> = s.iter().map(|u| u.file.as_str()).collect();
+        assert_eq!(
+            files,
+            [
+                "Prologue.md",
+                "Chapter_01.md",
+                "Interlude_A.md",
+                "Chapter_02.md",
+                "Epilogue.md"
+            ]
+        );
+        assert_eq!(s[2].label, "Interlude A");
+        assert_eq!(s[2].chapter_position(2), 1);
+    }
+
+    #[test]
+    fn next_unit_walks_sequence_and_reports_missing_chapters() {
+        let s = seq(&[
+            ("Prologue.md", ""),
+            ("Chapter_01.md", ""),
+            ("Interlude_A.md", "<!-- INK:ORDER 1.5 -->"),
+            ("Chapter_03.md", ""),
+            ("Epilogue.md", ""),
+        ]);
+        assert_eq!(next_file(&s, &s[0], 3), "Chapter_01.md");
+        assert_eq!(next_file(&s, &s[1], 3), "Interlude_A.md");
+        assert_eq!(next_file(&s, &s[2], 3), "missing 2");
+        assert_eq!(next_file(&s, &Unit::chapter(3), 3), "Epilogue.md");
+        assert_eq!(next_file(&s, &s[4], 3), "end");
+    }
+
+    #[test]
+    fn interlude_after_file_name_places_it() {
+        let s = seq(&[
+            ("Chapter_05.md", ""),
+            ("Interlude_after_05_storm.md", ""),
+            ("Chapter_06.md", ""),
+            ("Interlude_after_02.md", "<!-- INK:ORDER 6.5 -->"),
+        ]);
+        let files: Vec<&str> = s.iter().map(|u| u.file.as_str()).collect();
+        assert_eq!(
+            files,
+            [
+                "Chapter_05.md",
+                "Interlude_after_05_storm.md",
+                "Chapter_06.md",
+                "Interlude_after_02.md"
+            ]
+        );
+        assert_eq!(s[1].label, "Interlude after Chapter 5 (storm)");
+        assert_eq!(s[1].chapter_position(10), 5);
+        assert_eq!(next_file(&s, &s[0], 10), "Interlude_after_05_storm.md");
+        assert_eq!(
+            Unit::interlude_after("Interlude_after_05_storm.md"),
+            Some((5, Some("storm".to_string())))
+        );
+        assert_eq!(Unit::interlude_after("Interlude_A.md"), None);
+    }
+
+    #[test]
+    fn fresh_book_starts_on_prologue() {
+        let s = seq(&[("Prologue.md", ""), ("Chapter_01.md", "")]);
+        let state = InkState::default();
+        assert_eq!(opening_unit(&s, &state, 0).file, "Prologue.md");
+        assert_eq!(opening_unit(&s, &state, 40).file, "Chapter_01.md");
+        // Advanced past the prologue: chapter 1 with no words of its own yet.
+        assert_eq!(current_unit(&s, &state).file, "Chapter_01.md");
+    }
+
+    #[test]
+    fn target_words_prefers_front_matter_then_chapter_targets() {
+        let config = Config::parse(
+            "target_length: 60000\nchapter_count: 3\nchapter_structure: linear\n\
+             words_per_session: 500\nwords_per_chapter: 3000\n\
+             chapter_targets:\n  2: 6000\n  3: 1500\n",
+        )
+        .unwrap();
+        let s = seq(&[
+            ("Chapter_01.md", "# Chapter 1\n"),
+            ("Chapter_02.md", "# Chapter 2\n"),
+            (
+                "Chapter_03.md",
+                "---\ntarget_words: 2000\n---\n# Chapter 3\n",
+            ),
+            ("Epilogue.md", ""),
+        ]);
+        let targets: Vec<u32> = s.iter().map(|u| u.target_words(&config)).collect();
+        assert_eq!(targets, [3000, 6000, 2000, 500]);
+        assert_eq!(Unit::chapter(2).target_words(&config), 6000);
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::SeriesConfig;
+use crate::context::FileContent;
+use crate::errors::InkError;
+use crate::git;
+use crate::timeline::{self, TimelineEntry};
+
+/// The shared canon as merged into a session payload.
+#[derive(Debug, Serialize)]
+pub struct SeriesCanon {
+    /// `series.canon`, as configured.
+    pub canon: String,
+    /// HEAD of the canon checkout; None when it is not a git work tree.
+    pub commit: Option<String>,
+    /// Canon files found and merged into `global_material` / `timeline`.
+    pub files: Vec<String>,
+    /// Canon sections this book's Global Material replaces with different text.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overridden: Vec<Override>,
+    /// Canon Timeline.md rows, which come before this book's.
+    #[serde(skip)]
+    pub timeline: Vec<TimelineEntry>,
+}
+
+/// A `## ` section of a canon file that the book's copy of the file redefines.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Override {
+    pub file: String,
+    pub section: String,
+}
+
+/// The directory holding the canon files: the canon's `Global Material/`
+/// when it has one, else its root.
+fn canon_dir(repo: &Path, series: &SeriesConfig) -> Result<PathBuf> {
+    let root = repo.join(&series.canon);
+    if !root.is_dir() {
+        return Err(InkError::ConfigInvalid(format!(
+            "Config.yml: series.canon {} not found — clone the canon repository there",
+            root.display()
+        ))
+        .into());
+    }
+    let material = root.join("Global Material");
+    Ok(if material.is_dir() { material } else { root })
+}
+
+/// A Markdown file cut at its `## ` headings (outside code fences): the text
+/// before the first, then each section with its heading line, keyed by the
+/// heading in lowercase.
+fn sections(text: &str) -> (String, Vec<(String, String)>) {
+    let mut preamble = String::new();
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        match line.strip_prefix("## ") {
+            Some(heading) if !in_fence => {
+                sections.push((heading.trim().to_lowercase(), String::new()));
+            }
+            _ => {}
+        }
+        let text = match sections.last_mut() {
+            Some((_, text)) => text,
+            None => &mut preamble,
+        };
+        text.push_str(line);
+        text.push('\n');
+    }
+    (preamble, sections)
+}
+
+/// A section's words, without its `---` separators: what must match for the
+/// book's copy to agree with the canon.
+fn normalized(section: &str) -> String {
+    section
+        .lines()
+        .filter(|l| l.trim() != "---")
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The canon file with the book's sections put in: the book's preamble, each
+/// canon section or the book's section of the same heading, then the
+/// sections only the book has.
+fn merge(canon: &str, local: &str) -> String {
+    let (_, canon_sections) = sections(canon);
+    let (preamble, mut local_sections) = sections(local);
+    let mut merged = preamble;
+    for (key, text) in canon_sections {
+        match local_sections.iter().position(|(k, _)| *k == key) {
+            Some(i) => merged.push_str(&local_sections.remove(i).1),
+            None => merged.push_str(&text),
+        }
+    }
+    for (_, text) in local_sections {
+        merged.push_str(&text);
+    }
+    merged
+}
+
+/// Headings of the book's sections that redefine a canon section with
+/// different text.
+fn overrides(file: &str, canon: &str, local: &str) -> Vec<Override> {
+    let (_, canon_sections) = sections(canon);
+    sections(local)
+        .1
+        .into_iter()
+        .filter(|(key, text)| {
+            canon_sections
+                .iter()
+                .any(|(k, canon)| k == key && normalized(canon) != normalized(text))
+        })
+        .map(|(_, text)| Override {
+            file: file.to_string(),
+            section: text.lines().next().unwrap_or_default()[3..]
+                .trim()
+                .to_string(),
+        })
+        .collect()
+}
+
+/// The canon files `series.files` names that exist, with their content.
+fn canon_files(repo: &Path, series: &SeriesConfig) -> Result<Vec<(String, String)>> {
+    let dir = canon_dir(repo, series)?;
+    let mut files = Vec::new();
+    for file in &series.files {
+        let path = dir.join(file);
+        if !path.exists() {
+            warn!("Series canon has no {} — skipped", file);
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read canon file {}", path.display()))?;
+        files.push((file.clone(), content));
+    }
+    Ok(files)
+}
+
+/// Merge the canon into the session's Global Material: a canon file the
+/// book lacks is added whole, one it has is merged section by section, the
+/// book winning. Timeline.md rows are returned for the payload's `timeline`.
+pub fn merge_into(
+    repo: &Path,
+    series: &SeriesConfig,
+    global_material: &mut Vec<FileContent>,
+) -> Result<SeriesCanon> {
+    let mut canon = SeriesCanon {
+        canon: series.canon.clone(),
+        commit: git::run_git(&repo.join(&series.canon), &["rev-parse", "HEAD"]).ok(),
+        files: Vec::new(),
+        overridden: Vec::new(),
+        timeline: Vec::new(),
+    };
+    for (file, content) in canon_files(repo, series)? {
+        canon.files.push(file.clone());
+        if file == "Timeline.md" {
+            canon.timeline = timeline::parse(&content);
+            continue;
+        }
+        match global_material.iter_mut().find(|f| f.filename == file) {
+            Some(local) => {
+                canon
+                    .overridden
+                    .extend(overrides(&file, &content, &local.content));
+                local.content = merge(&content, &local.content);
+            }
+            None => global_material.push(FileContent {
+                filename: file,
+                content,
+            }),
+        }
+    }
+    global_material.sort_by(|a, b| a.filename.cmp(&b.filename));
+    Ok(canon)
+}
+
+/// For `doctor`: the canon sections this book's Global Material contradicts.
+pub fn contradictions(repo: &Path, series: &SeriesConfig) -> Result<Vec<Override>> {
+    let mut found = Vec::new();
+    for (file, canon) in canon_files(repo, series)? {
+        let local = repo.join("Global Material").join(&file);
+        if file == "Timeline.md" || !local.exists() {
+            continue;
+        }
+        let local = std::fs::read_to_string(&local)
+            .with_context(|| format!("Failed to read Global Material/{}", file))?;
+        found.extend(overrides(&file, &canon, &local));
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: &str =
+        "# Characters — Book 2\n\n## Tomas\n\nA smith.\n\n## Mara\n\nEyes: brown.\n\n\
+                         ## Ilse\n\nNew in book 2.\n";
+
+    /// Book 2 of a series whose canon sits next to it; Book 2 re-describes Mara.
+    fn book_2(tmp: &Path) -> (PathBuf, SeriesConfig) {
+        let repo = tmp.join("book-2");
+        let canon = tmp.join("canon");
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::create_dir_all(&canon).unwrap();
+        std::fs::write(
+            canon.join("Characters.md"),
+            "# Characters\n\n## Mara\n\nEyes: grey.\n\n---\n\n## Tomas\n\nA smith.\n",
+        )
+        .unwrap();
+        std::fs::write(canon.join("Lore.md"), "# Lore\n\n## Rules\n\nNo magic.\n").unwrap();
+        std::fs::write(
+            canon.join("Timeline.md"),
+            "| Date | Chapter | Event |\n|---|---|---|\n| 1890 | Chapter 9 | The fire |\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join("Global Material/Characters.md"), LOCAL).unwrap();
+        let series = SeriesConfig {
+            canon: "../canon".to_string(),
+            files: vec![
+                "Characters.md".to_string(),
+                "Lore.md".to_string(),
+                "Timeline.md".to_string(),
+                "Places.md".to_string(),
+            ],
+        };
+        (repo, series)
+    }
+
+    fn local_material() -> Vec<FileContent> {
+        vec![FileContent {
+            filename: "Characters.md".to_string(),
+            content: LOCAL.to_string(),
+        }]
+    }
+
+    #[test]
+    fn book_sections_override_the_canon() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, series) = book_2(tmp.path());
+        let mut material = local_material();
+        let merged = merge_into(&repo, &series, &mut material).unwrap();
+        assert_eq!(
+            material[0].content,
+            "# Characters — Book 2\n\n## Mara\n\nEyes: brown.\n\n## Tomas\n\nA smith.\n\n\
+             ## Ilse\n\nNew in book 2.\n"
+        );
+        let mara = Override {
+            file: "Characters.md".to_string(),
+            section: "Mara".to_string(),
+        };
+        assert_eq!(merged.overridden, [mara]);
+    }
+
+    #[test]
+    fn canon_files_the_book_lacks_are_added() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, series) = book_2(tmp.path());
+        let mut material = local_material();
+        let merged = merge_into(&repo, &series, &mut material).unwrap();
+        assert_eq!(merged.files, ["Characters.md", "Lore.md", "Timeline.md"]);
+        assert_eq!(material[1].filename, "Lore.md");
+        assert_eq!(merged.timeline[0].event, "The fire");
+    }
+
+    #[test]
+    fn overridden_canon_sections_are_contradictions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, series) = book_2(tmp.path());
+        assert_eq!(contradictions(&repo, &series).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn a_missing_canon_is_a_config_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, series) = book_2(tmp.path());
+        let missing = SeriesConfig {
+            canon: "../nowhere".to_string(),
+            files: series.files,
+        };
+        let err = contradictions(&repo, &missing).unwrap_err();
+        assert_eq!(crate::errors::code_of(&err), "config_invalid");
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::errors::InkError;
+
+// ─── Interrupt flag ────────────────────────────────────────────────────────────
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Install SIGINT/SIGTERM handlers that only raise the interrupt flag.
+/// The process keeps running so session-open / session-close can reach their
+/// next `checkpoint` and unwind cleanly instead of dying mid-git-operation.
+/// A second signal while already interrupted exits immediately. `program`
+/// prefixes the messages on stderr.
+pub fn install(program: &'static str) {
+    let result = ctrlc::set_handler(move || {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            eprintln!("{}: second interrupt — exiting immediately", program);
+            std::process::exit(130);
+        }
+        eprintln!(
+            "{}: interrupt received — cleaning up at the next safe point",
+            program
+        );
+    });
+    if let Err(e) = result {
+        warn!("Could not install signal handler: {}", e);
+    }
+}
+
+/// Returns true once SIGINT or SIGTERM has been received.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+// ─── Per-request cancellation ─────────────────────────────────────────────────
+
+thread_local! {
+    /// Cancellation flag of the MCP request this thread is running, if any.
+    static CANCEL: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `token` as this thread's cancellation flag: once the MCP
+/// client cancels the request, the next `checkpoint` fails exactly as if a
+/// signal had arrived, so the same cleanup paths run.
+#[allow(dead_code)] // used by ink-gateway-mcp only
+pub fn with_cancellation<T>(token: Arc<AtomicBool>, f: impl FnOnce() -> T) -> T {
+    CANCEL.with(|c| *c.borrow_mut() = Some(token));
+    let result = f();
+    CANCEL.with(|c| *c.borrow_mut() = None);
+    result
+}
+
+fn cancelled() -> bool {
+    CANCEL.with(|c| {
+        c.borrow()
+            .as_ref()
+            .is_some_and(|t| t.load(Ordering::SeqCst))
+    })
+}
+
+/// Bail out with an "interrupted" error if a signal has been received, or a
+/// "cancelled" one if the running MCP request was cancelled.
+/// Called between session steps so cleanup runs at a well-defined point.
+pub fn checkpoint(step: &str) -> Result<()> {
+    stop_at(step, interrupted(), cancelled())
+}
+
+/// The error `checkpoint` raises before `step` for the given flags; a signal
+/// takes precedence over a cancellation.
+fn stop_at(step: &str, interrupted: bool, cancelled: bool) -> Result<()> {
+    if interrupted {
+        return Err(InkError::Interrupted {
+            step: step.to_string(),
+        }
+        .into());
+    }
+    if cancelled {
+        return Err(InkError::Cancelled {
+            step: step.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoints_pass_until_the_call_is_cancelled() {
+        let token = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&token);
+        let err = with_cancellation(token, || {
+            assert!(checkpoint("fetch").is_ok());
+            flag.store(true, Ordering::SeqCst);
+            checkpoint("merge").unwrap_err()
+        });
+        assert_eq!(crate::errors::code_of(&err), "cancelled");
+        assert_eq!(err.to_string(), "cancelled by client before merge");
+    }
+
+    #[test]
+    fn cancellation_does_not_reach_other_threads() {
+        let token = Arc::new(AtomicBool::new(true));
+        with_cancellation(token, || {
+            let other = std::thread::spawn(|| checkpoint("fetch").is_ok());
+            assert!(other.join().unwrap());
+            assert!(checkpoint("fetch").is_err());
+        });
+    }
+
+    #[test]
+    fn cancellation_ends_with_its_call() {
+        let token = Arc::new(AtomicBool::new(true));
+        with_cancellation(token, || assert!(checkpoint("fetch").is_err()));
+        assert!(checkpoint("fetch").is_ok());
+    }
+
+    #[test]
+    fn a_signal_stops_the_step_as_interrupted() {
+        let err = stop_at("push", true, false).unwrap_err();
+        assert_eq!(crate::errors::code_of(&err), "interrupted");
+        assert_eq!(err.to_string(), "interrupted by signal before push");
+        // A signal wins over a cancellation of the same call
+        let err = stop_at("push", true, true).unwrap_err();
+        assert_eq!(crate::errors::code_of(&err), "interrupted");
+        assert!(stop_at("push", false, false).is_ok());
+    }
+}
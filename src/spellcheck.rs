@@ -0,0 +1,138 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::book::strip_engine_markers;
+use crate::config::SpellcheckConfig;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Misspelling {
+    pub word: String,
+    /// Uses in the session prose.
+    pub count: u32,
+    /// Up to ~120 characters of prose around the first use.
+    pub excerpt: String,
+}
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\p{L}(?:[\p{L}\p{M}'’-]*\p{L})?").unwrap())
+}
+
+/// Lowercased, without a possessive `'s`.
+fn key(word: &str) -> String {
+    let word = word
+        .strip_suffix("'s")
+        .or_else(|| word.strip_suffix("’s"))
+        .unwrap_or(word);
+    word.to_lowercase()
+}
+
+/// Words of `prose` hunspell rejects (`hunspell -l`), in the dictionary
+/// `config` selects for `language`.
+fn hunspell(config: &SpellcheckConfig, language: &str, prose: &str) -> Result<Vec<String>> {
+    let Some(dictionary) = config.dictionary_for(language) else {
+        bail!("no hunspell dictionary for language '{}'", language);
+    };
+    let mut child = Command::new(&config.command)
+        .args(["-l", "-i", "utf-8", "-d", &dictionary])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {} — is it installed?", config.command))?;
+    child
+        .stdin
+        .take()
+        .expect("piped stdin")
+        .write_all(prose.as_bytes())
+        .with_context(|| format!("Failed to write prose to {}", config.command))?;
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        bail!(
+            "{} -d {} failed: {}",
+            config.command,
+            dictionary,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Group the words hunspell `flagged` by use in `prose`, leaving out those
+/// the book's own vocabulary (`known`, lowercased) accepts.
+fn misspellings(prose: &str, flagged: &[String], known: &HashSet<String>) -> Vec<Misspelling> {
+    let flagged: HashSet<&str> = flagged.iter().map(String::as_str).collect();
+    let mut found: Vec<Misspelling> = Vec::new();
+    for m in word_re().find_iter(prose) {
+        let word = m.as_str();
+        let bare = word
+            .strip_suffix("'s")
+            .or_else(|| word.strip_suffix("’s"))
+            .unwrap_or(word);
+        if !(flagged.contains(word) || flagged.contains(bare)) || known.contains(&key(word)) {
+            continue;
+        }
+        match found.iter_mut().find(|f| f.word == word) {
+            Some(f) => f.count += 1,
+            None => found.push(Misspelling {
+                word: word.to_string(),
+                count: 1,
+                excerpt: crate::constraints::excerpt(prose, m.start(), m.end()),
+            }),
+        }
+    }
+    found
+}
+
+/// Spellcheck session `prose` with hunspell. Glossary terms, their variant
+/// spellings, and every word of the book's material (`known`) act as a
+/// custom dictionary. Misspellings in order of first use.
+pub fn check(
+    config: &SpellcheckConfig,
+    language: &str,
+    known: &HashSet<String>,
+    prose: &str,
+) -> Result<Vec<Misspelling>> {
+    let prose = strip_engine_markers(prose);
+    let flagged = hunspell(config, language, &prose)?;
+    Ok(misspellings(&prose, &flagged, known))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictionaries_follow_the_book_language() {
+        let config: SpellcheckConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config.dictionary_for("English").as_deref(), Some("en_US"));
+        assert_eq!(config.dictionary_for("fr-ca").as_deref(), Some("fr_CA"));
+        assert_eq!(config.dictionary_for("Klingon"), None);
+    }
+
+    #[test]
+    fn a_configured_dictionary_wins() {
+        let config: SpellcheckConfig = serde_yaml::from_str("dictionary: en_GB").unwrap();
+        assert_eq!(config.dictionary_for("English").as_deref(), Some("en_GB"));
+    }
+
+    #[test]
+    fn custom_words_are_not_misspellings() {
+        let prose = "Mara saw the Aethryn. The tyde rose; the tyde fell. Aethryn's light.";
+        let flagged = ["Mara", "Aethryn", "tyde"].map(String::from);
+        let known: HashSet<String> = ["aethryn", "mara"].map(String::from).into();
+        let found = misspellings(prose, &flagged, &known);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].word.as_str(), found[0].count), ("tyde", 2));
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -6,12 +7,29 @@ fn default_current_chapter() -> u32 {
     1
 }
 
+/// Fingerprint of the most recent successful session-close, so a retried close
+/// with the same session ID and prose can return the original payload.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LastClose {
+    pub session_id: String,
+    pub prose_hash: String,
+    pub payload: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InkState {
     #[serde(default = "default_current_chapter")]
     pub current_chapter: u32,
     #[serde(default)]
     pub current_chapter_word_count: u32,
+    /// Outline file of a non-numbered unit being written (`Prologue.md`,
+    /// `Interlude_A.md`, `Epilogue.md`). None while on a numbered chapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_close: Option<LastClose>,
 }
 
 impl Default for InkState {
@@ -19,6 +37,8 @@ impl Default for InkState {
         InkState {
             current_chapter: 1,
             current_chapter_word_count: 0,
+            current_unit: None,
+            last_close: None,
         }
     }
 }
@@ -35,8 +55,9 @@ impl InkState {
             .with_context(|| format!("Failed to read .ink-state.yml at {}", path.display()))?;
         let state: InkState =
             serde_yaml::from_str(&content).with_context(|| "Failed to parse .ink-state.yml")?;
+        // A prologue sits before chapter 1, so 0 is valid only alongside current_unit
         anyhow::ensure!(
-            state.current_chapter >= 1,
+            state.current_chapter >= 1 || state.current_unit.is_some(),
             ".ink-state.yml: current_chapter must be >= 1, got {}",
             state.current_chapter
         );
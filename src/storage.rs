@@ -0,0 +1,732 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::info;
+
+use crate::book::FULL_BOOK_HEADER;
+use crate::config::{self, BookStorage, Config, PartConfig};
+use crate::git;
+use crate::sequence::{self, Unit};
+use crate::state::InkState;
+use crate::wordcount::WordCounter;
+
+/// Repo-relative path of the single-file manuscript (and of `compile`'s output).
+pub const FULL_BOOK: &str = "Current version/Full_Book.md";
+const BOOK_DIR: &str = "Current version";
+
+/// Separates the unit files inside the in-memory book (`read_book`); never
+/// written to disk.
+fn file_mark_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^<!-- INK:FILE (\S+) -->$").unwrap())
+}
+
+fn file_mark(file: &str) -> String {
+    format!("<!-- INK:FILE {} -->", file)
+}
+
+/// True for a per-unit prose file (`Current version/Chapter_03.md`, …).
+pub fn is_unit_file(rel: &str) -> bool {
+    rel.strip_prefix("Current version/")
+        .is_some_and(|name| Unit::from_file(name, "").is_some())
+}
+
+/// Sort unit file names into reading order, using the outlines' `INK:ORDER`
+/// where an outline exists.
+fn reading_order(sequence: &[Unit], files: &mut [String]) {
+    let order = |file: &str| {
+        sequence
+            .iter()
+            .find(|u| u.file == file)
+            .map(|u| u.order)
+            .or_else(|| Unit::from_file(file, "").map(|u| u.order))
+            .unwrap_or(f64::MAX)
+    };
+    files.sort_by(|a, b| order(a).total_cmp(&order(b)).then(a.cmp(b)));
+}
+
+/// Unit files in `Current version/`, in reading order, with the current
+/// unit's file included even before it holds any prose.
+fn unit_files(repo: &Path) -> Result<Vec<String>> {
+    let sequence = sequence::load_sequence(repo)?;
+    let mut files: Vec<String> = std::fs::read_dir(repo.join(BOOK_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| Unit::from_file(name, "").is_some())
+                .collect()
+        })
+        .unwrap_or_default();
+    let current = sequence::current_unit(&sequence, &InkState::load(repo)?).file;
+    if !files.contains(&current) {
+        files.push(current);
+    }
+    reading_order(&sequence, &mut files);
+    Ok(files)
+}
+
+/// Repo-relative files holding the manuscript: Full_Book.md, or every unit
+/// file (the current one whether or not it exists yet).
+pub fn book_files(repo: &Path) -> Result<Vec<String>> {
+    match config::read_book_storage(repo) {
+        BookStorage::Single => Ok(vec![FULL_BOOK.to_string()]),
+        BookStorage::Chapters => Ok(unit_files(repo)?
+            .into_iter()
+            .map(|f| format!("{}/{}", BOOK_DIR, f))
+            .collect()),
+    }
+}
+
+/// The manuscript as one text, for code that edits it: Full_Book.md (None if
+/// it does not exist yet), or the unit files joined under a managed header,
+/// each introduced by an `INK:FILE` marker that `write_book` splits on.
+pub fn read_book(repo: &Path) -> Result<Option<String>> {
+    match config::read_book_storage(repo) {
+        BookStorage::Single => {
+            let path = repo.join(FULL_BOOK);
+            if !path.exists() {
+                return Ok(None);
+            }
+            Ok(Some(
+                std::fs::read_to_string(&path).with_context(|| "Failed to read Full_Book.md")?,
+            ))
+        }
+        BookStorage::Chapters => {
+            let mut book = format!("{}\n", FULL_BOOK_HEADER);
+            for file in unit_files(repo)? {
+                let rel = format!("{}/{}", BOOK_DIR, file);
+                let text = match std::fs::read_to_string(repo.join(&rel)) {
+                    Ok(text) => text,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                    Err(e) => return Err(e).with_context(|| format!("Failed to read {}", rel)),
+                };
+                push_part(&mut book, &file, &text);
+            }
+            Ok(Some(book))
+        }
+    }
+}
+
+fn push_part(book: &mut String, file: &str, text: &str) {
+    book.push('\n');
+    book.push_str(&file_mark(file));
+    book.push('\n');
+    if !text.trim().is_empty() {
+        book.push('\n');
+        book.push_str(text.trim());
+        book.push('\n');
+    }
+}
+
+/// `read_book` without the file markers: what Full_Book.md holds, in either
+/// mode. For code that only reads the prose.
+pub fn compiled_book(repo: &Path) -> Result<Option<String>> {
+    Ok(read_book(repo)?.map(|book| strip_file_marks(&book)))
+}
+
+fn strip_file_marks(book: &str) -> String {
+    let mut out = String::with_capacity(book.len());
+    let mut lines = book.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        if file_mark_re().is_match(line.trim_end()) {
+            // Drop the marker and the blank line after it
+            if lines.peek().is_some_and(|l| l.trim().is_empty()) {
+                lines.next();
+            }
+            continue;
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Split a `read_book` text into `(file, body)` parts. Text before the first
+/// marker (the header, a prepended title) goes to the first part; a text with
+/// no markers at all goes to `fallback`.
+fn split_parts(text: &str, fallback: &str) -> Vec<(String, String)> {
+    let mut front = String::new();
+    let mut parts: Vec<(String, String)> = Vec::new();
+    for line in text.split_inclusive('\n') {
+        if let Some(caps) = file_mark_re().captures(line.trim_end()) {
+            parts.push((caps[1].to_string(), String::new()));
+        } else if let Some((_, body)) = parts.last_mut() {
+            body.push_str(line);
+        } else {
+            front.push_str(line);
+        }
+    }
+    // The managed header is regenerated by read_book, whatever its wording was
+    let front = front.trim();
+    let front = match front.strip_prefix("<!-- ⚠ INK-GATEWAY:MANAGED") {
+        Some(rest) => rest.split_once("-->").map_or("", |(_, after)| after.trim()),
+        None => front,
+    };
+    if parts.is_empty() {
+        parts.push((fallback.to_string(), String::new()));
+    }
+    if !front.is_empty() {
+        let body = &mut parts[0].1;
+        *body = format!("{}\n\n{}", front, body.trim_start());
+    }
+    parts
+}
+
+/// Write a `read_book` text back. Returns the repo-relative files that changed.
+pub fn write_book(repo: &Path, text: &str) -> Result<Vec<String>> {
+    let text: &str = &crate::encoding::normalize(text);
+    std::fs::create_dir_all(repo.join(BOOK_DIR))
+        .with_context(|| "Failed to create 'Current version/'")?;
+    match config::read_book_storage(repo) {
+        BookStorage::Single => {
+            std::fs::write(repo.join(FULL_BOOK), text)
+                .with_context(|| "Failed to write Full_Book.md")?;
+            Ok(vec![FULL_BOOK.to_string()])
+        }
+        BookStorage::Chapters => {
+            let current =
+                sequence::current_unit(&sequence::load_sequence(repo)?, &InkState::load(repo)?)
+                    .file;
+            write_parts(repo, &split_parts(text, &current))
+        }
+    }
+}
+
+fn write_parts(repo: &Path, parts: &[(String, String)]) -> Result<Vec<String>> {
+    let mut written = Vec::new();
+    for (file, body) in parts {
+        let rel = format!("{}/{}", BOOK_DIR, file);
+        let path = repo.join(&rel);
+        let content = if body.trim().is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", body.trim())
+        };
+        let old = std::fs::read_to_string(&path).ok();
+        if old.as_deref() == Some(content.as_str()) || (old.is_none() && content.is_empty()) {
+            continue;
+        }
+        std::fs::write(&path, &content).with_context(|| format!("Failed to write {}", rel))?;
+        written.push(rel);
+    }
+    Ok(written)
+}
+
+/// `write_book` for a text that may have renamed or dropped unit files:
+/// unit files on disk that it no longer names are removed too.
+pub fn replace_book(repo: &Path, text: &str) -> Result<Vec<String>> {
+    if config::read_book_storage(repo) == BookStorage::Single {
+        return write_book(repo, text);
+    }
+    let before = book_files(repo)?;
+    let mut changed = write_book(repo, text)?;
+    let kept: Vec<String> = split_parts(text, "")
+        .into_iter()
+        .map(|(file, _)| format!("{}/{}", BOOK_DIR, file))
+        .collect();
+    for rel in before {
+        if !kept.contains(&rel) && repo.join(&rel).exists() {
+            std::fs::remove_file(repo.join(&rel))
+                .with_context(|| format!("Failed to remove {}", rel))?;
+            changed.push(rel);
+        }
+    }
+    Ok(changed)
+}
+
+/// Byte range of the unit `file` in a `read_book` text, from its `INK:FILE`
+/// marker line to the next marker (or the end). None if the text has no such
+/// unit.
+pub fn unit_span(text: &str, file: &str) -> Option<Range<usize>> {
+    let mut span: Option<Range<usize>> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(caps) = file_mark_re().captures(line.trim_end()) {
+            match &mut span {
+                Some(_) => break,
+                None if &caps[1] == file => span = Some(offset..text.len()),
+                None => {}
+            }
+        }
+        offset += line.len();
+        if let Some(span) = &mut span {
+            span.end = offset;
+        }
+    }
+    span
+}
+
+/// Rename the unit files a `read_book` text is split into.
+pub fn rename_units(text: &str, rename: impl Fn(&str) -> String) -> String {
+    text.split_inclusive('\n')
+        .map(|line| match file_mark_re().captures(line.trim_end()) {
+            Some(caps) => format!("{}\n", file_mark(&rename(&caps[1]))),
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// The manuscript as of `rev`, markers stripped: its unit files if it has
+/// any, else its Full_Book.md. None if `rev` has neither.
+pub fn book_at(repo: &Path, rev: &str) -> Option<String> {
+    let listed = git::run_git(
+        repo,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "ls-tree",
+            "--name-only",
+            rev,
+            &format!("{}/", BOOK_DIR),
+        ],
+    )
+    .ok()?;
+    let mut files: Vec<String> = listed
+        .lines()
+        .filter(|rel| is_unit_file(rel))
+        .map(|rel| rel.trim_start_matches("Current version/").to_string())
+        .collect();
+    if files.is_empty() {
+        return git::run_git(repo, &["show", &format!("{}:{}", rev, FULL_BOOK)]).ok();
+    }
+    reading_order(
+        &sequence::load_sequence(repo).unwrap_or_default(),
+        &mut files,
+    );
+    let mut book = format!("{}\n", FULL_BOOK_HEADER);
+    for file in files {
+        let text = git::run_git(repo, &["show", &format!("{}:{}/{}", rev, BOOK_DIR, file)])
+            .unwrap_or_default();
+        push_part(&mut book, &file, &text);
+    }
+    Some(strip_file_marks(&book))
+}
+
+// ─── compile ──────────────────────────────────────────────────────────────────
+
+/// Write Full_Book.md from the unit files (per-chapter storage), opening on
+/// the Metadata.yml title page when the book has none. The file is git-ignored
+/// in that mode and regenerated on every call.
+pub fn compile(repo: &Path) -> Result<Value> {
+    if config::read_book_storage(repo) == BookStorage::Single {
+        let words = compiled_book(repo)?.map_or(0, |b| WordCounter::for_repo(repo).count(&b));
+        return Ok(json!({
+            "status": "single_file",
+            "path": FULL_BOOK,
+            "total_word_count": words,
+        }));
+    }
+    let book = compiled_book(repo)?.unwrap_or_default();
+    let config = Config::load(repo)?;
+    let book = with_contents(&book, repo, &config)?;
+    let book = with_part_dividers(&book, &config.parts);
+    let book = match repo.join(crate::metadata::METADATA_FILE).exists() {
+        true => crate::metadata::with_title_page(&book, &crate::metadata::effective(repo)?),
+        false => book,
+    };
+    std::fs::write(repo.join(FULL_BOOK), &book).with_context(|| "Failed to write Full_Book.md")?;
+    let files: Vec<String> = book_files(repo)?
+        .into_iter()
+        .filter(|rel| repo.join(rel).exists())
+        .collect();
+    info!("Compiled {} unit file(s) into {}", files.len(), FULL_BOOK);
+    Ok(json!({
+        "status": "compiled",
+        "path": FULL_BOOK,
+        "files": files,
+        "total_word_count": WordCounter::new(&config).count(&book),
+    }))
+}
+
+/// `book` with a table of contents from Titles.yml before its first
+/// chapter-level heading; unchanged when no title is registered.
+fn with_contents(book: &str, repo: &Path, config: &Config) -> Result<String> {
+    let titles = crate::titles::load(repo)?;
+    if titles.is_empty() {
+        return Ok(book.to_string());
+    }
+    let mut chapters: Vec<u32> = Vec::new();
+    let mut first_heading = None;
+    let mut offset = 0;
+    for line in book.split_inclusive('\n') {
+        if let Some(caps) = crate::import::unit_heading_re().captures(line.trim()) {
+            first_heading.get_or_insert(offset);
+            if caps.get(2).is_some() {
+                let n = caps
+                    .get(3)
+                    .and_then(|n| n.as_str().parse().ok())
+                    .unwrap_or(chapters.last().map_or(1, |n| n + 1));
+                if !chapters.contains(&n) {
+                    chapters.push(n);
+                }
+            }
+        }
+        offset += line.len();
+    }
+    let at = first_heading.unwrap_or(book.len());
+    let toc = crate::titles::table_of_contents(&titles, &chapters, config);
+    Ok(format!("{}{}\n{}", &book[..at], toc, &book[at..]))
+}
+
+/// `book` with a `# <part title>` divider before the heading of the first
+/// chapter of each Config.yml part.
+pub fn with_part_dividers(book: &str, parts: &[PartConfig]) -> String {
+    let firsts: Vec<(u32, &str)> = parts
+        .iter()
+        .filter_map(|p| Some((p.chapter_range().ok()?.0, p.title.trim())))
+        .collect();
+    if firsts.is_empty() {
+        return book.to_string();
+    }
+    let mut out = String::with_capacity(book.len());
+    let mut chapters = 0;
+    for line in book.split_inclusive('\n') {
+        if let Some(caps) = crate::import::unit_heading_re().captures(line.trim()) {
+            if caps.get(2).is_some() {
+                chapters = caps
+                    .get(3)
+                    .and_then(|n| n.as_str().parse().ok())
+                    .unwrap_or(chapters + 1);
+                if let Some((_, title)) = firsts.iter().find(|(first, _)| *first == chapters) {
+                    out.push_str(&format!("# {}\n\n", title));
+                }
+            }
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+// ─── migrate ──────────────────────────────────────────────────────────────────
+
+/// Unit file for a chapter-level heading; `chapters` counts unnumbered
+/// "Chapter" headings.
+fn heading_file(caps: &regex::Captures, chapters: &mut u32) -> String {
+    if caps.get(2).is_some() {
+        *chapters = caps
+            .get(3)
+            .and_then(|n| n.as_str().parse().ok())
+            .unwrap_or(*chapters + 1);
+        return Unit::chapter(*chapters).file;
+    }
+    let heading = caps[1].trim();
+    let lower = heading.to_lowercase();
+    if lower.starts_with("prologue") {
+        "Prologue.md".to_string()
+    } else if lower.starts_with("epilogue") {
+        "Epilogue.md".to_string()
+    } else {
+        let name: Vec<String> = heading["interlude".len()..]
+            .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+            .filter(|w| !w.is_empty())
+            .map(String::from)
+            .collect();
+        // "Interlude after Chapter 5: Storm" is Interlude_after_05_Storm.md
+        let after = match name.as_slice() {
+            [after, rest @ ..] if after.eq_ignore_ascii_case("after") => {
+                let rest = match rest {
+                    [chapter, rest @ ..] if chapter.eq_ignore_ascii_case("chapter") => rest,
+                    rest => rest,
+                };
+                rest.split_first()
+                    .and_then(|(n, rest)| Some((n.parse::<u32>().ok()?, rest)))
+            }
+            _ => None,
+        };
+        match after {
+            Some((n, rest)) => {
+                let rest = rest.join("_");
+                Unit::interlude_after_file(n, Some(rest.as_str()).filter(|r| !r.is_empty()))
+            }
+            None if name.is_empty() => "Interlude.md".to_string(),
+            None => format!("Interlude_{}.md", name.join("_")),
+        }
+    }
+}
+
+/// Split Full_Book.md text at its chapter-level headings into unit files.
+/// Without headings, everything goes to `fallback` (the current unit).
+fn split_units(book: &str, fallback: &str) -> Vec<(String, String)> {
+    let mut parts: Vec<(String, String)> = Vec::new();
+    let mut front = String::new();
+    let mut chapters = 0;
+    for line in book.split_inclusive('\n') {
+        if let Some(caps) = crate::import::unit_heading_re().captures(line.trim()) {
+            let file = heading_file(&caps, &mut chapters);
+            match parts.iter().position(|(f, _)| *f == file) {
+                // A repeated heading continues its unit
+                Some(i) => {
+                    let part = parts.remove(i);
+                    parts.push(part);
+                }
+                None => parts.push((file, String::new())),
+            }
+        }
+        match parts.last_mut() {
+            Some((_, body)) => body.push_str(line),
+            None => front.push_str(line),
+        }
+    }
+    let mut text = String::new();
+    for (file, body) in &parts {
+        push_part(&mut text, file, body);
+    }
+    split_parts(&format!("{}\n{}", front, text), fallback)
+}
+
+/// Write a whole manuscript (Full_Book.md text, no file markers): as is, or
+/// split at its chapter headings into unit files. Returns the files written.
+pub fn write_manuscript(repo: &Path, book: &str) -> Result<Vec<String>> {
+    match config::read_book_storage(repo) {
+        BookStorage::Single => write_book(repo, book),
+        BookStorage::Chapters => {
+            std::fs::create_dir_all(repo.join(BOOK_DIR))
+                .with_context(|| "Failed to create 'Current version/'")?;
+            let current =
+                sequence::current_unit(&sequence::load_sequence(repo)?, &InkState::load(repo)?)
+                    .file;
+            write_parts(repo, &split_units(book, &current))
+        }
+    }
+}
+
+/// Switch `book_storage`: split Full_Book.md into per-unit files at its
+/// chapter headings (`chapters`, Full_Book.md then git-ignored and built by
+/// `compile`), or join the unit files back into it (`single`). Refuses while
+/// a session is running. Updates Config.yml, commits, and pushes (best-effort).
+pub fn migrate(repo: &Path, to: BookStorage) -> Result<Value> {
+    crate::journal::recover(repo)?;
+    anyhow::ensure!(
+        !repo.join(".ink-running").exists(),
+        "a session is in progress — migrate after it closes"
+    );
+    let from = config::read_book_storage(repo);
+    if from == to {
+        return Ok(json!({ "status": "already", "book_storage": to_str(to) }));
+    }
+    let before = compiled_book(repo)?.unwrap_or_default();
+    let counter = WordCounter::for_repo(repo);
+    let before_words = counter.count(&before);
+
+    let mut files = Vec::new();
+    match to {
+        BookStorage::Chapters => {
+            let current =
+                sequence::current_unit(&sequence::load_sequence(repo)?, &InkState::load(repo)?)
+                    .file;
+            let parts = split_units(&before, &current);
+            files = write_parts(repo, &parts)?;
+            let _ = git::run_git(
+                repo,
+                &["rm", "-q", "--cached", "--ignore-unmatch", FULL_BOOK],
+            );
+            std::fs::remove_file(repo.join(FULL_BOOK)).ok();
+            ignore_full_book(repo, true)?;
+        }
+        BookStorage::Single => {
+            for rel in book_files(repo)? {
+                if repo.join(&rel).exists() {
+                    std::fs::remove_file(repo.join(&rel))
+                        .with_context(|| format!("Failed to remove {}", rel))?;
+                    files.push(rel);
+                }
+            }
+            std::fs::write(repo.join(FULL_BOOK), &before)
+                .with_context(|| "Failed to write Full_Book.md")?;
+            ignore_full_book(repo, false)?;
+        }
+    }
+    let config_rel = "Global Material/Config.yml";
+    let content = std::fs::read_to_string(repo.join(config_rel))
+        .with_context(|| format!("Failed to read {}", config_rel))?;
+    std::fs::write(
+        repo.join(config_rel),
+        crate::maintenance::with_config_value(&content, "book_storage", to_str(to)),
+    )
+    .with_context(|| format!("Failed to write {}", config_rel))?;
+
+    let after_words = compiled_book(repo)?.map_or(0, |b| counter.count(&b));
+    if after_words != before_words {
+        bail!(
+            "migration changed the word count ({} → {}) — nothing committed; \
+             `git checkout -- .` restores the previous layout",
+            before_words,
+            after_words
+        );
+    }
+
+    git::run_git(
+        repo,
+        &["add", "-A", "--", BOOK_DIR, config_rel, ".gitignore"],
+    )?;
+    git::engine_commit(
+        repo,
+        &["-m", &format!("migrate: book_storage {}", to_str(to))],
+    )
+    .with_context(|| "Failed to commit the migration")?;
+    if let Err(e) = git::push_main(repo) {
+        tracing::warn!("git push skipped: {}", e);
+    }
+    info!("Migrated the manuscript to book_storage: {}", to_str(to));
+    Ok(json!({
+        "status": "migrated",
+        "book_storage": to_str(to),
+        "files": files,
+        "total_word_count": after_words,
+    }))
+}
+
+fn to_str(storage: BookStorage) -> &'static str {
+    match storage {
+        BookStorage::Single => "single",
+        BookStorage::Chapters => "chapters",
+    }
+}
+
+/// Add (or remove) the generated Full_Book.md in .gitignore.
+fn ignore_full_book(repo: &Path, ignore: bool) -> Result<()> {
+    let path = repo.join(".gitignore");
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let line = format!("/{}", FULL_BOOK);
+    let present = content.lines().any(|l| l.trim() == line);
+    let updated = match (ignore, present) {
+        (true, false) => {
+            let sep = if content.is_empty() || content.ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            format!(
+                "{}{}# Generated by `ink-cli compile`\n{}\n",
+                content, sep, line
+            )
+        }
+        (false, true) => content
+            .lines()
+            .filter(|l| l.trim() != line && l.trim() != "# Generated by `ink-cli compile`")
+            .map(|l| format!("{}\n", l))
+            .collect(),
+        _ => return Ok(()),
+    };
+    std::fs::write(&path, updated).with_context(|| "Failed to write .gitignore")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_place_chapters_and_open_with_dividers() {
+        let base = "target_length: 60000\nchapter_count: 6\nchapter_structure: linear\n\
+                    words_per_session: 500\n";
+        let config = Config::parse(&format!(
+            "{}parts:\n  - title: \"Part One: Ash\"\n    chapters: 1-3\n{}",
+            base, "  - title: Part Two\n    chapters: \"4-6\"\n"
+        ))
+        .unwrap();
+        let part = config.part_of(5).unwrap();
+        assert_eq!(
+            (part.number, part.title.as_str(), part.first_chapter),
+            (2, "Part Two", 4)
+        );
+        assert!(config.part_of(7).is_none());
+        assert!(
+            Config::parse(&format!(
+                "{}parts:\n  - title: A\n    chapters: 1-4\n  - title: B\n    chapters: 3-6\n",
+                base
+            ))
+            .is_err(),
+            "overlapping parts"
+        );
+
+        let book = "# The Book\n\n## Chapter 1\n\none\n\n## Chapter 2\n\ntwo\n\n\
+                    ## Interlude\n\nmid\n\n## Chapter 4\n\nfour\n";
+        assert_eq!(
+            with_part_dividers(book, &config.parts),
+            "# The Book\n\n# Part One: Ash\n\n## Chapter 1\n\none\n\n## Chapter 2\n\ntwo\n\n\
+             ## Interlude\n\nmid\n\n# Part Two\n\n## Chapter 4\n\nfour\n"
+        );
+    }
+
+    #[test]
+    fn per_chapter_storage_round_trips_through_migrate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::create_dir_all(repo.join("Global Material")).unwrap();
+        std::fs::create_dir_all(repo.join("Current version")).unwrap();
+        git::run_git(repo, &["init", "-q", "-b", "main"]).unwrap();
+        git::run_git(repo, &["config", "user.email", "t@t"]).unwrap();
+        git::run_git(repo, &["config", "user.name", "t"]).unwrap();
+        std::fs::write(
+            repo.join("Global Material/Config.yml"),
+            "target_length: 60000\nchapter_count: 20\nchapter_structure: linear\n\
+             words_per_session: 500\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join(".ink-state.yml"), "current_chapter: 2\n").unwrap();
+        let book = format!(
+            "{}\n\n# The Gate\n\n## Prologue\n\nBefore.\n\n## Chapter 1\n\none two\n\n\
+             <!-- PAGE 2 -->\n\n## Chapter 2\n\nthree\n",
+            FULL_BOOK_HEADER
+        );
+        std::fs::write(repo.join(FULL_BOOK), &book).unwrap();
+        git::run_git(repo, &["add", "-A"]).unwrap();
+        git::run_git(repo, &["commit", "-q", "-m", "book"]).unwrap();
+
+        let result = migrate(repo, BookStorage::Chapters).unwrap();
+        assert_eq!(
+            result["files"],
+            json!([
+                "Current version/Prologue.md",
+                "Current version/Chapter_01.md",
+                "Current version/Chapter_02.md"
+            ])
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("Current version/Prologue.md")).unwrap(),
+            "# The Gate\n\n## Prologue\n\nBefore.\n"
+        );
+        assert!(!repo.join(FULL_BOOK).exists());
+
+        // Appending goes to the current chapter's file
+        let mut text = read_book(repo).unwrap().unwrap();
+        text.push_str("\nfour five\n");
+        assert_eq!(
+            write_book(repo, &text).unwrap(),
+            ["Current version/Chapter_02.md"]
+        );
+        std::fs::write(repo.join(".ink-state.yml"), "current_chapter: 3\n").unwrap();
+        let mut text = read_book(repo).unwrap().unwrap();
+        text.push_str("\nsix\n");
+        write_book(repo, &text).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("Current version/Chapter_03.md")).unwrap(),
+            "six\n"
+        );
+
+        let result = compile(repo).unwrap();
+        let compiled = std::fs::read_to_string(repo.join(FULL_BOOK)).unwrap();
+        assert_eq!(
+            result["total_word_count"],
+            WordCounter::for_repo(repo).count(&book) + 3
+        );
+        assert!(compiled.contains("## Chapter 2\n\nthree\n\nfour five\n\nsix\n"));
+        assert!(!compiled.contains("INK:FILE"));
+        assert!(book_at(repo, "HEAD")
+            .unwrap()
+            .contains("## Chapter 1\n\none two"));
+
+        migrate(repo, BookStorage::Single).unwrap();
+        assert!(!repo.join("Current version/Chapter_03.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.join(FULL_BOOK)).unwrap(),
+            compiled
+        );
+        assert!(!std::fs::read_to_string(repo.join(".gitignore"))
+            .unwrap()
+            .contains("Full_Book"));
+    }
+}
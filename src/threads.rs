@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Plot-thread registry, maintained by the author.
+pub const THREADS_FILE: &str = "Global Material/Threads.yml";
+
+/// Written by `init`.
+pub const THREADS_TEMPLATE: &str =
+    "# Plot threads. `setups` are planted details awaiting payoff:\n\
+    # session-open lists the pending ones once their `payoff` chapter is reached\n\
+    # (all of them in the last chapters), `complete` audits what was never paid off.\n\
+    # Set `paid_off: N` once chapter N has addressed one.\n\
+    #\n\
+    #   - id: brass-key\n\
+    #     detail: Mara pockets the lighthouse keeper's brass key\n\
+    #     planted: 2\n\
+    #     payoff: 14\n\
+    setups: []\n";
+
+/// Chapters before `chapter_count` from which every pending setup is listed
+/// in the session-open payload, planned payoff or not.
+const PAYOFF_WINDOW_CHAPTERS: u32 = 3;
+
+/// Threads.yml. Only `setups:` is read; other sections are the author's.
+#[derive(Debug, Default, Deserialize)]
+pub struct Threads {
+    #[serde(default)]
+    pub setups: Vec<Setup>,
+}
+
+/// A planted detail awaiting its payoff (Chekhov's gun).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Setup {
+    pub id: String,
+    pub detail: String,
+    /// Chapter the detail was planted in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planted: Option<u32>,
+    /// Chapter meant to pay it off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payoff: Option<u32>,
+    /// Chapter that paid it off; unset while the setup is pending.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paid_off: Option<u32>,
+}
+
+/// The registry; empty when the file does not exist.
+pub fn load(repo: &Path) -> Result<Threads> {
+    let path = repo.join(THREADS_FILE);
+    if !path.exists() {
+        return Ok(Threads::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", THREADS_FILE))?;
+    let threads: Option<Threads> = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", THREADS_FILE))?;
+    Ok(threads.unwrap_or_default())
+}
+
+impl Threads {
+    pub fn pending(&self) -> impl Iterator<Item = &Setup> {
+        self.setups.iter().filter(|s| s.paid_off.is_none())
+    }
+
+    /// Pending setups the session writing `current_chapter` should keep in
+    /// view: those due by now, and all of them in the book's last chapters.
+    pub fn due(&self, current_chapter: u32, chapter_count: u32) -> Vec<Setup> {
+        let near_end = current_chapter + PAYOFF_WINDOW_CHAPTERS > chapter_count;
+        self.pending()
+            .filter(|s| near_end || s.payoff.is_some_and(|p| p <= current_chapter))
+            .cloned()
+            .collect()
+    }
+
+    /// `complete`'s payoff audit; None without setups.
+    pub fn audit(&self) -> Option<serde_json::Value> {
+        if self.setups.is_empty() {
+            return None;
+        }
+        let pending: Vec<&Setup> = self.pending().collect();
+        Some(serde_json::json!({
+            "status": if pending.is_empty() { "all_paid_off" } else { "unresolved" },
+            "total": self.setups.len(),
+            "paid_off": self.setups.len() - pending.len(),
+            "pending": pending,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threads() -> Threads {
+        serde_yaml::from_str(
+            "setups:\n\
+             \x20 - { id: key, detail: The brass key, planted: 2, payoff: 5 }\n\
+             \x20 - { id: letter, detail: The unsent letter, planted: 3 }\n\
+             \x20 - { id: gun, detail: The flare gun, planted: 1, paid_off: 4 }\n\
+             arcs: [ignored]\n",
+        )
+        .unwrap()
+    }
+
+    fn ids(setups: Vec<Setup>) -> Vec<String> {
+        setups.into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn setups_come_due_at_their_payoff_chapter() {
+        let threads = threads();
+        assert!(threads.due(4, 20).is_empty());
+        assert_eq!(ids(threads.due(5, 20)), ["key"]);
+    }
+
+    #[test]
+    fn every_pending_setup_is_due_in_the_last_chapters() {
+        assert_eq!(ids(threads().due(18, 20)), ["key", "letter"]);
+    }
+
+    #[test]
+    fn audit_counts_paid_off_and_pending_setups() {
+        let audit = threads().audit().unwrap();
+        assert_eq!(audit["status"], "unresolved");
+        assert_eq!(audit["paid_off"], 1);
+        assert_eq!(audit["pending"][1]["id"], "letter");
+    }
+
+    #[test]
+    fn no_setups_means_no_audit() {
+        assert!(Threads::default().audit().is_none());
+        let template: Threads = serde_yaml::from_str(THREADS_TEMPLATE).unwrap();
+        assert!(template.setups.is_empty());
+    }
+}
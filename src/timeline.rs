@@ -0,0 +1,209 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::errors::InkError;
+
+/// In-story chronology: one table row per event, appended by session-close.
+pub const TIMELINE_FILE: &str = "Global Material/Timeline.md";
+
+pub const TIMELINE_HEADER: &str = "# Timeline\n\n\
+    *In-story chronology, one row per event — appended by `session-close --timeline-event`. \
+    Edit freely; keep the table shape.*\n\n\
+    | Date | Chapter | Event |\n\
+    |---|---|---|\n";
+
+/// A row of Timeline.md.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    /// In-story date, free text ("Day 3, dawn", "1894-03-02").
+    pub date: String,
+    /// Unit the event was written in: "Chapter 4", "Prologue", …
+    pub chapter: String,
+    pub event: String,
+}
+
+/// An event as the engine submits it; `date` falls back to the outline's
+/// `date` front matter.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimelineEvent {
+    #[serde(default)]
+    pub date: Option<String>,
+    pub event: String,
+}
+
+impl TimelineEvent {
+    /// `--timeline-event "Day 3, dawn | Mara burns the ledger"`; without a
+    /// ` | ` the whole value is the event.
+    pub fn parse(arg: &str) -> Result<Self> {
+        let (date, event) = match arg.split_once('|') {
+            Some((date, event)) => (Some(date.trim().to_string()), event.trim()),
+            None => (None, arg.trim()),
+        };
+        if event.is_empty() {
+            return Err(InkError::InvalidInput(format!(
+                "timeline event '{}' has no description",
+                arg
+            ))
+            .into());
+        }
+        Ok(TimelineEvent {
+            date: date.filter(|d| !d.is_empty()),
+            event: event.to_string(),
+        })
+    }
+}
+
+/// Cells of a markdown table row; `\|` is a literal pipe.
+pub(crate) fn split_row(line: &str) -> Vec<String> {
+    let inner = line.trim().trim_start_matches('|');
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// `text` as a markdown table cell.
+pub(crate) fn cell(text: &str) -> String {
+    text.replace('\n', " ").replace('|', "\\|")
+}
+
+/// Every event row of Timeline.md, in file order; empty without the file.
+pub fn load(repo: &Path) -> Result<Vec<TimelineEntry>> {
+    let path = repo.join(TIMELINE_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", TIMELINE_FILE))?;
+    Ok(parse(&content))
+}
+
+/// The event rows of a Timeline.md table.
+pub fn parse(content: &str) -> Vec<TimelineEntry> {
+    content
+        .lines()
+        .filter(|l| l.trim_start().starts_with('|'))
+        .map(split_row)
+        .filter(|cells| cells.len() == 3)
+        // Header and separator rows
+        .filter(|cells| cells[0] != "Date" && !cells[0].starts_with("---"))
+        .map(|cells| TimelineEntry {
+            date: cells[0].clone(),
+            chapter: cells[1].clone(),
+            event: cells[2].clone(),
+        })
+        .collect()
+}
+
+/// The last `n` events.
+pub fn recent(repo: &Path, n: usize) -> Result<Vec<TimelineEntry>> {
+    Ok(last(load(repo)?, n))
+}
+
+/// The last `n` of `entries`.
+pub fn last(mut entries: Vec<TimelineEntry>, n: usize) -> Vec<TimelineEntry> {
+    entries.split_off(entries.len().saturating_sub(n))
+}
+
+/// Append `entries` as table rows, creating the file with its header.
+pub fn append(repo: &Path, entries: &[TimelineEntry]) -> Result<()> {
+    let path = repo.join(TIMELINE_FILE);
+    let mut content =
+        std::fs::read_to_string(&path).unwrap_or_else(|_| TIMELINE_HEADER.to_string());
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for e in entries {
+        content.push_str(&format!(
+            "| {} | {} | {} |\n",
+            cell(&e.date),
+            cell(&e.chapter),
+            cell(&e.event)
+        ));
+    }
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", TIMELINE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, event: &str) -> TimelineEntry {
+        TimelineEntry {
+            date: date.to_string(),
+            chapter: "Chapter 2".to_string(),
+            event: event.to_string(),
+        }
+    }
+
+    fn material() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("Global Material")).unwrap();
+        tmp
+    }
+
+    #[test]
+    fn events_parse_with_an_optional_date() {
+        let event = TimelineEvent::parse("Day 3, dawn | Mara burns the ledger").unwrap();
+        assert_eq!(event.date.as_deref(), Some("Day 3, dawn"));
+        assert!(TimelineEvent::parse("The storm breaks")
+            .unwrap()
+            .date
+            .is_none());
+    }
+
+    #[test]
+    fn an_event_needs_a_description() {
+        assert!(TimelineEvent::parse("Day 3 | ").is_err());
+    }
+
+    #[test]
+    fn a_missing_timeline_is_empty() {
+        let tmp = material();
+        assert!(load(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn appended_rows_escape_pipes_and_read_back() {
+        let tmp = material();
+        let repo = tmp.path();
+        append(repo, &[entry("Day 1", "Arrival")]).unwrap();
+        append(repo, &[entry("Day 2", "The A|B split")]).unwrap();
+        let content = std::fs::read_to_string(repo.join(TIMELINE_FILE)).unwrap();
+        assert!(content.contains("| Day 2 | Chapter 2 | The A\\|B split |\n"));
+        assert_eq!(
+            load(repo).unwrap(),
+            [entry("Day 1", "Arrival"), entry("Day 2", "The A|B split")]
+        );
+    }
+
+    #[test]
+    fn recent_reads_the_last_rows() {
+        let tmp = material();
+        let repo = tmp.path();
+        append(
+            repo,
+            &[
+                entry("Day 1", "Arrival"),
+                entry("Day 2", "Storm"),
+                entry("Day 3", "Fire"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            recent(repo, 2).unwrap(),
+            [entry("Day 2", "Storm"), entry("Day 3", "Fire")]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// Wall-clock time of the steps of a session command, for the `timings`
+/// object of its payload: milliseconds per step plus `total`, so a slow
+/// remote shows up as a slow `fetch` or `push` rather than a slow session.
+#[derive(Debug)]
+pub struct StepTimer {
+    started: Instant,
+    current: Option<(&'static str, Instant)>,
+    steps: BTreeMap<String, u64>,
+}
+
+impl StepTimer {
+    pub fn start() -> Self {
+        StepTimer {
+            started: Instant::now(),
+            current: None,
+            steps: BTreeMap::new(),
+        }
+    }
+
+    fn end_step(&mut self) {
+        if let Some((step, since)) = self.current.take() {
+            *self.steps.entry(step.to_string()).or_default() += since.elapsed().as_millis() as u64;
+        }
+    }
+
+    /// End the running step and start `step`; a step entered twice adds up.
+    pub fn step(&mut self, step: &'static str) {
+        self.end_step();
+        self.current = Some((step, Instant::now()));
+    }
+
+    /// Milliseconds per step so far and `total` since `start`; ends the
+    /// running step.
+    pub fn timings(&mut self) -> BTreeMap<String, u64> {
+        self.end_step();
+        let mut timings = self.steps.clone();
+        timings.insert(
+            "total".to_string(),
+            self.started.elapsed().as_millis() as u64,
+        );
+        timings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn steps_add_up_to_the_total() {
+        let mut timer = StepTimer::start();
+        timer.step("fetch");
+        std::thread::sleep(Duration::from_millis(20));
+        timer.step("push");
+        timer.step("fetch");
+        std::thread::sleep(Duration::from_millis(20));
+        let timings = timer.timings();
+        assert_eq!(
+            timings.keys().collect::<Vec<_>>(),
+            ["fetch", "push", "total"]
+        );
+        assert!(timings["fetch"] >= 40);
+        assert!(timings["total"] >= timings["fetch"] + timings["push"]);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::config::Config;
+
+/// Chapter title registry: `N: "Title"` per line, written by the engine or
+/// the author, renumbered with the chapters.
+pub const TITLES_FILE: &str = "Chapters material/Titles.yml";
+
+const TITLES_HEADER: &str = "# Chapter titles — `number: \"Title\"`, one per line. Read at \
+                             session-open, used by `compile` for the table of contents, \
+                             renumbered by `chapter insert` / `delete` / `merge`.\n";
+
+/// Titles by chapter number; empty when the file does not exist yet.
+pub fn load(repo: &Path) -> Result<BTreeMap<u32, String>> {
+    let path = repo.join(TITLES_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", TITLES_FILE))?;
+    let titles: Option<BTreeMap<u32, String>> =
+        serde_yaml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse {} — expected `N: \"Title\"` lines",
+                TITLES_FILE
+            )
+        })?;
+    Ok(titles
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, title)| !title.trim().is_empty())
+        .collect())
+}
+
+pub fn save(repo: &Path, titles: &BTreeMap<u32, String>) -> Result<()> {
+    let mut content = TITLES_HEADER.to_string();
+    for (n, title) in titles {
+        // A JSON string is a valid YAML scalar, quotes and colons included
+        content.push_str(&format!("{}: {}\n", n, serde_json::json!(title.trim())));
+    }
+    std::fs::write(repo.join(TITLES_FILE), content)
+        .with_context(|| format!("Failed to write {}", TITLES_FILE))
+}
+
+/// Move every title to `renumber(n)`; None drops it. No-op without the file.
+pub fn renumber(repo: &Path, renumber: impl Fn(u32) -> Option<u32>) -> Result<bool> {
+    if !repo.join(TITLES_FILE).exists() {
+        return Ok(false);
+    }
+    let titles = load(repo)?;
+    let moved: BTreeMap<u32, String> = titles
+        .iter()
+        .filter_map(|(n, title)| Some((renumber(*n)?, title.clone())))
+        .collect();
+    if moved == titles {
+        return Ok(false);
+    }
+    save(repo, &moved)?;
+    Ok(true)
+}
+
+/// Chapters 1..=`chapter_count` with no title.
+pub fn untitled(titles: &BTreeMap<u32, String>, chapter_count: u32) -> Vec<u32> {
+    (1..=chapter_count)
+        .filter(|n| !titles.contains_key(n))
+        .collect()
+}
+
+/// `## Contents` for the compiled manuscript: one line per chapter that has
+/// prose, its title when registered, grouped under the Config.yml parts.
+pub fn table_of_contents(
+    titles: &BTreeMap<u32, String>,
+    chapters: &[u32],
+    config: &Config,
+) -> String {
+    let mut toc = String::from("## Contents\n\n");
+    let mut part = None;
+    for &n in chapters {
+        let this_part = config.part_of(n);
+        if let Some(p) = &this_part {
+            if part != Some(p.number) {
+                toc.push_str(&format!("**{}**\n\n", p.title));
+            }
+        }
+        part = this_part.map(|p| p.number);
+        match titles.get(&n) {
+            Some(title) => toc.push_str(&format!("- Chapter {}: {}\n", n, title)),
+            None => toc.push_str(&format!("- Chapter {}\n", n)),
+        }
+    }
+    toc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titled() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        std::fs::create_dir_all(repo.join("Chapters material")).unwrap();
+        std::fs::write(
+            repo.join(TITLES_FILE),
+            "1: The Gate\n2: \"Ash: Part Two\"\n4: Home\n5: ''\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn a_missing_titles_file_has_no_titles() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(load(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn titles_load_quoted_values() {
+        let tmp = titled();
+        assert_eq!(load(tmp.path()).unwrap()[&2], "Ash: Part Two");
+    }
+
+    #[test]
+    fn blank_and_missing_titles_are_untitled() {
+        let tmp = titled();
+        assert_eq!(untitled(&load(tmp.path()).unwrap(), 5), [3, 5]);
+    }
+
+    #[test]
+    fn renumbering_drops_deleted_chapters_and_moves_later_ones() {
+        let tmp = titled();
+        let repo = tmp.path();
+        // Chapter 2 deleted: it goes, later ones move down
+        assert!(renumber(repo, |n| match n {
+            2 => None,
+            n if n > 2 => Some(n - 1),
+            n => Some(n),
+        })
+        .unwrap());
+        assert_eq!(
+            load(repo).unwrap().into_iter().collect::<Vec<_>>(),
+            [(1, "The Gate".to_string()), (3, "Home".to_string())]
+        );
+    }
+
+    #[test]
+    fn table_of_contents_groups_chapters_by_part() {
+        let titles = BTreeMap::from([(1, "The Gate".to_string()), (3, "Home".to_string())]);
+        let config = Config::parse(
+            "target_length: 60000\nchapter_count: 4\nchapter_structure: linear\n\
+             words_per_session: 500\nparts:\n  - title: One\n    chapters: 1-2\n",
+        )
+        .unwrap();
+        assert_eq!(
+            table_of_contents(&titles, &[1, 2, 3], &config),
+            "## Contents\n\n**One**\n\n- Chapter 1: The Gate\n- Chapter 2\n- Chapter 3: Home\n"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+use crate::book::{is_scene_break, strip_engine_markers, strip_html_comments};
+
+/// Closing marks allowed after a sentence's final punctuation.
+const CLOSERS: &str = "\"'”’»)]*_";
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TruncationSign {
+    /// `dangling_marker`, `unbalanced_quotes`, or `unterminated_sentence`.
+    pub check: &'static str,
+    /// The end of the prose where the sign shows.
+    pub excerpt: String,
+}
+
+/// Up to the last ~80 characters of `text`, from a char boundary.
+fn tail(text: &str) -> String {
+    let text = text.trim_end();
+    let start = text.char_indices().rev().nth(79).map_or(0, |(i, _)| i);
+    let excerpt = text[start..].trim_start();
+    if start > 0 {
+        format!("…{}", excerpt)
+    } else {
+        excerpt.to_string()
+    }
+}
+
+/// An engine block opened and never closed, or a comment cut off inside.
+fn dangling_marker(prose: &str) -> bool {
+    let opened = |kind: &str| {
+        prose
+            .matches(&format!("<!-- INK:{}:START -->", kind))
+            .count()
+    };
+    let closed = |kind: &str| prose.matches(&format!("<!-- INK:{}:END -->", kind)).count();
+    ["NEW", "REWORKED", "ORIGINAL"]
+        .iter()
+        .any(|k| opened(k) > closed(k))
+        || prose.rfind("<!--") > prose.rfind("-->")
+}
+
+/// A quotation the last paragraph opens and never closes. Earlier paragraphs
+/// may leave a quote open when the speech runs on into the next.
+fn unbalanced_quotes(paragraph: &str) -> bool {
+    let count = |c: char| paragraph.matches(c).count();
+    count('"') % 2 == 1 || count('“') > count('”') || count('«') > count('»')
+}
+
+fn unterminated(paragraph: &str) -> bool {
+    let last_line = paragraph.lines().last().unwrap_or_default().trim();
+    if is_scene_break(last_line) {
+        return false;
+    }
+    let end = paragraph.trim_end_matches(|c: char| CLOSERS.contains(c));
+    !end.ends_with(['.', '!', '?', '…'])
+}
+
+/// Signs that the engine was cut off mid-generation: an engine marker or
+/// comment left open, or a last paragraph with an unclosed quotation or no
+/// sentence-ending punctuation.
+pub fn check(prose: &str) -> Vec<TruncationSign> {
+    let mut signs = Vec::new();
+    if dangling_marker(prose) {
+        signs.push(TruncationSign {
+            check: "dangling_marker",
+            excerpt: tail(prose),
+        });
+    }
+    let text = strip_html_comments(&strip_engine_markers(prose));
+    let Some(last) = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .last()
+    else {
+        return signs;
+    };
+    if unbalanced_quotes(last) {
+        signs.push(TruncationSign {
+            check: "unbalanced_quotes",
+            excerpt: tail(last),
+        });
+    }
+    if unterminated(last) {
+        signs.push(TruncationSign {
+            check: "unterminated_sentence",
+            excerpt: tail(last),
+        });
+    }
+    signs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(prose: &str) -> Vec<&'static str> {
+        check(prose).into_iter().map(|s| s.check).collect()
+    }
+
+    #[test]
+    fn finished_prose_is_not_flagged() {
+        let whole = "<!-- INK:NEW:START -->\n\"Go on,\n\nand don't stop,\" she said.\n\n\
+                     He went.\n<!-- INK:NEW:END -->";
+        assert!(checks(whole).is_empty());
+        assert!(checks("The door opened.\n\n***").is_empty());
+        assert!(checks("“Wait!”").is_empty());
+    }
+
+    #[test]
+    fn an_unclosed_marker_and_sentence_are_flagged() {
+        assert_eq!(
+            checks("<!-- INK:NEW:START -->\nThe door opened and"),
+            ["dangling_marker", "unterminated_sentence"]
+        );
+    }
+
+    #[test]
+    fn an_open_quote_is_flagged() {
+        assert_eq!(
+            checks("The tide turned.\n\nHe said, \"Not until the"),
+            ["unbalanced_quotes", "unterminated_sentence"]
+        );
+        assert_eq!(checks("“She is gone.")[0], "unbalanced_quotes");
+    }
+
+    #[test]
+    fn excerpts_are_capped() {
+        assert_eq!(check(&"word ".repeat(40))[0].excerpt.chars().count(), 80);
+    }
+}
@@ -0,0 +1,244 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::book::is_scene_break;
+use crate::config::TypographyConfig;
+
+const NBSP: char = '\u{a0}';
+/// Narrow no-break space, before `; ! ?` in French.
+const NNBSP: char = '\u{202f}';
+
+fn comment_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<!--.*?-->").unwrap())
+}
+
+fn ellipsis_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\.[ \t]?\.[ \t]?\.").unwrap())
+}
+
+/// Double and single quotation marks: (open, close) pairs.
+struct QuoteStyle {
+    double: (char, char),
+    single: (char, char),
+}
+
+fn quote_style(language: &str) -> QuoteStyle {
+    let lang = language.trim().to_lowercase();
+    let code = lang.split(['-', '_']).next().unwrap_or_default();
+    match code {
+        "french" | "français" | "francais" | "fr" => QuoteStyle {
+            double: ('«', '»'),
+            single: ('“', '”'),
+        },
+        "german" | "deutsch" | "de" => QuoteStyle {
+            double: ('„', '“'),
+            single: ('‚', '‘'),
+        },
+        "spanish" | "español" | "espanol" | "es" | "italian" | "italiano" | "it" | "portuguese"
+        | "português" | "portugues" | "pt" => QuoteStyle {
+            double: ('«', '»'),
+            single: ('“', '”'),
+        },
+        _ => QuoteStyle {
+            double: ('“', '”'),
+            single: ('‘', '’'),
+        },
+    }
+}
+
+fn is_french(language: &str) -> bool {
+    let lang = language.trim().to_lowercase();
+    matches!(
+        lang.split(['-', '_']).next().unwrap_or_default(),
+        "french" | "français" | "francais" | "fr"
+    )
+}
+
+/// Whether a quote after `prev` opens: at the start, after a space, or after
+/// an opening bracket, dash, or quotation mark.
+fn opens(prev: Option<char>) -> bool {
+    prev.is_none_or(|c| c.is_whitespace() || "([{—–-/“‘«„‚".contains(c))
+}
+
+/// A run of exactly two hyphens becomes an em dash; `---` and longer runs
+/// (rules, scene breaks) are left alone.
+fn dashes(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '-' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let run = chars[i..].iter().take_while(|&&c| c == '-').count();
+        if run == 2 {
+            out.push('—');
+        } else {
+            out.extend(std::iter::repeat_n('-', run));
+        }
+        i += run;
+    }
+    out
+}
+
+fn quotes(text: &str, style: &QuoteStyle) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+        out.push(match c {
+            '"' if opens(prev) => style.double.0,
+            '"' => style.double.1,
+            // An apostrophe unless it opens before a letter (`'Run,' she said`)
+            '\'' if opens(prev) && next.is_some_and(char::is_alphabetic) => style.single.0,
+            '\'' if prev.is_some_and(char::is_alphanumeric) => '’',
+            '\'' => style.single.1,
+            c => c,
+        });
+    }
+    out
+}
+
+/// French spacing: a no-break space before `; ! ?` (narrow) and `:`, after
+/// `«` and before `»`, replacing an ordinary space or inserted after a word.
+fn french_spacing(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out: Vec<char> = Vec::with_capacity(chars.len() + 8);
+    for (i, &c) in chars.iter().enumerate() {
+        let next = chars.get(i + 1).copied();
+        let space = match c {
+            ';' | '!' | '?' => Some(NNBSP),
+            // Not in `10:30` or `http://`
+            ':' if !(out.last().is_some_and(char::is_ascii_digit)
+                && next.is_some_and(|n| n.is_ascii_digit()))
+                && next != Some('/') =>
+            {
+                Some(NBSP)
+            }
+            '»' => Some(NBSP),
+            _ => None,
+        };
+        if let Some(space) = space {
+            match out.last().copied() {
+                Some(' ') => *out.last_mut().unwrap() = space,
+                // `?!` takes one space; `!»` takes one before the guillemet
+                Some(p) if p.is_alphanumeric() || ".…’”)]»".contains(p) => out.push(space),
+                Some(p) if c == '»' && !p.is_whitespace() => out.push(space),
+                _ => {}
+            }
+        }
+        if c == ' ' && out.last() == Some(&'«') {
+            out.push(NBSP);
+            continue;
+        }
+        out.push(c);
+        if c == '«' && next.is_some_and(|n| !n.is_whitespace()) {
+            out.push(NBSP);
+        }
+    }
+    out.into_iter().collect()
+}
+
+fn apply_segment(text: &str, config: &TypographyConfig, language: &str) -> String {
+    let style = quote_style(language);
+    let french = is_french(language) && config.french_spacing;
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            if is_scene_break(line) {
+                return line.to_string();
+            }
+            let mut line = line.to_string();
+            if config.ellipses {
+                line = ellipsis_re().replace_all(&line, "…").into_owned();
+            }
+            if config.dashes {
+                line = dashes(&line);
+            }
+            if config.quotes {
+                line = quotes(&line, &style);
+            }
+            if french {
+                line = french_spacing(&line);
+            }
+            line
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Normalize the typography of `prose` per `config` and the book's
+/// `language`. HTML comments (engine markers, page markers, INK
+/// instructions) and scene-break lines are left untouched; applying the
+/// rules twice changes nothing.
+pub fn apply(prose: &str, config: &TypographyConfig, language: &str) -> String {
+    let mut out = String::with_capacity(prose.len());
+    let mut last = 0;
+    for m in comment_re().find_iter(prose) {
+        out.push_str(&apply_segment(&prose[last..m.start()], config, language));
+        out.push_str(m.as_str());
+        last = m.end();
+    }
+    out.push_str(&apply_segment(&prose[last..], config, language));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> TypographyConfig {
+        serde_yaml::from_str("{}").unwrap()
+    }
+
+    #[test]
+    fn english_gets_curly_quotes_dashes_and_ellipses() {
+        let prose = "<!-- INK:NEW:START -->\n\"Wait...\" she said -- it's 'late'.\n\n---\n\n\
+                     <!-- INK:NEW:END -->";
+        assert_eq!(
+            apply(prose, &defaults(), "English"),
+            "<!-- INK:NEW:START -->\n“Wait…” she said — it’s ‘late’.\n\n---\n\n\
+             <!-- INK:NEW:END -->"
+        );
+    }
+
+    #[test]
+    fn french_gets_guillemets_and_narrow_spaces() {
+        assert_eq!(
+            apply(
+                "\"Viens!\" dit-elle : il est 10:30. Pourquoi ?",
+                &defaults(),
+                "French"
+            ),
+            "«\u{a0}Viens\u{202f}!\u{a0}» dit-elle\u{a0}: il est 10:30. Pourquoi\u{202f}?"
+        );
+    }
+
+    #[test]
+    fn german_gets_low_opening_quotes() {
+        assert_eq!(apply("\"Ja\"", &defaults(), "de"), "„Ja“");
+    }
+
+    #[test]
+    fn applying_twice_changes_nothing() {
+        let config = defaults();
+        for (prose, language) in [
+            ("\"Wait...\" she said -- it's 'late'.", "English"),
+            ("\"Viens!\" dit-elle : Pourquoi ?", "French"),
+        ] {
+            let once = apply(prose, &config, language);
+            assert_eq!(apply(&once, &config, language), once);
+        }
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let quotes_only: TypographyConfig = serde_yaml::from_str("dashes: false").unwrap();
+        assert_eq!(apply("a -- b", &quotes_only, "English"), "a -- b");
+    }
+}
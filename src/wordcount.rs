@@ -0,0 +1,181 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::book::strip_html_comments;
+use crate::config::{Config, WordCountConfig};
+
+fn ink_comment_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<!--\s*INK[:\s].*?-->").unwrap())
+}
+
+/// Whether the book's `language` (Config.yml) is counted by character:
+/// Chinese, Japanese, Korean — by name or code (`zh`, `ja-JP`, `ko`).
+pub fn counts_characters(language: &str) -> bool {
+    let language = language.trim().to_lowercase();
+    matches!(
+        language.split(['-', '_']).next().unwrap_or_default(),
+        "chinese"
+            | "mandarin"
+            | "cantonese"
+            | "japanese"
+            | "korean"
+            | "zh"
+            | "ja"
+            | "ko"
+            | "中文"
+            | "汉语"
+            | "漢語"
+            | "日本語"
+            | "한국어"
+    )
+}
+
+/// Han ideographs, kana, and Hangul syllables: one counted unit each.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'     // Hiragana, Katakana
+        | '\u{31f0}'..='\u{31ff}'   // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4dbf}'   // CJK extension A
+        | '\u{4e00}'..='\u{9fff}'   // CJK unified ideographs
+        | '\u{ac00}'..='\u{d7af}'   // Hangul syllables
+        | '\u{f900}'..='\u{faff}'   // CJK compatibility ideographs
+        | '\u{ff66}'..='\u{ff9d}'   // Halfwidth katakana
+        | '\u{20000}'..='\u{2fa1f}' // Supplementary ideographs
+    )
+}
+
+/// Every CJK character, plus each run of other letters or digits (a Latin
+/// name, a year).
+fn count_characters(line: &str) -> u32 {
+    let mut count = 0;
+    let mut in_run = false;
+    for c in line.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_run = false;
+        } else if c.is_alphanumeric() {
+            count += u32::from(!in_run);
+            in_run = true;
+        } else {
+            in_run = false;
+        }
+    }
+    count
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    (1..=6).contains(&hashes)
+        && line[hashes..]
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace)
+}
+
+/// The one word counter behind every progress figure — session-close,
+/// session-open `word_count`, status, chapters, storage, export — so they
+/// never disagree. Follows the book's `language` and `word_count:` rules.
+#[derive(Debug, Clone, Default)]
+pub struct WordCounter {
+    by_character: bool,
+    rules: WordCountConfig,
+}
+
+impl WordCounter {
+    pub fn new(config: &Config) -> Self {
+        WordCounter {
+            by_character: counts_characters(&config.language),
+            rules: config.word_count.clone(),
+        }
+    }
+
+    /// The counter of the book at `repo`, where no `Config` is loaded.
+    pub fn for_repo(repo: &Path) -> Self {
+        let (language, rules) = crate::config::read_word_count(repo);
+        WordCounter {
+            by_character: counts_characters(&language),
+            rules,
+        }
+    }
+
+    /// Words of `text`: whitespace-separated tokens, or characters for a
+    /// Chinese, Japanese, or Korean book.
+    pub fn count(&self, text: &str) -> u32 {
+        let mut text = Cow::Borrowed(text);
+        if self.rules.exclude_front_matter {
+            if let Ok((Some(_), body)) = crate::front_matter::split(&text) {
+                text = Cow::Owned(body.to_string());
+            }
+        }
+        if self.rules.exclude_comments {
+            text = Cow::Owned(strip_html_comments(&text));
+        } else if self.rules.exclude_markers {
+            text = Cow::Owned(ink_comment_re().replace_all(&text, "").into_owned());
+        }
+        text.lines()
+            .filter(|l| !(self.rules.exclude_headings && is_heading(l.trim_start())))
+            .map(|l| {
+                if self.by_character {
+                    count_characters(l)
+                } else {
+                    l.split_whitespace().count() as u32
+                }
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(yaml: &str) -> WordCounter {
+        let config: Config = serde_yaml::from_str(&format!(
+            "target_length: 90000\nchapter_count: 20\nchapter_structure: linear\n\
+             words_per_session: 1500\n{}",
+            yaml
+        ))
+        .unwrap();
+        WordCounter::new(&config)
+    }
+
+    #[test]
+    fn count_words_ignores_html_comment_lines() {
+        let content = "Hello world\n<!-- PAGE 1 -->\nFoo bar baz";
+        assert_eq!(WordCounter::default().count(content), 5);
+        assert_eq!(WordCounter::default().count(""), 0);
+        let header = "<!-- Managed header\n  three more words -->\nProse.";
+        assert_eq!(WordCounter::default().count(header), 1);
+    }
+
+    #[test]
+    fn word_count_rules() {
+        let text = "---\npov: Mara\n---\n# Chapter 1\n\n<!-- INK:NEW:START -->\n\
+                    Mara ran. <!-- note to self -->\n<!-- INK: slower -->\n<!-- INK:NEW:END -->";
+        assert_eq!(counter("").count(text), 5);
+        assert_eq!(
+            counter("word_count: { exclude_headings: true }").count(text),
+            2
+        );
+        assert_eq!(
+            counter("word_count: { exclude_comments: false }").count(text),
+            10
+        );
+        assert_eq!(
+            counter("word_count: { exclude_front_matter: false }").count(text),
+            9
+        );
+    }
+
+    #[test]
+    fn count_words_by_character_for_cjk() {
+        let content = "<!-- PAGE 1 -->\n林小雨走进了房间。\nMara 说：「你好。」 2024年";
+        assert_eq!(counter("language: Chinese").count(content), 14);
+        assert_eq!(counter("language: zh-TW").count(content), 14);
+        assert_eq!(counter("language: ja").count("猫が好きです"), 6);
+        assert_eq!(counter("").count("林小雨 走进"), 2);
+    }
+}